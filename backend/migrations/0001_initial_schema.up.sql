@@ -0,0 +1,67 @@
+-- 初期スキーマ
+--
+-- アプリケーションが前提とするスキーマ（タスクの完了フラグは `is_completed`、論理削除は `deleted_at`）
+-- 旧 docker/postgresql/init.sql で作成済みの DB にも適用できるよう IF NOT EXISTS で作成する
+--
+-- init.sql のテスト用ユーザー（`123@gmail.com`）とサンプルのタスクは作成しない
+-- パスワードのハッシュがリポジトリで公開されているため、本番環境に作成されないよう意図的に削除した
+
+-- ユーザー
+CREATE TABLE IF NOT EXISTS users (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(255) NOT NULL,
+  email VARCHAR(255) UNIQUE NOT NULL,
+  password VARCHAR(255) NOT NULL,
+  photo TEXT,
+  bio TEXT DEFAULT 'Hello, I amm a new here!',
+  role VARCHAR(50) CHECK (role IN ('user', 'admin', 'creator')) DEFAULT 'user',
+  is_verified BOOLEAN DEFAULT FALSE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- トークン
+CREATE TABLE IF NOT EXISTS tokens (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER REFERENCES users(id),
+  verification_token VARCHAR(255),
+  password_reset_token VARCHAR(255),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- タスク
+CREATE TABLE IF NOT EXISTS tasks (
+  id SERIAL PRIMARY KEY,
+  title VARCHAR(100) NOT NULL UNIQUE,
+  description TEXT DEFAULT 'No description',
+  due_date TIMESTAMP WITH TIME ZONE,
+  status VARCHAR(50) CHECK (status IN ('active', 'inactive')) DEFAULT 'active',
+  is_completed BOOLEAN NOT NULL DEFAULT FALSE,
+  priority VARCHAR(50) CHECK (priority IN ('low', 'medium', 'high')) DEFAULT 'low',
+  user_id INTEGER REFERENCES users(id),
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  deleted_at TIMESTAMP WITH TIME ZONE,
+  CONSTRAINT fk_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- 旧 init.sql の `tasks` は `completed` で `deleted_at` がないため、アプリケーションのスキーマに揃える
+DO $$
+BEGIN
+  IF EXISTS (
+    SELECT 1 FROM information_schema.columns
+    WHERE table_name = 'tasks' AND column_name = 'completed'
+  ) THEN
+    ALTER TABLE tasks RENAME COLUMN completed TO is_completed;
+    UPDATE tasks SET is_completed = FALSE WHERE is_completed IS NULL;
+    ALTER TABLE tasks ALTER COLUMN is_completed SET NOT NULL;
+    ALTER TABLE tasks ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+  END IF;
+END
+$$;
+
+CREATE INDEX IF NOT EXISTS idx_status ON tasks(status);
+CREATE INDEX IF NOT EXISTS idx_priority ON tasks(priority);
+CREATE INDEX IF NOT EXISTS idx_title ON tasks(title);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_tasks_user_id;
+
+ALTER TABLE tasks ADD CONSTRAINT tasks_title_key UNIQUE (title);
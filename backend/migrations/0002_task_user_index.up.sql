@@ -0,0 +1,8 @@
+-- タスクのユーザーごとの検索
+--
+-- * タイトルの一意制約はユーザー間で衝突するため削除
+-- * 論理削除されていないタスクをユーザーで絞り込むインデックスを追加
+
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_title_key;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_user_id ON tasks(user_id) WHERE deleted_at IS NULL;
@@ -0,0 +1,6 @@
+-- メール認証トークンの検索用インデックス
+--
+-- トークンはハッシュ化して保存するため、検索は完全一致のみ
+
+CREATE INDEX IF NOT EXISTS idx_tokens_verification_token ON tokens(verification_token) WHERE verification_token IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_tokens_user_id ON tokens(user_id);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_tokens_password_reset_token;
+
+ALTER TABLE users DROP COLUMN IF EXISTS password_changed_at;
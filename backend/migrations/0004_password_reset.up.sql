@@ -0,0 +1,7 @@
+-- パスワードリセット
+--
+-- パスワード変更日時より前に発行された JWT は無効として扱う
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_changed_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_tokens_password_reset_token ON tokens(password_reset_token) WHERE password_reset_token IS NOT NULL;
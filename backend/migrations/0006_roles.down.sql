@@ -0,0 +1,4 @@
+ALTER TABLE tasks ADD CONSTRAINT tasks_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
+ALTER TABLE tokens ADD CONSTRAINT tokens_user_id_fkey FOREIGN KEY (user_id) REFERENCES users(id);
+DROP TABLE IF EXISTS role_audit_logs;
+ALTER TABLE users ALTER COLUMN role DROP NOT NULL;
@@ -0,0 +1,25 @@
+-- ロール
+--
+-- ロールを必須にし、ロール変更の監査ログを作成する
+-- ユーザーが削除されても監査ログは残す
+
+UPDATE users SET role = 'user' WHERE role IS NULL;
+ALTER TABLE users ALTER COLUMN role SET NOT NULL;
+
+CREATE TABLE IF NOT EXISTS role_audit_logs (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER,
+  changed_by INTEGER,
+  old_role VARCHAR(50) NOT NULL,
+  new_role VARCHAR(50) NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_role_audit_user FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL,
+  CONSTRAINT fk_role_audit_changed_by FOREIGN KEY (changed_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_role_audit_logs_user_id ON role_audit_logs(user_id);
+
+-- 管理者がユーザーを削除できるよう、`ON DELETE CASCADE` のない重複した外部キーを削除
+-- （初期スキーマでは `REFERENCES` と `fk_user` の2つの外部キーが作成されている）
+ALTER TABLE tokens DROP CONSTRAINT IF EXISTS tokens_user_id_fkey;
+ALTER TABLE tasks DROP CONSTRAINT IF EXISTS tasks_user_id_fkey;
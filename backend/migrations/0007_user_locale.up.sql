@@ -0,0 +1,5 @@
+-- ユーザーの言語
+--
+-- 設定されていない場合は `Accept-Language` から選択する
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS locale VARCHAR(5) CHECK (locale IN ('en', 'ja'));
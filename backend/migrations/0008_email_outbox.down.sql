@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_tasks_reminder;
+ALTER TABLE tasks DROP COLUMN IF EXISTS reminder_sent_at;
+DROP TABLE IF EXISTS email_outbox;
@@ -0,0 +1,30 @@
+-- メール送信キュー
+--
+-- メールは送信のきっかけとなる変更（新規登録・パスワードリセットなど）と同じトランザクションで登録し、
+-- バックグラウンドのワーカーが送信する
+--
+-- * `next_attempt_at` - 次に送信する日時。送信中は取得したワーカーの処理期限として使用する
+-- * `status`          - `pending`（未送信）/ `sent`（送信済み）/ `dead`（再送信の上限に達した）
+
+CREATE TABLE IF NOT EXISTS email_outbox (
+  id BIGSERIAL PRIMARY KEY,
+  recipient VARCHAR(320) NOT NULL,
+  subject TEXT NOT NULL,
+  text_body TEXT NOT NULL,
+  html_body TEXT NOT NULL,
+  status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'sent', 'dead')),
+  attempts INTEGER NOT NULL DEFAULT 0,
+  last_error TEXT,
+  next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  sent_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_pending ON email_outbox(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_email_outbox_status ON email_outbox(status, id);
+
+-- タスクの期限のリマインダーを送信した日時（期限を変更した場合はリセット）
+ALTER TABLE tasks ADD COLUMN IF NOT EXISTS reminder_sent_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_reminder ON tasks(due_date)
+  WHERE reminder_sent_at IS NULL AND is_completed = FALSE AND deleted_at IS NULL;
@@ -0,0 +1,37 @@
+-- ログインの総当たり攻撃対策
+--
+-- `login_throttle` は `login_protection.store = "postgres"` の場合に使用する、ログインのリクエスト数・失敗回数のカウンター
+--
+-- * `key`               - `request:ip:{IP アドレス}` / `failure:account:{メールアドレス}` などのカウンターの種類と対象
+-- * `window_started_at` - 回数を数え始めた日時。期間を過ぎると 1 から数え直す
+-- * `locked_until`      - ロックの期限
+
+CREATE TABLE IF NOT EXISTS login_throttle (
+  key VARCHAR(400) PRIMARY KEY,
+  count INTEGER NOT NULL DEFAULT 0,
+  window_started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  locked_until TIMESTAMP WITH TIME ZONE
+);
+
+-- ログインの失敗の履歴（管理者が確認する）
+--
+-- 存在しないメールアドレスの失敗も記録し、`user_id` は NULL とする
+--
+-- * `reason`       - `unknown_user`（メールアドレスが未登録）/ `wrong_password`（パスワードが正しくない）
+-- * `locked_until` - この失敗でロックした場合、その期限
+
+CREATE TABLE IF NOT EXISTS login_failures (
+  id BIGSERIAL PRIMARY KEY,
+  email VARCHAR(320) NOT NULL,
+  user_id INTEGER,
+  ip_address TEXT,
+  user_agent TEXT,
+  reason VARCHAR(20) NOT NULL CHECK (reason IN ('unknown_user', 'wrong_password')),
+  locked_until TIMESTAMP WITH TIME ZONE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_login_failure FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_failures_email ON login_failures(email, id);
+CREATE INDEX IF NOT EXISTS idx_login_failures_ip_address ON login_failures(ip_address, id);
+CREATE INDEX IF NOT EXISTS idx_login_failures_created_at ON login_failures(created_at);
@@ -0,0 +1,7 @@
+DELETE FROM login_failures WHERE reason = 'wrong_mfa_code';
+ALTER TABLE login_failures DROP CONSTRAINT IF EXISTS login_failures_reason_check;
+ALTER TABLE login_failures ADD CONSTRAINT login_failures_reason_check CHECK (reason IN ('unknown_user', 'wrong_password'));
+
+DROP TABLE IF EXISTS mfa_challenges;
+DROP TABLE IF EXISTS recovery_codes;
+DROP TABLE IF EXISTS user_totp;
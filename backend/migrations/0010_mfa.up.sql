@@ -0,0 +1,52 @@
+-- 2段階認証（TOTP）
+--
+-- `user_totp` はユーザーごとの TOTP の共有シークレット
+--
+-- * `enabled_at`     - 確認のコードを検証して有効にした日時。NULL の場合は登録中（ログインでは使用しない）
+-- * `last_used_step` - 最後に使用したコードのステップ。同じコード・それ以前のコードの再利用を拒否する
+
+CREATE TABLE IF NOT EXISTS user_totp (
+  user_id INTEGER PRIMARY KEY,
+  secret VARCHAR(64) NOT NULL,
+  enabled_at TIMESTAMP WITH TIME ZONE,
+  last_used_step BIGINT,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_totp FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- リカバリーコード（ハッシュのみ保存、一度だけ使用できる）
+
+CREATE TABLE IF NOT EXISTS recovery_codes (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL,
+  code_hash TEXT NOT NULL,
+  used_at TIMESTAMP WITH TIME ZONE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_recovery_code FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_recovery_codes_user_id_code_hash ON recovery_codes(user_id, code_hash);
+
+-- 2段階認証のチャレンジ
+--
+-- パスワードを検証した後、TOTP のコードを入力するまでの間のログインの状態（トークンはハッシュのみ保存）
+--
+-- * `attempts` - 正しくないコードを入力した回数。上限に達したチャレンジは使用できない
+-- * `used_at`  - ログインに成功した日時
+
+CREATE TABLE IF NOT EXISTS mfa_challenges (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL,
+  token_hash TEXT UNIQUE NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  used_at TIMESTAMP WITH TIME ZONE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_mfa_challenge FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_mfa_challenges_expires_at ON mfa_challenges(expires_at);
+
+-- TOTP のコードが正しくない失敗を、ログインの失敗の履歴に記録する
+ALTER TABLE login_failures DROP CONSTRAINT IF EXISTS login_failures_reason_check;
+ALTER TABLE login_failures ADD CONSTRAINT login_failures_reason_check CHECK (reason IN ('unknown_user', 'wrong_password', 'wrong_mfa_code'));
@@ -0,0 +1,4 @@
+ALTER TABLE users DROP COLUMN IF EXISTS has_password;
+
+DROP TABLE IF EXISTS oidc_states;
+DROP TABLE IF EXISTS accounts;
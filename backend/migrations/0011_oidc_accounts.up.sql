@@ -0,0 +1,52 @@
+-- 外部の ID プロバイダー（OpenID Connect）のアカウント
+--
+-- `_init.sql` の `account` テーブルの設計に合わせ、1人のユーザーに複数のプロバイダーを連携できる
+-- プロバイダーごとに連携できるアカウントは1つ
+--
+-- * `provider`            - 設定の `[oidc.providers]` のキー（`google` など）
+-- * `provider_account_id` - ID トークンの `sub`
+-- * `access_token` / `refresh_token` / `id_token` / `expires_at` / `token_type` / `scope` - 最後にログインした時のトークンのレスポンス
+
+CREATE TABLE IF NOT EXISTS accounts (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL,
+  type VARCHAR(20) NOT NULL DEFAULT 'oidc',
+  provider VARCHAR(50) NOT NULL,
+  provider_account_id VARCHAR(255) NOT NULL,
+  email VARCHAR(255),
+  access_token TEXT,
+  refresh_token TEXT,
+  expires_at TIMESTAMP WITH TIME ZONE,
+  token_type VARCHAR(50),
+  scope TEXT,
+  id_token TEXT,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_account FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+  CONSTRAINT unique_provider_provider_account_id UNIQUE (provider, provider_account_id),
+  CONSTRAINT unique_user_id_provider UNIQUE (user_id, provider)
+);
+
+-- 認可リクエストの状態
+--
+-- 認可リクエストからコールバックまでの間、`state`（ハッシュのみ保存）に PKCE の `code_verifier` と `nonce` を紐付ける
+--
+-- * `user_id` - 連携の場合は連携するユーザー。ログインの場合は NULL
+
+CREATE TABLE IF NOT EXISTS oidc_states (
+  id SERIAL PRIMARY KEY,
+  state_hash TEXT UNIQUE NOT NULL,
+  provider VARCHAR(50) NOT NULL,
+  code_verifier TEXT NOT NULL,
+  nonce TEXT NOT NULL,
+  user_id INTEGER,
+  expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  CONSTRAINT fk_user_oidc_state FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_oidc_states_expires_at ON oidc_states(expires_at);
+
+-- ID プロバイダーで登録したユーザーはパスワードを設定していない（パスワードリセットで設定できる）
+-- パスワードがないユーザーは、最後のアカウントの連携を解除できない
+ALTER TABLE users ADD COLUMN IF NOT EXISTS has_password BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,7 @@
+DELETE FROM users WHERE role = 'guest';
+
+DROP INDEX IF EXISTS idx_users_guest_expires_at;
+ALTER TABLE users DROP COLUMN IF EXISTS guest_expires_at;
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_role_check;
+ALTER TABLE users ADD CONSTRAINT users_role_check CHECK (role IN ('user', 'admin', 'creator'));
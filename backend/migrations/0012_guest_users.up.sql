@@ -0,0 +1,14 @@
+-- ゲストユーザー
+--
+-- メールアドレス・パスワードを登録せずに利用できる、`role = 'guest'` のユーザー
+-- メールアドレスには送信されないダミーのアドレス（`@guest.invalid`）を保存する
+-- ロールの CHECK 制約に `guest` を追加する
+--
+-- * `guest_expires_at` - ゲストの有効期限。期限までにアカウントを登録しない場合は、ユーザーとデータを削除する（登録後は NULL）
+
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_role_check;
+ALTER TABLE users ADD CONSTRAINT users_role_check CHECK (role IN ('user', 'admin', 'creator', 'guest'));
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS guest_expires_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_users_guest_expires_at ON users(guest_expires_at) WHERE guest_expires_at IS NOT NULL;
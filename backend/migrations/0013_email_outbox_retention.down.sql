@@ -0,0 +1,6 @@
+ALTER TABLE email_outbox DROP COLUMN IF EXISTS expires_at;
+
+UPDATE email_outbox SET text_body = COALESCE(text_body, ''), html_body = COALESCE(html_body, '');
+
+ALTER TABLE email_outbox ALTER COLUMN text_body SET NOT NULL;
+ALTER TABLE email_outbox ALTER COLUMN html_body SET NOT NULL;
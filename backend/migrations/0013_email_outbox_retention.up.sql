@@ -0,0 +1,12 @@
+-- メール送信キューの本文の保存期間
+--
+-- 本文にはトークンを含むリンクがあるため、送信済み・期限切れのメールの本文は削除する（NULL にする）
+--
+-- * `expires_at` - 本文のリンクの有効期限（トークンの有効期限）。期限を過ぎたメールは送信せず `dead` にする
+
+ALTER TABLE email_outbox ALTER COLUMN text_body DROP NOT NULL;
+ALTER TABLE email_outbox ALTER COLUMN html_body DROP NOT NULL;
+
+ALTER TABLE email_outbox ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE email_outbox SET text_body = NULL, html_body = NULL WHERE status = 'sent';
@@ -0,0 +1,425 @@
+//! API のレスポンスで使用するエラー
+//!
+//! ハンドラー・ミドルウェア共通のエラー。RFC 7807 の `application/problem+json` を返す。
+//! サービスのエラー（`AuthError` / `UserError` / `TaskError` / `OutboxError`）は `?` で変換する。
+//! `detail` とバリデーションエラーのメッセージは、リクエストの言語のメッセージカタログから取得する。
+//!
+//! * `ValidationError`           - 入力値バリデーションに関するエラー（項目ごとの詳細を含む）
+//! * `BadRequest`                - リクエストの形式が不正なエラー
+//! * `Unauthorized`              - 未ログイン、またはアクセストークンが無効なエラー
+//! * `InvalidCredentials`        - 認証情報が正しくないエラー
+//! * `InvalidToken`              - ワンタイムトークンが無効、または期限切れのエラー
+//! * `Forbidden`                 - 操作の権限がないエラー
+//! * `EmailNotVerified`          - メールアドレスが未認証のエラー
+//! * `UserNotFound`              - ユーザーが見つからないエラー
+//! * `TaskNotFound`              - タスクが見つからない、または他ユーザーのタスクであるエラー
+//! * `SessionNotFound`           - セッションが見つからない、または失効済みのエラー
+//! * `MailNotFound`              - メール送信キューのメールが見つからない、または再送信できない状態のエラー
+//! * `RouteNotFound`             - API が見つからないエラー
+//! * `CannotModifySelf`          - 管理者が自分自身を削除・ロール変更しようとしたエラー
+//! * `AlreadyExists`             - 一意制約に違反するエラー
+//! * `AlreadyVerified`           - メールアドレスが認証済みのエラー
+//! * `TooManyRequests`           - 再送信の待機時間中のエラー
+//! * `RateLimited`               - ログインの回数の制限・ロック中のエラー（`Retry-After` ヘッダーに再試行できるまでの秒数を返す）
+//! * `InvalidMfaCode`            - 2段階認証のコードが正しくないエラー
+//! * `MfaAlreadyEnabled`         - 2段階認証が有効なエラー
+//! * `MfaNotEnabled`             - 2段階認証が有効ではないエラー
+//! * `PasswordRequired`          - パスワードを設定していないユーザーのエラー
+//! * `OidcProviderError`         - OpenID Connect のプロバイダーとの通信に失敗したエラー（詳細はログのみに出力する）
+//! * `OidcProviderNotFound`      - 設定されていないプロバイダーのエラー
+//! * `OidcAccountInUse`          - プロバイダーのアカウント・メールアドレスが他のユーザーで使用されているエラー
+//! * `OidcProviderAlreadyLinked` - プロバイダーが既に連携されているエラー
+//! * `OidcEmailRequired`         - プロバイダーからメールアドレスを取得できないエラー
+//! * `OidcAccountNotFound`       - 連携しているアカウントが見つからないエラー
+//! * `OidcLastLoginMethod`       - 連携を解除するとログインできなくなるエラー
+//! * `GuestLoginDisabled`        - ゲストログインが無効なエラー
+//! * `GuestNotAllowed`           - ゲストが登録済みのユーザーのみの機能を使用しようとしたエラー
+//! * `NotGuest`                  - ゲストではない（登録済み、または有効期限切れの）ユーザーのエラー
+//! * `InternalError`             - サーバーエラー（詳細はログのみに出力し、レスポンスには含めない）
+
+use std::fmt;
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use postgres::error::SqlState;
+use serde::Serialize;
+
+use crate::application::errors::auth_error::AuthError;
+use crate::application::errors::outbox_error::OutboxError;
+use crate::application::errors::task_error::TaskError;
+use crate::application::errors::user_error::UserError;
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::domain::enums::locale::Locale;
+use crate::{app_log, error_log};
+
+/// problem+json の Content-Type
+const PROBLEM_JSON: &str = "application/problem+json";
+
+#[derive(Debug)]
+pub enum ApiError {
+    ValidationError(validator::ValidationErrors),
+    BadRequest(String),
+    Unauthorized,
+    InvalidCredentials,
+    InvalidToken,
+    Forbidden,
+    EmailNotVerified,
+    UserNotFound,
+    TaskNotFound,
+    SessionNotFound,
+    MailNotFound,
+    RouteNotFound,
+    CannotModifySelf,
+    AlreadyExists,
+    AlreadyVerified,
+    TooManyRequests,
+    RateLimited(u64),
+    InvalidMfaCode,
+    MfaAlreadyEnabled,
+    MfaNotEnabled,
+    PasswordRequired,
+    OidcProviderError(String),
+    OidcProviderNotFound,
+    OidcAccountInUse,
+    OidcProviderAlreadyLinked,
+    OidcEmailRequired,
+    OidcAccountNotFound,
+    OidcLastLoginMethod,
+    GuestLoginDisabled,
+    GuestNotAllowed,
+    NotGuest,
+    InternalError(String),
+}
+
+/// 項目ごとのバリデーションエラー
+///
+/// # フィールド
+///
+/// * `field`   - 項目名（リクエストの JSON のキー）
+/// * `code`    - バリデーションの種類（`email_invalid` / `password_too_short` など）
+/// * `message` - エラーメッセージ（カタログの `validation.{code}`）
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// エラーレスポンス（RFC 7807）
+///
+/// # フィールド
+///
+/// * `type`   - 問題の種類（`about:blank`）
+/// * `title`  - ステータスの説明
+/// * `status` - HTTP ステータス
+/// * `detail` - エラーの詳細
+/// * `code`   - クライアントで判定に使用する固定のエラーコード
+/// * `errors` - 項目ごとのバリデーションエラー
+#[derive(Serialize, Debug)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: &'static str,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl ApiError {
+    /// クライアントで判定に使用する固定のエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::ValidationError(_) => "validation_failed",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::InvalidCredentials => "invalid_credentials",
+            ApiError::InvalidToken => "invalid_token",
+            ApiError::Forbidden => "forbidden",
+            ApiError::EmailNotVerified => "email_not_verified",
+            ApiError::UserNotFound => "user_not_found",
+            ApiError::TaskNotFound => "task_not_found",
+            ApiError::SessionNotFound => "session_not_found",
+            ApiError::MailNotFound => "mail_not_found",
+            ApiError::RouteNotFound => "route_not_found",
+            ApiError::CannotModifySelf => "cannot_modify_self",
+            ApiError::AlreadyExists => "already_exists",
+            ApiError::AlreadyVerified => "already_verified",
+            ApiError::TooManyRequests => "too_many_requests",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::InvalidMfaCode => "invalid_mfa_code",
+            ApiError::MfaAlreadyEnabled => "mfa_already_enabled",
+            ApiError::MfaNotEnabled => "mfa_not_enabled",
+            ApiError::PasswordRequired => "password_required",
+            ApiError::OidcProviderError(_) => "oidc_provider_error",
+            ApiError::OidcProviderNotFound => "oidc_provider_not_found",
+            ApiError::OidcAccountInUse => "oidc_account_in_use",
+            ApiError::OidcProviderAlreadyLinked => "oidc_provider_already_linked",
+            ApiError::OidcEmailRequired => "oidc_email_required",
+            ApiError::OidcAccountNotFound => "oidc_account_not_found",
+            ApiError::OidcLastLoginMethod => "oidc_last_login_method",
+            ApiError::GuestLoginDisabled => "guest_login_disabled",
+            ApiError::GuestNotAllowed => "guest_not_allowed",
+            ApiError::NotGuest => "not_guest",
+            ApiError::InternalError(_) => "internal_error",
+        }
+    }
+
+    /// `detail` のメッセージのキー
+    pub fn message_key(&self) -> MessageKey {
+        match self {
+            ApiError::ValidationError(_) => MessageKey::ErrorValidationFailed,
+            ApiError::BadRequest(_) => MessageKey::ErrorBadRequest,
+            ApiError::Unauthorized => MessageKey::ErrorUnauthorized,
+            ApiError::InvalidCredentials => MessageKey::ErrorInvalidCredentials,
+            ApiError::InvalidToken => MessageKey::ErrorInvalidToken,
+            ApiError::Forbidden => MessageKey::ErrorForbidden,
+            ApiError::EmailNotVerified => MessageKey::ErrorEmailNotVerified,
+            ApiError::UserNotFound => MessageKey::ErrorUserNotFound,
+            ApiError::TaskNotFound => MessageKey::ErrorTaskNotFound,
+            ApiError::SessionNotFound => MessageKey::ErrorSessionNotFound,
+            ApiError::MailNotFound => MessageKey::ErrorMailNotFound,
+            ApiError::RouteNotFound => MessageKey::ErrorRouteNotFound,
+            ApiError::CannotModifySelf => MessageKey::ErrorCannotModifySelf,
+            ApiError::AlreadyExists => MessageKey::ErrorAlreadyExists,
+            ApiError::AlreadyVerified => MessageKey::ErrorAlreadyVerified,
+            ApiError::TooManyRequests => MessageKey::ErrorTooManyRequests,
+            ApiError::RateLimited(_) => MessageKey::ErrorRateLimited,
+            ApiError::InvalidMfaCode => MessageKey::ErrorInvalidMfaCode,
+            ApiError::MfaAlreadyEnabled => MessageKey::ErrorMfaAlreadyEnabled,
+            ApiError::MfaNotEnabled => MessageKey::ErrorMfaNotEnabled,
+            ApiError::PasswordRequired => MessageKey::ErrorPasswordRequired,
+            ApiError::OidcProviderError(_) => MessageKey::ErrorOidcProvider,
+            ApiError::OidcProviderNotFound => MessageKey::ErrorOidcProviderNotFound,
+            ApiError::OidcAccountInUse => MessageKey::ErrorOidcAccountInUse,
+            ApiError::OidcProviderAlreadyLinked => MessageKey::ErrorOidcProviderAlreadyLinked,
+            ApiError::OidcEmailRequired => MessageKey::ErrorOidcEmailRequired,
+            ApiError::OidcAccountNotFound => MessageKey::ErrorOidcAccountNotFound,
+            ApiError::OidcLastLoginMethod => MessageKey::ErrorOidcLastLoginMethod,
+            ApiError::GuestLoginDisabled => MessageKey::ErrorGuestLoginDisabled,
+            ApiError::GuestNotAllowed => MessageKey::ErrorGuestNotAllowed,
+            ApiError::NotGuest => MessageKey::ErrorNotGuest,
+            ApiError::InternalError(_) => MessageKey::ErrorInternal,
+        }
+    }
+
+    /// エラーレスポンスの本文
+    ///
+    /// # 引数
+    ///
+    /// * `locale` - `detail` とバリデーションエラーのメッセージの言語
+    pub fn problem_details(&self, locale: Locale) -> ProblemDetails {
+        let status = self.status_code();
+
+        ProblemDetails {
+            problem_type: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            detail: t(locale, self.message_key()),
+            code: self.code(),
+            errors: match self {
+                ApiError::ValidationError(errors) => field_errors(errors, locale),
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+/// ログ出力用（レスポンスには含めない詳細を含む）
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::ValidationError(err) => write!(f, "{}: {}", self.code(), err),
+            ApiError::BadRequest(err) | ApiError::OidcProviderError(err) | ApiError::InternalError(err) => write!(f, "{}: {}", self.code(), err),
+            _ => write!(f, "{}", self.code()),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::ValidationError(_)
+            | ApiError::BadRequest(_)
+            | ApiError::InvalidToken
+            | ApiError::CannotModifySelf
+            | ApiError::InvalidMfaCode
+            | ApiError::OidcEmailRequired => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized | ApiError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden
+            | ApiError::EmailNotVerified
+            | ApiError::GuestLoginDisabled
+            | ApiError::GuestNotAllowed => StatusCode::FORBIDDEN,
+            ApiError::UserNotFound
+            | ApiError::TaskNotFound
+            | ApiError::SessionNotFound
+            | ApiError::MailNotFound
+            | ApiError::RouteNotFound
+            | ApiError::OidcProviderNotFound
+            | ApiError::OidcAccountNotFound => StatusCode::NOT_FOUND,
+            ApiError::AlreadyExists
+            | ApiError::AlreadyVerified
+            | ApiError::MfaAlreadyEnabled
+            | ApiError::MfaNotEnabled
+            | ApiError::PasswordRequired
+            | ApiError::OidcAccountInUse
+            | ApiError::OidcProviderAlreadyLinked
+            | ApiError::OidcLastLoginMethod
+            | ApiError::NotGuest => StatusCode::CONFLICT,
+            ApiError::TooManyRequests | ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::OidcProviderError(_) => StatusCode::BAD_GATEWAY,
+            ApiError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let ApiError::InternalError(_) | ApiError::BadRequest(_) | ApiError::OidcProviderError(_) = self {
+            error_log!("[api_error] - [error_response] message: error = {}", self);
+        }
+
+        let mut response = HttpResponse::build(self.status_code());
+        if let ApiError::RateLimited(retry_after) = self {
+            response.insert_header((RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response
+            .content_type(PROBLEM_JSON)
+            .json(self.problem_details(current_locale()))
+    }
+}
+
+/// 項目ごとのバリデーションエラーに変換
+///
+/// メッセージはカタログの `validation.{code}` から取得し、ない場合はバリデーターのメッセージを使用する。
+/// 項目名の順に並べ替え、レスポンスを安定させる
+///
+/// # 引数
+///
+/// * `errors` - バリデーションエラー
+/// * `locale` - メッセージの言語
+///
+/// # 戻り値
+///
+/// * `Vec<FieldError>` - 項目ごとのバリデーションエラー
+pub fn field_errors(errors: &validator::ValidationErrors, locale: Locale) -> Vec<FieldError> {
+    let mut fields: Vec<FieldError> = errors.field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| errors.iter().map(move |error| FieldError {
+            field: field.to_string(),
+            code: error.code.to_string(),
+            message: MessageKey::from_key(&format!("validation.{}", error.code))
+                .map(|key| t(locale, key))
+                .or_else(|| error.message.as_ref().map(|message| message.to_string())),
+        }))
+        .collect();
+
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    fields
+}
+
+/// リクエストの JSON・クエリ・パスパラメータの解析エラーを変換
+///
+/// `web::JsonConfig` などの `error_handler` に指定する
+///
+/// # 引数
+///
+/// * `error` - 解析エラー
+/// * `_req`  - リクエスト
+///
+/// # 戻り値
+///
+/// * `actix_web::Error` - `BadRequest` のエラー
+pub fn bad_request_handler<E: fmt::Display>(error: E, _req: &HttpRequest) -> actix_web::Error {
+    ApiError::BadRequest(error.to_string()).into()
+}
+
+impl From<validator::ValidationErrors> for ApiError {
+    fn from(error: validator::ValidationErrors) -> Self {
+        ApiError::ValidationError(error)
+    }
+}
+
+impl From<tokio_postgres::Error> for ApiError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        match error.code() {
+            Some(code) if code == &SqlState::UNIQUE_VIOLATION => ApiError::AlreadyExists,
+            _ => ApiError::InternalError(format!("Database error: {}", error)),
+        }
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::DatabaseError(err) => ApiError::from(err),
+            AuthError::ValidationError(err) => ApiError::ValidationError(err),
+            AuthError::UserNotFound => ApiError::UserNotFound,
+            AuthError::InvalidCredentials => ApiError::InvalidCredentials,
+            AuthError::InvalidToken => ApiError::InvalidToken,
+            AuthError::AlreadyVerified => ApiError::AlreadyVerified,
+            AuthError::TooManyRequests => ApiError::TooManyRequests,
+            AuthError::RateLimited(retry_after) => ApiError::RateLimited(retry_after),
+            AuthError::SessionNotFound => ApiError::SessionNotFound,
+            AuthError::InvalidMfaCode => ApiError::InvalidMfaCode,
+            AuthError::MfaAlreadyEnabled => ApiError::MfaAlreadyEnabled,
+            AuthError::MfaNotEnabled => ApiError::MfaNotEnabled,
+            AuthError::PasswordRequired => ApiError::PasswordRequired,
+            AuthError::Oidc(err) => ApiError::OidcProviderError(err.to_string()),
+            AuthError::OidcProviderNotFound => ApiError::OidcProviderNotFound,
+            AuthError::OidcAccountInUse => ApiError::OidcAccountInUse,
+            AuthError::OidcProviderAlreadyLinked => ApiError::OidcProviderAlreadyLinked,
+            AuthError::OidcEmailRequired => ApiError::OidcEmailRequired,
+            AuthError::OidcAccountNotFound => ApiError::OidcAccountNotFound,
+            AuthError::OidcLastLoginMethod => ApiError::OidcLastLoginMethod,
+            AuthError::GuestLoginDisabled => ApiError::GuestLoginDisabled,
+            AuthError::NotGuest => ApiError::NotGuest,
+            err @ (AuthError::PoolError(_) | AuthError::HashingError(_) | AuthError::TokenCreationError(_)) => {
+                ApiError::InternalError(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<UserError> for ApiError {
+    fn from(error: UserError) -> Self {
+        match error {
+            UserError::DatabaseError(err) => ApiError::from(err),
+            UserError::ValidationError(err) => ApiError::ValidationError(err),
+            UserError::UserNotFound => ApiError::UserNotFound,
+            UserError::InvalidCredentials => ApiError::InvalidCredentials,
+            UserError::CannotModifySelf => ApiError::CannotModifySelf,
+            UserError::LoginGuardError(err) => ApiError::from(err),
+            err @ (UserError::PoolError(_) | UserError::HashingError(_) | UserError::TokenCreationError(_)) => {
+                ApiError::InternalError(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<TaskError> for ApiError {
+    fn from(error: TaskError) -> Self {
+        match error {
+            TaskError::DatabaseError(err) => ApiError::from(err),
+            TaskError::ValidationError(err) => ApiError::ValidationError(err),
+            // トークンのユーザーが存在しない場合は再ログインが必要
+            TaskError::UserNotFound => ApiError::Unauthorized,
+            TaskError::TaskNotFound => ApiError::TaskNotFound,
+            err @ (TaskError::PoolError(_) | TaskError::HashingError(_) | TaskError::TokenCreationError(_)) => {
+                ApiError::InternalError(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<OutboxError> for ApiError {
+    fn from(error: OutboxError) -> Self {
+        match error {
+            OutboxError::DatabaseError(err) => ApiError::from(err),
+            OutboxError::EntryNotFound => ApiError::MailNotFound,
+            err @ OutboxError::PoolError(_) => ApiError::InternalError(err.to_string()),
+        }
+    }
+}
\ No newline at end of file
@@ -1,26 +1,65 @@
 //! 認証のサービスロジックで使用するカスタムエラー
 //! 
-//! * `DatabaseError`      - DB処理に関するエラー
-//! * `PoolError`          - DB接続時に関するエラー
-//! * `HashingError`       - ハッシュ化に関するエラー
-//! * `TokenCreationError` - トークン作成に関するエラー
-//! * `ValidationError`    - 入力値バリデーションに関するエラー
+//! * `DatabaseError`             - DB処理に関するエラー
+//! * `PoolError`                 - DB接続時に関するエラー
+//! * `HashingError`              - ハッシュ化に関するエラー
+//! * `TokenCreationError`        - トークン作成に関するエラー
+//! * `ValidationError`           - 入力値バリデーションに関するエラー
+//! * `UserNotFound`              - ユーザーが見つからないエラー
+//! * `InvalidCredentials`        - 認証情報が正しくないエラー
+//! * `InvalidToken`              - ワンタイムトークンが無効、または期限切れのエラー
+//! * `AlreadyVerified`           - メールアドレスが認証済みのエラー
+//! * `TooManyRequests`           - 再送信の待機時間中のエラー
+//! * `RateLimited`               - ログインの回数の制限・ロック中のエラー（再試行できるまでの秒数を含む）
+//! * `SessionNotFound`           - セッションが見つからない、または失効済みのエラー
+//! * `InvalidMfaCode`            - 2段階認証のコード（TOTP・リカバリーコード）が正しくないエラー
+//! * `MfaAlreadyEnabled`         - 2段階認証が有効なエラー
+//! * `MfaNotEnabled`             - 2段階認証が有効ではない（登録していない）エラー
+//! * `PasswordRequired`          - パスワードを設定していない（外部の ID プロバイダーでのみログインする）ユーザーのエラー
+//! * `Oidc`                      - OpenID Connect のプロバイダーとの通信に関するエラー
+//! * `OidcProviderNotFound`      - 設定されていないプロバイダーのエラー
+//! * `OidcAccountInUse`          - プロバイダーのアカウント・メールアドレスが他のユーザーで使用されているエラー
+//! * `OidcProviderAlreadyLinked` - プロバイダーが既に連携されているエラー
+//! * `OidcEmailRequired`         - プロバイダーからメールアドレスを取得できないエラー
+//! * `OidcAccountNotFound`       - 連携しているアカウントが見つからないエラー
+//! * `OidcLastLoginMethod`       - 連携を解除するとログインできなくなるエラー
+//! * `GuestLoginDisabled`        - ゲストログインが無効なエラー
+//! * `NotGuest`                  - ゲストではない（登録済み、または有効期限切れの）ユーザーのエラー
 
 use std::fmt;
 use bb8_postgres::bb8;
 use tokio_postgres;
-use argon2;
+use crate::application::errors::oidc_error::OidcError;
+use crate::application::errors::password_error::PasswordError;
 use jsonwebtoken;
 
 #[derive(Debug)]
 pub enum AuthError {
     DatabaseError(tokio_postgres::Error),
     PoolError(bb8::RunError<tokio_postgres::Error>),
-    HashingError(argon2::password_hash::Error),
+    HashingError(PasswordError),
     TokenCreationError(jsonwebtoken::errors::Error),
     ValidationError(validator::ValidationErrors),
     UserNotFound,
-    InvalidCredentials
+    InvalidCredentials,
+    InvalidToken,
+    AlreadyVerified,
+    TooManyRequests,
+    RateLimited(u64),
+    SessionNotFound,
+    InvalidMfaCode,
+    MfaAlreadyEnabled,
+    MfaNotEnabled,
+    PasswordRequired,
+    Oidc(OidcError),
+    OidcProviderNotFound,
+    OidcAccountInUse,
+    OidcProviderAlreadyLinked,
+    OidcEmailRequired,
+    OidcAccountNotFound,
+    OidcLastLoginMethod,
+    GuestLoginDisabled,
+    NotGuest
 }
 
 impl fmt::Display for AuthError {
@@ -32,7 +71,25 @@ impl fmt::Display for AuthError {
             AuthError::TokenCreationError(err) => write!(f, "JWT error: {}", err),
             AuthError::ValidationError(err) => write!(f, "Validation error: {}", err),
             AuthError::UserNotFound => write!(f, "User not found"),
-            AuthError::InvalidCredentials => write!(f, "Invalid credentials")
+            AuthError::InvalidCredentials => write!(f, "Invalid credentials"),
+            AuthError::InvalidToken => write!(f, "Invalid or expired token"),
+            AuthError::AlreadyVerified => write!(f, "Email already verified"),
+            AuthError::TooManyRequests => write!(f, "Too many requests"),
+            AuthError::RateLimited(retry_after) => write!(f, "Too many login attempts, retry after {}s", retry_after),
+            AuthError::SessionNotFound => write!(f, "Session not found"),
+            AuthError::InvalidMfaCode => write!(f, "Invalid two-factor authentication code"),
+            AuthError::MfaAlreadyEnabled => write!(f, "Two-factor authentication already enabled"),
+            AuthError::MfaNotEnabled => write!(f, "Two-factor authentication not enabled"),
+            AuthError::PasswordRequired => write!(f, "Password not set"),
+            AuthError::Oidc(err) => write!(f, "{}", err),
+            AuthError::OidcProviderNotFound => write!(f, "OIDC provider not found"),
+            AuthError::OidcAccountInUse => write!(f, "OIDC account or email already used by another user"),
+            AuthError::OidcProviderAlreadyLinked => write!(f, "OIDC provider already linked"),
+            AuthError::OidcEmailRequired => write!(f, "OIDC provider did not return an email"),
+            AuthError::OidcAccountNotFound => write!(f, "Linked OIDC account not found"),
+            AuthError::OidcLastLoginMethod => write!(f, "Cannot unlink the last login method"),
+            AuthError::GuestLoginDisabled => write!(f, "Guest login is disabled"),
+            AuthError::NotGuest => write!(f, "User is not a guest")
         }
     }
 }
@@ -51,12 +108,18 @@ impl From<bb8::RunError<tokio_postgres::Error>> for AuthError {
     }
 }
 
-impl From<argon2::password_hash::Error> for AuthError {
-    fn from(error: argon2::password_hash::Error) -> Self {
+impl From<PasswordError> for AuthError {
+    fn from(error: PasswordError) -> Self {
         AuthError::HashingError(error)
     }
 }
 
+impl From<OidcError> for AuthError {
+    fn from(error: OidcError) -> Self {
+        AuthError::Oidc(error)
+    }
+}
+
 impl From<jsonwebtoken::errors::Error> for AuthError {
     fn from(error: jsonwebtoken::errors::Error) -> Self {
         AuthError::TokenCreationError(error)
@@ -0,0 +1,34 @@
+//! 設定の読み込み時に使用するカスタムエラー
+//! 
+//! 設定ファイル・環境変数・値の検証で見つかったエラーを全てまとめて保持し、起動時に一度に報告する
+
+use std::fmt;
+
+#[derive(Debug, Default)]
+pub struct ConfigError {
+    pub errors: Vec<String>,
+}
+
+impl ConfigError {
+    /// エラーを追加
+    pub fn push(&mut self, error: impl Into<String>) {
+        self.errors.push(error.into());
+    }
+
+    /// エラーがなければ `Ok`
+    pub fn into_result<T>(self, value: T) -> Result<T, ConfigError> {
+        if self.errors.is_empty() { Ok(value) } else { Err(self) }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration ({} errors):", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
\ No newline at end of file
@@ -0,0 +1,47 @@
+//! JWT の鍵設定で使用するカスタムエラー
+//! 
+//! * `Config` - 環境変数の設定に関するエラー
+//! * `Io`     - 鍵ファイルの読み込みに関するエラー
+//! * `Pem`    - PEM の形式に関するエラー
+//! * `Key`    - 鍵の内容に関するエラー
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum JwtKeyError {
+    Config(String),
+    Io(std::io::Error),
+    Pem(pem::PemError),
+    Key(String),
+}
+
+impl fmt::Display for JwtKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JwtKeyError::Config(err) => write!(f, "JWT key configuration error: {}", err),
+            JwtKeyError::Io(err) => write!(f, "JWT key file error: {}", err),
+            JwtKeyError::Pem(err) => write!(f, "JWT key PEM error: {}", err),
+            JwtKeyError::Key(err) => write!(f, "JWT key error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for JwtKeyError {}
+
+impl From<std::io::Error> for JwtKeyError {
+    fn from(error: std::io::Error) -> Self {
+        JwtKeyError::Io(error)
+    }
+}
+
+impl From<pem::PemError> for JwtKeyError {
+    fn from(error: pem::PemError) -> Self {
+        JwtKeyError::Pem(error)
+    }
+}
+
+impl From<ring::error::KeyRejected> for JwtKeyError {
+    fn from(error: ring::error::KeyRejected) -> Self {
+        JwtKeyError::Key(error.to_string())
+    }
+}
\ No newline at end of file
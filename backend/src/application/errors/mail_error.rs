@@ -0,0 +1,59 @@
+//! メール送信で使用するカスタムエラー
+//! 
+//! * `Config`  - SMTP 設定に関するエラー
+//! * `Address` - メールアドレスの形式に関するエラー
+//! * `Message` - メール本文の作成に関するエラー
+//! * `Smtp`    - SMTP 送信に関するエラー
+//! * `File`    - ファイル出力に関するエラー
+//! * `Unavailable` - 送信先（SMTP サーバー・出力先ディレクトリ）に接続できないエラー
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum MailError {
+    Config(String),
+    Address(lettre::address::AddressError),
+    Message(lettre::error::Error),
+    Smtp(lettre::transport::smtp::Error),
+    File(lettre::transport::file::Error),
+    Unavailable(String),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Config(err) => write!(f, "Mail configuration error: {}", err),
+            MailError::Address(err) => write!(f, "Mail address error: {}", err),
+            MailError::Message(err) => write!(f, "Mail message error: {}", err),
+            MailError::Smtp(err) => write!(f, "SMTP error: {}", err),
+            MailError::File(err) => write!(f, "Mail file error: {}", err),
+            MailError::Unavailable(err) => write!(f, "Mail transport unavailable: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MailError {}
+
+impl From<lettre::address::AddressError> for MailError {
+    fn from(error: lettre::address::AddressError) -> Self {
+        MailError::Address(error)
+    }
+}
+
+impl From<lettre::error::Error> for MailError {
+    fn from(error: lettre::error::Error) -> Self {
+        MailError::Message(error)
+    }
+}
+
+impl From<lettre::transport::smtp::Error> for MailError {
+    fn from(error: lettre::transport::smtp::Error) -> Self {
+        MailError::Smtp(error)
+    }
+}
+
+impl From<lettre::transport::file::Error> for MailError {
+    fn from(error: lettre::transport::file::Error) -> Self {
+        MailError::File(error)
+    }
+}
\ No newline at end of file
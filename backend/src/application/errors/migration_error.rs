@@ -0,0 +1,47 @@
+//! マイグレーション実行時に使用するカスタムエラー
+//! 
+//! * `DatabaseError`    - DB処理に関するエラー
+//! * `PoolError`        - DB接続時に関するエラー
+//! * `ChecksumMismatch` - 適用済みのマイグレーションが変更されているエラー
+//! * `UnknownVersion`   - DBに適用済みのバージョンがアプリケーションに存在しないエラー
+
+use std::fmt;
+use bb8_postgres::bb8;
+use tokio_postgres;
+
+#[derive(Debug)]
+pub enum MigrationError {
+    DatabaseError(tokio_postgres::Error),
+    PoolError(bb8::RunError<tokio_postgres::Error>),
+    ChecksumMismatch { version: i64, name: String },
+    UnknownVersion(i64),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::DatabaseError(err) => write!(f, "Database connection error: {}", err),
+            MigrationError::PoolError(err) => write!(f, "Pool error: {}", err),
+            MigrationError::ChecksumMismatch { version, name } => {
+                write!(f, "Checksum mismatch: migration {} ({}) was modified after being applied", version, name)
+            }
+            MigrationError::UnknownVersion(version) => {
+                write!(f, "Unknown migration version {} is applied to the database", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<tokio_postgres::Error> for MigrationError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        MigrationError::DatabaseError(error)
+    }
+}
+
+impl From<bb8::RunError<tokio_postgres::Error>> for MigrationError {
+    fn from(error: bb8::RunError<tokio_postgres::Error>) -> Self {
+        MigrationError::PoolError(error)
+    }
+}
\ No newline at end of file
@@ -1,3 +1,11 @@
+pub mod api_error;
 pub mod auth_error;
+pub mod config_error;
+pub mod jwt_error;
+pub mod mail_error;
+pub mod migration_error;
+pub mod oidc_error;
+pub mod outbox_error;
+pub mod password_error;
 pub mod task_error;
 pub mod user_error;
\ No newline at end of file
@@ -0,0 +1,35 @@
+//! OpenID Connect のプロバイダーとの通信で使用するカスタムエラー
+//!
+//! * `Http`      - プロバイダーへのリクエストに関するエラー
+//! * `Provider`  - プロバイダーがエラーを返したエラー（トークンエンドポイントの `error` など）
+//! * `Discovery` - ディスカバリー・JWKS の形式が不正なエラー
+//! * `IdToken`   - ID トークンの検証に失敗したエラー
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum OidcError {
+    Http(reqwest::Error),
+    Provider(String),
+    Discovery(String),
+    IdToken(String),
+}
+
+impl fmt::Display for OidcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OidcError::Http(err) => write!(f, "OIDC request error: {}", err),
+            OidcError::Provider(err) => write!(f, "OIDC provider error: {}", err),
+            OidcError::Discovery(err) => write!(f, "OIDC discovery error: {}", err),
+            OidcError::IdToken(err) => write!(f, "OIDC ID token error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for OidcError {}
+
+impl From<reqwest::Error> for OidcError {
+    fn from(error: reqwest::Error) -> Self {
+        OidcError::Http(error)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,40 @@
+//! メール送信キューで使用するカスタムエラー
+//! 
+//! * `DatabaseError` - DB処理に関するエラー
+//! * `PoolError`     - DB接続時に関するエラー
+//! * `EntryNotFound` - メールが見つからない、または再送信できない状態であるエラー
+
+use std::fmt;
+use bb8_postgres::bb8;
+use tokio_postgres;
+
+#[derive(Debug)]
+pub enum OutboxError {
+    DatabaseError(tokio_postgres::Error),
+    PoolError(bb8::RunError<tokio_postgres::Error>),
+    EntryNotFound,
+}
+
+impl fmt::Display for OutboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutboxError::DatabaseError(err) => write!(f, "Database connection error: {}", err),
+            OutboxError::PoolError(err) => write!(f, "Pool error: {}", err),
+            OutboxError::EntryNotFound => write!(f, "Outbox entry not found"),
+        }
+    }
+}
+
+impl std::error::Error for OutboxError {}
+
+impl From<tokio_postgres::Error> for OutboxError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        OutboxError::DatabaseError(error)
+    }
+}
+
+impl From<bb8::RunError<tokio_postgres::Error>> for OutboxError {
+    fn from(error: bb8::RunError<tokio_postgres::Error>) -> Self {
+        OutboxError::PoolError(error)
+    }
+}
\ No newline at end of file
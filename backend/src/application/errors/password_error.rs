@@ -0,0 +1,47 @@
+//! パスワードのハッシュ化・検証で使用するカスタムエラー
+//!
+//! * `Hash`              - argon2 のハッシュ化・PHC 形式のハッシュに関するエラー
+//! * `Bcrypt`            - bcrypt のハッシュに関するエラー
+//! * `Params`            - argon2 のコスト設定に関するエラー
+//! * `UnsupportedScheme` - 対応していない形式のハッシュのエラー
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum PasswordError {
+    Hash(argon2::password_hash::Error),
+    Bcrypt(bcrypt::BcryptError),
+    Params(argon2::Error),
+    UnsupportedScheme,
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasswordError::Hash(err) => write!(f, "Argon2 hash error: {}", err),
+            PasswordError::Bcrypt(err) => write!(f, "Bcrypt hash error: {}", err),
+            PasswordError::Params(err) => write!(f, "Argon2 parameter error: {}", err),
+            PasswordError::UnsupportedScheme => write!(f, "Unsupported password hash scheme"),
+        }
+    }
+}
+
+impl std::error::Error for PasswordError {}
+
+impl From<argon2::password_hash::Error> for PasswordError {
+    fn from(error: argon2::password_hash::Error) -> Self {
+        PasswordError::Hash(error)
+    }
+}
+
+impl From<bcrypt::BcryptError> for PasswordError {
+    fn from(error: bcrypt::BcryptError) -> Self {
+        PasswordError::Bcrypt(error)
+    }
+}
+
+impl From<argon2::Error> for PasswordError {
+    fn from(error: argon2::Error) -> Self {
+        PasswordError::Params(error)
+    }
+}
\ No newline at end of file
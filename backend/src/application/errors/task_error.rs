@@ -6,23 +6,25 @@
 //! * `TokenCreationError` - トークン作成に関するエラー
 //! * `ValidationError`    - 入力値バリデーションに関するエラー
 //! * `UserNotFound`       - ユーザーが見つからないエラー
+//! * `TaskNotFound`       - タスクが見つからない、または他ユーザーのタスクであるエラー
 
 use std::fmt;
 use bb8_postgres::bb8;
 use tokio_postgres;
-use argon2;
+use crate::application::errors::password_error::PasswordError;
 use jsonwebtoken;
 
 use super::user_error::UserError;
 
 #[derive(Debug)]
 pub enum TaskError {
-    DatabaseError(String),
+    DatabaseError(tokio_postgres::Error),
     PoolError(bb8::RunError<tokio_postgres::Error>),
-    HashingError(argon2::password_hash::Error),
+    HashingError(PasswordError),
     TokenCreationError(jsonwebtoken::errors::Error),
     ValidationError(validator::ValidationErrors),
     UserNotFound,
+    TaskNotFound,
 }
 
 impl fmt::Display for TaskError {
@@ -34,6 +36,7 @@ impl fmt::Display for TaskError {
             TaskError::TokenCreationError(err) => write!(f, "JWT error: {}", err),
             TaskError::ValidationError(err) => write!(f, "Validation error: {}", err),
             TaskError::UserNotFound => write!(f, "User not found"),
+            TaskError::TaskNotFound => write!(f, "Task not found"),
         }
     }
 }
@@ -42,7 +45,7 @@ impl std::error::Error for TaskError {}
 
 impl From<tokio_postgres::Error> for TaskError {
     fn from(error: tokio_postgres::Error) -> Self {
-        TaskError::DatabaseError(error.to_string())
+        TaskError::DatabaseError(error)
     }
 }
 
@@ -52,8 +55,8 @@ impl From<bb8::RunError<tokio_postgres::Error>> for TaskError {
     }
 }
 
-impl From<argon2::password_hash::Error> for TaskError {
-    fn from(error: argon2::password_hash::Error) -> Self {
+impl From<PasswordError> for TaskError {
+    fn from(error: PasswordError) -> Self {
         TaskError::HashingError(error)
     }
 }
@@ -67,12 +70,15 @@ impl From<jsonwebtoken::errors::Error> for TaskError {
 impl From<UserError> for TaskError {
     fn from(error: UserError) -> Self {
         match error {
-            UserError::DatabaseError(err) => TaskError::DatabaseError(err.to_string()),
+            UserError::DatabaseError(err) => TaskError::DatabaseError(err),
             UserError::PoolError(err) => TaskError::PoolError(err),
             UserError::HashingError(err) => TaskError::HashingError(err),
             UserError::TokenCreationError(err) => TaskError::TokenCreationError(err),
             UserError::ValidationError(err) => TaskError::ValidationError(err),
-            UserError::UserNotFound => TaskError::UserNotFound,
+            UserError::UserNotFound
+            | UserError::InvalidCredentials
+            | UserError::CannotModifySelf
+            | UserError::LoginGuardError(_) => TaskError::UserNotFound,
         }
     }
 }
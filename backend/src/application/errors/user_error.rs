@@ -6,21 +6,28 @@
 //! * `TokenCreationError` - トークン作成に関するエラー
 //! * `ValidationError`    - 入力値バリデーションに関するエラー
 //! * `UserNotFound`       - ユーザーが見つからないエラー
+//! * `InvalidCredentials` - 現在のパスワードが正しくないエラー
+//! * `CannotModifySelf`   - 管理者が自分自身を削除・ロール変更しようとしたエラー
+//! * `LoginGuardError`    - ログイン保護（パスワードの試行回数の制限・ロック）のエラー
 
 use std::fmt;
 use bb8_postgres::bb8;
 use tokio_postgres;
-use argon2;
+use crate::application::errors::auth_error::AuthError;
+use crate::application::errors::password_error::PasswordError;
 use jsonwebtoken;
 
 #[derive(Debug)]
 pub enum UserError {
     DatabaseError(tokio_postgres::Error),
     PoolError(bb8::RunError<tokio_postgres::Error>),
-    HashingError(argon2::password_hash::Error),
+    HashingError(PasswordError),
     TokenCreationError(jsonwebtoken::errors::Error),
     ValidationError(validator::ValidationErrors),
     UserNotFound,
+    InvalidCredentials,
+    CannotModifySelf,
+    LoginGuardError(AuthError),
 }
 
 impl fmt::Display for UserError {
@@ -32,6 +39,9 @@ impl fmt::Display for UserError {
             UserError::TokenCreationError(err) => write!(f, "JWT error: {}", err),
             UserError::ValidationError(err) => write!(f, "Validation error: {}", err),
             UserError::UserNotFound => write!(f, "User not found"),
+            UserError::InvalidCredentials => write!(f, "Invalid credentials"),
+            UserError::CannotModifySelf => write!(f, "Cannot modify your own account"),
+            UserError::LoginGuardError(err) => write!(f, "{}", err),
         }
     }
 }
@@ -50,8 +60,8 @@ impl From<bb8::RunError<tokio_postgres::Error>> for UserError {
     }
 }
 
-impl From<argon2::password_hash::Error> for UserError {
-    fn from(error: argon2::password_hash::Error) -> Self {
+impl From<PasswordError> for UserError {
+    fn from(error: PasswordError) -> Self {
         UserError::HashingError(error)
     }
 }
@@ -68,6 +78,12 @@ impl From<validator::ValidationErrors> for UserError {
     }
 }
 
+impl From<AuthError> for UserError {
+    fn from(error: AuthError) -> Self {
+        UserError::LoginGuardError(error)
+    }
+}
+
 impl From<()> for UserError {
     fn from(_: ()) -> Self {
         UserError::UserNotFound
@@ -1,12 +1,36 @@
 use actix_web::cookie::{time, Cookie, SameSite};
 
+use crate::application::helpers::token::refresh_token_ttl;
+use crate::application::jwt::jwt::access_token_ttl;
+use crate::infrastructure::config::app_config;
+
+/// リフレッシュトークンのクッキーを送信するパス（認証 API のみ）
+const REFRESH_COOKIE_PATH: &str = "/api/v1/auth";
+
+/// リフレッシュトークンのクッキーの `SameSite`
+///
+/// 他のサイトからのリクエストでトークンを再発行できないよう、同じサイトからのリクエストのみ送信する
+const REFRESH_COOKIE_SAME_SITE: SameSite = SameSite::Strict;
+
+/// OpenID Connect の認可リクエストを開始したブラウザーを確認するクッキーの名前
+pub const OIDC_STATE_COOKIE: &str = "oidc_state";
+
+/// OpenID Connect のクッキーを送信するパス（認可リクエスト・コールバック・連携の API のみ）
+const OIDC_STATE_COOKIE_PATH: &str = "/api/v1/auth/oidc";
+
+/// アクセストークンのクッキーの `SameSite`
+///
+/// 他のサイトからのフォーム送信・`fetch` にはクッキーを送信しない（リンクからの遷移のみ送信する）
+/// 同じサイトの別オリジンからの更新系のリクエストは `jwt::verify` の `Origin` の確認で拒否する
+const TOKEN_COOKIE_SAME_SITE: SameSite = SameSite::Lax;
+
 pub fn create_cookie(token: String) -> Cookie<'static> {
     Cookie::build("token", token)
         .path("/")
         .http_only(true)
-        .same_site(SameSite::None)
+        .same_site(TOKEN_COOKIE_SAME_SITE)
         .secure(true)
-        .max_age(time::Duration::days(30))
+        .max_age(time::Duration::seconds(access_token_ttl().as_secs() as i64))
         .finish()
 }
 
@@ -14,7 +38,51 @@ pub fn clear_cookie() -> Cookie<'static> {
     Cookie::build("token", "")
         .path("/")
         .http_only(true)
-        .same_site(SameSite::None)
+        .same_site(TOKEN_COOKIE_SAME_SITE)
+        .secure(true)
+        .max_age(time::Duration::seconds(0))
+        .finish()
+}
+
+pub fn create_refresh_cookie(token: String) -> Cookie<'static> {
+    Cookie::build("refresh_token", token)
+        .path(REFRESH_COOKIE_PATH)
+        .http_only(true)
+        .same_site(REFRESH_COOKIE_SAME_SITE)
+        .secure(true)
+        .max_age(time::Duration::seconds(refresh_token_ttl().num_seconds()))
+        .finish()
+}
+
+pub fn clear_refresh_cookie() -> Cookie<'static> {
+    Cookie::build("refresh_token", "")
+        .path(REFRESH_COOKIE_PATH)
+        .http_only(true)
+        .same_site(REFRESH_COOKIE_SAME_SITE)
+        .secure(true)
+        .max_age(time::Duration::seconds(0))
+        .finish()
+}
+
+/// 認可リクエストを開始したブラウザーのクッキー
+///
+/// コールバックで `state` と照合し、他のブラウザーで開始したログインを完了できないようにする（ログイン CSRF 対策）
+/// 有効期限は `state` と同じ（`oidc.state_ttl_secs`）
+pub fn create_oidc_state_cookie(binding: String) -> Cookie<'static> {
+    Cookie::build(OIDC_STATE_COOKIE, binding)
+        .path(OIDC_STATE_COOKIE_PATH)
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .secure(true)
+        .max_age(time::Duration::seconds(app_config::current().oidc.state_ttl_secs))
+        .finish()
+}
+
+pub fn clear_oidc_state_cookie() -> Cookie<'static> {
+    Cookie::build(OIDC_STATE_COOKIE, "")
+        .path(OIDC_STATE_COOKIE_PATH)
+        .http_only(true)
+        .same_site(SameSite::Lax)
         .secure(true)
         .max_age(time::Duration::seconds(0))
         .finish()
@@ -0,0 +1,23 @@
+//! # フロントエンドのリンク
+//!
+//! ## 関数
+//!
+//! - `app_link`: フロントエンドの URL（`[app]` の `url`）のパスのリンクを作成
+
+use crate::infrastructure::config::app_config;
+
+/// フロントエンドのリンクを作成
+///
+/// # 引数
+///
+/// * `path`  - パス
+/// * `param` - パスパラメータ（トークン・ID など）
+///
+/// # 戻り値
+///
+/// * `String` - `{url}/{path}/{param}`
+pub fn app_link(path: &str, param: &str) -> String {
+    let app_url = &app_config::current().app.url;
+
+    format!("{}/{}/{}", app_url.trim_end_matches('/'), path, param)
+}
\ No newline at end of file
@@ -1,77 +1,115 @@
 //! # カスタムロガー
 //! 
-//! ヘッダーにステータスを付与してログを出力
+//! `tracing` のイベントとしてログを出力
+//! 標準出力とログファイルへの書き込みは別スレッドで行い（`tracing_appender::non_blocking`）、リクエストの処理を待たせない
+//! 
+//! * 形式: JSON（1行1イベント）。標準出力は `LOG_FORMAT=text` で人が読みやすい形式にできる
+//! * レベル: `RUST_LOG` でモジュールごとに指定（マクロは呼び出し元のモジュールをターゲットとする）
+//! * リクエストID: `RequestIdMiddleware` のスパンの `request_id` をリクエスト中の全てのログに付与
+//! * ローテーション: `RollingFileWriter` でサイズ・日時ごとにローテーションし、保持数を超えたファイルは削除
+//! * マスク: 出力前にパスワード・トークンなどの秘匿情報を `[REDACTED]` に置き換える
 //! 
 //! ## 関数
 //! 
-//! - `log`: ログ出力
+//! - `init`: ロガーの初期化
 //! 
 //! ## マクロ
 //! 
 //! - `app_log`:     以下ログを出力するための土台　※直接的には使用しない
-//! - `success_log`: 成功ログ
+//! - `success_log`: 成功ログ（`INFO` レベル、`outcome = "success"`）
 //! - `info_log`:    情報ログ
 //! - `warning_log`: 警告ログ
 //! - `error_log`:   エラーログ
 
-use chrono::Local;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::Path;
-use lazy_static::lazy_static;
-
-use crate::PROJECT_PATH;
+use std::io;
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter, Layer, Registry};
 
-lazy_static! {
-    pub static ref LOG_PATH: String = format!("{}/log/actix.log", PROJECT_PATH);
-}
+use crate::infrastructure::config::log_config::{LogConfig, LogFormat};
+use crate::infrastructure::logging::{redacting_writer::RedactingWriter, rolling_file::RollingFileWriter};
 
-/// Header 列挙子
-#[allow(dead_code)]
-pub enum Header {
-    SUCCESS,
-    INFO,
-    WARNING,
-    ERROR
+/// JSON 形式の出力
+///
+/// イベントのフィールドはトップレベルに、リクエストのスパンのフィールド（`request_id` など）は `span` に出力する
+fn json_layer(writer: NonBlocking) -> Box<dyn Layer<Registry> + Send + Sync> {
+    fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+        .with_writer(writer)
+        .boxed()
 }
 
-/// log 関数
+/// ロガーの初期化
+/// 
+/// ログファイルを開けない場合は標準出力のみに出力する（起動は継続する）
 /// 
 /// # 引数
 /// 
-/// * `header`  - `Header` 列挙子
-/// * `message` - `&str`   出力するメッセージ
-pub fn log(header: Header, message: &str) {
-    let header = match header {
-        Header::SUCCESS => "SUCCESS",
-        Header::INFO => "INFO",
-        Header::WARNING => "WARNING",
-        Header::ERROR => "ERROR"
+/// * `config` - ログ設定
+/// 
+/// # 戻り値
+/// 
+/// * `Vec<WorkerGuard>` - 書き込みスレッドのガード。破棄すると未出力のログを書き込んで終了するため、`main` の終了まで保持する
+pub fn init(config: &LogConfig) -> Vec<WorkerGuard> {
+    let mut guards = Vec::new();
+
+    let (filter, filter_error) = match EnvFilter::try_new(&config.filter) {
+        Ok(filter) => (filter, None),
+        Err(err) => (EnvFilter::new("info"), Some(err.to_string())),
     };
 
-    println!("[{}] {} {}", Local::now().format("%m-%d-%Y %H:%M:%S").to_string(), header, message);
+    let (stdout, guard) = tracing_appender::non_blocking(RedactingWriter::new(io::stdout()));
+    guards.push(guard);
+    let mut layers = vec![match config.format {
+        LogFormat::Json => json_layer(stdout),
+        LogFormat::Text => fmt::layer().with_writer(stdout).boxed(),
+    }];
 
-    if Path::new(&*LOG_PATH).exists() {
-        // ログファイルが存在する場合は、そのファイルにログを出力
-        let mut log_file = OpenOptions::new().append(true).open(&*LOG_PATH).unwrap();
-        writeln!(log_file, "[{}] {} {}", Local::now().format("%m-%d-%Y %H:%M:%S").to_string(), header, message).unwrap();
-    } else {
-        // ログファイルが存在しない場合は、ファイルを生成しログを出力
-        let mut log_file = OpenOptions::new().create_new(true).append(true).open(&*LOG_PATH).unwrap();
-        writeln!(log_file, "[{}] {} {}", Local::now().format("%m-%d-%Y %H:%M:%S").to_string(), header, message).unwrap();
+    let file_error = match config.file.clone().map(RollingFileWriter::open) {
+        Some(Ok(writer)) => {
+            let (file, guard) = tracing_appender::non_blocking(RedactingWriter::new(writer));
+            guards.push(guard);
+            layers.push(json_layer(file));
+            None
+        }
+        Some(Err(err)) => Some(err.to_string()),
+        None => None,
+    };
+
+    // `log` クレートのログ（actix-web など）も `tracing` のイベントとして出力する
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .init();
+
+    if let Some(err) = filter_error {
+        tracing::warn!("invalid RUST_LOG, falling back to `info`: {}", err);
+    }
+    if let Some(err) = file_error {
+        tracing::warn!("failed to open log file, logging to stdout only: {}", err);
     }
+
+    guards
 }
 
 #[macro_export]
 macro_rules! app_log {
+    // 結果のフィールドあり
+    (@outcome $outcome:expr, $level:expr, $msg:expr, $($arg:tt)*) => {
+        tracing::event!($level, outcome = $outcome, $msg, $($arg)*)
+    };
+    (@outcome $outcome:expr, $level:expr, $msg:expr) => {
+        tracing::event!($level, outcome = $outcome, "{}", $msg)
+    };
     // 引数あり
-    ($header:expr, $msg:expr, $($arg:tt)*) => {
-        let formatted_message = format!($msg, $($arg)*);
-        crate::application::helpers::logger::log($header, &formatted_message);
+    ($level:expr, $msg:expr, $($arg:tt)*) => {
+        tracing::event!($level, $msg, $($arg)*)
     };
     // 引数なし
-    ($header:expr, $msg:expr) => {
-        crate::application::helpers::logger::log($header, $msg);
+    ($level:expr, $msg:expr) => {
+        tracing::event!($level, "{}", $msg)
     };
 }
 
@@ -79,11 +117,11 @@ macro_rules! app_log {
 macro_rules! success_log {
     // 引数あり
     ($msg:expr, $($arg:tt)*) => {
-        app_log!(crate::application::helpers::logger::Header::SUCCESS, $msg, $($arg)*);
+        app_log!(@outcome "success", tracing::Level::INFO, $msg, $($arg)*)
     };
     // 引数なし
     ($msg:expr) => {
-        app_log!(crate::application::helpers::logger::Header::SUCCESS, $msg);
+        app_log!(@outcome "success", tracing::Level::INFO, $msg)
     }
 }
 
@@ -91,11 +129,11 @@ macro_rules! success_log {
 macro_rules! info_log {
     // 引数あり
     ($msg:expr, $($arg:tt)*) => {
-        app_log!(crate::application::helpers::logger::Header::INFO, $msg, $($arg)*);
+        app_log!(tracing::Level::INFO, $msg, $($arg)*)
     };
     // 引数なし
     ($msg:expr) => {
-        app_log!(crate::application::helpers::logger::Header::INFO, $msg);
+        app_log!(tracing::Level::INFO, $msg)
     };
 }
 
@@ -103,11 +141,11 @@ macro_rules! info_log {
 macro_rules! warning_log {
     // 引数あり
     ($msg:expr, $($arg:tt)*) => {
-        app_log!(crate::application::helpers::logger::Header::WARNING, $msg, $($arg)*);
+        app_log!(tracing::Level::WARN, $msg, $($arg)*)
     };
     // 引数なし
     ($msg:expr) => {
-        app_log!(crate::application::helpers::logger::Header::WARNING, $msg);
+        app_log!(tracing::Level::WARN, $msg)
     };
 }
 
@@ -115,10 +153,10 @@ macro_rules! warning_log {
 macro_rules! error_log {
     // 引数あり
     ($msg:expr, $($arg:tt)*) => {
-        app_log!(crate::application::helpers::logger::Header::ERROR, $msg, $($arg)*);
+        app_log!(tracing::Level::ERROR, $msg, $($arg)*)
     };
     // 引数なし
     ($msg:expr) => {
-        app_log!(crate::application::helpers::logger::Header::ERROR, $msg);
+        app_log!(tracing::Level::ERROR, $msg)
     };
 }
\ No newline at end of file
@@ -1,4 +1,9 @@
 pub mod cookie;
+pub mod link;
 pub mod logger;
-pub mod message;
+pub mod password;
+pub mod redact;
+pub mod request;
+pub mod token;
+pub mod totp;
 pub mod validator;
\ No newline at end of file
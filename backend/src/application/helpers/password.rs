@@ -0,0 +1,145 @@
+//! # パスワード
+//!
+//! パスワードのハッシュ化と検証
+//!
+//! 新しいハッシュは argon2id（`AppConfig` の `[password]` のコスト）で作成し、
+//! 検証は以前の形式（bcrypt・コストの異なる argon2）のハッシュにも対応する
+//! 以前の形式のハッシュはログインに成功した時に `needs_rehash` で判定し、現在の設定で作り直す
+//!
+//! ## メソッド
+//!
+//! - `from_settings`: `[password]` の設定から作成
+//! - `hash`:          パスワードをハッシュ化
+//! - `verify`:        パスワードがハッシュと一致するか検証
+//! - `needs_rehash`:  ハッシュを現在の設定で作り直す必要があるか
+//! - `dummy_hash`:    未登録のユーザーの検証に使用するハッシュ
+
+use std::sync::OnceLock;
+use argon2::{Algorithm, Argon2, Params, Version};
+use argon2::password_hash::{
+    rand_core::OsRng,
+    PasswordHash,
+    PasswordHasher as _,
+    PasswordVerifier,
+    SaltString
+};
+
+use crate::application::errors::password_error::PasswordError;
+use crate::infrastructure::config::app_config::PasswordSettings;
+
+/// ハッシュの形式
+///
+/// * `Argon2` - PHC 形式の argon2（`$argon2id$` / `$argon2i$` / `$argon2d$`）
+/// * `Bcrypt` - bcrypt（`$2a$` / `$2b$` / `$2x$` / `$2y$`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordScheme {
+    Argon2,
+    Bcrypt,
+}
+
+impl PasswordScheme {
+    /// ハッシュの接頭辞から形式を判定
+    pub fn detect(hash: &str) -> Option<PasswordScheme> {
+        if hash.starts_with("$argon2") {
+            Some(PasswordScheme::Argon2)
+        } else if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+            Some(PasswordScheme::Bcrypt)
+        } else {
+            None
+        }
+    }
+}
+
+/// パスワードのハッシュ化と検証
+///
+/// * `params`     - 新しいハッシュに使用する argon2id のコスト
+/// * `dummy_hash` - 未登録のユーザーの検証に使用するハッシュ（最初のログインで一度だけ作成）
+pub struct PasswordHasher {
+    params: Params,
+    dummy_hash: OnceLock<String>,
+}
+
+impl PasswordHasher {
+    /// `AppConfig` の `[password]` から作成
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<Self, PasswordError>` - コストの組み合わせが argon2 の範囲外の場合はエラー
+    pub fn from_settings(settings: &PasswordSettings) -> Result<Self, PasswordError> {
+        let params = Params::new(settings.argon2_memory_kib, settings.argon2_iterations, settings.argon2_parallelism, None)?;
+
+        Ok(PasswordHasher { params, dummy_hash: OnceLock::new() })
+    }
+
+    /// パスワードをハッシュ化
+    ///
+    /// # 引数
+    ///
+    /// * `password` - パスワード
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<String, PasswordError>` - PHC 形式の argon2id のハッシュ
+    pub fn hash(&self, password: &str) -> Result<String, PasswordError> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        Ok(self.argon2().hash_password(password.as_bytes(), &salt)?.to_string())
+    }
+
+    /// パスワードがハッシュと一致するか検証
+    ///
+    /// argon2 のハッシュは、ハッシュに含まれるアルゴリズム・コストで検証する
+    ///
+    /// # 引数
+    ///
+    /// * `password` - パスワード
+    /// * `hash`     - 保存されているハッシュ
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<bool, PasswordError>` - 一致する場合は `true`（ハッシュの形式が不正な場合はエラー）
+    pub fn verify(&self, password: &str, hash: &str) -> Result<bool, PasswordError> {
+        match PasswordScheme::detect(hash) {
+            Some(PasswordScheme::Argon2) => {
+                let parsed_hash = PasswordHash::new(hash)?;
+
+                Ok(self.argon2().verify_password(password.as_bytes(), &parsed_hash).is_ok())
+            }
+            Some(PasswordScheme::Bcrypt) => Ok(bcrypt::verify(password, hash)?),
+            None => Err(PasswordError::UnsupportedScheme),
+        }
+    }
+
+    /// ハッシュを現在の設定で作り直す必要があるか
+    ///
+    /// argon2id 以外の形式、または現在の設定とコストが異なる場合は `true`
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed_hash) = PasswordHash::new(hash) else {
+            return true;
+        };
+        let Ok(params) = Params::try_from(&parsed_hash) else {
+            return true;
+        };
+
+        parsed_hash.algorithm != Algorithm::Argon2id.ident()
+            || parsed_hash.version != Some(Version::V0x13.into())
+            || params.m_cost() != self.params.m_cost()
+            || params.t_cost() != self.params.t_cost()
+            || params.p_cost() != self.params.p_cost()
+    }
+
+    /// 未登録のユーザーの検証に使用するハッシュ
+    ///
+    /// 登録済みのユーザーと同じ設定でハッシュ化しているため、検証にかかる時間が同じになり、
+    /// 応答時間からメールアドレスが登録済みかどうかを推測できない
+    pub fn dummy_hash(&self) -> &str {
+        self.dummy_hash.get_or_init(|| {
+            self.hash(SaltString::generate(&mut OsRng).as_ref())
+                .expect("ダミーのパスワードハッシュの作成に失敗しました")
+        })
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+//! # リクエスト
+//! 
+//! HTTP リクエストから認証に必要な情報を抽出
+//! 
+//! ## 関数
+//! 
+//! - `client_ip`:     接続元の IP アドレスを抽出
+//! - `session_meta`:  ログイン端末の情報を抽出
+//! - `refresh_token`: リフレッシュトークンを抽出
+
+use actix_web::HttpRequest;
+use actix_web::http::header::{HeaderMap, USER_AGENT};
+
+use crate::domain::entities::session::SessionMeta;
+use crate::infrastructure::config::app_config;
+
+/// 保存する User-Agent の最大文字数
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// 接続元の IP アドレスを抽出
+/// 
+/// `server.trust_proxy_headers` が `true` の場合は、リバースプロキシが設定した `X-Real-IP`、
+/// または `X-Forwarded-For` の最後の値（プロキシが追加した値）を使用する
+/// クライアントが送信した `X-Forwarded-For` の先頭の値は偽装できるため使用しない
+/// 
+/// # 引数
+/// 
+/// * `req` - リクエスト
+/// 
+/// # 戻り値
+/// 
+/// * `Option<String>` - IP アドレス（ポート番号を除く）
+pub fn client_ip(req: &HttpRequest) -> Option<String> {
+    if app_config::current().server.trust_proxy_headers {
+        if let Some(ip) = forwarded_ip(req.headers()) {
+            return Some(ip);
+        }
+    }
+
+    req.peer_addr().map(|addr| addr.ip().to_string())
+}
+
+/// リバースプロキシが設定した接続元の IP アドレス
+fn forwarded_ip(headers: &HeaderMap) -> Option<String> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    header("x-real-ip")
+        .map(str::trim)
+        .or_else(|| header("x-forwarded-for").and_then(|value| value.rsplit(',').next()).map(str::trim))
+        .filter(|ip| !ip.is_empty())
+        .map(str::to_string)
+}
+
+/// ログイン端末の情報を抽出
+/// 
+/// # 引数
+/// 
+/// * `req` - リクエスト
+/// 
+/// # 戻り値
+/// 
+/// * `SessionMeta` - User-Agent と接続元の IP アドレス
+pub fn session_meta(req: &HttpRequest) -> SessionMeta {
+    let user_agent = req.headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.chars().take(MAX_USER_AGENT_LEN).collect());
+
+    SessionMeta { user_agent, ip_address: client_ip(req) }
+}
+
+/// リフレッシュトークンを抽出
+/// 
+/// リクエストボディに指定がない場合は、クッキーから取得する
+/// 
+/// # 引数
+/// 
+/// * `req`  - リクエスト
+/// * `body` - リクエストボディのリフレッシュトークン
+/// 
+/// # 戻り値
+/// 
+/// * `Option<String>` - リフレッシュトークン
+pub fn refresh_token(req: &HttpRequest, body: Option<String>) -> Option<String> {
+    body.filter(|token| !token.is_empty())
+        .or_else(|| req.cookie("refresh_token").map(|cookie| cookie.value().to_string()))
+        .filter(|token| !token.is_empty())
+}
\ No newline at end of file
@@ -0,0 +1,106 @@
+//! # ワンタイムトークン
+//! 
+//! メール認証・パスワードリセット・リフレッシュトークン等で使用するランダムなトークンを生成
+//! DB にはトークンそのものではなく SHA-256 ハッシュを保存する
+//! 
+//! ## 関数
+//! 
+//! - `generate_token`:         トークンとハッシュを生成
+//! - `hash_token`:             トークンのハッシュを計算
+//! - `refresh_token_ttl`:      リフレッシュトークン（セッション）の有効期限
+//! - `generate_recovery_code`: 2段階認証のリカバリーコードとハッシュを生成
+//! - `hash_recovery_code`:     入力されたリカバリーコードのハッシュを計算
+
+use chrono::Duration;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+
+use crate::infrastructure::config::app_config;
+
+/// トークンのバイト数
+const TOKEN_BYTES: usize = 32;
+
+/// リカバリーコードの文字（読み間違えやすい `0` / `1` / `o` / `l` などを含まない Base32 の小文字）
+const RECOVERY_CODE_ALPHABET: &[u8; 32] = b"abcdefghijkmnpqrstuvwxyz23456789";
+
+/// リカバリーコードの区切りごとの文字数と区切りの数（`xxxx-xxxx-xxxx`）
+const RECOVERY_CODE_GROUP: usize = 4;
+const RECOVERY_CODE_GROUPS: usize = 3;
+
+/// トークンを生成
+/// 
+/// # 戻り値
+/// 
+/// * `(String, String)` - メールで送信するトークンと、DB に保存するハッシュ
+pub fn generate_token() -> (String, String) {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let hash = hash_token(&token);
+
+    (token, hash)
+}
+
+/// トークンのハッシュを計算
+/// 
+/// # 引数
+/// 
+/// * `token` - トークン
+/// 
+/// # 戻り値
+/// 
+/// * `String` - 16進数表記の SHA-256 ハッシュ
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// リフレッシュトークン（セッション）の有効期限
+///
+/// `[jwt]` の `refresh_token_ttl_days`（既定 30 日）
+pub fn refresh_token_ttl() -> Duration {
+    Duration::days(app_config::current().jwt.refresh_token_ttl_days)
+}
+
+/// 2段階認証のリカバリーコードを生成
+///
+/// 認証アプリを使用できない場合に、TOTP のコードの代わりに一度だけ使用できる
+///
+/// # 戻り値
+///
+/// * `(String, String)` - ユーザーに表示するコード（`xxxx-xxxx-xxxx`）と、DB に保存するハッシュ
+pub fn generate_recovery_code() -> (String, String) {
+    let mut bytes = [0u8; RECOVERY_CODE_GROUP * RECOVERY_CODE_GROUPS];
+    OsRng.fill_bytes(&mut bytes);
+
+    let code = bytes
+        .chunks(RECOVERY_CODE_GROUP)
+        .map(|group| group.iter().map(|b| RECOVERY_CODE_ALPHABET[(b & 0x1f) as usize] as char).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("-");
+    let hash = hash_recovery_code(&code);
+
+    (code, hash)
+}
+
+/// 入力されたリカバリーコードのハッシュを計算
+///
+/// 区切り・空白・大文字と小文字の違いは無視する
+///
+/// # 引数
+///
+/// * `code` - リカバリーコード
+///
+/// # 戻り値
+///
+/// * `String` - 16進数表記の SHA-256 ハッシュ
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    hash_token(&normalized)
+}
\ No newline at end of file
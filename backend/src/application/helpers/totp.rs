@@ -0,0 +1,185 @@
+//! # ワンタイムパスワード (TOTP)
+//!
+//! RFC 6238 の TOTP（HMAC-SHA1・6桁・30秒）で2段階認証のコードを生成・検証
+//! 認証アプリ（Google Authenticator など）の既定の設定と同じ
+//!
+//! ## 関数
+//!
+//! - `generate_secret`: 共有シークレット（Base32）を生成
+//! - `otpauth_uri`:     認証アプリに登録する `otpauth://` URI
+//! - `totp_code`:       指定したステップのコード
+//! - `verify_code`:     コードを検証し、一致したステップを返す
+//! - `is_totp_code`:    TOTP のコードの形式か（リカバリーコードと区別する）
+//! - `base32_encode`:   Base32（RFC 4648・パディングなし）にエンコード
+//! - `base32_decode`:   Base32 をデコード
+
+use rand::RngCore;
+use rand::rngs::OsRng;
+use ring::constant_time::verify_slices_are_equal;
+use ring::hmac;
+
+/// コードの桁数
+pub const DIGITS: usize = 6;
+
+/// コードが切り替わる間隔（秒）
+pub const PERIOD_SECS: i64 = 30;
+
+/// 前後に許容するステップ数（端末の時刻のずれ）
+pub const SKEW_STEPS: i64 = 1;
+
+/// シークレットのバイト数（RFC 4226 の推奨値）
+const SECRET_BYTES: usize = 20;
+
+/// Base32 の文字
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// 共有シークレットを生成
+///
+/// # 戻り値
+///
+/// * `String` - Base32 のシークレット（認証アプリへの手入力にも使用）
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    base32_encode(&bytes)
+}
+
+/// 認証アプリに登録する `otpauth://` URI
+///
+/// QR コードにして認証アプリで読み取る
+///
+/// # 引数
+///
+/// * `issuer`  - 発行者（認証アプリに表示するサービス名）
+/// * `account` - アカウント名（メールアドレス）
+/// * `secret`  - Base32 のシークレット
+///
+/// # 戻り値
+///
+/// * `String` - `otpauth://totp/{issuer}:{account}?secret=...&issuer=...`
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        PERIOD_SECS
+    )
+}
+
+/// 指定したステップのコード
+///
+/// # 引数
+///
+/// * `secret` - シークレット（デコード済み）
+/// * `step`   - UNIX 時刻を `PERIOD_SECS` で割った値
+///
+/// # 戻り値
+///
+/// * `String` - 先頭を 0 で埋めた `DIGITS` 桁のコード
+pub fn totp_code(secret: &[u8], step: i64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let digest = hmac::sign(&key, &step.to_be_bytes());
+    let digest = digest.as_ref();
+
+    // 動的切り捨て（RFC 4226 5.3）
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+
+    format!("{:0width$}", binary % 10u32.pow(DIGITS as u32), width = DIGITS)
+}
+
+/// コードを検証
+///
+/// 前後 `SKEW_STEPS` ステップのコードも受け付ける
+/// 同じコードを再利用できないよう、呼び出し元で一致したステップを記録し、それ以前のステップを拒否する
+///
+/// # 引数
+///
+/// * `secret` - Base32 のシークレット
+/// * `code`   - 入力されたコード
+/// * `now`    - 現在の UNIX 時刻（秒）
+///
+/// # 戻り値
+///
+/// * `Option<i64>` - 一致したステップ（一致しない場合・シークレットが不正な場合は `None`）
+pub fn verify_code(secret: &str, code: &str, now: i64) -> Option<i64> {
+    let code = code.trim();
+    if !is_totp_code(code) {
+        return None;
+    }
+    let secret = base32_decode(secret)?;
+    let current = now.div_euclid(PERIOD_SECS);
+
+    // 一致したかどうかで処理時間が変わらないよう、全てのステップを比較する
+    let mut matched = None;
+    for step in (current - SKEW_STEPS)..=(current + SKEW_STEPS) {
+        if verify_slices_are_equal(totp_code(&secret, step).as_bytes(), code.as_bytes()).is_ok() && matched.is_none() {
+            matched = Some(step);
+        }
+    }
+
+    matched
+}
+
+/// TOTP のコードの形式か（`DIGITS` 桁の数字）
+pub fn is_totp_code(code: &str) -> bool {
+    code.len() == DIGITS && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Base32（RFC 4648・パディングなし）にエンコード
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+/// Base32 をデコード
+///
+/// 大文字・小文字、空白・パディング（`=`）は無視する
+///
+/// # 戻り値
+///
+/// * `Option<Vec<u8>>` - デコードしたバイト列（Base32 以外の文字を含む場合は `None`）
+pub fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET.iter().position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+/// URI のパス・クエリに使用できない文字をエンコード（RFC 3986 の非予約文字以外）
+fn percent_encode(value: &str) -> String {
+    value.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
\ No newline at end of file
@@ -1,5 +1,6 @@
 use validator::ValidationError;
 use regex::Regex;
+use crate::domain::enums::role::Role;
 
 /// Eメールバリデーター
 /// 
@@ -65,5 +66,24 @@ pub fn validate_password(password: &str) -> Result<(), ValidationError> {
         return Err(ValidationError::new("password_no_uppercase"));
     }
 
+    Ok(())
+}
+
+/// ロール変更のバリデーター
+///
+/// ゲストはゲストログインで作成したユーザーのみのロールのため、変更先に指定できない
+///
+/// # 引数
+///
+/// * `role` - 変更先のロール
+///
+/// # 戻り値
+///
+/// なし
+pub fn validate_assignable_role(role: &Role) -> Result<(), ValidationError> {
+    if *role == Role::Guest {
+        return Err(ValidationError::new("role_not_assignable"));
+    }
+
     Ok(())
 }
\ No newline at end of file
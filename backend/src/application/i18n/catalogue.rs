@@ -0,0 +1,242 @@
+//! # メッセージカタログ
+//!
+//! API のレスポンス・メールの件名を言語ごとのリソースファイル（`locales/{locale}.json`）から取得
+//! メールの本文は `application::mail::template` のテンプレートファイルで管理する
+//!
+//! ## 関数
+//!
+//! - `t`:                    メッセージを取得
+//! - `t_with`:               プレースホルダー（`{name}`）を置換してメッセージを取得
+//! - `replace_placeholders`: プレースホルダー（`{name}`）を置換
+//! - `missing_messages`:     リソースファイルに定義されていないメッセージ
+//!
+//! メッセージのキーは `MessageKey` で指定するため、存在しないキーはコンパイル時に検出される。
+//! リソースファイルはバイナリに埋め込み、起動時に一度だけ読み込む。
+
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+
+use crate::domain::enums::locale::Locale;
+
+/// メッセージのキーを定義
+///
+/// `MessageKey` の列挙子と、リソースファイルのキーの対応を生成する
+macro_rules! message_keys {
+    ($($variant:ident => $key:literal,)*) => {
+        /// メッセージのキー
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum MessageKey {
+            $($variant,)*
+        }
+
+        impl MessageKey {
+            /// 全てのキー
+            pub const ALL: &'static [MessageKey] = &[$(MessageKey::$variant,)*];
+
+            /// リソースファイルのキー
+            pub fn key(&self) -> &'static str {
+                match self {
+                    $(MessageKey::$variant => $key,)*
+                }
+            }
+
+            /// リソースファイルのキーから取得
+            pub fn from_key(key: &str) -> Option<MessageKey> {
+                match key {
+                    $($key => Some(MessageKey::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+message_keys! {
+    ErrorValidationFailed => "error.validation_failed",
+    ErrorBadRequest => "error.bad_request",
+    ErrorUnauthorized => "error.unauthorized",
+    ErrorInvalidCredentials => "error.invalid_credentials",
+    ErrorInvalidToken => "error.invalid_token",
+    ErrorForbidden => "error.forbidden",
+    ErrorEmailNotVerified => "error.email_not_verified",
+    ErrorUserNotFound => "error.user_not_found",
+    ErrorTaskNotFound => "error.task_not_found",
+    ErrorSessionNotFound => "error.session_not_found",
+    ErrorMailNotFound => "error.mail_not_found",
+    ErrorRouteNotFound => "error.route_not_found",
+    ErrorCannotModifySelf => "error.cannot_modify_self",
+    ErrorAlreadyExists => "error.already_exists",
+    ErrorAlreadyVerified => "error.already_verified",
+    ErrorTooManyRequests => "error.too_many_requests",
+    ErrorRateLimited => "error.rate_limited",
+    ErrorInvalidMfaCode => "error.invalid_mfa_code",
+    ErrorMfaAlreadyEnabled => "error.mfa_already_enabled",
+    ErrorMfaNotEnabled => "error.mfa_not_enabled",
+    ErrorPasswordRequired => "error.password_required",
+    ErrorOidcProvider => "error.oidc_provider_error",
+    ErrorOidcProviderNotFound => "error.oidc_provider_not_found",
+    ErrorOidcAccountInUse => "error.oidc_account_in_use",
+    ErrorOidcProviderAlreadyLinked => "error.oidc_provider_already_linked",
+    ErrorOidcEmailRequired => "error.oidc_email_required",
+    ErrorOidcAccountNotFound => "error.oidc_account_not_found",
+    ErrorOidcLastLoginMethod => "error.oidc_last_login_method",
+    ErrorGuestLoginDisabled => "error.guest_login_disabled",
+    ErrorGuestNotAllowed => "error.guest_not_allowed",
+    ErrorNotGuest => "error.not_guest",
+    ErrorInternal => "error.internal_error",
+
+    AuthTokenNotFound => "auth.token_not_found",
+    AuthOriginNotAllowed => "auth.origin_not_allowed",
+    AuthLoggedOut => "auth.logged_out",
+    AuthLoggedOutAll => "auth.logged_out_all",
+    AuthSessionRefreshed => "auth.session_refreshed",
+    AuthVerificationEmailSent => "auth.verification_email_sent",
+    AuthEmailVerified => "auth.email_verified",
+    AuthResetLinkSent => "auth.reset_link_sent",
+    AuthPasswordReset => "auth.password_reset",
+    AuthMfaDisabled => "auth.mfa_disabled",
+    AuthOidcUnlinked => "auth.oidc_unlinked",
+    UserPasswordChanged => "user.password_changed",
+
+    MailVerificationSubject => "mail.verification_subject",
+    MailResetSubject => "mail.reset_subject",
+    MailTaskReminderSubject => "mail.task_reminder_subject",
+
+    ValidationNameLength => "validation.name_length",
+    ValidationEmailInvalid => "validation.email_invalid",
+    ValidationEmailTooLong => "validation.email_too_long",
+    ValidationEmailMissingAtSign => "validation.email_missing_at_sign",
+    ValidationEmailInvalidSpecialCharacters => "validation.email_contains_invalid_special_characters",
+    ValidationEmailMissingDomainSpecialCharacter => "validation.email_missing_special_character_in_domain",
+    ValidationEmailInvalidDomain => "validation.email_invalid_domain",
+    ValidationPasswordTooLong => "validation.password_too_long",
+    ValidationPasswordTooShort => "validation.password_too_short",
+    ValidationPasswordNoDigit => "validation.password_no_digit",
+    ValidationPasswordNoUppercase => "validation.password_no_uppercase",
+    ValidationCurrentPasswordRequired => "validation.current_password_required",
+    ValidationMfaTokenRequired => "validation.mfa_token_required",
+    ValidationMfaCodeRequired => "validation.mfa_code_required",
+    ValidationOidcCodeRequired => "validation.oidc_code_required",
+    ValidationOidcStateRequired => "validation.oidc_state_required",
+    ValidationBioTooLong => "validation.bio_too_long",
+    ValidationPhotoInvalidUrl => "validation.photo_invalid_url",
+    ValidationPhotoTooLong => "validation.photo_too_long",
+    ValidationTitleLength => "validation.title_length",
+    ValidationSearchTooLong => "validation.search_too_long",
+    ValidationPageInvalid => "validation.page_invalid",
+    ValidationPerPageInvalid => "validation.per_page_invalid",
+    ValidationRoleNotAssignable => "validation.role_not_assignable",
+}
+
+/// 言語ごとのリソースファイル
+const RESOURCES: [(Locale, &str); 2] = [
+    (Locale::En, include_str!("../../../locales/en.json")),
+    (Locale::Ja, include_str!("../../../locales/ja.json")),
+];
+
+/// 翻訳がない場合に使用する言語
+const FALLBACK_LOCALE: Locale = Locale::En;
+
+lazy_static! {
+    static ref CATALOGUE: HashMap<Locale, HashMap<String, String>> = RESOURCES
+        .iter()
+        .map(|(locale, resource)| {
+            let messages = serde_json::from_str(resource)
+                .unwrap_or_else(|err| panic!("locales/{}.json の形式が正しくありません: {}", locale.as_str(), err));
+            (*locale, messages)
+        })
+        .collect();
+}
+
+/// メッセージを取得
+///
+/// 指定した言語に翻訳がない場合は英語、英語もない場合はキーを返す
+///
+/// # 引数
+///
+/// * `locale` - 言語
+/// * `key`    - メッセージのキー
+///
+/// # 戻り値
+///
+/// * `String` - メッセージ
+pub fn t(locale: Locale, key: MessageKey) -> String {
+    [locale, FALLBACK_LOCALE]
+        .iter()
+        .find_map(|locale| CATALOGUE.get(locale).and_then(|messages| messages.get(key.key())))
+        .cloned()
+        .unwrap_or_else(|| key.key().to_string())
+}
+
+/// プレースホルダー（`{name}`）を置換してメッセージを取得
+///
+/// # 引数
+///
+/// * `locale` - 言語
+/// * `key`    - メッセージのキー
+/// * `args`   - プレースホルダーの名前と値
+///
+/// # 戻り値
+///
+/// * `String` - メッセージ
+pub fn t_with(locale: Locale, key: MessageKey, args: &[(&str, &str)]) -> String {
+    replace_placeholders(&t(locale, key), args, str::to_string)
+}
+
+/// プレースホルダー（`{name}`）を置換
+///
+/// 一度の走査で全てのプレースホルダーを置換するため、置換した値に含まれる `{name}` は置換しない
+/// `args` にない名前の `{...}` はそのまま残す
+///
+/// # 引数
+///
+/// * `source` - 置換する文字列
+/// * `args`   - プレースホルダーの名前と値
+/// * `encode` - 値の変換（HTML のエスケープなど）
+///
+/// # 戻り値
+///
+/// * `String` - 置換した文字列
+pub fn replace_placeholders(source: &str, args: &[(&str, &str)], encode: fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let placeholder = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            args.iter().find(|(arg, _)| *arg == name).map(|(_, value)| (end, value))
+        });
+
+        match placeholder {
+            Some((end, value)) => {
+                output.push_str(&encode(value));
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// リソースファイルに定義されていないメッセージ
+///
+/// # 戻り値
+///
+/// * `Vec<(Locale, MessageKey)>` - 言語とメッセージのキー
+pub fn missing_messages() -> Vec<(Locale, MessageKey)> {
+    Locale::ALL
+        .iter()
+        .flat_map(|locale| MessageKey::ALL.iter().map(move |key| (*locale, *key)))
+        .filter(|(locale, key)| {
+            CATALOGUE.get(locale).is_none_or(|messages| !messages.contains_key(key.key()))
+        })
+        .collect()
+}
\ No newline at end of file
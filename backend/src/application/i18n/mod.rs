@@ -0,0 +1,2 @@
+pub mod catalogue;
+pub mod request_locale;
\ No newline at end of file
@@ -0,0 +1,92 @@
+//! # リクエストの言語
+//!
+//! ## 関数
+//!
+//! - `default_locale`:       既定の言語
+//! - `negotiate`:            `Accept-Language` から言語を選択
+//! - `scope`:                リクエストの処理中に使用する言語を設定
+//! - `current_locale`:       処理中のリクエストの言語
+//! - `set_current_locale`:   処理中のリクエストの言語を変更（ユーザーの設定を優先する場合）
+//! - `preferred_or_current`: ユーザーの設定、なければ処理中のリクエストの言語
+//!
+//! 言語はリクエストごとに `LocaleMiddleware` で設定し、`ApiError` やメールの文言はここから取得する。
+
+use std::cell::Cell;
+use std::future::Future;
+
+use crate::domain::enums::locale::Locale;
+use crate::infrastructure::config::app_config;
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Cell<Locale>;
+}
+
+/// 既定の言語（`[app]` の `default_locale`、既定は `en`）
+pub fn default_locale() -> Locale {
+    app_config::current().app.default_locale
+}
+
+/// `Accept-Language` から言語を選択
+///
+/// 品質値（`q`）の高い順に、対応している言語のうち最初のものを選択する
+///
+/// # 引数
+///
+/// * `accept_language` - `Accept-Language` ヘッダーの値
+///
+/// # 戻り値
+///
+/// * `Option<Locale>` - 言語（対応している言語がない場合は `None`）
+pub fn negotiate(accept_language: &str) -> Option<Locale> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+            (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+
+    // 同じ品質値の場合は指定順を維持する
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().find_map(|(tag, _)| tag.parse().ok())
+}
+
+/// リクエストの処理中に使用する言語を設定
+///
+/// # 引数
+///
+/// * `locale` - 言語
+/// * `future` - リクエストの処理
+pub async fn scope<F: Future>(locale: Locale, future: F) -> F::Output {
+    REQUEST_LOCALE.scope(Cell::new(locale), future).await
+}
+
+/// 処理中のリクエストの言語
+///
+/// リクエストの処理外（起動時のログなど）では既定の言語を返す
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE.try_with(Cell::get).unwrap_or_else(|_| default_locale())
+}
+
+/// 処理中のリクエストの言語を変更
+///
+/// ログイン中のユーザーが言語を設定している場合、`Accept-Language` より優先する
+pub fn set_current_locale(locale: Locale) {
+    let _ = REQUEST_LOCALE.try_with(|current| current.set(locale));
+}
+
+/// ユーザーの設定、なければ処理中のリクエストの言語
+///
+/// # 引数
+///
+/// * `preference` - ユーザーが設定した言語（DB の `users.locale`）
+pub fn preferred_or_current(preference: Option<&str>) -> Locale {
+    preference
+        .and_then(|locale| locale.parse().ok())
+        .unwrap_or_else(current_locale)
+}
\ No newline at end of file
@@ -0,0 +1,94 @@
+//! # 認証済みユーザー
+//!
+//! ハンドラーの引数で認証済みユーザーの Claims を受け取るエクストラクター
+//!
+//! ## メソッド
+//!
+//! - `authenticate`: トークンを検証し、リクエストに保存
+//! - `into_inner`:   Claims を取り出す
+//!
+//! トークンは `Authorization: Bearer` ヘッダー、または `create_cookie` の `token` クッキーから取得する。
+//! 検証済みの Claims はリクエストの extensions に保存し、同じリクエスト内のミドルウェア・ハンドラーでは再検証しない。
+
+use std::ops::Deref;
+use actix_web::{dev::Payload, web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::LocalBoxFuture;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::i18n::request_locale::set_current_locale;
+use crate::application::jwt::jwt::{self, Claims};
+use crate::application::states::app_state::AppState;
+use crate::{app_log, error_log};
+
+/// 認証済みユーザー
+///
+/// ハンドラーの引数に指定すると、未認証のリクエストには `Unauthorized` を返す。
+/// 認証が任意の API では `Option<AuthenticatedUser>` を指定する。
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser(pub Claims);
+
+impl AuthenticatedUser {
+    /// トークンを検証し、リクエストに保存
+    ///
+    /// 失効したセッション、パスワード・ロール変更前に発行されたトークンは無効
+    /// ユーザーが言語を設定している場合は、リクエストの言語をその言語に変更する
+    ///
+    /// # 引数
+    ///
+    /// * `req` - リクエスト
+    ///
+    /// # 戻り値
+    ///
+    /// * `Option<AuthenticatedUser>` - 認証済みユーザー（未認証の場合は `None`）
+    pub async fn authenticate(req: &HttpRequest) -> Option<AuthenticatedUser> {
+        if let Some(user) = req.extensions().get::<AuthenticatedUser>() {
+            return Some(user.clone());
+        }
+
+        let claims = jwt::verify(req).ok()?;
+        let app_state = req.app_data::<web::Data<AppState>>()?;
+
+        match app_state.auth_service.find_valid_session(&claims).await {
+            Ok(Some(status)) => {
+                if let Some(locale) = status.locale.and_then(|locale| locale.parse().ok()) {
+                    set_current_locale(locale);
+                }
+            }
+            Ok(None) => return None,
+            Err(error) => {
+                error_log!("[authenticated_user] - [authenticate] message: error = {}", error);
+                return None;
+            }
+        }
+
+        let user = AuthenticatedUser(claims);
+        req.extensions_mut().insert(user.clone());
+        Some(user)
+    }
+
+    /// Claims を取り出す
+    pub fn into_inner(self) -> Claims {
+        self.0
+    }
+}
+
+impl Deref for AuthenticatedUser {
+    type Target = Claims;
+
+    fn deref(&self) -> &Claims {
+        &self.0
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+
+        Box::pin(async move {
+            AuthenticatedUser::authenticate(&req).await.ok_or_else(|| ApiError::Unauthorized.into())
+        })
+    }
+}
\ No newline at end of file
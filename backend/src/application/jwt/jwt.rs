@@ -2,18 +2,22 @@
 //!
 //! ## 関数
 //! 
-//! - `create_token`: JWTをエンコード
-//! - `decode_token`: JWTをデコード
-//! - `verify`:       JWTを検証
+//! - `access_token_ttl`: アクセストークンの有効期限
+//! - `verify`:           JWTを検証
+//!
+//! 署名と検証に使用する鍵は `jwt_keys::JwtKeys` を参照
 
-use actix_web::{HttpRequest, http::header::HeaderMap, dev::ServiceRequest};
-use jsonwebtoken::{encode, decode, Header, Algorithm, EncodingKey, DecodingKey, Validation, TokenData};
+use actix_web::{HttpRequest, http::{header::{self, HeaderMap}, Method}, dev::ServiceRequest, web};
 use serde::{Serialize, Deserialize};
-use std::time::{SystemTime, Duration};
-use std::env;
+use std::time::Duration;
 
-use crate::application::helpers::message::AUTH_MSG;
-use crate::{app_log, error_log, info_log};
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::jwt_keys::JwtKeys;
+use crate::application::states::app_state::AppState;
+use crate::domain::enums::role::Role;
+use crate::infrastructure::config::app_config;
+use crate::{app_log, error_log};
 
 /// JWT Claims 構造体
 ///
@@ -21,83 +25,83 @@ use crate::{app_log, error_log, info_log};
 ///
 /// * `id`  - ユーザーID.
 /// * `sub` - サブジェクト（Eメール）.
+/// * `sid` - セッションの公開ID.
+/// * `role` - ユーザーのロール.
+/// * `iss` - 発行者（`JWT_ISSUER` を設定した場合）.
+/// * `aud` - 対象者（`JWT_AUDIENCE` を設定した場合）.
+/// * `iat` - トークンの発行日時 (UNIX タイムスタンプ).
 /// * `exp` - トークンの有効期限 (UNIX タイムスタンプ).
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Claims {
     pub id: i32,
     pub sub: String,
+    #[serde(default)]
+    pub sid: String,
+    #[serde(default)]
+    pub role: Role,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default)]
+    pub iat: usize,
     pub exp: usize,
 }
 
-/// ヘッダーを抽出　トレイト
+/// アクセストークンのクッキー名（`cookie::create_cookie` を参照）
+const TOKEN_COOKIE: &str = "token";
+
+/// ヘッダー・メソッド・クッキー・JWT の鍵を抽出　トレイト
 pub trait RequestHeaders {
     fn get_headers(&self) -> &HeaderMap;
+    fn get_method(&self) -> &Method;
+    fn get_token_cookie(&self) -> Option<String>;
+    fn get_jwt_keys(&self) -> Option<&JwtKeys>;
 }
-/// HttpRequest からヘッダーを抽出
+/// HttpRequest からヘッダー・クッキー・JWT の鍵を抽出
 impl RequestHeaders for HttpRequest {
     fn get_headers(&self) -> &HeaderMap {
         self.headers()
     }
+    fn get_method(&self) -> &Method {
+        self.method()
+    }
+    fn get_token_cookie(&self) -> Option<String> {
+        self.cookie(TOKEN_COOKIE).map(|cookie| cookie.value().to_string())
+    }
+    fn get_jwt_keys(&self) -> Option<&JwtKeys> {
+        self.app_data::<web::Data<AppState>>().map(|app_state| app_state.jwt_keys.as_ref())
+    }
 }
-/// ServiceRequest からヘッダーを抽出
+/// ServiceRequest からヘッダー・クッキー・JWT の鍵を抽出
 impl RequestHeaders for ServiceRequest {
     fn get_headers(&self) -> &HeaderMap {
         self.headers()
     }
+    fn get_method(&self) -> &Method {
+        self.method()
+    }
+    fn get_token_cookie(&self) -> Option<String> {
+        self.cookie(TOKEN_COOKIE).map(|cookie| cookie.value().to_string())
+    }
+    fn get_jwt_keys(&self) -> Option<&JwtKeys> {
+        self.app_data::<web::Data<AppState>>().map(|app_state| app_state.jwt_keys.as_ref())
+    }
 }
 
-/// JWTをエンコード
-///
-/// # 引数
-///
-/// * `email` - ユーザーのEメール
-/// * `id`    - ユーザーID
-///
-/// # 戻り値
-///
-/// * `Result<String, jsonwebtoken::errors::Error>` - エンコード化したトークン
-pub fn create_token(email: &str, id: &i32) -> Result<String, jsonwebtoken::errors::Error> {
-
-    // トークンの有効期限 10日
-    let days = 60 * 60 * 24 * 10;
-
-    let expiration = SystemTime::now() + Duration::from_secs(days);
-    let claims = Claims {
-        id: id.to_owned(),
-        sub: email.to_owned(),
-        exp: expiration.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs() as usize,
-    };
-
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret("secret".as_ref())
-    )
-}
-
-/// JWTをデコード
+/// アクセストークンの有効期限
 ///
-/// # 引数
-///
-/// * `token` - トークン
-///
-/// # 戻り値
-///
-/// * `Result<String, jsonwebtoken::errors::Error>` - エンコード化したトークン
-pub fn decode_token(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::errors::Error> {
-    let jwt_secret = env::var("JWT_SECRET").expect("環境変数 `JWT_SECRET` は設定する必要があります。");
-
-    info_log!("[jwt] - [decode_token] jwt_secret = {}", jwt_secret);
-
-    decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(jwt_secret.as_ref()),
-        &Validation::new(Algorithm::HS256)
-    )
+/// `[jwt]` の `access_token_ttl_minutes`（既定 15 分）
+pub fn access_token_ttl() -> Duration {
+    Duration::from_secs(app_config::current().jwt.access_token_ttl_minutes * 60)
 }
 
 /// JWTを検証
 ///
+/// `Authorization: Bearer` ヘッダーを優先し、ない場合は `token` クッキーのトークンを検証する
+/// クッキーで認証する更新系のリクエスト（GET / HEAD / OPTIONS 以外）は、CSRF を防ぐため
+/// `Origin`（ない場合は `Referer`）が CORS で許可したオリジンの場合のみ受け付ける
+///
 /// # 引数
 ///
 /// * `req` - リクエスト
@@ -106,25 +110,59 @@ pub fn decode_token(token: &str) -> Result<TokenData<Claims>, jsonwebtoken::erro
 ///
 /// * `Result<Claims, String>` - Claims
 pub fn verify <R: RequestHeaders>(req: &R)  -> Result<Claims, String> {
-    // リクエストヘッダーから Bearer トークンを抽出できる場合
-    if let Some(auth_header) = req.get_headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            // 接頭辞の "Bearer" を抽出
-            let parts: Vec<&str> = auth_str.split_whitespace().collect();
-            if parts.len() == 2 && parts[0] == "Bearer" {
-                let token = parts[1];
-                // トークンを認証し、ユーザー情報をデコード
-                match decode_token(token) {
-                    Ok(user_info) => {
-                        return Ok(user_info.claims);
-                    },
-                    Err(error) => {
-                        error_log!("[jwt] - [verify] error = {}", error);
-                        return Err(error.to_string());
-                    }
-                }
+    let keys = match req.get_jwt_keys() {
+        Some(keys) => keys,
+        None => {
+            error_log!("[jwt] - [verify] JWT の鍵が AppState に登録されていません");
+            return Err("JWT の鍵が設定されていません。".to_string());
+        }
+    };
+
+    let token = match bearer_token(req.get_headers()) {
+        Some(token) => Some(token),
+        None => {
+            let token = req.get_token_cookie();
+            if token.is_some() && !is_safe_method(req.get_method()) && !has_allowed_origin(req.get_headers()) {
+                error_log!("[jwt] - [verify] message: cookie authentication from a disallowed origin");
+                return Err(t(current_locale(), MessageKey::AuthOriginNotAllowed));
             }
+            token
         }
+    };
+    let token = match token {
+        Some(token) if !token.is_empty() => token,
+        _ => return Err(t(current_locale(), MessageKey::AuthTokenNotFound)),
+    };
+
+    // トークンを認証し、ユーザー情報をデコード
+    keys.decode_token(&token).map_err(|error| {
+        error_log!("[jwt] - [verify] error = {}", error);
+        error.to_string()
+    })
+}
+
+/// 状態を変更しないメソッドか
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// `Origin`（ない場合は `Referer`）が CORS で許可したオリジンか
+fn has_allowed_origin(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ORIGIN)
+        .or_else(|| headers.get(header::REFERER))
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|origin| app_config::current().is_allowed_origin(origin))
+}
+
+/// リクエストヘッダーから Bearer トークンを抽出
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let auth_str = headers.get("Authorization")?.to_str().ok()?;
+    // 接頭辞の "Bearer" を抽出
+    let parts: Vec<&str> = auth_str.split_whitespace().collect();
+    if parts.len() == 2 && parts[0] == "Bearer" {
+        Some(parts[1].to_string())
+    } else {
+        None
     }
-    return Err(AUTH_MSG.get("TOKEN_NOT_FOUND_IN_REQUEST_HEADER_MSG").unwrap_or(&"").to_string());
 }
\ No newline at end of file
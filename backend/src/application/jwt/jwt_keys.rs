@@ -0,0 +1,567 @@
+//! # JWT の鍵
+//!
+//! アクセストークンの署名鍵と検証鍵を管理
+//! 起動時に一度だけ読み込み、`AppState` を通じて共有する
+//!
+//! * 署名アルゴリズムは HS256 / RS256 / EdDSA (Ed25519)
+//! * ヘッダーの `kid` で検証鍵を選択するため、鍵のローテーション中は旧鍵で署名したトークンも検証できる
+//! * `jsonwebtoken` は EdDSA に対応していないため、署名と検証は `ring` で行う
+//!
+//! ## メソッド
+//!
+//! `from_settings`             - `AppConfig` の `[jwt]` から鍵を読み込み
+//! `from_secret`               - 共通鍵 (HS256) の署名鍵で作成
+//! `from_private_key_pem`      - 秘密鍵の PEM (RS256 / EdDSA) で作成
+//! `with_verification_secret`  - 検証専用の共通鍵を追加
+//! `with_verification_key_pem` - 検証専用の公開鍵の PEM を追加
+//! `with_issuer`               - 発行者 (`iss`) を設定
+//! `with_audience`             - 対象者 (`aud`) を設定
+//! `with_leeway`               - 有効期限の検証で許容する時刻のずれ（秒）を設定
+//! `create_token`              - JWT を発行
+//! `create_token_until`        - 有効期限の上限を指定して JWT を発行
+//! `decode_token`              - JWT を検証してデコード
+//! `jwks`                      - 検証用の公開鍵の一覧 (JWK Set)
+
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::SystemTime;
+use jsonwebtoken::errors::{Error, ErrorKind};
+use ring::hmac;
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, RsaKeyPair, UnparsedPublicKey};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use simple_asn1::{oid, ASN1Block, BigUint, OID};
+
+use crate::application::errors::jwt_error::JwtKeyError;
+use crate::application::jwt::jwt::{access_token_ttl, Claims};
+use crate::domain::enums::role::Role;
+use crate::infrastructure::config::app_config::JwtSettings;
+
+/// 署名アルゴリズム
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyAlgorithm {
+    HS256,
+    RS256,
+    EdDSA,
+}
+
+impl KeyAlgorithm {
+    fn as_str(&self) -> &'static str {
+        match self {
+            KeyAlgorithm::HS256 => "HS256",
+            KeyAlgorithm::RS256 => "RS256",
+            KeyAlgorithm::EdDSA => "EdDSA",
+        }
+    }
+}
+
+impl FromStr for KeyAlgorithm {
+    type Err = JwtKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HS256" => Ok(KeyAlgorithm::HS256),
+            "RS256" => Ok(KeyAlgorithm::RS256),
+            "EdDSA" => Ok(KeyAlgorithm::EdDSA),
+            _ => Err(JwtKeyError::Config(format!("対応していない署名アルゴリズムです: {}", s))),
+        }
+    }
+}
+
+/// 署名鍵
+enum SigningKey {
+    Hmac(hmac::Key),
+    Rsa(RsaKeyPair),
+    Ed25519(Ed25519KeyPair),
+}
+
+/// 検証鍵
+///
+/// * `Rsa`     - `public_key` は RSAPublicKey の DER、`n` / `e` は JWK 用
+/// * `Ed25519` - 32 バイトの公開鍵
+enum VerifyingKey {
+    Hmac(hmac::Key),
+    Rsa { public_key: Vec<u8>, n: Vec<u8>, e: Vec<u8> },
+    Ed25519(Vec<u8>),
+}
+
+impl VerifyingKey {
+    fn algorithm(&self) -> KeyAlgorithm {
+        match self {
+            VerifyingKey::Hmac(_) => KeyAlgorithm::HS256,
+            VerifyingKey::Rsa { .. } => KeyAlgorithm::RS256,
+            VerifyingKey::Ed25519(_) => KeyAlgorithm::EdDSA,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            VerifyingKey::Hmac(key) => hmac::verify(key, message, signature).is_ok(),
+            VerifyingKey::Rsa { public_key, .. } => {
+                UnparsedPublicKey::new(&signature::RSA_PKCS1_2048_8192_SHA256, public_key)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+            VerifyingKey::Ed25519(public_key) => {
+                UnparsedPublicKey::new(&signature::ED25519, public_key)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+        }
+    }
+
+    /// 共通鍵は公開しない
+    fn to_jwk(&self, kid: &str) -> Option<Jwk> {
+        match self {
+            VerifyingKey::Hmac(_) => None,
+            VerifyingKey::Rsa { n, e, .. } => Some(Jwk {
+                kty: "RSA".to_string(),
+                key_use: "sig".to_string(),
+                alg: self.algorithm().as_str().to_string(),
+                kid: kid.to_string(),
+                n: Some(b64_encode(n)),
+                e: Some(b64_encode(e)),
+                crv: None,
+                x: None,
+            }),
+            VerifyingKey::Ed25519(public_key) => Some(Jwk {
+                kty: "OKP".to_string(),
+                key_use: "sig".to_string(),
+                alg: self.algorithm().as_str().to_string(),
+                kid: kid.to_string(),
+                n: None,
+                e: None,
+                crv: Some("Ed25519".to_string()),
+                x: Some(b64_encode(public_key)),
+            }),
+        }
+    }
+}
+
+/// JWT ヘッダー
+#[derive(Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+}
+
+/// 公開鍵 (JWK)
+#[derive(Serialize, Debug)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+}
+
+/// 公開鍵の一覧 (JWK Set)
+#[derive(Serialize, Debug)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// JWT の鍵
+///
+/// # フィールド
+///
+/// * `kid`               - 署名鍵の ID
+/// * `signing_key`       - 署名鍵
+/// * `verification_keys` - `kid` ごとの検証鍵（署名鍵の公開鍵を含む）
+/// * `issuer`            - 発行者。設定した場合は `iss` を検証
+/// * `audience`          - 対象者。設定した場合は `aud` を検証し、発行時は先頭の値を使用
+/// * `leeway`            - 時刻のずれの許容秒数
+pub struct JwtKeys {
+    kid: String,
+    signing_key: SigningKey,
+    verification_keys: Vec<(String, VerifyingKey)>,
+    issuer: Option<String>,
+    audience: Vec<String>,
+    leeway: u64,
+}
+
+impl JwtKeys {
+    /// `AppConfig` の `[jwt]` から鍵を読み込み
+    ///
+    /// HS256 の場合は `secret`、RS256 / EdDSA の場合は `private_key_path` が必要
+    /// `previous_secrets` / `previous_public_keys` はローテーション前の鍵として検証のみに使用する
+    pub fn from_settings(settings: &JwtSettings) -> Result<Self, JwtKeyError> {
+        let kid = &settings.key_id;
+
+        let mut keys = match settings.algorithm {
+            KeyAlgorithm::HS256 => {
+                let secret = settings.secret.as_deref().filter(|secret| !secret.is_empty()).ok_or_else(|| {
+                    JwtKeyError::Config("`jwt.secret`（環境変数 `JWT_SECRET`）は設定する必要があります。".to_string())
+                })?;
+                JwtKeys::from_secret(kid, secret.as_bytes())
+            }
+            algorithm => {
+                let path = settings.private_key_path.as_ref().ok_or_else(|| {
+                    JwtKeyError::Config("`jwt.private_key_path`（環境変数 `JWT_PRIVATE_KEY_PATH`）は設定する必要があります。".to_string())
+                })?;
+                JwtKeys::from_private_key_pem(algorithm, kid, &read_key_file(path)?)?
+            }
+        };
+
+        for (kid, secret) in &settings.previous_secrets {
+            keys = keys.with_verification_secret(kid, secret.as_bytes())?;
+        }
+        for (kid, path) in &settings.previous_public_keys {
+            keys = keys.with_verification_key_pem(kid, &read_key_file(path)?)?;
+        }
+
+        Ok(keys
+            .with_issuer(settings.issuer.clone())
+            .with_audience(settings.audience.clone())
+            .with_leeway(settings.leeway_secs))
+    }
+
+    /// 共通鍵 (HS256) の署名鍵で作成
+    ///
+    /// # 引数
+    ///
+    /// * `kid`    - 鍵の ID
+    /// * `secret` - 共通鍵
+    pub fn from_secret(kid: &str, secret: &[u8]) -> Self {
+        JwtKeys::new(
+            kid,
+            SigningKey::Hmac(hmac::Key::new(hmac::HMAC_SHA256, secret)),
+            VerifyingKey::Hmac(hmac::Key::new(hmac::HMAC_SHA256, secret)),
+        )
+    }
+
+    /// 秘密鍵の PEM で作成
+    ///
+    /// # 引数
+    ///
+    /// * `algorithm` - `RS256`（PKCS#1 / PKCS#8）または `EdDSA`（PKCS#8）
+    /// * `kid`       - 鍵の ID
+    /// * `pem`       - 秘密鍵の PEM
+    pub fn from_private_key_pem(algorithm: KeyAlgorithm, kid: &str, pem: &[u8]) -> Result<Self, JwtKeyError> {
+        let pem = pem::parse(pem)?;
+
+        let (signing_key, verifying_key) = match (algorithm, pem.tag.as_str()) {
+            (KeyAlgorithm::RS256, "RSA PRIVATE KEY") | (KeyAlgorithm::RS256, "PRIVATE KEY") => {
+                let key_pair = if pem.tag == "RSA PRIVATE KEY" {
+                    RsaKeyPair::from_der(&pem.contents)?
+                } else {
+                    RsaKeyPair::from_pkcs8(&pem.contents)?
+                };
+                let public_key = key_pair.public_key();
+                let verifying_key = VerifyingKey::Rsa {
+                    public_key: public_key.as_ref().to_vec(),
+                    n: public_key.modulus().big_endian_without_leading_zero().to_vec(),
+                    e: public_key.exponent().big_endian_without_leading_zero().to_vec(),
+                };
+
+                (SigningKey::Rsa(key_pair), verifying_key)
+            }
+            (KeyAlgorithm::EdDSA, "PRIVATE KEY") => {
+                let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pem.contents)?;
+                let verifying_key = VerifyingKey::Ed25519(key_pair.public_key().as_ref().to_vec());
+
+                (SigningKey::Ed25519(key_pair), verifying_key)
+            }
+            (algorithm, tag) => {
+                return Err(JwtKeyError::Key(format!("{} の秘密鍵として `{}` は使用できません。", algorithm.as_str(), tag)));
+            }
+        };
+
+        Ok(JwtKeys::new(kid, signing_key, verifying_key))
+    }
+
+    fn new(kid: &str, signing_key: SigningKey, verifying_key: VerifyingKey) -> Self {
+        JwtKeys {
+            kid: kid.to_string(),
+            signing_key,
+            verification_keys: vec![(kid.to_string(), verifying_key)],
+            issuer: None,
+            audience: Vec::new(),
+            leeway: 0,
+        }
+    }
+
+    /// 検証専用の共通鍵を追加
+    ///
+    /// # 引数
+    ///
+    /// * `kid`    - 鍵の ID
+    /// * `secret` - 共通鍵
+    pub fn with_verification_secret(self, kid: &str, secret: &[u8]) -> Result<Self, JwtKeyError> {
+        self.with_verifying_key(kid, VerifyingKey::Hmac(hmac::Key::new(hmac::HMAC_SHA256, secret)))
+    }
+
+    /// 検証専用の公開鍵の PEM を追加
+    ///
+    /// # 引数
+    ///
+    /// * `kid` - 鍵の ID
+    /// * `pem` - RSA または Ed25519 の公開鍵の PEM
+    pub fn with_verification_key_pem(self, kid: &str, pem: &[u8]) -> Result<Self, JwtKeyError> {
+        let key = parse_public_key(&pem::parse(pem)?)?;
+
+        self.with_verifying_key(kid, key)
+    }
+
+    fn with_verifying_key(mut self, kid: &str, key: VerifyingKey) -> Result<Self, JwtKeyError> {
+        if self.verification_keys.iter().any(|(id, _)| id == kid) {
+            return Err(JwtKeyError::Config(format!("JWT の鍵 `{}` が重複しています。", kid)));
+        }
+
+        self.verification_keys.push((kid.to_string(), key));
+        Ok(self)
+    }
+
+    /// 発行者 (`iss`) を設定
+    pub fn with_issuer(mut self, issuer: Option<String>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    /// 対象者 (`aud`) を設定
+    pub fn with_audience(mut self, audience: Vec<String>) -> Self {
+        self.audience = audience;
+        self
+    }
+
+    /// 有効期限の検証で許容する時刻のずれ（秒）を設定
+    pub fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// JWT を発行
+    ///
+    /// # 引数
+    ///
+    /// * `email` - ユーザーのEメール
+    /// * `id`    - ユーザーID
+    /// * `sid`   - セッションの公開ID
+    /// * `role`  - ユーザーのロール
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<String, jsonwebtoken::errors::Error>` - 署名したトークン
+    pub fn create_token(&self, email: &str, id: &i32, sid: &str, role: Role) -> Result<String, Error> {
+        self.create_token_until(email, id, sid, role, u64::MAX)
+    }
+
+    /// 有効期限の上限を指定して JWT を発行
+    ///
+    /// セッション・ゲストの有効期限を超えるトークンを発行しないよう、`exp` は `not_after` までに短縮する
+    ///
+    /// # 引数
+    ///
+    /// * `not_after` - 有効期限の上限 (UNIX タイムスタンプ)
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<String, jsonwebtoken::errors::Error>` - 署名したトークン
+    pub fn create_token_until(&self, email: &str, id: &i32, sid: &str, role: Role, not_after: u64) -> Result<String, Error> {
+        let issued_at = now();
+        let claims = Claims {
+            id: id.to_owned(),
+            sub: email.to_owned(),
+            sid: sid.to_owned(),
+            role,
+            iss: self.issuer.clone(),
+            aud: self.audience.first().cloned(),
+            iat: issued_at as usize,
+            exp: (issued_at + access_token_ttl().as_secs()).min(not_after) as usize,
+        };
+        let header = JwtHeader {
+            alg: self.signing_algorithm().as_str().to_string(),
+            typ: Some("JWT".to_string()),
+            kid: Some(self.kid.clone()),
+        };
+
+        let message = format!(
+            "{}.{}",
+            b64_encode(&serde_json::to_vec(&header)?),
+            b64_encode(&serde_json::to_vec(&claims)?)
+        );
+
+        let signature = match &self.signing_key {
+            SigningKey::Hmac(key) => hmac::sign(key, message.as_bytes()).as_ref().to_vec(),
+            SigningKey::Rsa(key_pair) => {
+                let mut signature = vec![0; key_pair.public_modulus_len()];
+                key_pair.sign(&signature::RSA_PKCS1_SHA256, &SystemRandom::new(), message.as_bytes(), &mut signature)?;
+                signature
+            }
+            SigningKey::Ed25519(key_pair) => key_pair.sign(message.as_bytes()).as_ref().to_vec(),
+        };
+
+        Ok(format!("{}.{}", message, b64_encode(&signature)))
+    }
+
+    /// JWT を検証してデコード
+    ///
+    /// `kid` のないトークンは現在の署名鍵で検証する
+    ///
+    /// # 引数
+    ///
+    /// * `token` - トークン
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<Claims, jsonwebtoken::errors::Error>` - Claims
+    pub fn decode_token(&self, token: &str) -> Result<Claims, Error> {
+        let (message, signature) = token.rsplit_once('.').ok_or(ErrorKind::InvalidToken)?;
+        let (header, payload) = message.split_once('.').ok_or(ErrorKind::InvalidToken)?;
+        let header: JwtHeader = b64_decode_json(header)?;
+
+        let kid = header.kid.as_deref().unwrap_or(&self.kid);
+        let (_, key) = self.verification_keys
+            .iter()
+            .find(|(id, _)| id == kid)
+            .ok_or(ErrorKind::InvalidSignature)?;
+
+        // ヘッダーのアルゴリズムは鍵の種類と一致する必要がある
+        if header.alg != key.algorithm().as_str() {
+            return Err(ErrorKind::InvalidAlgorithm.into());
+        }
+        if !key.verify(message.as_bytes(), &b64_decode(signature)?) {
+            return Err(ErrorKind::InvalidSignature.into());
+        }
+
+        let claims: Map<String, Value> = b64_decode_json(payload)?;
+        self.validate(&claims)?;
+
+        Ok(serde_json::from_value(Value::Object(claims))?)
+    }
+
+    /// 検証用の公開鍵の一覧 (JWK Set)
+    pub fn jwks(&self) -> Jwks {
+        Jwks {
+            keys: self.verification_keys
+                .iter()
+                .filter_map(|(kid, key)| key.to_jwk(kid))
+                .collect(),
+        }
+    }
+
+    fn signing_algorithm(&self) -> KeyAlgorithm {
+        match self.signing_key {
+            SigningKey::Hmac(_) => KeyAlgorithm::HS256,
+            SigningKey::Rsa(_) => KeyAlgorithm::RS256,
+            SigningKey::Ed25519(_) => KeyAlgorithm::EdDSA,
+        }
+    }
+
+    /// `exp` / `nbf` / `iss` / `aud` を検証
+    fn validate(&self, claims: &Map<String, Value>) -> Result<(), Error> {
+        let now = now();
+
+        if let Some(exp) = claims.get("exp").and_then(Value::as_u64) {
+            if exp.saturating_add(self.leeway) < now {
+                return Err(ErrorKind::ExpiredSignature.into());
+            }
+        }
+        if let Some(nbf) = claims.get("nbf").and_then(Value::as_u64) {
+            if nbf > now.saturating_add(self.leeway) {
+                return Err(ErrorKind::ImmatureSignature.into());
+            }
+        }
+        if let Some(issuer) = &self.issuer {
+            if claims.get("iss").and_then(Value::as_str) != Some(issuer.as_str()) {
+                return Err(ErrorKind::InvalidIssuer.into());
+            }
+        }
+        if !self.audience.is_empty() {
+            let is_valid = match claims.get("aud") {
+                Some(Value::String(aud)) => self.audience.contains(aud),
+                Some(Value::Array(auds)) => auds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|aud| self.audience.iter().any(|expected| expected == aud)),
+                _ => false,
+            };
+            if !is_valid {
+                return Err(ErrorKind::InvalidAudience.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 鍵ファイルを読み込み（エラーにはパスを含める）
+fn read_key_file(path: &Path) -> Result<Vec<u8>, JwtKeyError> {
+    fs::read(path).map_err(|err| JwtKeyError::Io(std::io::Error::new(err.kind(), format!("{}: {}", path.display(), err))))
+}
+
+/// 公開鍵の PEM（SubjectPublicKeyInfo または PKCS#1）を検証鍵に変換
+fn parse_public_key(pem: &pem::Pem) -> Result<VerifyingKey, JwtKeyError> {
+    let invalid = || JwtKeyError::Key(format!("公開鍵として `{}` は使用できません。", pem.tag));
+
+    match pem.tag.as_str() {
+        "RSA PUBLIC KEY" => parse_rsa_public_key(&pem.contents),
+        "PUBLIC KEY" => {
+            let blocks = simple_asn1::from_der(&pem.contents).map_err(|err| JwtKeyError::Key(err.to_string()))?;
+            let (algorithm, public_key) = match blocks.as_slice() {
+                [ASN1Block::Sequence(_, items)] => match items.as_slice() {
+                    [ASN1Block::Sequence(_, algorithm), ASN1Block::BitString(_, _, public_key)] => (algorithm, public_key),
+                    _ => return Err(invalid()),
+                },
+                _ => return Err(invalid()),
+            };
+
+            match algorithm.first() {
+                Some(ASN1Block::ObjectIdentifier(_, id)) if *id == oid!(1, 2, 840, 113549, 1, 1, 1) => {
+                    parse_rsa_public_key(public_key)
+                }
+                Some(ASN1Block::ObjectIdentifier(_, id)) if *id == oid!(1, 3, 101, 112) => {
+                    Ok(VerifyingKey::Ed25519(public_key.clone()))
+                }
+                _ => Err(invalid()),
+            }
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// RSAPublicKey の DER を検証鍵に変換
+fn parse_rsa_public_key(der: &[u8]) -> Result<VerifyingKey, JwtKeyError> {
+    let blocks = simple_asn1::from_der(der).map_err(|err| JwtKeyError::Key(err.to_string()))?;
+
+    match blocks.as_slice() {
+        [ASN1Block::Sequence(_, items)] => match items.as_slice() {
+            [ASN1Block::Integer(_, n), ASN1Block::Integer(_, e)] => Ok(VerifyingKey::Rsa {
+                public_key: der.to_vec(),
+                n: n.to_bytes_be().1,
+                e: e.to_bytes_be().1,
+            }),
+            _ => Err(JwtKeyError::Key("RSA の公開鍵の形式が正しくありません。".to_string())),
+        },
+        _ => Err(JwtKeyError::Key("RSA の公開鍵の形式が正しくありません。".to_string())),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn b64_encode(input: &[u8]) -> String {
+    base64::encode_config(input, base64::URL_SAFE_NO_PAD)
+}
+
+fn b64_decode(input: &str) -> Result<Vec<u8>, Error> {
+    base64::decode_config(input, base64::URL_SAFE_NO_PAD).map_err(|_| ErrorKind::InvalidToken.into())
+}
+
+fn b64_decode_json<T: DeserializeOwned>(input: &str) -> Result<T, Error> {
+    Ok(serde_json::from_slice(&b64_decode(input)?)?)
+}
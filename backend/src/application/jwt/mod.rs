@@ -1 +1,3 @@
-pub mod jwt;
\ No newline at end of file
+pub mod authenticated_user;
+pub mod jwt;
+pub mod jwt_keys;
\ No newline at end of file
@@ -0,0 +1,57 @@
+//! # メール送信
+//!
+//! メールの送信方法（SMTP・ファイル出力・ログ出力）を切り替えるためのトレイト
+//!
+//! 送信方法は起動時に `MailConfig` から作成し、`AppState` で共有する。
+
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::Message;
+
+use crate::application::errors::mail_error::MailError;
+
+/// 送信するメール
+///
+/// * `to`      - 送信先のメールアドレス
+/// * `subject` - 件名
+/// * `text`    - テキスト形式の本文
+/// * `html`    - HTML 形式の本文
+#[derive(Clone, Debug)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+impl Mail {
+    /// テキストと HTML の本文を持つメッセージを作成
+    ///
+    /// # 引数
+    ///
+    /// * `from` - 送信元
+    ///
+    /// # 戻り値
+    ///
+    /// * `Message` - 送信するメッセージ
+    pub fn to_message(&self, from: &Mailbox) -> Result<Message, MailError> {
+        let message = Message::builder()
+            .from(from.clone())
+            .to(self.to.parse()?)
+            .subject(&self.subject)
+            .multipart(MultiPart::alternative_plain_html(self.text.clone(), self.html.clone()))?;
+
+        Ok(message)
+    }
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// メールを送信
+    async fn send(&self, mail: &Mail) -> Result<(), MailError>;
+
+    /// 送信できる状態か確認（ヘルスチェック用）
+    async fn check(&self) -> Result<(), MailError> {
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,92 @@
+//! # メールテンプレート
+//!
+//! `templates/mail/{name}.{locale}.{txt,html}` のテンプレートからメールを作成
+//!
+//! ## 関数
+//!
+//! - `render`: プレースホルダー（`{name}`）を置換してメールを作成
+//!
+//! 件名はメッセージカタログから取得する。
+//! テンプレートはバイナリに埋め込むため、言語ごとのファイルがない場合はコンパイルエラーになる。
+
+use crate::application::i18n::catalogue::{replace_placeholders, t_with, MessageKey};
+use crate::application::mail::mailer::Mail;
+use crate::domain::enums::locale::Locale;
+
+/// メールテンプレートを定義
+///
+/// `MailTemplate` の列挙子と、テンプレートファイル・件名のキーの対応を生成する
+macro_rules! mail_templates {
+    ($($variant:ident => $name:literal, $subject:ident,)*) => {
+        /// メールテンプレート
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum MailTemplate {
+            $($variant,)*
+        }
+
+        impl MailTemplate {
+            /// 件名のキー
+            fn subject_key(&self) -> MessageKey {
+                match self {
+                    $(MailTemplate::$variant => MessageKey::$subject,)*
+                }
+            }
+
+            /// テキスト形式・HTML 形式のテンプレート
+            fn source(&self, locale: Locale) -> (&'static str, &'static str) {
+                match (self, locale) {
+                    $(
+                        (MailTemplate::$variant, Locale::En) => (
+                            include_str!(concat!("../../../templates/mail/", $name, ".en.txt")),
+                            include_str!(concat!("../../../templates/mail/", $name, ".en.html")),
+                        ),
+                        (MailTemplate::$variant, Locale::Ja) => (
+                            include_str!(concat!("../../../templates/mail/", $name, ".ja.txt")),
+                            include_str!(concat!("../../../templates/mail/", $name, ".ja.html")),
+                        ),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+mail_templates! {
+    Verification => "verification", MailVerificationSubject,
+    PasswordReset => "password_reset", MailResetSubject,
+    TaskReminder => "task_reminder", MailTaskReminderSubject,
+}
+
+/// プレースホルダー（`{name}`）を置換してメールを作成
+///
+/// HTML 形式の本文に置換する値はエスケープする
+///
+/// # 引数
+///
+/// * `template` - テンプレート
+/// * `locale`   - 言語
+/// * `to`       - 送信先のメールアドレス
+/// * `args`     - プレースホルダーの名前と値
+///
+/// # 戻り値
+///
+/// * `Mail` - 送信するメール
+pub fn render(template: MailTemplate, locale: Locale, to: &str, args: &[(&str, &str)]) -> Mail {
+    let (text, html) = template.source(locale);
+
+    Mail {
+        to: to.to_string(),
+        subject: t_with(locale, template.subject_key(), args),
+        text: replace_placeholders(text, args, str::to_string),
+        html: replace_placeholders(html, args, escape_html),
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
\ No newline at end of file
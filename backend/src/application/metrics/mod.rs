@@ -0,0 +1 @@
+pub mod registry;
\ No newline at end of file
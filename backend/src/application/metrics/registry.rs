@@ -0,0 +1,135 @@
+//! # メトリクス
+//!
+//! Prometheus 形式で公開するメトリクスを定義
+//! 各メトリクスは起動時に一度だけ登録し、処理中はカウンター・ヒストグラムを更新する
+//!
+//! | メトリクス                      | 種類      | ラベル                                | 説明                         |
+//! | ------------------------------- | --------- | ------------------------------------- | ---------------------------- |
+//! | `http_requests_total`           | counter   | `method` `route` `status`             | リクエスト数                 |
+//! | `http_request_duration_seconds` | histogram | `method` `route` `status`             | リクエストの処理時間         |
+//! | `db_pool_connections`           | gauge     |                                       | プールの接続数               |
+//! | `db_pool_idle_connections`      | gauge     |                                       | プールのアイドル接続数       |
+//! | `db_pool_max_connections`       | gauge     |                                       | プールの最大接続数           |
+//! | `db_pool_wait_seconds`          | histogram |                                       | 接続を取得するまでの待機時間 |
+//! | `db_pool_errors_total`          | counter   |                                       | 接続の取得に失敗した回数     |
+//! | `user_registrations_total`      | counter   |                                       | 新規登録数                   |
+//! | `user_logins_total`             | counter   | `result`（`success` / `failure`）     | ログイン数                   |
+//! | `login_rejections_total`        | counter   | `reason`（`rate_limited` / `locked`） | 拒否したログインのリクエスト |
+//! | `tasks_created_total`           | counter   |                                       | 作成したタスク数             |
+//! | `tasks_completed_total`         | counter   |                                       | 完了したタスク数             |
+//! | `mail_deliveries_total`         | counter   | `result`（`sent` / `retry` / `dead`） | メールの送信結果             |
+//!
+//! ## 関数
+//!
+//! - `init`:   全てのメトリクスを登録（一度も更新されていないメトリクスも 0 として出力するため）
+//! - `render`: 登録した全てのメトリクスを Prometheus のテキスト形式で出力
+
+use lazy_static::lazy_static;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+/// HTTP リクエストの処理時間のバケット（秒）
+const HTTP_DURATION_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// DB 接続の待機時間のバケット（秒）
+const DB_WAIT_BUCKETS: &[f64] = &[0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0];
+
+lazy_static! {
+    pub static ref REGISTRY: Registry = Registry::new();
+
+    // HTTP
+    pub static ref HTTP_REQUESTS_TOTAL: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("http_requests_total", "Total number of HTTP requests"),
+        &["method", "route", "status"]
+    ).unwrap());
+    pub static ref HTTP_REQUEST_DURATION_SECONDS: HistogramVec = register(HistogramVec::new(
+        HistogramOpts::new("http_request_duration_seconds", "HTTP request latency in seconds").buckets(HTTP_DURATION_BUCKETS.to_vec()),
+        &["method", "route", "status"]
+    ).unwrap());
+
+    // DB 接続プール
+    pub static ref DB_POOL_CONNECTIONS: IntGauge = register(IntGauge::new(
+        "db_pool_connections", "Number of connections managed by the pool"
+    ).unwrap());
+    pub static ref DB_POOL_IDLE_CONNECTIONS: IntGauge = register(IntGauge::new(
+        "db_pool_idle_connections", "Number of idle connections in the pool"
+    ).unwrap());
+    pub static ref DB_POOL_MAX_CONNECTIONS: IntGauge = register(IntGauge::new(
+        "db_pool_max_connections", "Maximum number of connections in the pool"
+    ).unwrap());
+    pub static ref DB_POOL_WAIT_SECONDS: Histogram = register(Histogram::with_opts(
+        HistogramOpts::new("db_pool_wait_seconds", "Time spent waiting for a pooled connection in seconds").buckets(DB_WAIT_BUCKETS.to_vec())
+    ).unwrap());
+    pub static ref DB_POOL_ERRORS_TOTAL: IntCounter = register(IntCounter::new(
+        "db_pool_errors_total", "Total number of failed attempts to get a pooled connection"
+    ).unwrap());
+
+    // ドメインイベント
+    pub static ref USER_REGISTRATIONS_TOTAL: IntCounter = register(IntCounter::new(
+        "user_registrations_total", "Total number of user registrations"
+    ).unwrap());
+    pub static ref USER_LOGINS_TOTAL: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("user_logins_total", "Total number of login attempts"),
+        &["result"]
+    ).unwrap());
+    pub static ref LOGIN_REJECTIONS_TOTAL: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("login_rejections_total", "Total number of login requests rejected by rate limiting or lockout"),
+        &["reason"]
+    ).unwrap());
+    pub static ref TASKS_CREATED_TOTAL: IntCounter = register(IntCounter::new(
+        "tasks_created_total", "Total number of tasks created"
+    ).unwrap());
+    pub static ref TASKS_COMPLETED_TOTAL: IntCounter = register(IntCounter::new(
+        "tasks_completed_total", "Total number of tasks completed"
+    ).unwrap());
+    pub static ref MAIL_DELIVERIES_TOTAL: IntCounterVec = register(IntCounterVec::new(
+        Opts::new("mail_deliveries_total", "Total number of mail delivery attempts from the outbox"),
+        &["result"]
+    ).unwrap());
+}
+
+/// メトリクスをレジストリに登録
+fn register<M: prometheus::core::Collector + Clone + 'static>(metric: M) -> M {
+    REGISTRY.register(Box::new(metric.clone())).unwrap();
+    metric
+}
+
+/// 全てのメトリクスを登録
+///
+/// `lazy_static` は最初の参照時に初期化されるため、起動時に参照して登録する
+/// ラベルの値が決まっているメトリクスは、アラートのために 0 の系列も作成する
+pub fn init() {
+    lazy_static::initialize(&HTTP_REQUESTS_TOTAL);
+    lazy_static::initialize(&HTTP_REQUEST_DURATION_SECONDS);
+    lazy_static::initialize(&DB_POOL_CONNECTIONS);
+    lazy_static::initialize(&DB_POOL_IDLE_CONNECTIONS);
+    lazy_static::initialize(&DB_POOL_MAX_CONNECTIONS);
+    lazy_static::initialize(&DB_POOL_WAIT_SECONDS);
+    lazy_static::initialize(&DB_POOL_ERRORS_TOTAL);
+    lazy_static::initialize(&USER_REGISTRATIONS_TOTAL);
+    lazy_static::initialize(&TASKS_CREATED_TOTAL);
+    lazy_static::initialize(&TASKS_COMPLETED_TOTAL);
+
+    for result in ["success", "failure"] {
+        USER_LOGINS_TOTAL.with_label_values(&[result]);
+    }
+    for reason in ["rate_limited", "locked"] {
+        LOGIN_REJECTIONS_TOTAL.with_label_values(&[reason]);
+    }
+    for result in ["sent", "retry", "dead", "expired"] {
+        MAIL_DELIVERIES_TOTAL.with_label_values(&[result]);
+    }
+}
+
+/// 登録した全てのメトリクスを Prometheus のテキスト形式で出力
+///
+/// # 戻り値
+///
+/// * `String` - `text/plain; version=0.0.4` 形式のメトリクス
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer).unwrap();
+
+    String::from_utf8(buffer).unwrap()
+}
\ No newline at end of file
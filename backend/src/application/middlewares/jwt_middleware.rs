@@ -1,23 +1,28 @@
 //! # JWT ミドルウェア
 //! 
 //! HTTP リクエストに含まれる JWT トークンを検証
-//! 無効または欠如している場合は、`Unauthorized` を返す
+//! 無効または欠如している場合、セッションが失効している場合、パスワード・ロール変更前に発行された場合は、`Unauthorized` を返す
+//!
+//! スコープ単位で `.wrap(JwtMiddleware)` のように使用する（`api_routes::protected_scope` を参照）
+//! 検証済みの Claims はリクエストに保存され、ハンドラーでは `AuthenticatedUser` で受け取る
 
+use std::rc::Rc;
 use actix_web::{body::EitherBody, dev};
 use actix_service::Service;
 use actix_web::{
     dev::{ServiceRequest, ServiceResponse, Transform},
     Error, 
-    HttpResponse,
+    ResponseError,
 };
 use futures::future::{ok, Ready, LocalBoxFuture};
-use crate::application::jwt::jwt;
+use crate::application::errors::api_error::ApiError;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
 
 pub struct JwtMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
     where
-        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>,
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
         S::Future: 'static,
         B: 'static,
 {
@@ -28,17 +33,17 @@ impl<S, B> Transform<S, ServiceRequest> for JwtMiddleware
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(JwtMiddlewareService { service })
+        ok(JwtMiddlewareService { service: Rc::new(service) })
     }
 }
 
 pub struct JwtMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -49,40 +54,17 @@ where
     dev::forward_ready!(service);
 
     fn call(&self, request: ServiceRequest) -> Self::Future {
-        // 認証なしでコールが可能な API パスのリスト
-        let exempt_paths = vec![
-            "/api/v1/auth/register",
-            "/api/v1/auth/login",
-            "/api/v1/auth/logout",
-            "/api/v1/auth/user",
-            "/api/v1/auth/login-status",
-            "/api/v1/auth/healthcheck",
-            "/api/v1/tasks"
-        ];
+        let service = self.service.clone();
 
-        let is_exempt = exempt_paths.contains(&request.path());
-
-        if !is_exempt {
-            let is_logged_in = match jwt::verify(&request) {
-                Ok(_user_info) => true,
-                Err(_) => false
-            };
-    
-            if !is_logged_in {
+        Box::pin(async move {
+            if AuthenticatedUser::authenticate(request.request()).await.is_none() {
                 let (request, _pl) = request.into_parts();
-    
-                let response = HttpResponse::Unauthorized()
-                    .finish()
-                    .map_into_right_body();
-    
-                return Box::pin(async { Ok(ServiceResponse::new(request, response)) });
-            }
-        }
+                let response = ApiError::Unauthorized.error_response().map_into_right_body();
 
-        let res = self.service.call(request);
+                return Ok(ServiceResponse::new(request, response));
+            }
 
-        Box::pin(async move {
-            res.await.map(ServiceResponse::map_into_left_body)
+            service.call(request).await.map(ServiceResponse::map_into_left_body)
         })
     }
-}
+}
\ No newline at end of file
@@ -0,0 +1,70 @@
+//! # 言語ミドルウェア
+//!
+//! `Accept-Language` からリクエストの言語を選択し、処理中の `ApiError` やメッセージの言語として設定する
+//! 対応している言語がない場合は既定の言語（`[app]` の `default_locale`）を使用する
+//! レスポンスには使用した言語を `Content-Language` で返す
+
+use std::rc::Rc;
+use actix_web::dev;
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderValue, ACCEPT_LANGUAGE, CONTENT_LANGUAGE},
+    Error,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use crate::application::i18n::request_locale::{self, current_locale, default_locale, negotiate};
+
+pub struct LocaleMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for LocaleMiddleware
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = LocaleMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(LocaleMiddlewareService { service: Rc::new(service) })
+    }
+}
+
+pub struct LocaleMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocaleMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let locale = request.headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(negotiate)
+            .unwrap_or_else(default_locale);
+
+        Box::pin(request_locale::scope(locale, async move {
+            let mut response = service.call(request).await?;
+
+            // ログイン中のユーザーの設定で変更されている場合があるため、処理後の言語を返す
+            response.headers_mut().insert(CONTENT_LANGUAGE, HeaderValue::from_static(current_locale().as_str()));
+
+            Ok(response)
+        }))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+//! # ログインの回数制限ミドルウェア
+//!
+//! 接続元の IP アドレスと、リクエストボディのメールアドレスごとにログインのリクエスト数を数え、
+//! ロック中・上限を超えた場合は `429 Too Many Requests`（`Retry-After` ヘッダー付き）を返す
+//! 回数・ロックの判定は `LoginGuardService` が行う
+//!
+//! リクエストボディは読み取った後にハンドラーのために戻す
+
+use std::pin::Pin;
+use std::rc::Rc;
+use actix_web::{body::EitherBody, dev, web};
+use actix_service::Service;
+use actix_web::{
+    dev::{Payload, ServiceRequest, ServiceResponse, Transform},
+    error::PayloadError,
+    Error,
+    ResponseError,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use futures::Stream;
+use serde::Deserialize;
+use crate::application::errors::api_error::ApiError;
+use crate::application::helpers::request::client_ip;
+use crate::application::states::app_state::AppState;
+
+pub struct LoginRateLimit;
+
+/// リクエストボディのメールアドレス
+///
+/// 形式が不正な場合はハンドラーでエラーを返すため、ここでは IP アドレスのみで判定する
+#[derive(Deserialize)]
+struct LoginEmail {
+    email: String,
+}
+
+/// 読み取ったリクエストボディをハンドラーに戻す
+fn restore_payload(body: web::Bytes) -> Payload {
+    let stream: Pin<Box<dyn Stream<Item = Result<web::Bytes, PayloadError>>>> = Box::pin(futures::stream::once(async move { Ok(body) }));
+
+    Payload::from(stream)
+}
+
+impl<S, B> Transform<S, ServiceRequest> for LoginRateLimit
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = LoginRateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(LoginRateLimitService { service: Rc::new(service) })
+    }
+}
+
+pub struct LoginRateLimitService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LoginRateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, mut request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let login_guard_service = match request.app_data::<web::Data<AppState>>() {
+                Some(app_state) => app_state.login_guard_service.clone(),
+                None => return service.call(request).await.map(ServiceResponse::map_into_left_body),
+            };
+
+            let result = match request.extract::<web::Bytes>().await {
+                Ok(body) => {
+                    let email = serde_json::from_slice::<LoginEmail>(&body).ok().map(|login| login.email);
+                    request.set_payload(restore_payload(body));
+
+                    let ip_address = client_ip(request.request());
+                    login_guard_service
+                        .check_request(ip_address.as_deref(), email.as_deref())
+                        .await
+                        .map_err(ApiError::from)
+                }
+                Err(error) => Err(ApiError::BadRequest(error.to_string())),
+            };
+
+            match result {
+                Ok(()) => service.call(request).await.map(ServiceResponse::map_into_left_body),
+                Err(error) => {
+                    let (request, _pl) = request.into_parts();
+                    let response = error.error_response().map_into_right_body();
+
+                    Ok(ServiceResponse::new(request, response))
+                }
+            }
+        })
+    }
+}
\ No newline at end of file
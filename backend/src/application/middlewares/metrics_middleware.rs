@@ -0,0 +1,85 @@
+//! # メトリクスミドルウェア
+//!
+//! リクエスト数と処理時間を、メソッド・ルート・ステータスごとにメトリクスに記録する
+//! ルートはパスではなく登録したパターン（`/api/v1/tasks/{id}`）とし、一致しないパスは `unmatched` にまとめる
+
+use std::rc::Rc;
+use std::time::Instant;
+use actix_web::dev;
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    http::StatusCode,
+    Error,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+
+use crate::application::metrics::registry::{HTTP_REQUESTS_TOTAL, HTTP_REQUEST_DURATION_SECONDS};
+
+/// ルートに一致しないリクエストのラベル
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+pub struct MetricsMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for MetricsMiddleware
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = MetricsMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(MetricsMiddlewareService { service: Rc::new(service) })
+    }
+}
+
+pub struct MetricsMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for MetricsMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let method = request.method().to_string();
+        let route = request.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = service.call(request).await;
+
+            let status = match &result {
+                Ok(response) => response.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            record(&method, &route, status, started);
+
+            result
+        })
+    }
+}
+
+/// リクエスト数と処理時間を記録
+fn record(method: &str, route: &str, status: StatusCode, started: Instant) {
+    let status = status.as_str();
+
+    HTTP_REQUESTS_TOTAL.with_label_values(&[method, route, status]).inc();
+    HTTP_REQUEST_DURATION_SECONDS
+        .with_label_values(&[method, route, status])
+        .observe(started.elapsed().as_secs_f64());
+}
\ No newline at end of file
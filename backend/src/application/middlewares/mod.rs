@@ -1 +1,8 @@
-pub mod jwt_middleware;
\ No newline at end of file
+pub mod jwt_middleware;
+pub mod locale_middleware;
+pub mod login_rate_limit_middleware;
+pub mod metrics_middleware;
+pub mod permission_middleware;
+pub mod registered_user_middleware;
+pub mod request_id_middleware;
+pub mod verified_email_middleware;
\ No newline at end of file
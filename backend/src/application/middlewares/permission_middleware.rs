@@ -0,0 +1,90 @@
+//! # 権限ミドルウェア
+//!
+//! JWT のロールに必要な操作の権限がない場合は `Forbidden` を返す
+//! ルート・スコープ単位で `.wrap(RequirePermission::new(Permission::ManageUsers))` のように使用する
+//!
+//! ロールが DB と一致することは `AuthenticatedUser` の検証で確認済みのため、ここでは JWT のロールのみを参照する
+
+use std::rc::Rc;
+use actix_web::{body::EitherBody, dev};
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    Error,
+    ResponseError,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use crate::application::errors::api_error::ApiError;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::domain::enums::role::Permission;
+use crate::{app_log, error_log};
+
+pub struct RequirePermission {
+    permission: Permission,
+}
+
+impl RequirePermission {
+    /// 必要な操作の権限を指定
+    pub fn new(permission: Permission) -> Self {
+        RequirePermission { permission }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequirePermissionService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequirePermissionService { service: Rc::new(service), permission: self.permission })
+    }
+}
+
+pub struct RequirePermissionService<S> {
+    service: Rc<S>,
+    permission: Permission,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let permission = self.permission;
+
+        Box::pin(async move {
+            let response = match AuthenticatedUser::authenticate(request.request()).await {
+                Some(user) if user.role.has_permission(permission) => {
+                    return service.call(request).await.map(ServiceResponse::map_into_left_body);
+                }
+                Some(user) => {
+                    error_log!(
+                        "[permission_middleware] - [call] message: permission denied user_id = {}, role = {}, permission = {:?}",
+                        user.id, user.role.as_str(), permission
+                    );
+                    ApiError::Forbidden.error_response()
+                }
+                None => ApiError::Unauthorized.error_response(),
+            };
+
+            let (request, _pl) = request.into_parts();
+            Ok(ServiceResponse::new(request, response.map_into_right_body()))
+        })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+//! # 登録済みユーザーミドルウェア
+//!
+//! ゲスト（`Role::Guest`）のリクエストを拒否し、`Forbidden` を返す
+//! メール認証・パスワード変更・2段階認証など、メールアドレスとパスワードが必要な操作のルートに `.wrap(RequireRegisteredUser)` で使用する
+//!
+//! ロールが DB と一致することは `AuthenticatedUser` の検証で確認済みのため、ここでは JWT のロールのみを参照する
+
+use std::rc::Rc;
+use actix_web::{body::EitherBody, dev};
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    Error,
+    ResponseError,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use crate::application::errors::api_error::ApiError;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::domain::enums::role::Role;
+use crate::{app_log, error_log};
+
+pub struct RequireRegisteredUser;
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRegisteredUser
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireRegisteredUserService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequireRegisteredUserService { service: Rc::new(service) })
+    }
+}
+
+pub struct RequireRegisteredUserService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRegisteredUserService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let response = match AuthenticatedUser::authenticate(request.request()).await {
+                Some(user) if user.role != Role::Guest => {
+                    return service.call(request).await.map(ServiceResponse::map_into_left_body);
+                }
+                Some(user) => {
+                    error_log!("[registered_user_middleware] - [call] message: guest not allowed user_id = {}", user.id);
+                    ApiError::GuestNotAllowed.error_response()
+                }
+                None => ApiError::Unauthorized.error_response(),
+            };
+
+            let (request, _pl) = request.into_parts();
+            Ok(ServiceResponse::new(request, response.map_into_right_body()))
+        })
+    }
+}
\ No newline at end of file
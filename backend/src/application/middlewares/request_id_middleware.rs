@@ -0,0 +1,131 @@
+//! # リクエストIDミドルウェア
+//!
+//! リクエストごとに ID を割り当て、処理中に出力する全てのログに `request_id` として付与する
+//! クライアント・プロキシが `X-Request-Id` を指定した場合はその値を使用し、ない場合（または不正な値の場合）は生成する
+//! レスポンスには使用した ID を `X-Request-Id` で返し、処理の完了をステータスと処理時間とともにログに出力する
+
+use std::rc::Rc;
+use std::time::Instant;
+use actix_web::dev;
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// リクエストIDのヘッダー
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 指定されたリクエストIDの最大長
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// リクエストID
+///
+/// ログへの不正な文字の混入を防ぐため、英数字と `-` `_` `.` `:` のみの値を受け付ける
+///
+/// # 引数
+///
+/// * `header` - `X-Request-Id` の値
+///
+/// # 戻り値
+///
+/// * `String` - 指定された値、または生成した UUID
+pub fn request_id(header: Option<&HeaderValue>) -> String {
+    header
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| {
+            !value.is_empty()
+                && value.len() <= MAX_REQUEST_ID_LENGTH
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdMiddlewareService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestIdMiddlewareService { service: Rc::new(service) })
+    }
+}
+
+pub struct RequestIdMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let request_id = request_id(request.headers().get(&REQUEST_ID_HEADER));
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id,
+            method = %request.method(),
+            path = %request.path(),
+        );
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let header = HeaderValue::from_str(&request_id).ok();
+
+            match service.call(request).await {
+                Ok(mut response) => {
+                    tracing::info!(
+                        status = response.status().as_u16(),
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "request completed"
+                    );
+
+                    if let Some(header) = header {
+                        response.headers_mut().insert(REQUEST_ID_HEADER, header);
+                    }
+
+                    Ok(response)
+                }
+                // 内側のミドルウェアのエラー（認証エラーなど）も、レスポンスに変換してリクエストIDを返す
+                Err(err) => {
+                    let mut response = err.error_response();
+                    tracing::info!(
+                        status = response.status().as_u16(),
+                        elapsed_ms = started.elapsed().as_millis() as u64,
+                        "request completed"
+                    );
+
+                    if let Some(header) = header {
+                        response.headers_mut().insert(REQUEST_ID_HEADER, header);
+                    }
+
+                    Err(InternalError::from_response(err.to_string(), response).into())
+                }
+            }
+        }.instrument(span))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,99 @@
+//! # メール認証済みミドルウェア
+//!
+//! メールアドレスが未認証のユーザーのリクエストを拒否し、`Forbidden` を返す
+//! `AppConfig` の `[app]` の `require_verified_email` が `true` の場合のみ有効
+//!
+//! ゲストはメールアドレスを持たないため対象外とし、アカウント登録前でもタスクの作成などを試せるようにする
+
+use std::rc::Rc;
+use actix_web::{body::EitherBody, dev, web};
+use actix_service::Service;
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse, Transform},
+    Error,
+    ResponseError,
+};
+use futures::future::{ok, Ready, LocalBoxFuture};
+use crate::application::errors::api_error::ApiError;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::application::states::app_state::AppState;
+use crate::domain::enums::role::Role;
+
+pub struct RequireVerifiedEmail;
+
+impl<S, B> Transform<S, ServiceRequest> for RequireVerifiedEmail
+    where
+        S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+        S::Future: 'static,
+        B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequireVerifiedEmailService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequireVerifiedEmailService { service: Rc::new(service) })
+    }
+}
+
+pub struct RequireVerifiedEmailService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireVerifiedEmailService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let enabled = request
+            .app_data::<web::Data<AppState>>()
+            .is_some_and(|app_state| app_state.config.app.require_verified_email);
+
+        if !enabled {
+            return Box::pin(async move {
+                service.call(request).await.map(ServiceResponse::map_into_left_body)
+            });
+        }
+
+        Box::pin(async move {
+            // 未ログインの場合はハンドラー側で `Unauthorized` を返す。ゲストは確認しない
+            let claims = match AuthenticatedUser::authenticate(request.request()).await {
+                Some(claims) if claims.role != Role::Guest => claims,
+                _ => return service.call(request).await.map(ServiceResponse::map_into_left_body),
+            };
+
+            let is_verified = match request.app_data::<web::Data<AppState>>() {
+                Some(app_state) => app_state.auth_service.is_email_verified(claims.id).await,
+                None => Ok(false),
+            };
+
+            match is_verified {
+                Ok(true) => service.call(request).await.map(ServiceResponse::map_into_left_body),
+                Ok(false) => {
+                    let (request, _pl) = request.into_parts();
+                    let response = ApiError::EmailNotVerified.error_response().map_into_right_body();
+
+                    Ok(ServiceResponse::new(request, response))
+                }
+                Err(error) => {
+                    let (request, _pl) = request.into_parts();
+                    let response = ApiError::from(error).error_response().map_into_right_body();
+
+                    Ok(ServiceResponse::new(request, response))
+                }
+            }
+        })
+    }
+}
\ No newline at end of file
@@ -1,7 +1,12 @@
 pub mod errors;
 pub mod helpers;
+pub mod i18n;
 pub mod jwt;
+pub mod mail;
+pub mod metrics;
 pub mod middlewares;
+pub mod oidc;
 pub mod states;
 pub mod types;
-pub mod use_cases;
\ No newline at end of file
+pub mod use_cases;
+pub mod workers;
\ No newline at end of file
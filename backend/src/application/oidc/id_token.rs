@@ -0,0 +1,210 @@
+//! # ID トークン
+//!
+//! プロバイダーが発行した ID トークン（JWT）を、プロバイダーの公開鍵（JWKS）で検証する
+//!
+//! * 署名アルゴリズムは RS256 / RS384 / RS512 / ES256 / EdDSA (Ed25519)。`none` と共通鍵の署名は受け付けない
+//! * `iss` / `aud` / `azp` / `exp` / `nonce` を検証する
+//!
+//! ## 関数
+//!
+//! - `token_kid`:       ヘッダーの `kid`（JWKS の再取得の判定に使用）
+//! - `verify_id_token`: 署名とクレームを検証してデコード
+
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::application::errors::oidc_error::OidcError;
+
+/// 有効期限の検証で許容する時刻のずれ（秒）
+const LEEWAY_SECS: i64 = 60;
+
+/// プロバイダーの公開鍵の一覧 (JWK Set)
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+/// プロバイダーの公開鍵
+///
+/// * `n` / `e`         - RSA の公開鍵
+/// * `crv` / `x` / `y` - 楕円曲線（`P-256`）・Ed25519（`Ed25519`）の公開鍵
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    #[serde(rename = "use")]
+    pub key_use: Option<String>,
+    pub n: Option<String>,
+    pub e: Option<String>,
+    pub crv: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+}
+
+/// ID トークンのクレーム
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub aud: Vec<String>,
+    pub azp: Option<String>,
+    pub exp: i64,
+    pub nonce: Option<String>,
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "bool_or_string")]
+    pub email_verified: Option<bool>,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+}
+
+/// ID トークンの検証に使用する値
+///
+/// * `issuer`    - ディスカバリーの `issuer`
+/// * `client_id` - クライアント ID（`aud` に含まれる必要がある）
+/// * `nonce`     - 認可リクエストで送信した `nonce`
+/// * `now`       - 現在時刻（UNIX 時間）
+pub struct IdTokenExpectation<'a> {
+    pub issuer: &'a str,
+    pub client_id: &'a str,
+    pub nonce: &'a str,
+    pub now: i64,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// ヘッダーの `kid`
+///
+/// JWKS のキャッシュに `kid` の鍵がない場合は、鍵がローテーションされたとみなして再取得する
+pub fn token_kid(token: &str) -> Option<String> {
+    let header = token.split('.').next()?;
+
+    decode_json::<Header>(header).ok()?.kid
+}
+
+/// 署名とクレームを検証してデコード
+///
+/// # 引数
+///
+/// * `token`    - ID トークン
+/// * `jwks`     - プロバイダーの公開鍵の一覧
+/// * `expected` - 検証に使用する値
+///
+/// # 戻り値
+///
+/// * `Result<IdTokenClaims, OidcError>` - 署名・クレームが正しくない場合は `OidcError::IdToken`
+pub fn verify_id_token(token: &str, jwks: &JwkSet, expected: &IdTokenExpectation) -> Result<IdTokenClaims, OidcError> {
+    let (message, signature) = token.rsplit_once('.').ok_or_else(|| invalid("malformed token"))?;
+    let (header, payload) = message.split_once('.').ok_or_else(|| invalid("malformed token"))?;
+    let header: Header = decode_json(header)?;
+    let signature = decode_base64(signature)?;
+
+    // `kid` がない場合は、アルゴリズムに対応する全ての鍵で検証する
+    let verified = jwks.keys
+        .iter()
+        .filter(|jwk| header.kid.is_none() || jwk.kid == header.kid)
+        .filter(|jwk| jwk.key_use.as_deref().is_none_or(|key_use| key_use == "sig"))
+        .filter(|jwk| jwk.alg.as_deref().is_none_or(|alg| alg == header.alg))
+        .any(|jwk| verify_signature(&header.alg, jwk, message.as_bytes(), &signature));
+    if !verified {
+        return Err(invalid(&format!("signature verification failed (alg = {}, kid = {:?})", header.alg, header.kid)));
+    }
+
+    let claims: IdTokenClaims = decode_json(payload)?;
+    if claims.iss != expected.issuer {
+        return Err(invalid(&format!("unexpected issuer: {}", claims.iss)));
+    }
+    if !claims.aud.iter().any(|aud| aud == expected.client_id) {
+        return Err(invalid("audience does not contain the client id"));
+    }
+    // 複数の対象者に発行されたトークンは、`azp` がこのクライアントの場合のみ受け付ける
+    if (claims.aud.len() > 1 || claims.azp.is_some()) && claims.azp.as_deref() != Some(expected.client_id) {
+        return Err(invalid("authorized party is not the client id"));
+    }
+    if claims.exp + LEEWAY_SECS < expected.now {
+        return Err(invalid("token expired"));
+    }
+    if claims.nonce.as_deref() != Some(expected.nonce) {
+        return Err(invalid("nonce mismatch"));
+    }
+
+    Ok(claims)
+}
+
+fn verify_signature(alg: &str, jwk: &Jwk, message: &[u8], signature: &[u8]) -> bool {
+    let decode = |value: &Option<String>| value.as_deref().and_then(|value| decode_base64(value).ok());
+
+    match (alg, jwk.kty.as_str()) {
+        ("RS256" | "RS384" | "RS512", "RSA") => {
+            let (Some(n), Some(e)) = (decode(&jwk.n), decode(&jwk.e)) else {
+                return false;
+            };
+            let params = match alg {
+                "RS256" => &signature::RSA_PKCS1_2048_8192_SHA256,
+                "RS384" => &signature::RSA_PKCS1_2048_8192_SHA384,
+                _ => &signature::RSA_PKCS1_2048_8192_SHA512,
+            };
+
+            RsaPublicKeyComponents { n, e }.verify(params, message, signature).is_ok()
+        }
+        ("ES256", "EC") if jwk.crv.as_deref() == Some("P-256") => {
+            let (Some(x), Some(y)) = (decode(&jwk.x), decode(&jwk.y)) else {
+                return false;
+            };
+            // 非圧縮形式の点（0x04 || x || y）
+            let public_key = [&[0x04][..], &x, &y].concat();
+
+            UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, public_key).verify(message, signature).is_ok()
+        }
+        ("EdDSA", "OKP") if jwk.crv.as_deref() == Some("Ed25519") => {
+            let Some(x) = decode(&jwk.x) else {
+                return false;
+            };
+
+            UnparsedPublicKey::new(&signature::ED25519, x).verify(message, signature).is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, OidcError> {
+    base64::decode_config(input, base64::URL_SAFE_NO_PAD).map_err(|_| invalid("invalid base64url"))
+}
+
+fn decode_json<T: for<'de> Deserialize<'de>>(input: &str) -> Result<T, OidcError> {
+    serde_json::from_slice(&decode_base64(input)?).map_err(|err| invalid(&err.to_string()))
+}
+
+fn invalid(message: &str) -> OidcError {
+    OidcError::IdToken(message.to_string())
+}
+
+/// `aud` は文字列、または文字列の配列
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(aud) => vec![aud],
+        OneOrMany::Many(aud) => aud,
+    })
+}
+
+/// `email_verified` を文字列（`"true"`）で返すプロバイダーがあるため、どちらも受け付ける
+pub fn bool_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Bool(value)) => Some(value),
+        Some(Value::String(value)) => Some(value == "true"),
+        _ => None,
+    })
+}
\ No newline at end of file
@@ -0,0 +1,2 @@
+pub mod id_token;
+pub mod oidc_client;
\ No newline at end of file
@@ -0,0 +1,348 @@
+//! # OpenID Connect のクライアント
+//!
+//! 認可コードフロー（PKCE・S256）でプロバイダーのユーザー情報を取得する
+//! プロバイダーは `AppConfig` の `[oidc.providers.{name}]` の発行者 URL から、ディスカバリーでエンドポイントを取得する
+//!
+//! * ディスカバリーと JWKS は一定時間キャッシュし、ID トークンの `kid` の鍵がない場合は JWKS を再取得する
+//! * クライアントシークレットがある場合は、ディスカバリーの `token_endpoint_auth_methods_supported` に従い
+//!   `client_secret_post` または `client_secret_basic` で送信する
+//!
+//! ## メソッド
+//!
+//! - `OidcProviders::from_settings`: `[oidc]` の設定から作成
+//! - `OidcProviders::get`:           名前でプロバイダーを取得
+//! - `OidcProviders::names`:         プロバイダーの名前の一覧
+//! - `authorization_request`:        認可リクエストの URL と、コールバックで使用する値を作成
+//! - `exchange_code`:                認可コードをトークンに交換し、ID トークンを検証してユーザー情報を取得
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use rand::RngCore;
+use rand::rngs::OsRng;
+use reqwest::{Client, RequestBuilder, Url};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+use crate::application::errors::oidc_error::OidcError;
+use crate::application::oidc::id_token::{bool_or_string, token_kid, verify_id_token, IdTokenExpectation, JwkSet};
+use crate::domain::entities::oidc::{OidcIdentity, OidcTokens};
+use crate::infrastructure::config::app_config::{OidcProviderSettings, OidcSettings};
+
+/// ディスカバリー・JWKS をキャッシュする時間
+const METADATA_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// state・nonce・PKCE の検証コードのバイト数
+const RANDOM_BYTES: usize = 32;
+
+/// 設定されているプロバイダー
+pub struct OidcProviders {
+    providers: BTreeMap<String, Arc<OidcClient>>,
+}
+
+impl OidcProviders {
+    /// `AppConfig` の `[oidc]` から作成
+    ///
+    /// # 引数
+    ///
+    /// * `settings` - `[oidc]` の設定
+    /// * `app_url`  - フロントエンドの URL（`redirect_base_url` が未指定の場合のリダイレクト先）
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<Self, OidcError>` - HTTP クライアントを作成できない場合はエラー
+    pub fn from_settings(settings: &OidcSettings, app_url: &str) -> Result<Self, OidcError> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(settings.http_timeout_secs))
+            .build()?;
+        let redirect_base_url = settings.redirect_base_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/auth/callback", app_url.trim_end_matches('/')));
+
+        let providers = settings.providers
+            .iter()
+            .map(|(name, provider)| {
+                let redirect_uri = format!("{}/{}", redirect_base_url.trim_end_matches('/'), name);
+                (name.clone(), Arc::new(OidcClient::new(provider.clone(), redirect_uri, http.clone())))
+            })
+            .collect();
+
+        Ok(OidcProviders { providers })
+    }
+
+    /// 名前でプロバイダーを取得
+    pub fn get(&self, name: &str) -> Option<Arc<OidcClient>> {
+        self.providers.get(name).cloned()
+    }
+
+    /// プロバイダーの名前の一覧
+    pub fn names(&self) -> Vec<String> {
+        self.providers.keys().cloned().collect()
+    }
+}
+
+/// 認可リクエスト
+///
+/// * `url`           - プロバイダーの認可エンドポイントの URL
+/// * `state`         - コールバックで照合する値（DB にはハッシュを保存する）
+/// * `nonce`         - ID トークンの `nonce` と照合する値
+/// * `code_verifier` - PKCE の検証コード（トークンの交換で送信する）
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub nonce: String,
+    pub code_verifier: String,
+}
+
+/// ディスカバリーで取得するプロバイダーのメタデータ
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: Option<String>,
+    jwks_uri: String,
+    #[serde(default)]
+    token_endpoint_auth_methods_supported: Vec<String>,
+}
+
+/// トークンエンドポイントのレスポンス
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: Option<String>,
+    expires_in: Option<i64>,
+    refresh_token: Option<String>,
+    id_token: Option<String>,
+    scope: Option<String>,
+}
+
+/// トークンエンドポイント・UserInfo エンドポイントのエラーレスポンス
+#[derive(Debug, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// UserInfo エンドポイントのレスポンス
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default, deserialize_with = "bool_or_string")]
+    email_verified: Option<bool>,
+    name: Option<String>,
+    picture: Option<String>,
+}
+
+/// 取得した時刻とキャッシュしている値
+type Cached<T> = Mutex<Option<(Instant, Arc<T>)>>;
+
+/// プロバイダーのクライアント
+pub struct OidcClient {
+    settings: OidcProviderSettings,
+    redirect_uri: String,
+    http: Client,
+    metadata: Cached<ProviderMetadata>,
+    jwks: Cached<JwkSet>,
+}
+
+impl OidcClient {
+    fn new(settings: OidcProviderSettings, redirect_uri: String, http: Client) -> Self {
+        OidcClient { settings, redirect_uri, http, metadata: Mutex::new(None), jwks: Mutex::new(None) }
+    }
+
+    /// 認可リクエストの URL と、コールバックで使用する値を作成
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<AuthorizationRequest, OidcError>` - ディスカバリーに失敗した場合はエラー
+    pub async fn authorization_request(&self) -> Result<AuthorizationRequest, OidcError> {
+        let metadata = self.metadata().await?;
+        let (state, nonce, code_verifier) = (random_string(), random_string(), random_string());
+
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|err| OidcError::Discovery(format!("invalid authorization_endpoint: {}", err)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.settings.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &self.scope())
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &pkce_challenge(&code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        Ok(AuthorizationRequest { url: url.into(), state, nonce, code_verifier })
+    }
+
+    /// 認可コードをトークンに交換し、ID トークンを検証してユーザー情報を取得
+    ///
+    /// ID トークンにメールアドレスが含まれない場合は、UserInfo エンドポイントから取得する
+    ///
+    /// # 引数
+    ///
+    /// * `code`          - リダイレクト先で受け取った認可コード
+    /// * `code_verifier` - 認可リクエストで作成した PKCE の検証コード
+    /// * `nonce`         - 認可リクエストで作成した `nonce`
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<OidcIdentity, OidcError>` - プロバイダーのユーザー情報とトークン
+    pub async fn exchange_code(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<OidcIdentity, OidcError> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_uri),
+            ("code_verifier", code_verifier),
+            ("client_id", &self.settings.client_id),
+        ];
+        let mut request = self.http.post(&metadata.token_endpoint);
+        if let Some(client_secret) = &self.settings.client_secret {
+            // `client_secret_post` に対応していないプロバイダーのみ Basic 認証で送信する
+            let methods = &metadata.token_endpoint_auth_methods_supported;
+            if methods.is_empty() || methods.iter().any(|method| method == "client_secret_post") {
+                form.push(("client_secret", client_secret));
+            } else {
+                request = request.basic_auth(&self.settings.client_id, Some(client_secret));
+            }
+        }
+        let token: TokenResponse = send_json(request.form(&form)).await?;
+        let id_token = token.id_token.ok_or_else(|| OidcError::Provider("id_token is missing in the token response".to_string()))?;
+
+        let expected = IdTokenExpectation {
+            issuer: &metadata.issuer,
+            client_id: &self.settings.client_id,
+            nonce,
+            now: Utc::now().timestamp(),
+        };
+        // 鍵がローテーションされた場合は、JWKS を再取得する
+        let mut jwks = self.jwks(&metadata, false).await?;
+        if token_kid(&id_token).is_some_and(|kid| !jwks.keys.iter().any(|jwk| jwk.kid.as_deref() == Some(&kid))) {
+            jwks = self.jwks(&metadata, true).await?;
+        }
+        let claims = verify_id_token(&id_token, &jwks, &expected)?;
+
+        let mut identity = OidcIdentity {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified.unwrap_or(false),
+            name: claims.name,
+            picture: claims.picture,
+            tokens: OidcTokens {
+                access_token: token.access_token,
+                refresh_token: token.refresh_token,
+                expires_at: token.expires_in.map(|expires_in| Utc::now() + chrono::Duration::seconds(expires_in)),
+                token_type: token.token_type,
+                scope: token.scope,
+                id_token,
+            },
+        };
+
+        if let (None, Some(userinfo_endpoint)) = (&identity.email, &metadata.userinfo_endpoint) {
+            let userinfo: UserInfo = send_json(self.http.get(userinfo_endpoint).bearer_auth(&identity.tokens.access_token)).await?;
+            // 他のユーザーの情報を取得していないか、ID トークンの `sub` と照合する
+            if userinfo.sub != identity.subject {
+                return Err(OidcError::Provider("userinfo sub does not match the id_token".to_string()));
+            }
+            identity.email = userinfo.email;
+            identity.email_verified = userinfo.email_verified.unwrap_or(false);
+            identity.name = identity.name.or(userinfo.name);
+            identity.picture = identity.picture.or(userinfo.picture);
+        }
+
+        Ok(identity)
+    }
+
+    /// 要求するスコープ（`openid` は常に含める）
+    fn scope(&self) -> String {
+        let mut scopes = vec!["openid"];
+        scopes.extend(self.settings.scopes.iter().map(String::as_str).filter(|scope| *scope != "openid"));
+
+        scopes.join(" ")
+    }
+
+    async fn metadata(&self) -> Result<Arc<ProviderMetadata>, OidcError> {
+        if let Some(metadata) = cached(&self.metadata) {
+            return Ok(metadata);
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", self.settings.issuer.trim_end_matches('/'));
+        let metadata: ProviderMetadata = send_json(self.http.get(&url)).await?;
+        // 他の発行者のメタデータを使用しないよう、設定の発行者と照合する
+        if metadata.issuer.trim_end_matches('/') != self.settings.issuer.trim_end_matches('/') {
+            return Err(OidcError::Discovery(format!("issuer mismatch: {}", metadata.issuer)));
+        }
+
+        Ok(store(&self.metadata, metadata))
+    }
+
+    async fn jwks(&self, metadata: &ProviderMetadata, refresh: bool) -> Result<Arc<JwkSet>, OidcError> {
+        if let Some(jwks) = cached(&self.jwks).filter(|_| !refresh) {
+            return Ok(jwks);
+        }
+
+        let jwks: JwkSet = send_json(self.http.get(&metadata.jwks_uri)).await?;
+
+        Ok(store(&self.jwks, jwks))
+    }
+}
+
+/// リクエストを送信して JSON のレスポンスをデコード
+///
+/// エラーのステータスの場合は、OAuth 2.0 のエラーレスポンス（`error` / `error_description`）を `OidcError::Provider` にする
+async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, OidcError> {
+    let response = request.header(reqwest::header::ACCEPT, "application/json").send().await?;
+    let status = response.status();
+
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(OidcError::Provider(match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error) => format!("{} {}: {}", status, error.error, error.error_description.unwrap_or_default()),
+            Err(_) => format!("{}", status),
+        }));
+    }
+
+    Ok(response.json().await?)
+}
+
+fn cached<T>(cache: &Cached<T>) -> Option<Arc<T>> {
+    let cache = cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    cache.as_ref()
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < METADATA_TTL)
+        .map(|(_, value)| value.clone())
+}
+
+fn store<T>(cache: &Cached<T>, value: T) -> Arc<T> {
+    let value = Arc::new(value);
+    *cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((Instant::now(), value.clone()));
+
+    value
+}
+
+/// URL に使用できるランダムな文字列（base64url）
+fn random_string() -> String {
+    let mut bytes = [0u8; RANDOM_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+/// PKCE の `code_challenge`（S256）
+///
+/// # 引数
+///
+/// * `code_verifier` - 検証コード
+///
+/// # 戻り値
+///
+/// * `String` - 検証コードの SHA-256 ハッシュの base64url
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    base64::encode_config(Sha256::digest(code_verifier.as_bytes()), base64::URL_SAFE_NO_PAD)
+}
\ No newline at end of file
@@ -4,43 +4,136 @@
 //! 各サービスは DI に基づいて初期化され、`AppState` を通じてアクセス可能
 
 use std::sync::Arc;
-use bb8_postgres::{bb8::Pool, PostgresConnectionManager};
-use postgres::NoTls;
+use std::time::Duration;
 use crate::{
-    application::types::di_type::{AuthServiceArc, TaskServiceArc, UserServiceArc},
+    application::helpers::password::PasswordHasher,
+    application::jwt::jwt_keys::JwtKeys,
+    application::oidc::oidc_client::OidcProviders,
+    application::types::di_type::{
+        AuthServiceArc, HealthServiceArc, LoginGuardServiceArc, MailerArc, OidcServiceArc, OutboxRepositoryArc, OutboxServiceArc, TaskServiceArc,
+        ThrottleStoreArc, UserServiceArc
+    },
+    application::workers::supervisor::Supervisor,
     domain::services::auth_service::AuthServiceImpl,
+    domain::services::health_service::HealthServiceImpl,
+    domain::services::login_guard_service::LoginGuardServiceImpl,
+    domain::services::oidc_service::OidcServiceImpl,
+    domain::services::outbox_service::OutboxServiceImpl,
     domain::services::task_service::TaskServiceImpl,
     domain::services::user_service::UserServiceImpl,
+    infrastructure::config::app_config::{AppConfig, ThrottleStoreKind},
+    infrastructure::db::connection::DbPool,
     infrastructure::repositories::auth_repository::AuthRepositoryImpl,
+    infrastructure::repositories::login_failure_repository::LoginFailureRepositoryImpl,
+    infrastructure::repositories::memory_throttle_store::MemoryThrottleStore,
+    infrastructure::repositories::mfa_repository::MfaRepositoryImpl,
+    infrastructure::repositories::oidc_repository::OidcRepositoryImpl,
+    infrastructure::repositories::outbox_repository::OutboxRepositoryImpl,
+    infrastructure::repositories::pg_throttle_store::PgThrottleStore,
+    infrastructure::repositories::session_repository::SessionRepositoryImpl,
     infrastructure::repositories::task_repository::TaskRepositoryImpl,
     infrastructure::repositories::user_repository::UserRepositoryImpl
 };
 
 #[derive(Clone)]
 pub struct AppState {
+    /// 起動時に読み込んだ設定
+    pub config: Arc<AppConfig>,
+
     /// 認証サービス
     pub auth_service: AuthServiceArc,
 
+    /// 外部の ID プロバイダー（OpenID Connect）のログイン・アカウント連携サービス
+    pub oidc_service: OidcServiceArc,
+
+    /// ログイン保護サービス（ログインのミドルウェア・管理者 API が使用する）
+    pub login_guard_service: LoginGuardServiceArc,
+
     /// タスク管理サービス
     pub task_service: TaskServiceArc,
 
     /// ユーザー管理サービス
-    pub user_service: UserServiceArc
+    pub user_service: UserServiceArc,
+
+    /// メール送信キューサービス
+    pub outbox_service: OutboxServiceArc,
+
+    /// ヘルスチェックサービス
+    pub health_service: HealthServiceArc,
+
+    /// JWT の署名鍵・検証鍵
+    pub jwt_keys: Arc<JwtKeys>,
+
+    /// メール送信（メール送信キューのワーカーが使用する）
+    pub mailer: MailerArc,
+
+    /// メール送信キュー（メール送信キューのワーカーが使用する）
+    pub outbox_repository: OutboxRepositoryArc,
+
+    /// バックグラウンドジョブの管理
+    pub supervisor: Arc<Supervisor>
 }
 
 impl AppState {
-    pub fn init(pool: &Pool<PostgresConnectionManager<NoTls>>) -> AppState {
+    pub fn init(
+        config: Arc<AppConfig>,
+        pool: &DbPool,
+        jwt_keys: JwtKeys,
+        password_hasher: PasswordHasher,
+        oidc_providers: OidcProviders,
+        mailer: MailerArc
+    ) -> AppState {
+        let jwt_keys = Arc::new(jwt_keys);
+        let password_hasher = Arc::new(password_hasher);
         let auth_repository= Arc::new(AuthRepositoryImpl::new(pool.clone()));
+        let session_repository= Arc::new(SessionRepositoryImpl::new(pool.clone()));
+        let mfa_repository= Arc::new(MfaRepositoryImpl::new(pool.clone()));
+        let oidc_repository= Arc::new(OidcRepositoryImpl::new(pool.clone()));
         let task_repository= Arc::new(TaskRepositoryImpl::new(pool.clone()));
         let user_repository= Arc::new(UserRepositoryImpl::new(pool.clone()));
-        let user_service = Arc::new(UserServiceImpl::new(user_repository.clone()));
-        let auth_service= Arc::new(AuthServiceImpl::new(auth_repository.clone()));
+        let outbox_repository= Arc::new(OutboxRepositoryImpl::new(pool.clone()));
+        let login_failure_repository= Arc::new(LoginFailureRepositoryImpl::new(pool.clone()));
+        let throttle_store: ThrottleStoreArc = match config.login_protection.store {
+            ThrottleStoreKind::Memory => Arc::new(MemoryThrottleStore::new()),
+            ThrottleStoreKind::Postgres => Arc::new(PgThrottleStore::new(pool.clone())),
+        };
+        let login_guard_service= Arc::new(LoginGuardServiceImpl::new(throttle_store, login_failure_repository, config.login_protection.clone()));
+        let user_service = Arc::new(UserServiceImpl::new(user_repository.clone(), login_guard_service.clone(), password_hasher.clone(), jwt_keys.clone()));
+        let auth_service= Arc::new(AuthServiceImpl::new(
+            auth_repository.clone(),
+            session_repository.clone(),
+            mfa_repository,
+            login_guard_service.clone(),
+            password_hasher.clone(),
+            jwt_keys.clone(),
+            &config
+        ));
+        let oidc_service= Arc::new(OidcServiceImpl::new(
+            oidc_repository,
+            auth_repository.clone(),
+            auth_service.clone(),
+            Arc::new(oidc_providers),
+            password_hasher,
+            config.oidc.clone()
+        ));
         let task_service= Arc::new(TaskServiceImpl::new(task_repository.clone(), user_service.clone()));
+        let outbox_service= Arc::new(OutboxServiceImpl::new(outbox_repository.clone()));
+        let health_service= Arc::new(HealthServiceImpl::new(pool.clone(), mailer.clone(), &config.health));
+        let supervisor = Arc::new(Supervisor::new(Duration::from_secs(config.server.shutdown_timeout_secs)));
 
         AppState {
+            config,
             auth_service,
+            oidc_service,
+            login_guard_service,
             task_service,
-            user_service
+            user_service,
+            outbox_service,
+            health_service,
+            jwt_keys,
+            mailer,
+            outbox_repository,
+            supervisor
         }
     }
 }
@@ -4,10 +4,21 @@
 
 use std::sync::Arc;
 use crate::{
+    application::mail::mailer::Mailer,
     domain::repositories::auth_repository::AuthRepository,
+    domain::repositories::login_failure_repository::LoginFailureRepository,
+    domain::repositories::mfa_repository::MfaRepository,
+    domain::repositories::oidc_repository::OidcRepository,
+    domain::repositories::outbox_repository::OutboxRepository,
+    domain::repositories::session_repository::SessionRepository,
     domain::repositories::task_repository::TaskRepository,
+    domain::repositories::throttle_store::ThrottleStore,
     domain::repositories::user_repository::UserRepository,
     domain::services::auth_service::AuthService,
+    domain::services::health_service::HealthService,
+    domain::services::login_guard_service::LoginGuardService,
+    domain::services::oidc_service::OidcService,
+    domain::services::outbox_service::OutboxService,
     domain::services::task_service::TaskService,
     domain::services::user_service::UserService
 };
@@ -15,9 +26,22 @@ use crate::{
 // 認証
 pub type AuthServiceArc = Arc<dyn AuthService>;
 pub type AuthRepositoryArc = Arc<dyn AuthRepository>;
+pub type SessionRepositoryArc = Arc<dyn SessionRepository>;
+pub type MfaRepositoryArc = Arc<dyn MfaRepository>;
+pub type LoginGuardServiceArc = Arc<dyn LoginGuardService>;
+pub type LoginFailureRepositoryArc = Arc<dyn LoginFailureRepository>;
+pub type ThrottleStoreArc = Arc<dyn ThrottleStore>;
+pub type OidcServiceArc = Arc<dyn OidcService>;
+pub type OidcRepositoryArc = Arc<dyn OidcRepository>;
 // タスク
 pub type TaskServiceArc = Arc<dyn TaskService>;
 pub type TaskRepositoryArc = Arc<dyn TaskRepository>;
 // ユーザー
 pub type UserServiceArc = Arc<dyn UserService>;
-pub type UserRepositoryArc = Arc<dyn UserRepository>;
\ No newline at end of file
+pub type UserRepositoryArc = Arc<dyn UserRepository>;
+// メール
+pub type MailerArc = Arc<dyn Mailer>;
+pub type OutboxServiceArc = Arc<dyn OutboxService>;
+pub type OutboxRepositoryArc = Arc<dyn OutboxRepository>;
+// ヘルスチェック
+pub type HealthServiceArc = Arc<dyn HealthService>;
\ No newline at end of file
@@ -0,0 +1,61 @@
+//! # メール作成
+//!
+//! テンプレートから送信するメールを作成
+//! 件名・本文は受信者の言語で作成し、送信はメール送信キューのワーカーが行う
+//!
+//! ## 関数
+//!
+//! - `verification_mail`:  メール認証用のリンク
+//! - `reset_mail`:         パスワードリセット用のリンク
+//! - `task_reminder_mail`: タスクの期限のリマインダー
+
+use crate::application::helpers::link::app_link;
+use crate::application::i18n::request_locale::preferred_or_current;
+use crate::application::mail::mailer::Mail;
+use crate::application::mail::template::{render, MailTemplate};
+use crate::domain::entities::outbox::TaskReminder;
+use crate::domain::enums::locale::Locale;
+
+/// メール認証用のリンク
+///
+/// # 引数
+///
+/// * `email`  - 送信先のメールアドレス
+/// * `token`  - メール認証トークン
+/// * `locale` - メールの言語
+pub fn verification_mail(email: &str, token: &str, locale: Locale) -> Mail {
+    render(MailTemplate::Verification, locale, email, &[("link", &app_link("verify-email", token))])
+}
+
+/// パスワードリセット用のリンク
+///
+/// # 引数
+///
+/// * `email`  - 送信先のメールアドレス
+/// * `token`  - パスワードリセットトークン
+/// * `locale` - メールの言語
+pub fn reset_mail(email: &str, token: &str, locale: Locale) -> Mail {
+    render(MailTemplate::PasswordReset, locale, email, &[("link", &app_link("reset-password", token))])
+}
+
+/// タスクの期限のリマインダー
+///
+/// リクエスト外で作成するため、ユーザーが言語を設定していない場合は既定の言語を使用する
+///
+/// # 引数
+///
+/// * `reminder` - リマインダーを送信するタスク
+pub fn task_reminder_mail(reminder: &TaskReminder) -> Mail {
+    let due_date = reminder.due_date.format("%Y-%m-%d %H:%M UTC").to_string();
+
+    render(
+        MailTemplate::TaskReminder,
+        preferred_or_current(reminder.locale.as_deref()),
+        &reminder.email,
+        &[
+            ("title", &reminder.title),
+            ("due_date", &due_date),
+            ("link", &app_link("tasks", &reminder.task_id.to_string())),
+        ],
+    )
+}
\ No newline at end of file
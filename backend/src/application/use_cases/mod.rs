@@ -0,0 +1 @@
+pub mod mail_composer;
\ No newline at end of file
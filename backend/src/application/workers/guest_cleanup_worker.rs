@@ -0,0 +1,45 @@
+//! # ゲスト削除のワーカー
+//!
+//! 有効期限（`guest.ttl_hours`）までにアカウント登録しなかったゲストを削除する
+//! タスク・セッションなどのデータは外部キーの `ON DELETE CASCADE` で削除される
+//!
+//! ## 関数
+//!
+//! - `run`: ワーカーを実行
+
+use std::time::Duration;
+
+use crate::application::types::di_type::AuthServiceArc;
+use crate::application::workers::supervisor::JobContext;
+use crate::{app_log, error_log, info_log};
+
+/// ワーカーを実行
+///
+/// ゲストログインを無効にした後も、作成済みのゲストを削除するため常に実行する
+///
+/// # 引数
+///
+/// * `context`      - 停止の通知と実行結果の記録
+/// * `auth_service` - 認証サービス
+/// * `interval`     - 削除する間隔（`guest.cleanup_interval_secs`）
+pub async fn run(mut context: JobContext, auth_service: AuthServiceArc, interval: Duration) {
+    info_log!("[guest_cleanup_worker] - [run] started, interval = {:?}", interval);
+
+    loop {
+        let result = auth_service.prune_expired_guests().await;
+        match &result {
+            Ok(deleted) if *deleted > 0 => {
+                info_log!("[guest_cleanup_worker] - [run] deleted {} expired guests", deleted);
+            }
+            Ok(_) => {}
+            Err(err) => {
+                error_log!("[guest_cleanup_worker] - [run] - [message: Failed to delete expired guests] - Error: {}", err);
+            }
+        }
+        context.record_run(&result.map(|_| ()));
+
+        if !context.sleep(interval).await {
+            break;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+//! # ログイン保護のワーカー
+//!
+//! 期限切れのログイン試行のカウンターと、保存期間（`login_protection.history_retention_days`）を過ぎた失敗の履歴を削除する
+//!
+//! ## 関数
+//!
+//! - `run`: ワーカーを実行
+
+use std::time::Duration;
+
+use crate::application::types::di_type::LoginGuardServiceArc;
+use crate::application::workers::supervisor::JobContext;
+use crate::{app_log, error_log, info_log};
+
+/// 削除する間隔
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// ワーカーを実行
+///
+/// # 引数
+///
+/// * `context`             - 停止の通知と実行結果の記録
+/// * `login_guard_service` - ログイン保護サービス
+pub async fn run(mut context: JobContext, login_guard_service: LoginGuardServiceArc) {
+    info_log!("[login_protection_worker] - [run] started, interval = {:?}", PRUNE_INTERVAL);
+
+    loop {
+        let result = login_guard_service.prune().await;
+        if let Err(err) = &result {
+            error_log!("[login_protection_worker] - [run] - [message: Failed to prune login throttle] - Error: {}", err);
+        }
+        context.record_run(&result);
+
+        if !context.sleep(PRUNE_INTERVAL).await {
+            break;
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,5 @@
+pub mod guest_cleanup_worker;
+pub mod login_protection_worker;
+pub mod outbox_worker;
+pub mod supervisor;
+pub mod task_reminder_worker;
\ No newline at end of file
@@ -0,0 +1,134 @@
+//! # メール送信キューのワーカー
+//!
+//! `email_outbox` の送信日時を過ぎたメールを取得して送信する
+//! 送信に失敗したメールは指数バックオフで再送信し、上限に達したメールは `dead` として管理者の再送信を待つ
+//! 本文のリンク（トークン）の有効期限を過ぎたメールは送信せず、本文を削除して `dead` にする
+//!
+//! | `[outbox]` のキー    | 既定値 | 説明                                 |
+//! | -------------------- | ------ | ------------------------------------ |
+//! | `poll_interval_secs` | 5      | 送信待ちのメールを確認する間隔（秒） |
+//! | `batch_size`         | 20     | 一度に取得するメールの件数           |
+//! | `max_attempts`       | 8      | 送信回数の上限                       |
+//! | `backoff_base_secs`  | 30     | 最初の再送信までの待機時間（秒）     |
+//! | `backoff_max_secs`   | 3600   | 再送信までの待機時間の上限（秒）     |
+//! | `lease_secs`         | 300    | 取得したメールの処理期限（秒）       |
+//!
+//! ## 関数
+//!
+//! - `backoff`: 再送信までの待機時間
+//! - `run`:     ワーカーを実行
+
+use std::time::Duration as StdDuration;
+use chrono::{Duration, Utc};
+
+use crate::application::errors::outbox_error::OutboxError;
+use crate::application::metrics::registry::MAIL_DELIVERIES_TOTAL;
+use crate::application::types::di_type::{MailerArc, OutboxRepositoryArc};
+use crate::application::workers::supervisor::JobContext;
+use crate::infrastructure::config::app_config::OutboxSettings;
+use crate::{app_log, error_log, info_log, warning_log};
+
+/// ワーカーの設定
+#[derive(Debug, Clone)]
+pub struct OutboxWorkerConfig {
+    pub poll_interval: StdDuration,
+    pub batch_size: i64,
+    pub max_attempts: i32,
+    pub backoff_base_secs: i64,
+    pub backoff_max_secs: i64,
+    pub lease_secs: i64,
+}
+
+impl OutboxWorkerConfig {
+    /// `AppConfig` の `[outbox]` から作成
+    pub fn from_settings(settings: &OutboxSettings) -> Self {
+        OutboxWorkerConfig {
+            poll_interval: StdDuration::from_secs(settings.poll_interval_secs),
+            batch_size: settings.batch_size,
+            max_attempts: settings.max_attempts,
+            backoff_base_secs: settings.backoff_base_secs,
+            backoff_max_secs: settings.backoff_max_secs,
+            lease_secs: settings.lease_secs,
+        }
+    }
+}
+
+/// 再送信までの待機時間（秒）
+///
+/// 1回目の失敗後は `base_secs`、以降は失敗するたびに2倍にし、`max_secs` を上限とする
+///
+/// # 引数
+///
+/// * `attempts`  - 失敗した送信を含む送信回数
+/// * `base_secs` - 最初の再送信までの待機時間
+/// * `max_secs`  - 待機時間の上限
+///
+/// # 戻り値
+///
+/// * `i64` - 待機時間（秒）
+pub fn backoff(attempts: i32, base_secs: i64, max_secs: i64) -> i64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 62) as u32;
+
+    base_secs.saturating_mul(1_i64 << exponent).min(max_secs)
+}
+
+/// ワーカーを実行
+///
+/// 取得したメールは処理期限まで他のワーカーから取得されないため、複数のプロセスで実行できる
+/// 停止を要求された場合は、取得済みのメールを送信してから終了する
+///
+/// # 引数
+///
+/// * `context`           - 停止の通知と実行結果の記録
+/// * `outbox_repository` - メール送信キュー
+/// * `mailer`            - メール送信
+/// * `config`            - ワーカーの設定
+pub async fn run(mut context: JobContext, outbox_repository: OutboxRepositoryArc, mailer: MailerArc, config: OutboxWorkerConfig) {
+    info_log!("[outbox_worker] - [run] started, config = {:?}", config);
+
+    loop {
+        let result = process_batch(&outbox_repository, &mailer, &config).await;
+        if let Err(err) = &result {
+            error_log!("[outbox_worker] - [run] - [message: Failed to process outbox] - Error: {}", err);
+        }
+        context.record_run(&result);
+
+        if !context.sleep(config.poll_interval).await {
+            break;
+        }
+    }
+}
+
+/// 送信日時を過ぎたメールを送信
+async fn process_batch(outbox_repository: &OutboxRepositoryArc, mailer: &MailerArc, config: &OutboxWorkerConfig) -> Result<(), OutboxError> {
+    let expired = outbox_repository.discard_expired().await?;
+    if expired > 0 {
+        warning_log!("[outbox_worker] - [process_batch] - [message: Expired mail discarded] count = {}", expired);
+        MAIL_DELIVERIES_TOTAL.with_label_values(&["expired"]).inc_by(expired);
+    }
+
+    let lease_until = Utc::now() + Duration::seconds(config.lease_secs);
+    let messages = outbox_repository.claim_due(config.batch_size, lease_until).await?;
+
+    for message in messages {
+        match mailer.send(&message.mail).await {
+            Ok(()) => {
+                outbox_repository.mark_sent(message.id).await?;
+                MAIL_DELIVERIES_TOTAL.with_label_values(&["sent"]).inc();
+            }
+            Err(err) if message.attempts >= config.max_attempts => {
+                error_log!("[outbox_worker] - [process_batch] - [message: Mail moved to dead letter] id = {}, attempts = {} - Error: {}", message.id, message.attempts, err);
+                outbox_repository.mark_failed(message.id, &err.to_string(), None).await?;
+                MAIL_DELIVERIES_TOTAL.with_label_values(&["dead"]).inc();
+            }
+            Err(err) => {
+                let delay = backoff(message.attempts, config.backoff_base_secs, config.backoff_max_secs);
+                warning_log!("[outbox_worker] - [process_batch] - [message: Mail will be retried] id = {}, attempts = {}, retry_in = {}s - Error: {}", message.id, message.attempts, delay, err);
+                outbox_repository.mark_failed(message.id, &err.to_string(), Some(Utc::now() + Duration::seconds(delay))).await?;
+                MAIL_DELIVERIES_TOTAL.with_label_values(&["retry"]).inc();
+            }
+        }
+    }
+
+    Ok(())
+}
\ No newline at end of file
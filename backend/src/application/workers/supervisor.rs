@@ -0,0 +1,249 @@
+//! # バックグラウンドジョブの管理
+//!
+//! 名前付きのジョブを Web サーバーと同じランタイムで起動し、状態を記録する
+//! 停止を要求すると各ジョブに通知し、期限までに終了しなかったジョブは中断する
+//!
+//! ## 構造体
+//!
+//! - `Supervisor`: ジョブの起動・停止・状態の取得
+//! - `JobContext`: 各ジョブに渡す停止の通知と実行結果の記録
+//!
+//! ## 関数
+//!
+//! - `shutdown_signal`: 停止のシグナル（SIGTERM / SIGINT）を待機
+
+use std::any::Any;
+use std::fmt::Display;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use chrono::Utc;
+use futures::FutureExt;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::domain::entities::job::JobStatus;
+use crate::domain::enums::job::JobState;
+use crate::{app_log, error_log, info_log, warning_log};
+
+/// ジョブの状態を共有する型
+type SharedStatus = Arc<Mutex<JobStatus>>;
+
+/// 状態を取得
+///
+/// 状態の更新中にパニックしても記録済みの値は有効なため、ロックの破損は無視する
+fn lock(status: &SharedStatus) -> MutexGuard<'_, JobStatus> {
+    status.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 起動したジョブ
+struct Job {
+    status: SharedStatus,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// バックグラウンドジョブの管理
+pub struct Supervisor {
+    shutdown: watch::Sender<bool>,
+    shutdown_requested_at: OnceLock<Instant>,
+    shutdown_timeout: Duration,
+    jobs: Mutex<Vec<Job>>,
+}
+
+impl Supervisor {
+    /// # 引数
+    ///
+    /// * `shutdown_timeout` - 停止の要求から、ジョブの終了を待つ時間
+    pub fn new(shutdown_timeout: Duration) -> Self {
+        Supervisor {
+            shutdown: watch::Sender::new(false),
+            shutdown_requested_at: OnceLock::new(),
+            shutdown_timeout,
+            jobs: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// ジョブを起動
+    ///
+    /// ジョブは `JobContext::sleep` が `false` を返したら（停止を要求されたら）処理を終えて戻る必要がある
+    /// パニックした場合は `failed` として記録し、他のジョブ・Web サーバーは停止しない
+    ///
+    /// # 引数
+    ///
+    /// * `name` - ジョブの名前（ログ・状態の一覧に使用）
+    /// * `job`  - `JobContext` を受け取ってジョブを作成する関数
+    pub fn spawn<F, Fut>(&self, name: &str, job: F)
+    where
+        F: FnOnce(JobContext) -> Fut,
+        Fut: Future<Output = ()> + 'static,
+    {
+        let status = Arc::new(Mutex::new(JobStatus::new(name)));
+        let future = job(JobContext { shutdown: self.shutdown.subscribe(), status: status.clone() });
+
+        let job_status = status.clone();
+        let handle = actix_web::rt::spawn(async move {
+            let result = AssertUnwindSafe(future).catch_unwind().await;
+
+            let mut status = lock(&job_status);
+            status.stopped_at = Some(Utc::now());
+            match result {
+                Ok(()) => {
+                    status.state = JobState::Stopped;
+                    info_log!("[supervisor] - [spawn] job stopped, name = {}", status.name);
+                }
+                Err(panic) => {
+                    let message = panic_message(panic.as_ref());
+                    error_log!("[supervisor] - [spawn] - [message: Job panicked] name = {} - Error: {}", status.name, message);
+                    status.state = JobState::Failed;
+                    status.last_error = Some(message);
+                }
+            }
+        });
+
+        info_log!("[supervisor] - [spawn] job started, name = {}", name);
+        self.jobs.lock().unwrap().push(Job { status, handle: Some(handle) });
+    }
+
+    /// 全てのジョブの状態
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.jobs.lock().unwrap().iter().map(|job| lock(&job.status).clone()).collect()
+    }
+
+    /// 停止を要求されたか
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// 全てのジョブに停止を要求
+    ///
+    /// 2回目以降の呼び出しでは何もしない（停止の期限は最初の要求から数える）
+    pub fn shutdown(&self) {
+        if self.shutdown_requested_at.set(Instant::now()).is_err() {
+            return;
+        }
+
+        info_log!("[supervisor] - [shutdown] stopping jobs, timeout = {:?}", self.shutdown_timeout);
+        self.shutdown.send_replace(true);
+
+        for job in self.jobs.lock().unwrap().iter() {
+            let mut status = lock(&job.status);
+            if status.state == JobState::Running {
+                status.state = JobState::Stopping;
+            }
+        }
+    }
+
+    /// 全てのジョブの終了を待機
+    ///
+    /// 停止を要求していない場合は要求してから待機する
+    /// 停止の要求から `shutdown_timeout` を過ぎても終了しないジョブは中断し、`aborted` として記録する
+    ///
+    /// # 戻り値
+    ///
+    /// * `bool` - 全てのジョブが期限までに終了したか
+    pub async fn wait(&self) -> bool {
+        self.shutdown();
+        let deadline = *self.shutdown_requested_at.get().unwrap() + self.shutdown_timeout;
+
+        let jobs: Vec<(SharedStatus, JoinHandle<()>)> = self.jobs.lock().unwrap()
+            .iter_mut()
+            .filter_map(|job| job.handle.take().map(|handle| (job.status.clone(), handle)))
+            .collect();
+
+        let mut all_stopped = true;
+        for (status, mut handle) in jobs {
+            if tokio::time::timeout_at(deadline, &mut handle).await.is_ok() {
+                continue;
+            }
+
+            handle.abort();
+            all_stopped = false;
+
+            let mut status = lock(&status);
+            warning_log!("[supervisor] - [wait] - [message: Job did not stop before the deadline, aborted] name = {}", status.name);
+            status.state = JobState::Aborted;
+            status.stopped_at = Some(Utc::now());
+            status.last_error = Some("did not stop before the shutdown deadline".to_string());
+        }
+
+        all_stopped
+    }
+}
+
+/// 各ジョブに渡す停止の通知と実行結果の記録
+pub struct JobContext {
+    shutdown: watch::Receiver<bool>,
+    status: SharedStatus,
+}
+
+impl JobContext {
+    /// 停止を要求されたか
+    pub fn is_shutting_down(&self) -> bool {
+        *self.shutdown.borrow()
+    }
+
+    /// 次の処理まで待機
+    ///
+    /// 待機中に停止を要求された場合は、すぐに戻る
+    ///
+    /// # 戻り値
+    ///
+    /// * `bool` - 処理を続けるか（停止を要求された場合は `false`）
+    pub async fn sleep(&mut self, duration: Duration) -> bool {
+        if self.is_shutting_down() {
+            return false;
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            // `Supervisor` が破棄された場合も停止する
+            _ = self.shutdown.wait_for(|stop| *stop) => return false,
+        }
+
+        !self.is_shutting_down()
+    }
+
+    /// 1回の処理の結果を記録
+    pub fn record_run<E: Display>(&self, result: &Result<(), E>) {
+        let mut status = lock(&self.status);
+        status.runs += 1;
+        status.last_run_at = Some(Utc::now());
+        if let Err(err) = result {
+            status.failures += 1;
+            status.last_error = Some(err.to_string());
+        }
+    }
+}
+
+/// パニックのメッセージ
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// 停止のシグナル（SIGTERM / SIGINT）を待機
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("SIGTERM のハンドラーの登録に失敗しました");
+
+        tokio::select! {
+            _ = terminate.recv() => info_log!("[supervisor] - [shutdown_signal] SIGTERM received"),
+            _ = tokio::signal::ctrl_c() => info_log!("[supervisor] - [shutdown_signal] SIGINT received"),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info_log!("[supervisor] - [shutdown_signal] Ctrl-C received");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,72 @@
+//! # タスクのリマインダーのワーカー
+//!
+//! 期限が近い未完了のタスクのリマインダーを、メール送信キューに登録する
+//! 各タスクのリマインダーは一度だけ登録し、期限を変更した場合は再度登録する
+//!
+//! | `[task_reminder]` のキー | 既定値 | 説明                                       |
+//! | ------------------------ | ------ | ------------------------------------------ |
+//! | `lead_hours`             | 24     | 期限の何時間前からリマインダーを送信するか |
+//! | `interval_secs`          | 60     | 期限が近いタスクを確認する間隔（秒）       |
+//!
+//! ## 関数
+//!
+//! - `run`: ワーカーを実行
+
+use std::time::Duration as StdDuration;
+use chrono::{Duration, Utc};
+
+use crate::application::types::di_type::OutboxRepositoryArc;
+use crate::application::workers::supervisor::JobContext;
+use crate::application::use_cases::mail_composer::task_reminder_mail;
+use crate::infrastructure::config::app_config::TaskReminderSettings;
+use crate::{app_log, error_log, info_log};
+
+/// 一度に登録するリマインダーの件数
+const REMINDER_BATCH_SIZE: i64 = 100;
+
+/// ワーカーの設定
+#[derive(Debug, Clone)]
+pub struct TaskReminderWorkerConfig {
+    pub lead_hours: i64,
+    pub interval: StdDuration,
+}
+
+impl TaskReminderWorkerConfig {
+    /// `AppConfig` の `[task_reminder]` から作成
+    pub fn from_settings(settings: &TaskReminderSettings) -> Self {
+        TaskReminderWorkerConfig {
+            lead_hours: settings.lead_hours,
+            interval: StdDuration::from_secs(settings.interval_secs),
+        }
+    }
+}
+
+/// ワーカーを実行
+///
+/// # 引数
+///
+/// * `context`           - 停止の通知と実行結果の記録
+/// * `outbox_repository` - メール送信キュー
+/// * `config`            - ワーカーの設定
+pub async fn run(mut context: JobContext, outbox_repository: OutboxRepositoryArc, config: TaskReminderWorkerConfig) {
+    info_log!("[task_reminder_worker] - [run] started, config = {:?}", config);
+
+    loop {
+        let due_before = Utc::now() + Duration::hours(config.lead_hours);
+        let result = outbox_repository.enqueue_task_reminders(due_before, REMINDER_BATCH_SIZE, &task_reminder_mail).await;
+        match &result {
+            Ok(0) => {}
+            Ok(count) => {
+                info_log!("[task_reminder_worker] - [run] {} reminders enqueued", count);
+            }
+            Err(err) => {
+                error_log!("[task_reminder_worker] - [run] - [message: Failed to enqueue reminders] - Error: {}", err);
+            }
+        }
+        context.record_run(&result.map(|_| ()));
+
+        if !context.sleep(config.interval).await {
+            break;
+        }
+    }
+}
\ No newline at end of file
@@ -1,16 +1,20 @@
 use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
 use validator::Validate;
 use crate::application::helpers::validator::{validate_email, validate_password};
+use crate::domain::entities::mfa::MfaChallengeResponse;
+use crate::domain::entities::session::IssuedTokens;
 
 /// 新規登録　リクエスト
 #[derive(Deserialize, Debug, Validate)]
 pub struct SignupRequest {
+    #[validate(length(min = 1, max = 255, code = "name_length"))]
     pub name: String,
-    #[validate(email(message = "Invalid email address"))]
-    #[validate(length(max = 319, message = "Email address too long"))]
+    #[validate(email(code = "email_invalid"))]
+    #[validate(length(max = 319, code = "email_too_long"))]
     #[validate(custom(function = "validate_email"))]
     pub email: String,
-    #[validate(length(max = 127, message = "Password too long"))]
+    #[validate(length(max = 127, code = "password_too_long"))]
     #[validate(custom(function = "validate_password"))]
     pub password: String,
 }
@@ -23,7 +27,7 @@ pub struct SignupInsertResult {
     pub role: String,
     pub photo: Option<String>,
     pub bio: Option<String>,
-    pub is_verified: String,
+    pub is_verified: bool,
 }
 
 /// 新規登録　レスポンス
@@ -35,18 +39,19 @@ pub struct SignupResponse {
     pub role: String,
     pub photo: Option<String>,
     pub bio: Option<String>,
-    pub is_verified: String,
+    pub is_verified: bool,
     pub token: String,
+    pub refresh_token: String,
 }
 
 /// ログイン　リクエスト
 #[derive(Deserialize, Debug, Validate)]
 pub struct LoginRequest {
-    #[validate(email(message = "Invalid email address"))]
-    #[validate(length(max = 319, message = "Email address too long"))]
+    #[validate(email(code = "email_invalid"))]
+    #[validate(length(max = 319, code = "email_too_long"))]
     #[validate(custom(function = "validate_email"))]
     pub email: String,
-    #[validate(length(max = 127, message = "Password too long"))]
+    #[validate(length(max = 127, code = "password_too_long"))]
     #[validate(custom(function = "validate_password"))]
     pub password: String,
 }
@@ -60,7 +65,8 @@ pub struct LoginSelectResult {
     pub role: String,
     pub photo: Option<String>,
     pub bio: Option<String>,
-    pub is_verified: String,
+    pub is_verified: bool,
+    pub locale: Option<String>,
 }
 
 /// 新規登録　レスポンス
@@ -72,6 +78,56 @@ pub struct LoginResponse {
     pub role: String,
     pub photo: Option<String>,
     pub bio: Option<String>,
-    pub is_verified: String,
+    pub is_verified: bool,
     pub token: String,
+    pub refresh_token: String,
+}
+
+/// ログインの結果
+///
+/// * `Authenticated` - セッションを開始した
+/// * `MfaRequired`   - 2段階認証が有効なため、TOTP のコードの入力が必要
+pub enum LoginOutcome {
+    Authenticated(Box<LoginResponse>, IssuedTokens),
+    MfaRequired(MfaChallengeResponse),
+}
+
+/// ゲストログイン　レスポンス
+///
+/// * `expires_at` - ゲストの有効期限。期限までにアカウントを登録しない場合は、ユーザーとデータを削除する
+#[derive(Serialize, Debug)]
+pub struct GuestLoginResponse {
+    pub id: String,
+    pub name: String,
+    pub role: String,
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// メール認証　パスパラメータ
+///
+/// `/verify-email/{verificationToken}` の `verificationToken` を受け取る
+#[derive(Deserialize, Debug)]
+pub struct VerifyEmailPath {
+    #[serde(rename = "verificationToken")]
+    pub verification_token: String,
+}
+
+/// パスワードリセット　パスパラメータ
+///
+/// `/reset-password/{resetPasswordToken}` の `resetPasswordToken` を受け取る
+#[derive(Deserialize, Debug)]
+pub struct ResetPasswordPath {
+    #[serde(rename = "resetPasswordToken")]
+    pub reset_password_token: String,
+}
+
+/// メール認証・トークン再発行　DB結果
+pub struct VerificationSelectResult {
+    pub id: i32,
+    pub email: String,
+    pub role: String,
+    pub is_verified: bool,
+    pub locale: Option<String>,
 }
\ No newline at end of file
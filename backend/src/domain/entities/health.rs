@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use serde::Serialize;
+
+/// アプリケーション全体の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Degraded,
+}
+
+/// 依存先ごとの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Ok,
+    Error,
+}
+
+/// 依存先ごとのチェック結果
+///
+/// * `latency_ms` - チェックにかかった時間（キャッシュした結果の場合はキャッシュ時の値）
+/// * `error`      - 失敗した理由
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentCheck {
+    pub status: ComponentStatus,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ComponentCheck {
+    pub fn ok(latency_ms: u64) -> Self {
+        ComponentCheck { status: ComponentStatus::Ok, latency_ms, error: None }
+    }
+
+    pub fn error(latency_ms: u64, error: impl Into<String>) -> Self {
+        ComponentCheck { status: ComponentStatus::Error, latency_ms, error: Some(error.into()) }
+    }
+}
+
+/// ビルド情報
+///
+/// * `git_sha`     - ビルド時の環境変数 `GIT_SHA`（未設定の場合は実行時の `GIT_SHA`、それもなければ `unknown`）
+/// * `uptime_secs` - 起動してからの秒数
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: String,
+    pub uptime_secs: u64,
+}
+
+/// 生存確認のレスポンス
+#[derive(Debug, Serialize)]
+pub struct LivenessResponse {
+    pub status: HealthStatus,
+    pub build: BuildInfo,
+}
+
+/// 準備完了確認のレスポンス
+///
+/// * `checks` - 依存先ごとのチェック結果（`database` / `migrations` / `mail`）
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub status: HealthStatus,
+    pub checks: BTreeMap<&'static str, ComponentCheck>,
+    pub build: BuildInfo,
+}
+
+impl ReadinessResponse {
+    /// 全ての依存先が正常な場合のみ `Ok`、1つでも失敗していれば `Degraded`
+    pub fn new(checks: BTreeMap<&'static str, ComponentCheck>, build: BuildInfo) -> Self {
+        let status = if checks.values().all(|c| c.status == ComponentStatus::Ok) {
+            HealthStatus::Ok
+        } else {
+            HealthStatus::Degraded
+        };
+
+        ReadinessResponse { status, checks, build }
+    }
+}
\ No newline at end of file
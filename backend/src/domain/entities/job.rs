@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::enums::job::JobState;
+
+/// バックグラウンドジョブの状態
+///
+/// * `runs`       - 処理を実行した回数（失敗を含む）
+/// * `failures`   - 処理に失敗した回数
+/// * `last_error` - 最後に失敗した処理のエラー（パニック・中断の場合はその理由）
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: String,
+    pub state: JobState,
+    pub started_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub runs: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub stopped_at: Option<DateTime<Utc>>,
+}
+
+impl JobStatus {
+    pub fn new(name: impl Into<String>) -> Self {
+        JobStatus {
+            name: name.into(),
+            state: JobState::Running,
+            started_at: Utc::now(),
+            last_run_at: None,
+            runs: 0,
+            failures: 0,
+            last_error: None,
+            stopped_at: None,
+        }
+    }
+}
+
+/// バックグラウンドジョブ一覧のレスポンス
+#[derive(Debug, Serialize)]
+pub struct JobListResponse {
+    pub shutting_down: bool,
+    pub jobs: Vec<JobStatus>,
+}
\ No newline at end of file
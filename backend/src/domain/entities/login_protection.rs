@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+use crate::domain::enums::login_failure::LoginFailureReason;
+
+/// ログインのリクエスト数・失敗回数のカウンター
+///
+/// * `count`             - `window_started_at` からの回数
+/// * `window_started_at` - 回数を数え始めた日時
+/// * `locked_until`      - ロックの期限
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThrottleCounter {
+    pub count: i32,
+    pub window_started_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// ログインの試行
+///
+/// * `email`      - 入力されたメールアドレス（小文字に正規化）
+/// * `ip_address` - 接続元の IP アドレス
+#[derive(Debug, Clone, Default)]
+pub struct LoginAttempt {
+    pub email: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// 記録するログインの失敗
+///
+/// * `user_id`      - 登録済みのユーザーの場合はその ID
+/// * `locked_until` - この失敗でロックした場合、その期限
+pub struct NewLoginFailure<'a> {
+    pub attempt: &'a LoginAttempt,
+    pub user_id: Option<i32>,
+    pub reason: LoginFailureReason,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+/// ログインの失敗の履歴（管理者）　クエリパラメータ
+///
+/// * `email`      - 指定したメールアドレスのみ（大文字・小文字は区別しない）
+/// * `ip_address` - 指定した IP アドレスのみ
+#[derive(Deserialize)]
+pub struct LoginFailureListQuery {
+    pub email: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// ログインの失敗　レスポンス
+#[derive(Serialize, Debug)]
+pub struct LoginFailureResponse {
+    pub id: i64,
+    pub email: String,
+    pub user_id: Option<i32>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub reason: LoginFailureReason,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// ログインの失敗の履歴（管理者）　レスポンス
+#[derive(Serialize)]
+pub struct LoginFailureListResponse {
+    pub entries: Vec<LoginFailureResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
\ No newline at end of file
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+/// ユーザーの TOTP　DB結果
+///
+/// * `secret`         - Base32 の共有シークレット
+/// * `enabled_at`     - 有効にした日時（`None` の場合は登録中）
+/// * `last_used_step` - 最後に使用したコードのステップ
+#[derive(Debug, Clone)]
+pub struct UserTotp {
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+}
+
+impl UserTotp {
+    /// 2段階認証が有効か
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+}
+
+/// 2段階認証のチャレンジ　DB結果
+///
+/// * `email`    - ログインするユーザーのメールアドレス
+/// * `attempts` - 正しくないコードを入力した回数
+#[derive(Debug, Clone)]
+pub struct MfaChallenge {
+    pub id: i32,
+    pub user_id: i32,
+    pub email: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl MfaChallenge {
+    /// 未使用・有効期限内で、入力回数の上限に達していないか
+    pub fn is_active(&self, now: DateTime<Utc>, max_attempts: i32) -> bool {
+        self.used_at.is_none() && self.expires_at > now && self.attempts < max_attempts
+    }
+}
+
+/// ログイン　レスポンス（2段階認証が有効な場合）
+///
+/// `mfa_token` と TOTP のコードを `/auth/login/mfa` に送信してログインを完了する
+#[derive(Serialize, Debug)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 2段階認証のログイン　リクエスト
+///
+/// `code` は認証アプリの6桁のコード、またはリカバリーコード
+#[derive(Deserialize, Debug, Validate)]
+pub struct MfaLoginRequest {
+    #[validate(length(min = 1, max = 128, code = "mfa_token_required"))]
+    pub mfa_token: String,
+    #[validate(length(min = 1, max = 32, code = "mfa_code_required"))]
+    pub code: String,
+}
+
+/// TOTP の確認　リクエスト
+#[derive(Deserialize, Debug, Validate)]
+pub struct TotpCodeRequest {
+    #[validate(length(min = 1, max = 32, code = "mfa_code_required"))]
+    pub code: String,
+}
+
+/// 2段階認証の無効化・リカバリーコードの再発行　リクエスト
+///
+/// 操作の前にパスワードで本人を確認する
+#[derive(Deserialize, Validate)]
+pub struct PasswordConfirmRequest {
+    #[serde(rename = "currentPassword")]
+    #[validate(length(min = 1, max = 127, code = "current_password_required"))]
+    pub current_password: String,
+}
+
+/// TOTP の登録　レスポンス
+///
+/// * `secret`      - 認証アプリに手入力する場合のシークレット
+/// * `otpauth_uri` - QR コードにして認証アプリで読み取る URI
+#[derive(Serialize, Debug)]
+pub struct TotpSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// リカバリーコード　レスポンス
+///
+/// コードは発行時にのみ返す（DB にはハッシュのみ保存）
+#[derive(Serialize, Debug)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// 2段階認証の状態　レスポンス
+///
+/// * `recovery_codes_remaining` - 未使用のリカバリーコードの数
+#[derive(Serialize, Debug)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub recovery_codes_remaining: i64,
+}
\ No newline at end of file
@@ -1,3 +1,11 @@
 pub mod auth;
+pub mod health;
+pub mod job;
+pub mod login_protection;
+pub mod mfa;
+pub mod oidc;
+pub mod outbox;
+pub mod pagination;
+pub mod session;
 pub mod task;
 pub mod user;
\ No newline at end of file
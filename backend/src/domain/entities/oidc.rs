@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use crate::domain::entities::auth::LoginOutcome;
+
+/// プロバイダーが発行したトークン
+///
+/// * `access_token`  - UserInfo エンドポイントなど、プロバイダーの API に使用するトークン
+/// * `refresh_token` - アクセストークンの再発行に使用するトークン（発行されない場合は `None`）
+/// * `expires_at`    - アクセストークンの有効期限
+/// * `id_token`      - 検証済みの ID トークン
+#[derive(Debug, Clone)]
+pub struct OidcTokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub token_type: Option<String>,
+    pub scope: Option<String>,
+    pub id_token: String,
+}
+
+/// プロバイダーのユーザー情報
+///
+/// * `subject`        - プロバイダーのユーザー ID（ID トークンの `sub`）
+/// * `email_verified` - プロバイダーがメールアドレスを確認済みか
+#[derive(Debug, Clone)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub picture: Option<String>,
+    pub tokens: OidcTokens,
+}
+
+/// 認可リクエストの状態　DB結果
+///
+/// * `code_verifier` - PKCE の検証コード
+/// * `nonce`         - ID トークンの `nonce` と照合する値
+/// * `user_id`       - 連携の場合は連携するユーザー（ログインの場合は `None`）
+#[derive(Debug, Clone)]
+pub struct OidcState {
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub user_id: Option<i32>,
+}
+
+/// 連携しているアカウント　DB結果
+///
+/// * `provider_account_id` - プロバイダーのユーザー ID（`sub`）
+/// * `email`               - 連携した時点のプロバイダーのメールアドレス
+#[derive(Serialize, Debug, Clone)]
+pub struct LinkedAccount {
+    pub provider: String,
+    pub provider_account_id: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// プロバイダーのアカウントの所有者　DB結果
+#[derive(Debug, Clone)]
+pub struct AccountOwner {
+    pub user_id: i32,
+    pub email: String,
+}
+
+/// 認可リクエスト　レスポンス
+///
+/// フロントエンドは `authorization_url` に遷移し、リダイレクト先で受け取った `code` と `state` をコールバックに送信する
+#[derive(Serialize, Debug)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// プロバイダー　パスパラメータ
+#[derive(Deserialize, Debug)]
+pub struct OidcProviderPath {
+    pub provider: String,
+}
+
+/// コールバック　リクエスト
+#[derive(Deserialize, Debug, Validate)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1, max = 2048, code = "oidc_code_required"))]
+    pub code: String,
+    #[validate(length(min = 1, max = 128, code = "oidc_state_required"))]
+    pub state: String,
+}
+
+/// 連携しているアカウントの一覧　レスポンス
+///
+/// * `has_password` - パスワードでログインできるか（`false` の場合は最後の連携を解除できない）
+#[derive(Serialize, Debug)]
+pub struct LinkedAccountsResponse {
+    pub accounts: Vec<LinkedAccount>,
+    pub has_password: bool,
+}
+
+/// ログインに使用できるプロバイダーの一覧　レスポンス
+#[derive(Serialize, Debug)]
+pub struct OidcProvidersResponse {
+    pub providers: Vec<String>,
+}
+
+/// コールバックの結果
+///
+/// * `Login`  - ログイン（2段階認証が有効な場合はチャレンジ）
+/// * `Linked` - ログイン中のユーザーにアカウントを連携した
+pub enum OidcCallbackOutcome {
+    Login(LoginOutcome),
+    Linked(LinkedAccountsResponse),
+}
\ No newline at end of file
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+use crate::application::mail::mailer::Mail;
+use crate::domain::enums::outbox::OutboxStatus;
+
+/// ワーカーが取得した送信待ちのメール
+///
+/// * `attempts` - 今回を含む送信回数
+pub struct OutboxMessage {
+    pub id: i64,
+    pub mail: Mail,
+    pub attempts: i32,
+}
+
+/// リマインダーを送信するタスク
+pub struct TaskReminder {
+    pub task_id: i32,
+    pub title: String,
+    pub due_date: DateTime<Utc>,
+    pub email: String,
+    pub locale: Option<String>,
+}
+
+/// メール送信キュー一覧（管理者）　クエリパラメータ
+///
+/// * `status` - 指定した状態のみ（未指定の場合は全て）
+#[derive(Deserialize)]
+pub struct OutboxListQuery {
+    pub status: Option<OutboxStatus>,
+}
+
+/// メール送信キュー　レスポンス
+///
+/// 本文にはトークンを含むリンクがあるため返さない
+#[derive(Serialize, Debug)]
+pub struct OutboxEntryResponse {
+    pub id: i64,
+    pub recipient: String,
+    pub subject: String,
+    pub status: OutboxStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// メール送信キュー一覧（管理者）　レスポンス
+#[derive(Serialize)]
+pub struct OutboxListResponse {
+    pub entries: Vec<OutboxEntryResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+/// メール送信キュー指定　パスパラメータ
+///
+/// `/admin/mail-outbox/{id}` の `id` を受け取る
+#[derive(Deserialize, Debug)]
+pub struct OutboxPath {
+    pub id: i64,
+}
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! # ページ分割
+//!
+//! 一覧 API で共通のクエリパラメータ（`page` / `per_page`）
+//! ハンドラーで絞り込み条件とは別の `web::Query<Pagination>` として受け取る
+
+use serde::Deserialize;
+use validator::Validate;
+
+/// 1ページあたりの件数（既定値）
+pub const DEFAULT_PER_PAGE: i64 = 20;
+
+/// 1ページあたりの件数（上限）
+pub const MAX_PER_PAGE: i64 = 100;
+
+/// ページ番号（上限）
+///
+/// 読み飛ばす件数（`(page - 1) * per_page`）が `i64` を超えないよう制限する
+pub const MAX_PAGE: i64 = 1_000_000;
+
+/// ページ分割　クエリパラメータ
+///
+/// 範囲外の値は `validate` でエラーにする。検証前でも `page` / `per_page` / `offset` は範囲内に丸めた値を返す
+///
+/// * `page`     - ページ番号（1始まり）
+/// * `per_page` - 1ページあたりの件数
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Validate)]
+pub struct Pagination {
+    #[validate(range(min = 1, max = MAX_PAGE, code = "page_invalid"))]
+    pub page: Option<i64>,
+    #[validate(range(min = 1, max = MAX_PER_PAGE, code = "per_page_invalid"))]
+    pub per_page: Option<i64>,
+}
+
+impl Pagination {
+    /// ページ番号（未指定の場合は1）
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).clamp(1, MAX_PAGE)
+    }
+
+    /// 1ページあたりの件数（未指定の場合は既定値）
+    pub fn per_page(&self) -> i64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// 読み飛ばす件数
+    pub fn offset(&self) -> i64 {
+        (self.page() - 1) * self.per_page()
+    }
+
+    /// 総ページ数
+    pub fn total_pages(&self, total: i64) -> i64 {
+        (total + self.per_page() - 1) / self.per_page()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// ログイン端末の情報
+///
+/// * `user_agent` - リクエストの User-Agent
+/// * `ip_address` - 接続元の IP アドレス
+#[derive(Debug, Default, Clone)]
+pub struct SessionMeta {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// 発行したトークン
+///
+/// * `access_token`  - 有効期限の短い JWT
+/// * `refresh_token` - アクセストークンの再発行に使用するトークン（DB にはハッシュのみ保存）
+#[derive(Debug, Clone)]
+pub struct IssuedTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// トークン再発行　リクエスト
+///
+/// 省略した場合はクッキーのリフレッシュトークンを使用する
+#[derive(Deserialize, Debug, Default)]
+pub struct RefreshRequest {
+    pub refresh_token: Option<String>,
+}
+
+/// トークン再発行　レスポンス
+#[derive(Serialize, Debug)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub refresh_token: String,
+}
+
+/// セッション指定　パスパラメータ
+///
+/// `/sessions/{id}` の `id`（セッションの公開ID）を受け取る
+#[derive(Deserialize, Debug)]
+pub struct SessionPath {
+    pub id: String,
+}
+
+/// セッション　DB結果
+pub struct SessionSelectResult {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionSelectResult {
+    /// 失効しておらず、有効期限内かどうか
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+/// リフレッシュトークン　DB結果
+///
+/// * `id`      - トークンID
+/// * `used_at` - 使用済みの場合は使用日時
+/// * `session` - トークンが属するセッション
+pub struct RefreshTokenSelectResult {
+    pub id: i32,
+    pub used_at: Option<DateTime<Utc>>,
+    pub session: SessionSelectResult,
+}
+
+/// セッションの検証に必要な状態
+///
+/// * `password_changed_at` - パスワード変更日時
+/// * `is_active`           - セッションが有効かどうか
+/// * `role`                - ユーザーの現在のロール
+/// * `locale`              - ユーザーが設定した言語
+pub struct SessionStatus {
+    pub password_changed_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub role: String,
+    pub locale: Option<String>,
+}
+
+/// セッション一覧　レスポンス
+///
+/// * `current` - リクエストしたトークンのセッションかどうか
+#[derive(Serialize, Debug)]
+pub struct SessionResponse {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub current: bool,
+}
\ No newline at end of file
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDateTime;
+use validator::Validate;
 
-use crate::domain::enums::task::{Priority, Status};
+use crate::domain::enums::task::{Priority, SortOrder, Status, TaskSortField};
 
 /// タスク取得　リクエスト
 #[derive(Serialize, Debug, Clone, PartialEq)]
@@ -9,7 +10,7 @@ pub struct TaskItem {
     pub id: i32,
     pub title: String,
     pub description: String,
-    pub due_date: NaiveDateTime,
+    pub due_date: Option<NaiveDateTime>,
     pub status: Option<Status>,
     pub completed: bool,
     pub priority: Option<Priority>,
@@ -24,61 +25,81 @@ pub struct TaskListRequest {
     pub user_id: i32
 }
 
+/// タスク一覧　クエリパラメータ
+///
+/// 全ての項目は任意で、指定された条件のみ AND で絞り込む
+///
+/// * `status`      - ステータス
+/// * `priority`    - 優先度
+/// * `completed`   - 完了済みかどうか
+/// * `due_from`    - 期限日の開始（この日時を含む）
+/// * `due_to`      - 期限日の終了（この日時を含む）
+/// * `overdue`     - `true` の場合、期限切れかつ未完了のタスクのみ
+/// * `q`           - タイトルの部分一致（大文字小文字を区別しない）
+/// * `sort`        - 並び替え項目
+/// * `order`       - 並び順
+#[derive(Deserialize, Debug, Default, Clone, Validate)]
+pub struct TaskListQuery {
+    pub status: Option<Status>,
+    pub priority: Option<Priority>,
+    pub completed: Option<bool>,
+    pub due_from: Option<NaiveDateTime>,
+    pub due_to: Option<NaiveDateTime>,
+    pub overdue: Option<bool>,
+    #[validate(length(max = 100, code = "search_too_long"))]
+    pub q: Option<String>,
+    #[serde(default)]
+    pub sort: TaskSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+}
+
 #[derive(Serialize)]
 /// タスク一覧　レスポンス
+///
+/// * `tasks`       - 現在のページのタスク
+/// * `total`       - 条件に一致するタスクの総件数
+/// * `page`        - 現在のページ番号
+/// * `per_page`    - 1ページあたりの件数
+/// * `total_pages` - 総ページ数
 pub struct TaskListResponse {
     pub tasks: Vec<TaskItem>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+    pub total_pages: i64,
 }
 
-/// TODO一覧　レスポンス
-#[derive(Serialize, Debug)]
-pub struct ResponseTaskList {
-    pub todos: Vec<TaskItem>,
+/// タスク指定　パスパラメータ
+///
+/// `/tasks/{id}` の `id` を受け取る
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct TaskPath {
+    pub id: i32
 }
 
 /// TODO作成　リクエスト
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+///
+/// `title` は `tasks.title`（`VARCHAR(100)`）に収まる長さのみ許可
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Validate)]
 pub struct RequestCreateTaskItem {
+    #[validate(length(min = 1, max = 100, code = "title_length"))]
     pub title: String,
     pub description: String,
-}
-
-// TODO作成　レスポンス
-#[derive(Serialize, Debug, PartialEq, Clone)]
-pub struct ResponseCreateTaskItem {
-    pub title: String,
-    pub description: String,
-    pub is_completed: bool,
+    pub due_date: Option<NaiveDateTime>,
+    pub priority: Option<Priority>,
 }
 
 /// TODO更新　リクエスト
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+///
+/// 指定されなかった項目は更新しない
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Validate)]
 pub struct RequestUpdateTaskItem {
-    pub id: i32,
-    pub title: Option<String>,
-    pub description: Option<String>,
-    pub is_completed: Option<bool>,
-    pub updated_at: NaiveDateTime,
-}
-
-/// TODO更新　レスポンス
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct ResponseUpdateTaskItem {
-    pub id: i32,
+    #[validate(length(min = 1, max = 100, code = "title_length"))]
     pub title: Option<String>,
     pub description: Option<String>,
+    pub due_date: Option<NaiveDateTime>,
+    pub priority: Option<Priority>,
+    #[serde(alias = "completed")]
     pub is_completed: Option<bool>,
-    pub updated_at: NaiveDateTime,
-}
-
-/// TODO削除　リクエスト
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct RequestDeleteTaskItem {
-    pub id: i32
 }
-
-/// TODO削除　レスポンス
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct RequestCompleteTaskItem {
-    pub id: i32
-}
\ No newline at end of file
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
-use crate::application::helpers::validator::validate_email;
+use crate::application::helpers::validator::{validate_assignable_role, validate_email, validate_password};
+use crate::domain::enums::locale::Locale;
+use crate::domain::enums::role::{Permission, Role};
 
 #[derive(Debug)]
 pub struct User {
@@ -40,13 +42,88 @@ pub struct UserResponse {
    pub photo: Option<String>,
    pub bio: Option<String>,
    pub is_verified: bool,
+   pub locale: Option<String>,
 }
 
 // パスワード忘れた　リクエスト
 #[derive(Deserialize, Validate)]
 pub struct ForgotPasswordRequest {
-   #[validate(email(message = "Invalid email address"))]
-   #[validate(length(max = 319, message = "Email address too long"))]
+   #[validate(email(code = "email_invalid"))]
+   #[validate(length(max = 319, code = "email_too_long"))]
    #[validate(custom(function = "validate_email"))]
    pub email: String,
+}
+
+// パスワードリセット　リクエスト
+#[derive(Deserialize, Validate)]
+pub struct ResetPasswordRequest {
+   #[validate(length(max = 127, code = "password_too_long"))]
+   #[validate(custom(function = "validate_password"))]
+   pub password: String,
+}
+
+// プロフィール更新　リクエスト
+//
+// 指定されなかった項目は更新しない
+#[derive(Deserialize, Validate)]
+pub struct UpdateUserRequest {
+   #[validate(length(min = 1, max = 255, code = "name_length"))]
+   pub name: Option<String>,
+   #[validate(length(max = 1000, code = "bio_too_long"))]
+   pub bio: Option<String>,
+   #[validate(url(code = "photo_invalid_url"))]
+   #[validate(length(max = 2048, code = "photo_too_long"))]
+   pub photo: Option<String>,
+   pub locale: Option<Locale>,
+}
+
+// パスワード変更　リクエスト
+#[derive(Deserialize, Validate)]
+pub struct ChangePasswordRequest {
+   #[serde(rename = "currentPassword")]
+   #[validate(length(min = 1, max = 127, code = "current_password_required"))]
+   pub current_password: String,
+   #[serde(rename = "newPassword")]
+   #[validate(length(max = 127, code = "password_too_long"))]
+   #[validate(custom(function = "validate_password"))]
+   pub new_password: String,
+}
+
+// パスワード変更　レスポンス
+#[derive(Serialize)]
+pub struct ChangePasswordResponse {
+   pub message: String,
+   pub token: String,
+}
+
+// ユーザー指定　パスパラメータ
+//
+// `/admin/users/{id}` の `id` を受け取る
+#[derive(Deserialize, Debug)]
+pub struct UserPath {
+   pub id: i32,
+}
+
+// ユーザー一覧（管理者）　レスポンス
+#[derive(Serialize)]
+pub struct UserListResponse {
+   pub users: Vec<UserResponse>,
+   pub total: i64,
+   pub page: i64,
+   pub per_page: i64,
+   pub total_pages: i64,
+}
+
+// ロール変更（管理者）　リクエスト
+#[derive(Deserialize, Debug, Validate)]
+pub struct ChangeRoleRequest {
+   #[validate(custom(function = "validate_assignable_role"))]
+   pub role: Role,
+}
+
+// 権限一覧　レスポンス
+#[derive(Serialize)]
+pub struct PermissionsResponse {
+   pub role: Role,
+   pub permissions: &'static [Permission],
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// バックグラウンドジョブの状態
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    /// 実行中
+    Running,
+    /// 停止を要求し、終了を待っている
+    Stopping,
+    /// 終了した
+    Stopped,
+    /// パニックで終了した
+    Failed,
+    /// 停止の期限までに終了しなかったため中断した
+    Aborted,
+}
\ No newline at end of file
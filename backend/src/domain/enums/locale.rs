@@ -0,0 +1,40 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// メッセージの言語
+///
+/// `Accept-Language` の `ja-JP` のような地域付きの指定は、言語部分のみで判定する
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// 対応している全ての言語
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Ja];
+
+    /// DB・`Content-Language` に使用する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['-', '_']).next().unwrap_or_default().trim().to_ascii_lowercase();
+
+        match language.as_str() {
+            "en" => Ok(Locale::En),
+            "ja" => Ok(Locale::Ja),
+            _ => Err(format!("Unsupported locale: {}", s)),
+        }
+    }
+}
\ No newline at end of file
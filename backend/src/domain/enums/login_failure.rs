@@ -0,0 +1,40 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// ログインに失敗した理由
+///
+/// メールアドレス・パスワードの失敗はレスポンスでは区別しない（どちらも `invalid_credentials`）。管理者が履歴で確認するために記録する
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginFailureReason {
+    /// メールアドレスが未登録
+    UnknownUser,
+    /// パスワードが正しくない
+    WrongPassword,
+    /// 2段階認証のコードが正しくない
+    WrongMfaCode,
+}
+
+impl LoginFailureReason {
+    /// DB に保存する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginFailureReason::UnknownUser => "unknown_user",
+            LoginFailureReason::WrongPassword => "wrong_password",
+            LoginFailureReason::WrongMfaCode => "wrong_mfa_code",
+        }
+    }
+}
+
+impl FromStr for LoginFailureReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unknown_user" => Ok(LoginFailureReason::UnknownUser),
+            "wrong_password" => Ok(LoginFailureReason::WrongPassword),
+            "wrong_mfa_code" => Ok(LoginFailureReason::WrongMfaCode),
+            _ => Err(format!("Invalid login failure reason: {}", s)),
+        }
+    }
+}
\ No newline at end of file
@@ -1 +1,6 @@
+pub mod job;
+pub mod locale;
+pub mod login_failure;
+pub mod outbox;
+pub mod role;
 pub mod task;
\ No newline at end of file
@@ -0,0 +1,38 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// メール送信キューの状態
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutboxStatus {
+    /// 未送信（再送信待ちを含む）
+    Pending,
+    /// 送信済み
+    Sent,
+    /// 再送信の上限に達した
+    Dead,
+}
+
+impl OutboxStatus {
+    /// DB に保存する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OutboxStatus::Pending => "pending",
+            OutboxStatus::Sent => "sent",
+            OutboxStatus::Dead => "dead",
+        }
+    }
+}
+
+impl FromStr for OutboxStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(OutboxStatus::Pending),
+            "sent" => Ok(OutboxStatus::Sent),
+            "dead" => Ok(OutboxStatus::Dead),
+            _ => Err(format!("Invalid outbox status: {}", s)),
+        }
+    }
+}
\ No newline at end of file
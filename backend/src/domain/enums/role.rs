@@ -0,0 +1,90 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// ユーザーの権限ロール
+///
+/// 旧形式の JWT など、ロールが含まれない場合は `User` とする
+/// `Guest` はゲストログインで作成したユーザーのみのロールで、管理者は変更先に指定できない
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Guest,
+    #[default]
+    User,
+    Creator,
+    Admin,
+}
+
+impl Role {
+    /// DB に保存する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Guest => "guest",
+            Role::User => "user",
+            Role::Creator => "creator",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// ロールに許可された操作（権限マトリクス）
+    ///
+    /// | 操作                      | guest | user | creator | admin |
+    /// | ------------------------- | ----- | ---- | ------- | ----- |
+    /// | `PublishTrainingMenus`    |       |      | ○       | ○     |
+    /// | `ModerateContent`         |       |      |         | ○     |
+    /// | `ManageUsers`             |       |      |         | ○     |
+    /// | `ManageRoles`             |       |      |         | ○     |
+    /// | `ManageMail`              |       |      |         | ○     |
+    /// | `ManageJobs`              |       |      |         | ○     |
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::Guest | Role::User => &[],
+            Role::Creator => &[Permission::PublishTrainingMenus],
+            Role::Admin => &[
+                Permission::PublishTrainingMenus,
+                Permission::ModerateContent,
+                Permission::ManageUsers,
+                Permission::ManageRoles,
+                Permission::ManageMail,
+                Permission::ManageJobs,
+            ],
+        }
+    }
+
+    /// 操作が許可されているかどうか
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "guest" => Ok(Role::Guest),
+            "user" => Ok(Role::User),
+            "creator" => Ok(Role::Creator),
+            "admin" => Ok(Role::Admin),
+            _ => Err(format!("Invalid role: {}", s)),
+        }
+    }
+}
+
+/// ロールによって許可される操作
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// トレーニングメニューの公開
+    PublishTrainingMenus,
+    /// 投稿されたコンテンツの管理
+    ModerateContent,
+    /// ユーザーの一覧・削除
+    ManageUsers,
+    /// ユーザーのロール変更
+    ManageRoles,
+    /// メール送信キューの確認・再送信
+    ManageMail,
+    /// バックグラウンドジョブの状態の確認
+    ManageJobs,
+}
\ No newline at end of file
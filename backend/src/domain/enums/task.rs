@@ -1,12 +1,23 @@
 use std::str::FromStr;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
     Active,
     Inactive,
 }
 
+impl Status {
+    /// DB に保存する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Active => "active",
+            Status::Inactive => "inactive",
+        }
+    }
+}
+
 impl FromStr for Status {
     type Err = String;
 
@@ -19,13 +30,25 @@ impl FromStr for Status {
     }
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum Priority {
     Low,
     Medium,
     High,
 }
 
+impl Priority {
+    /// DB に保存する文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+}
+
 impl FromStr for Priority {
     type Err = String;
 
@@ -38,3 +61,24 @@ impl FromStr for Priority {
         }
     }
 }
+
+/// タスク一覧の並び替え項目
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    DueDate,
+    Priority,
+    Title,
+}
+
+/// 並び順
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
@@ -1,13 +1,51 @@
 //! # 認証リポジトリ　インタフェース
+//!
+//! メール認証・パスワードリセットのトークンは呼び出し元でハッシュ化した値を受け取る
+//! トークンを含むメールは、トークンと同じトランザクションでメール送信キューに登録する
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use crate::{
     application::errors::auth_error::AuthError,
-    domain::entities::auth::{LoginSelectResult, SignupInsertResult}
+    application::mail::mailer::Mail,
+    domain::entities::auth::{LoginSelectResult, SignupInsertResult, VerificationSelectResult}
 };
 
 #[async_trait]
 pub trait AuthRepository: Send + Sync {
-    async fn register_user(&self, name: &str, email: &str, password: &str) -> Result<SignupInsertResult, AuthError>;
+    async fn register_user(
+        &self,
+        name: &str,
+        email: &str,
+        password: &str,
+        verification_token_hash: &str,
+        expires_at: DateTime<Utc>,
+        verification_mail: &Mail,
+    ) -> Result<SignupInsertResult, AuthError>;
+    async fn create_guest(&self, name: &str, email: &str, password: &str, expires_at: DateTime<Utc>) -> Result<SignupInsertResult, AuthError>;
+    async fn convert_guest(&self, user_id: i32, name: &str, email: &str, password: &str) -> Result<Option<SignupInsertResult>, AuthError>;
+    async fn delete_expired_guests(&self) -> Result<u64, AuthError>;
     async fn get_user_by_email(&self, email: &str) -> Result<Option<LoginSelectResult>, AuthError>;
+    async fn get_verification_user(&self, user_id: i32) -> Result<Option<VerificationSelectResult>, AuthError>;
+    async fn get_password_hash(&self, user_id: i32) -> Result<Option<String>, AuthError>;
+    async fn has_password(&self, user_id: i32) -> Result<bool, AuthError>;
+    async fn update_password_hash(&self, user_id: i32, current_hash: &str, new_hash: &str) -> Result<bool, AuthError>;
+    async fn create_verification_token(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        mail: &Mail,
+    ) -> Result<(), AuthError>;
+    async fn get_last_verification_sent_at(&self, user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError>;
+    async fn verify_email_token(&self, token_hash: &str) -> Result<Option<i32>, AuthError>;
+    async fn create_password_reset_token(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        mail: &Mail,
+    ) -> Result<(), AuthError>;
+    async fn get_last_password_reset_sent_at(&self, user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError>;
+    async fn reset_password(&self, token_hash: &str, password_hash: &str) -> Result<Option<i32>, AuthError>;
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+//! # ログインの失敗の履歴リポジトリ　インタフェース
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::entities::login_protection::{LoginFailureListQuery, LoginFailureListResponse, NewLoginFailure},
+    domain::entities::pagination::Pagination
+};
+
+#[async_trait]
+pub trait LoginFailureRepository: Send + Sync {
+    async fn record_failure(&self, failure: &NewLoginFailure<'_>) -> Result<(), AuthError>;
+    async fn get_failures(&self, query: &LoginFailureListQuery, pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError>;
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, AuthError>;
+}
\ No newline at end of file
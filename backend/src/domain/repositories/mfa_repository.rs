@@ -0,0 +1,26 @@
+//! # 2段階認証リポジトリ　インタフェース
+//!
+//! リカバリーコード・チャレンジのトークンは呼び出し元でハッシュ化した値を受け取る
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::entities::mfa::{MfaChallenge, UserTotp}
+};
+
+#[async_trait]
+pub trait MfaRepository: Send + Sync {
+    async fn get_totp(&self, user_id: i32) -> Result<Option<UserTotp>, AuthError>;
+    async fn save_pending_totp(&self, user_id: i32, secret: &str) -> Result<bool, AuthError>;
+    async fn enable_totp(&self, user_id: i32, step: i64, recovery_code_hashes: &[String]) -> Result<bool, AuthError>;
+    async fn use_totp_step(&self, user_id: i32, step: i64) -> Result<bool, AuthError>;
+    async fn disable_totp(&self, user_id: i32) -> Result<bool, AuthError>;
+    async fn replace_recovery_codes(&self, user_id: i32, recovery_code_hashes: &[String]) -> Result<(), AuthError>;
+    async fn use_recovery_code(&self, user_id: i32, code_hash: &str) -> Result<bool, AuthError>;
+    async fn count_recovery_codes(&self, user_id: i32) -> Result<i64, AuthError>;
+    async fn create_challenge(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), AuthError>;
+    async fn find_challenge(&self, token_hash: &str) -> Result<Option<MfaChallenge>, AuthError>;
+    async fn record_challenge_failure(&self, challenge_id: i32) -> Result<(), AuthError>;
+    async fn consume_challenge(&self, challenge_id: i32) -> Result<bool, AuthError>;
+}
\ No newline at end of file
@@ -1,3 +1,9 @@
 pub mod auth_repository;
+pub mod login_failure_repository;
+pub mod mfa_repository;
+pub mod oidc_repository;
+pub mod outbox_repository;
+pub mod session_repository;
 pub mod task_repository;
+pub mod throttle_store;
 pub mod user_repository;
\ No newline at end of file
@@ -0,0 +1,21 @@
+//! # OpenID Connect リポジトリ　インタフェース
+//!
+//! 認可リクエストの `state` は呼び出し元でハッシュ化した値を受け取る
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::entities::oidc::{AccountOwner, LinkedAccount, OidcIdentity, OidcState}
+};
+
+#[async_trait]
+pub trait OidcRepository: Send + Sync {
+    async fn create_state(&self, state_hash: &str, state: &OidcState, expires_at: DateTime<Utc>) -> Result<(), AuthError>;
+    async fn consume_state(&self, state_hash: &str, provider: &str) -> Result<Option<OidcState>, AuthError>;
+    async fn find_account_owner(&self, provider: &str, provider_account_id: &str) -> Result<Option<AccountOwner>, AuthError>;
+    async fn link_account(&self, user_id: i32, provider: &str, identity: &OidcIdentity) -> Result<bool, AuthError>;
+    async fn create_user_with_account(&self, name: &str, email: &str, password_hash: &str, provider: &str, identity: &OidcIdentity) -> Result<i32, AuthError>;
+    async fn list_accounts(&self, user_id: i32) -> Result<Vec<LinkedAccount>, AuthError>;
+    async fn unlink_account(&self, user_id: i32, provider: &str) -> Result<bool, AuthError>;
+}
\ No newline at end of file
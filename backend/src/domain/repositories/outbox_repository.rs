@@ -0,0 +1,28 @@
+//! # メール送信キューリポジトリ　インタフェース
+//!
+//! メールの登録は送信のきっかけとなる変更と同じトランザクションで行うため、各リポジトリから `enqueue_mail` を使用する
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use crate::{
+    application::errors::outbox_error::OutboxError,
+    application::mail::mailer::Mail,
+    domain::entities::outbox::{OutboxListQuery, OutboxListResponse, OutboxMessage, TaskReminder},
+    domain::entities::pagination::Pagination
+};
+
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    async fn claim_due(&self, limit: i64, lease_until: DateTime<Utc>) -> Result<Vec<OutboxMessage>, OutboxError>;
+    async fn mark_sent(&self, id: i64) -> Result<(), OutboxError>;
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), OutboxError>;
+    async fn discard_expired(&self) -> Result<u64, OutboxError>;
+    async fn get_entries(&self, query: &OutboxListQuery, pagination: &Pagination) -> Result<OutboxListResponse, OutboxError>;
+    async fn requeue(&self, id: i64) -> Result<bool, OutboxError>;
+    async fn enqueue_task_reminders(
+        &self,
+        due_before: DateTime<Utc>,
+        limit: i64,
+        compose: &(dyn for<'r> Fn(&'r TaskReminder) -> Mail + Send + Sync),
+    ) -> Result<usize, OutboxError>;
+}
\ No newline at end of file
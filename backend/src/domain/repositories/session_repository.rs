@@ -0,0 +1,36 @@
+//! # セッションリポジトリ　インタフェース
+//!
+//! リフレッシュトークンは呼び出し元でハッシュ化した値を受け取る
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::entities::session::{RefreshTokenSelectResult, SessionMeta, SessionSelectResult, SessionStatus}
+};
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn create_session(
+        &self,
+        user_id: i32,
+        public_id: Uuid,
+        meta: &SessionMeta,
+        expires_at: DateTime<Utc>,
+        token_hash: &str,
+    ) -> Result<(), AuthError>;
+    async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenSelectResult>, AuthError>;
+    async fn rotate_refresh_token(
+        &self,
+        token_id: i32,
+        session_id: i32,
+        meta: &SessionMeta,
+        new_token_hash: &str,
+    ) -> Result<bool, AuthError>;
+    async fn revoke_session(&self, session_id: i32) -> Result<(), AuthError>;
+    async fn revoke_session_by_public_id(&self, user_id: i32, public_id: Uuid) -> Result<bool, AuthError>;
+    async fn revoke_all_sessions(&self, user_id: i32) -> Result<u64, AuthError>;
+    async fn get_active_sessions(&self, user_id: i32) -> Result<Vec<SessionSelectResult>, AuthError>;
+    async fn get_session_status(&self, user_id: i32, public_id: Uuid) -> Result<Option<SessionStatus>, AuthError>;
+}
\ No newline at end of file
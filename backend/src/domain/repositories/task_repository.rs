@@ -1,38 +1,50 @@
 //! # タスクリポジトリ　インタフェース
+//!
+//! 更新系のメソッドは全て `user_id` で所有者を絞り込み、
+//! 他ユーザーのタスクは存在しないものとして扱う
 
 use async_trait::async_trait;
 use crate::{
     application::errors::task_error::TaskError,
-    domain::entities::task::*
+    domain::entities::{pagination::Pagination, task::*}
 };
 
 #[async_trait]
 pub trait TaskRepository: Send + Sync {
     async fn get_tasks(&self,
         user_id: i32,
+        query: &TaskListQuery,
+        pagination: &Pagination,
     ) -> Result<TaskListResponse, TaskError>;
 
+    async fn get_task(
+        &self,
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<Option<TaskItem>, TaskError>;
+
     async fn create_task(
         &self,
         user_id: i32,
         task_req: &RequestCreateTaskItem,
-    ) -> Result<ResponseCreateTaskItem, TaskError>;
+    ) -> Result<TaskItem, TaskError>;
 
     async fn update_task(
         &self,
+        user_id: i32,
+        task_id: i32,
         task_req: &RequestUpdateTaskItem,
-
-    ) -> Result<(), TaskError>;
+    ) -> Result<Option<TaskItem>, TaskError>;
 
     async fn delete_task(
         &self,
-        task_req: &RequestDeleteTaskItem,
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<bool, TaskError>;
 
-    ) -> Result<(), TaskError>;
-    
     async fn complete_task(
         &self,
-        task_req: &RequestCompleteTaskItem,
-
-    ) -> Result<(), TaskError>;
-}
\ No newline at end of file
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<Option<TaskItem>, TaskError>;
+}
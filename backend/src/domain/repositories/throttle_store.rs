@@ -0,0 +1,22 @@
+//! # ログイン試行のカウンター　インタフェース
+//!
+//! ログインのリクエスト数・失敗回数を、キー（`request:ip:{IP アドレス}` など）ごとに期間を区切って数える
+//! 実装はプロセスのメモリ（`MemoryThrottleStore`）と DB（`PgThrottleStore`）から設定で選択する
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::entities::login_protection::ThrottleCounter
+};
+
+#[async_trait]
+pub trait ThrottleStore: Send + Sync {
+    /// 回数を1つ増やす（`window` を過ぎている場合は 1 から数え直す）
+    async fn increment(&self, key: &str, window: Duration) -> Result<ThrottleCounter, AuthError>;
+    async fn get(&self, key: &str) -> Result<Option<ThrottleCounter>, AuthError>;
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), AuthError>;
+    async fn reset(&self, key: &str) -> Result<(), AuthError>;
+    /// `window_started_before` より前に数え始め、ロックしていないカウンターを削除
+    async fn prune(&self, window_started_before: DateTime<Utc>) -> Result<u64, AuthError>;
+}
\ No newline at end of file
@@ -3,11 +3,19 @@
 use async_trait::async_trait;
 use crate::{
     application::{errors::user_error::UserError, jwt::jwt::Claims},
-    domain::entities::user::UserResponse
+    domain::entities::pagination::Pagination,
+    domain::entities::user::{UpdateUserRequest, UserListResponse, UserResponse},
+    domain::enums::role::Role
 };
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn get_user_id(&self, user: &Claims) ->Result<Option<i32>, UserError>;
     async fn find_user_by_id(&self, user_id: &str) -> Result<Option<UserResponse>, UserError>;
+    async fn update_user(&self, user_id: i32, req: &UpdateUserRequest) -> Result<Option<UserResponse>, UserError>;
+    async fn get_password_hash(&self, user_id: i32) -> Result<Option<String>, UserError>;
+    async fn update_password(&self, user_id: i32, password_hash: &str, current_session: &str) -> Result<bool, UserError>;
+    async fn get_users(&self, pagination: &Pagination) -> Result<UserListResponse, UserError>;
+    async fn delete_user(&self, user_id: i32) -> Result<bool, UserError>;
+    async fn update_role(&self, user_id: i32, role: Role, changed_by: i32) -> Result<Option<UserResponse>, UserError>;
 }
\ No newline at end of file
@@ -4,103 +4,886 @@
 //! 
 //! ## メソッド
 //! 
-//! `guest_login` - ゲストログイン
-//! `signup`      - 新規登録
-//! `login`       - ログイン
+//! `guest_login`               - ゲストログイン（ゲストユーザーを作成してセッションを開始）
+//! `convert_guest`             - ゲストのアカウント登録（データを引き継いで一般ユーザーにする）
+//! `prune_expired_guests`      - 有効期限切れのゲストとデータを削除
+//! `signup`                    - 新規登録
+//! `login`                     - ログイン
+//! `login_verified_user`       - 外部の ID プロバイダーで本人確認済みのユーザーのログイン
+//! `resend_verification_email` - メール認証リンクの再送信
+//! `verify_email`              - メール認証
+//! `is_email_verified`         - メール認証済みかどうか
+//! `forgot_password`           - パスワードリセットリンクの送信
+//! `reset_password`            - パスワードリセット
+//! `refresh_session`           - リフレッシュトークンによるトークンの再発行
+//! `logout`                    - 現在のセッションを失効
+//! `logout_all`                - 全端末のセッションを失効
+//! `get_sessions`              - 有効なセッション一覧
+//! `revoke_session`            - 指定したセッションを失効
+//! `find_valid_session`        - JWT のセッションが有効かつパスワード・ロール変更後に発行されたものであれば、その状態
+//! `verify_mfa_login`          - 2段階認証のコードを検証してログインを完了
+//! `get_mfa_status`            - 2段階認証の状態
+//! `setup_totp`                - TOTP の登録（シークレットの発行）
+//! `confirm_totp`              - TOTP の確認のコードを検証して有効にし、リカバリーコードを発行
+//! `disable_totp`              - 2段階認証を無効化（パスワードで本人確認）
+//! `regenerate_recovery_codes` - リカバリーコードの再発行（パスワードで本人確認）
 
+use std::sync::Arc;
 use async_trait::async_trait;
-use argon2::Argon2;
-use argon2::password_hash::{
-    rand_core::OsRng,
-    PasswordHash,
-    PasswordHasher,
-    PasswordVerifier,
-    SaltString
-};
-use crate::domain::entities::auth::{LoginResponse, SignupResponse};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use crate::domain::entities::auth::{GuestLoginResponse, LoginOutcome, LoginResponse, LoginSelectResult, SignupInsertResult, SignupResponse};
 use crate::info_log;
 use crate::{
     application::errors::auth_error::AuthError,
-    application::jwt::jwt,
-    application::types::di_type::AuthRepositoryArc,
+    application::helpers::password::PasswordHasher,
+    application::helpers::token::{generate_recovery_code, generate_token, hash_recovery_code, hash_token, refresh_token_ttl},
+    application::helpers::totp::{self, is_totp_code},
+    application::i18n::request_locale::{current_locale, preferred_or_current},
+    application::jwt::{jwt::Claims, jwt_keys::JwtKeys},
+    application::metrics::registry::{USER_LOGINS_TOTAL, USER_REGISTRATIONS_TOTAL},
+    application::use_cases::mail_composer::{reset_mail, verification_mail},
+    application::types::di_type::{AuthRepositoryArc, LoginGuardServiceArc, MfaRepositoryArc, SessionRepositoryArc},
     domain::entities::auth::{LoginRequest, SignupRequest},
+    domain::entities::login_protection::LoginAttempt,
+    domain::entities::mfa::{MfaChallengeResponse, MfaLoginRequest, MfaStatusResponse, RecoveryCodesResponse, TotpSetupResponse, UserTotp},
+    domain::entities::session::{IssuedTokens, SessionMeta, SessionResponse, SessionStatus},
+    domain::enums::locale::Locale,
+    domain::enums::login_failure::LoginFailureReason,
+    domain::enums::role::Role,
+    domain::services::login_guard_service::normalize_email,
+    infrastructure::config::app_config::{AppConfig, AuthSettings, GuestSettings, MfaSettings},
     {app_log, error_log}
 };
 
+/// ゲストのメールアドレスのドメイン
+///
+/// 予約済みのドメイン（RFC 2606）のため、メールは送信されず、登録済みのユーザーのメールアドレスとも重複しない
+const GUEST_EMAIL_DOMAIN: &str = "guest.invalid";
+
+/// パスワードリセットのリクエストの最短の処理時間
+///
+/// 登録済みのメールアドレス（トークンの保存・メールの登録あり）と未登録のメールアドレスで、
+/// レスポンスまでの時間に差が出ないよう、この時間まで待ってから返す
+pub const FORGOT_PASSWORD_MIN_DURATION: std::time::Duration = std::time::Duration::from_millis(500);
+
 #[async_trait]
 pub trait AuthService: Send + Sync {
-    async fn register_user(&self, req: &SignupRequest) -> Result<(SignupResponse, String), AuthError>;
-    async fn login_user(&self, req: &LoginRequest) -> Result<(LoginResponse, String), AuthError>;
+    async fn guest_login(&self, meta: &SessionMeta) -> Result<(GuestLoginResponse, IssuedTokens), AuthError>;
+    async fn convert_guest(&self, user_id: i32, req: &SignupRequest, meta: &SessionMeta) -> Result<(SignupResponse, IssuedTokens), AuthError>;
+    async fn prune_expired_guests(&self) -> Result<u64, AuthError>;
+    async fn register_user(&self, req: &SignupRequest, meta: &SessionMeta) -> Result<(SignupResponse, IssuedTokens), AuthError>;
+    async fn login_user(&self, req: &LoginRequest, meta: &SessionMeta) -> Result<LoginOutcome, AuthError>;
+    async fn login_verified_user(&self, email: &str, meta: &SessionMeta) -> Result<LoginOutcome, AuthError>;
+    async fn resend_verification_email(&self, user_id: i32) -> Result<(), AuthError>;
+    async fn verify_email(&self, token: &str) -> Result<(), AuthError>;
+    async fn is_email_verified(&self, user_id: i32) -> Result<bool, AuthError>;
+    async fn forgot_password(&self, email: &str) -> Result<(), AuthError>;
+    async fn reset_password(&self, token: &str, password: &str) -> Result<(), AuthError>;
+    async fn refresh_session(&self, refresh_token: &str, meta: &SessionMeta) -> Result<IssuedTokens, AuthError>;
+    async fn logout(&self, refresh_token: Option<&str>, claims: Option<&Claims>) -> Result<(), AuthError>;
+    async fn logout_all(&self, user_id: i32) -> Result<u64, AuthError>;
+    async fn get_sessions(&self, claims: &Claims) -> Result<Vec<SessionResponse>, AuthError>;
+    async fn revoke_session(&self, user_id: i32, session_id: &str) -> Result<(), AuthError>;
+    async fn find_valid_session(&self, claims: &Claims) -> Result<Option<SessionStatus>, AuthError>;
+    async fn verify_mfa_login(&self, req: &MfaLoginRequest, meta: &SessionMeta) -> Result<(LoginResponse, IssuedTokens), AuthError>;
+    async fn get_mfa_status(&self, user_id: i32) -> Result<MfaStatusResponse, AuthError>;
+    async fn setup_totp(&self, claims: &Claims) -> Result<TotpSetupResponse, AuthError>;
+    async fn confirm_totp(&self, user_id: i32, code: &str) -> Result<RecoveryCodesResponse, AuthError>;
+    async fn disable_totp(&self, user_id: i32, password: &str, meta: &SessionMeta) -> Result<(), AuthError>;
+    async fn regenerate_recovery_codes(&self, user_id: i32, password: &str, meta: &SessionMeta) -> Result<RecoveryCodesResponse, AuthError>;
 }
 
 pub struct AuthServiceImpl {
     auth_repository: AuthRepositoryArc,
+    session_repository: SessionRepositoryArc,
+    mfa_repository: MfaRepositoryArc,
+    login_guard_service: LoginGuardServiceArc,
+    password_hasher: Arc<PasswordHasher>,
+    jwt_keys: Arc<JwtKeys>,
+    settings: AuthSettings,
+    mfa_settings: MfaSettings,
+    guest_settings: GuestSettings,
 }
 
 impl AuthServiceImpl {
-    pub fn new(auth_repository: AuthRepositoryArc) -> Self {
-        AuthServiceImpl { auth_repository }
+    pub fn new(
+        auth_repository: AuthRepositoryArc,
+        session_repository: SessionRepositoryArc,
+        mfa_repository: MfaRepositoryArc,
+        login_guard_service: LoginGuardServiceArc,
+        password_hasher: Arc<PasswordHasher>,
+        jwt_keys: Arc<JwtKeys>,
+        config: &AppConfig
+    ) -> Self {
+        AuthServiceImpl {
+            auth_repository,
+            session_repository,
+            mfa_repository,
+            login_guard_service,
+            password_hasher,
+            jwt_keys,
+            settings: config.auth.clone(),
+            mfa_settings: config.mfa.clone(),
+            guest_settings: config.guest.clone()
+        }
+    }
+
+    /// メール認証トークンの有効期限
+    fn verification_token_ttl(&self) -> Duration {
+        Duration::hours(self.settings.verification_token_ttl_hours)
+    }
+
+    /// メール認証リンクを再送信できるまでの待機時間
+    fn verification_resend_cooldown(&self) -> Duration {
+        Duration::seconds(self.settings.verification_resend_cooldown_secs)
+    }
+
+    /// パスワードリセットトークンの有効期限
+    fn password_reset_token_ttl(&self) -> Duration {
+        Duration::minutes(self.settings.password_reset_token_ttl_minutes)
+    }
+
+    /// パスワードリセットリンクを再送信できるまでの待機時間
+    fn password_reset_cooldown(&self) -> Duration {
+        Duration::seconds(self.settings.password_reset_cooldown_secs)
+    }
+
+    /// パスワードリセットトークンを発行し、リセットリンクのメールをメール送信キューに登録
+    ///
+    /// 未登録のメールアドレス・ゲスト・再送信の待機時間中はメールを送信せず、`Ok(())` を返す
+    async fn send_password_reset(&self, email: &str) -> Result<(), AuthError> {
+        let user = self.auth_repository
+            .get_user_by_email(email)
+            .await?
+            .filter(|user| user.role != Role::Guest.as_str());
+        let Some(user) = user else {
+            info_log!("[auth_service] - [forgot_password] no user for requested email");
+            return Ok(());
+        };
+
+        if let Some(sent_at) = self.auth_repository.get_last_password_reset_sent_at(user.id).await? {
+            if Utc::now() < sent_at + self.password_reset_cooldown() {
+                info_log!("[auth_service] - [forgot_password] user_id = {} reset mail skipped during cooldown", user.id);
+                return Ok(());
+            }
+        }
+
+        let (token, token_hash) = generate_token();
+        let expires_at = Utc::now() + self.password_reset_token_ttl();
+
+        let mail = reset_mail(&user.email, &token, preferred_or_current(user.locale.as_deref()));
+
+        self.auth_repository.create_password_reset_token(user.id, &token_hash, expires_at, &mail).await
+    }
+
+    /// 新しいセッションを作成し、アクセストークンとリフレッシュトークンを発行
+    ///
+    /// `not_after` を指定した場合（ゲスト）は、セッションとアクセストークンの有効期限をその日時までにする
+    async fn start_session(
+        &self,
+        user_id: i32,
+        email: &str,
+        role: &str,
+        meta: &SessionMeta,
+        not_after: Option<DateTime<Utc>>
+    ) -> Result<IssuedTokens, AuthError> {
+        let public_id = Uuid::new_v4();
+        let (refresh_token, refresh_token_hash) = generate_token();
+        let expires_at = not_after.map_or(Utc::now() + refresh_token_ttl(), |not_after| not_after.min(Utc::now() + refresh_token_ttl()));
+
+        self.session_repository
+            .create_session(user_id, public_id, meta, expires_at, &refresh_token_hash)
+            .await?;
+
+        Ok(IssuedTokens {
+            access_token: self.jwt_keys.create_token_until(
+                email,
+                &user_id,
+                &public_id.to_string(),
+                role.parse().unwrap_or_default(),
+                expires_at.timestamp() as u64
+            )?,
+            refresh_token,
+        })
+    }
+
+    /// 新規登録・ゲストのアカウント登録のレスポンスを作成
+    fn signup_response(&self, user: SignupInsertResult, tokens: &IssuedTokens) -> SignupResponse {
+        SignupResponse {
+            id: user.id.to_string(),
+            name: user.name,
+            email: user.email,
+            role: user.role,
+            photo: user.photo,
+            bio: user.bio,
+            is_verified: user.is_verified,
+            token: tokens.access_token.clone(),
+            refresh_token: tokens.refresh_token.clone(),
+        }
+    }
+
+    /// メール認証トークンを発行し、認証リンクのメールをメール送信キューに登録
+    ///
+    /// DB にはトークンのハッシュのみを保存する
+    async fn issue_verification_token(&self, user_id: i32, email: &str, locale: Locale) -> Result<(), AuthError> {
+        let (token, token_hash) = generate_token();
+        let expires_at = Utc::now() + self.verification_token_ttl();
+        let mail = verification_mail(email, &token, locale);
+
+        self.auth_repository.create_verification_token(user_id, &token_hash, expires_at, &mail).await
+    }
+
+    /// セッションを開始し、ログインのレスポンスを作成
+    async fn complete_login(&self, user: LoginSelectResult, meta: &SessionMeta) -> Result<(LoginResponse, IssuedTokens), AuthError> {
+        let tokens = self.start_session(user.id, &user.email, &user.role, meta, None).await?;
+
+        let response = LoginResponse {
+            id: user.id.to_string(),
+            name: user.name,
+            email: user.email,
+            role: user.role,
+            photo: user.photo,
+            bio: user.bio,
+            is_verified: user.is_verified,
+            token: tokens.access_token.clone(),
+            refresh_token: tokens.refresh_token.clone(),
+        };
+
+        USER_LOGINS_TOTAL.with_label_values(&["success"]).inc();
+        Ok((response, tokens))
+    }
+
+    /// ログインの失敗を記録し、失敗が続いた場合はレスポンスを遅らせる
+    async fn reject_login(&self, attempt: &LoginAttempt, user_id: Option<i32>, reason: LoginFailureReason) -> Result<(), AuthError> {
+        error_log!("[auth_service] - [login] - [message: Authentication Failed] reason = {}", reason.as_str());
+        USER_LOGINS_TOTAL.with_label_values(&["failure"]).inc();
+
+        let delay = self.login_guard_service.record_failure(attempt, user_id, reason).await?;
+        tokio::time::sleep(delay).await;
+
+        Ok(())
+    }
+
+    /// 本人確認済みのユーザーのログインを完了
+    ///
+    /// 2段階認証が有効なユーザーは、セッションを開始せずにチャレンジを返す
+    async fn finish_login(&self, user: LoginSelectResult, attempt: &LoginAttempt, meta: &SessionMeta) -> Result<LoginOutcome, AuthError> {
+        if self.mfa_repository.get_totp(user.id).await?.is_some_and(|totp| totp.is_enabled()) {
+            return Ok(LoginOutcome::MfaRequired(self.issue_mfa_challenge(user.id).await?));
+        }
+        self.login_guard_service.record_success(attempt).await?;
+
+        let (response, tokens) = self.complete_login(user, meta).await?;
+
+        Ok(LoginOutcome::Authenticated(Box::new(response), tokens))
+    }
+
+    /// 2段階認証のチャレンジを作成
+    ///
+    /// DB にはトークンのハッシュのみを保存する
+    async fn issue_mfa_challenge(&self, user_id: i32) -> Result<MfaChallengeResponse, AuthError> {
+        let (token, token_hash) = generate_token();
+        let expires_at = Utc::now() + Duration::seconds(self.mfa_settings.challenge_ttl_secs);
+
+        self.mfa_repository.create_challenge(user_id, &token_hash, expires_at).await?;
+        info_log!("[auth_service] - [login_user] user_id = {} mfa required", user_id);
+
+        Ok(MfaChallengeResponse { mfa_required: true, mfa_token: token, expires_at })
+    }
+
+    /// TOTP のコード、またはリカバリーコードを検証
+    ///
+    /// 使用したコードは記録し、同じコードは再度使用できない
+    async fn verify_second_factor(&self, user_id: i32, code: &str) -> Result<bool, AuthError> {
+        let code = code.trim();
+
+        if !is_totp_code(code) {
+            let used = self.mfa_repository.use_recovery_code(user_id, &hash_recovery_code(code)).await?;
+            if used {
+                info_log!("[auth_service] - [verify_mfa_login] user_id = {} used a recovery code", user_id);
+            }
+            return Ok(used);
+        }
+
+        let Some(user_totp) = self.mfa_repository.get_totp(user_id).await?.filter(UserTotp::is_enabled) else {
+            return Ok(false);
+        };
+
+        // 使用済みのステップのコードは DB を更新せずに拒否する
+        match totp::verify_code(&user_totp.secret, code, Utc::now().timestamp()) {
+            Some(step) if user_totp.last_used_step.is_none_or(|last| step > last) => {
+                self.mfa_repository.use_totp_step(user_id, step).await
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// 以前の形式（bcrypt・コストの異なる argon2）のハッシュを、現在の設定で作り直す
+    ///
+    /// 検証に成功したパスワードのみ渡す。失敗してもログインは続行する
+    async fn rehash_password(&self, user_id: i32, current_hash: &str, password: &str) {
+        if !self.password_hasher.needs_rehash(current_hash) {
+            return;
+        }
+
+        let result = match self.password_hasher.hash(password) {
+            Ok(new_hash) => self.auth_repository.update_password_hash(user_id, current_hash, &new_hash).await,
+            Err(err) => Err(AuthError::from(err)),
+        };
+        match result {
+            Ok(true) => info_log!("[auth_service] - [rehash_password] user_id = {} password hash upgraded", user_id),
+            Ok(false) => {}
+            Err(err) => error_log!("[auth_service] - [rehash_password] - [message: Failed to upgrade password hash] user_id = {}, error = {}", user_id, err),
+        }
+    }
+
+    /// パスワードで本人を確認
+    ///
+    /// ログインと同じく、アカウント・IP アドレスがロック中の場合は拒否し、失敗を記録する
+    /// 盗まれたセッションでパスワードを総当たりできないようにする
+    async fn confirm_password(&self, user_id: i32, password: &str, meta: &SessionMeta) -> Result<(), AuthError> {
+        let user = self.auth_repository
+            .get_verification_user(user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+        let attempt = LoginAttempt {
+            email: normalize_email(&user.email),
+            ip_address: meta.ip_address.clone(),
+            user_agent: meta.user_agent.clone(),
+        };
+        self.login_guard_service.check_request(attempt.ip_address.as_deref(), Some(&attempt.email)).await?;
+
+        let hash = self.auth_repository
+            .get_password_hash(user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        if !self.password_hasher.verify(password, &hash)? {
+            error_log!("[auth_service] - [confirm_password] - [message: Authentication Failed] user_id = {}", user_id);
+            let delay = self.login_guard_service.record_failure(&attempt, Some(user_id), LoginFailureReason::WrongPassword).await?;
+            tokio::time::sleep(delay).await;
+
+            return Err(AuthError::InvalidCredentials);
+        }
+        self.login_guard_service.record_success(&attempt).await?;
+
+        Ok(())
+    }
+
+    /// リカバリーコードを生成
+    ///
+    /// # 戻り値
+    ///
+    /// * `(Vec<String>, Vec<String>)` - ユーザーに表示するコードと、DB に保存するハッシュ
+    fn generate_recovery_codes(&self) -> (Vec<String>, Vec<String>) {
+        (0..self.mfa_settings.recovery_code_count).map(|_| generate_recovery_code()).unzip()
     }
 }
 
 #[async_trait]
 impl AuthService for AuthServiceImpl {
-    async fn register_user(&self, req: &SignupRequest) -> Result<(SignupResponse, String), AuthError> {
+    /// メールアドレス・パスワードのないゲストユーザーを作成し、セッションを開始
+    ///
+    /// ゲストはタスクの作成などを試すことができ、有効期限（`guest.ttl_hours`）までに
+    /// `convert_guest` でアカウントを登録しない場合は、ユーザーとデータを削除します。
+    /// セッションとアクセストークンは、ゲストの有効期限を超えて発行しません。
+    ///
+    /// # 引数
+    ///
+    /// * `meta` - ログイン端末の情報
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok((GuestLoginResponse, IssuedTokens))` - 作成したゲストと発行したトークンを返します。
+    /// - `Err(AuthError)`                         - ゲストログインが無効な場合は `GuestLoginDisabled` を返します。
+    async fn guest_login(&self, meta: &SessionMeta) -> Result<(GuestLoginResponse, IssuedTokens), AuthError> {
+        if !self.guest_settings.enabled {
+            return Err(AuthError::GuestLoginDisabled);
+        }
+
+        let guest_id = Uuid::new_v4().simple().to_string();
+        let name = format!("guest-{}", &guest_id[..8]);
+        let email = format!("{}@{}", guest_id, GUEST_EMAIL_DOMAIN);
+        // パスワードでログインできないよう、ランダムな値のハッシュを保存する
+        let (random_password, _) = generate_token();
+        let password_hash = self.password_hasher.hash(&random_password)?;
+        let expires_at = Utc::now() + Duration::hours(self.guest_settings.ttl_hours);
+
+        let guest = self.auth_repository.create_guest(&name, &email, &password_hash, expires_at).await?;
+        let tokens = self.start_session(guest.id, &guest.email, &guest.role, meta, Some(expires_at)).await?;
+        info_log!("[auth_service] - [guest_login] user_id = {} guest created", guest.id);
+
+        let response = GuestLoginResponse {
+            id: guest.id.to_string(),
+            name: guest.name,
+            role: guest.role,
+            token: tokens.access_token.clone(),
+            refresh_token: tokens.refresh_token.clone(),
+            expires_at,
+        };
+
+        Ok((response, tokens))
+    }
+
+    /// ゲストのアカウント登録
+    ///
+    /// ゲストに名前・メールアドレス・パスワードを設定して一般ユーザーにします。
+    /// ユーザー ID は変わらないため、ゲストで作成したタスクなどのデータはそのまま引き継ぎます。
+    /// 新規登録と同じく認証メールを送信し、ゲストのセッションを失効させて新しいセッションを開始します。
+    ///
+    /// # 引数
+    ///
+    /// * `user_id` - ゲストのユーザー ID
+    /// * `req`     - 登録する名前・メールアドレス・パスワード
+    /// * `meta`    - ログイン端末の情報
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok((SignupResponse, IssuedTokens))` - 登録したユーザーと発行したトークンを返します。
+    /// - `Err(AuthError)`                     - ゲストではない、または有効期限切れの場合は `NotGuest` を返します。
+    async fn convert_guest(&self, user_id: i32, req: &SignupRequest, meta: &SessionMeta) -> Result<(SignupResponse, IssuedTokens), AuthError> {
+        let hashed_password = self.password_hasher.hash(&req.password)?;
+
+        let converted = self.auth_repository
+            .convert_guest(user_id, &req.name, &req.email, &hashed_password)
+            .await?
+            .ok_or(AuthError::NotGuest)?;
+        USER_REGISTRATIONS_TOTAL.inc();
+        info_log!("[auth_service] - [convert_guest] user_id = {} guest converted", user_id);
+
+        // 認証メールの送信に失敗しても登録は取り消さない（`/auth/verify-email` で再送信できる）
+        if let Err(err) = self.issue_verification_token(converted.id, &converted.email, current_locale()).await {
+            error_log!("[auth_service] - [convert_guest] - [message: Failed to create verification token] - Error: {}", err);
+        }
+
+        let tokens = self.start_session(converted.id, &converted.email, &converted.role, meta, None).await?;
+
+        Ok((self.signup_response(converted, &tokens), tokens))
+    }
+
+    async fn prune_expired_guests(&self) -> Result<u64, AuthError> {
+        self.auth_repository.delete_expired_guests().await
+    }
+
+    async fn register_user(&self, req: &SignupRequest, meta: &SessionMeta) -> Result<(SignupResponse, IssuedTokens), AuthError> {
         // パスワードを暗号化
-        let argon2 = Argon2::default();
-        let salt = SaltString::generate(&mut OsRng);
-        let hashed_password = argon2.hash_password(&req.password.as_bytes(), &salt)?.to_string();
+        let hashed_password = self.password_hasher.hash(&req.password)?;
         
+        // メール認証トークンと認証メールは、ユーザーと同じトランザクションで登録する
+        let (token, token_hash) = generate_token();
+        let expires_at = Utc::now() + self.verification_token_ttl();
+        let mail = verification_mail(&req.email, &token, current_locale());
+
         // DB結果
-        let insert_result = self.auth_repository.register_user(&req.name, &req.email, &hashed_password).await?;
-
-        // JWT トークン生成
-        let token = jwt::create_token(&insert_result.email, &insert_result.id)?;
-
-        let response = SignupResponse {
-            id: insert_result.id.to_string(),
-            name: insert_result.name,
-            email: insert_result.email,
-            role: insert_result.role,
-            photo: insert_result.photo,
-            bio: insert_result.bio,
-            is_verified: insert_result.is_verified,
-            token: token.clone()
-        };
+        let insert_result = self.auth_repository
+            .register_user(&req.name, &req.email, &hashed_password, &token_hash, expires_at, &mail)
+            .await?;
+        USER_REGISTRATIONS_TOTAL.inc();
 
-        Ok((response, token))
+        // セッション作成・トークン生成
+        let tokens = self.start_session(insert_result.id, &insert_result.email, &insert_result.role, meta, None).await?;
+
+        Ok((self.signup_response(insert_result, &tokens), tokens))
     }
 
-    async fn login_user(&self, req: &LoginRequest) -> Result<(LoginResponse, String), AuthError> {
+    /// 2段階認証が有効なユーザーは、セッションを開始せずにチャレンジを返す
+    ///
+    /// アカウントの失敗回数は、2段階認証を含めてログインが完了した時点でリセットする
+    async fn login_user(&self, req: &LoginRequest, meta: &SessionMeta) -> Result<LoginOutcome, AuthError> {
         info_log!("[auth_service] - [login_user] login_user called");
-        if let Some(select_result) = self.auth_repository.get_user_by_email(&req.email).await? {
-            // パスワードのハッシュを検証
-            let argon2 = Argon2::default();
-            let parsed_hash = PasswordHash::new(&select_result.password)?;
-
-            // 検証
-            if let Err(err) = argon2.verify_password(req.password.as_bytes(), &parsed_hash) {
-                error_log!("[auth_service] - [login] - [message: Authentication Failed] - Error: {:?}", err);
+        let attempt = LoginAttempt {
+            email: normalize_email(&req.email),
+            ip_address: meta.ip_address.clone(),
+            user_agent: meta.user_agent.clone(),
+        };
+        let user = self.auth_repository.get_user_by_email(&req.email).await?;
+
+        // 未登録のメールアドレスもダミーのハッシュで検証し、応答時間とエラーを登録済みの場合と揃える
+        let hash = user.as_ref().map_or(self.password_hasher.dummy_hash(), |user| user.password.as_str());
+        let verified = self.password_hasher.verify(&req.password, hash)?;
+
+        let select_result = match user {
+            Some(user) if verified => user,
+            user => {
+                let (user_id, reason) = match &user {
+                    Some(user) => (Some(user.id), LoginFailureReason::WrongPassword),
+                    None => (None, LoginFailureReason::UnknownUser),
+                };
+                self.reject_login(&attempt, user_id, reason).await?;
+
                 return Err(AuthError::InvalidCredentials);
             }
-    
-            let token = jwt::create_token(&select_result.email, &select_result.id)?;
-
-            let response = LoginResponse {
-                id: select_result.id.to_string(),
-                name: select_result.name,
-                email: select_result.email,
-                role: select_result.role,
-                photo: select_result.photo,
-                bio: select_result.bio,
-                is_verified: select_result.is_verified,
-                token: token.clone()
-            };
-    
-            Ok((response, token))
+        };
+        self.rehash_password(select_result.id, &select_result.password, &req.password).await;
+
+        self.finish_login(select_result, &attempt, meta).await
+    }
+
+    /// パスワードの代わりに、外部の ID プロバイダーで本人を確認したユーザーをログインさせる
+    ///
+    /// 2段階認証が有効なユーザーは、パスワードでのログインと同じくチャレンジを返す
+    async fn login_verified_user(&self, email: &str, meta: &SessionMeta) -> Result<LoginOutcome, AuthError> {
+        info_log!("[auth_service] - [login_verified_user] login_verified_user called");
+        let attempt = LoginAttempt {
+            email: normalize_email(email),
+            ip_address: meta.ip_address.clone(),
+            user_agent: meta.user_agent.clone(),
+        };
+        let select_result = self.auth_repository
+            .get_user_by_email(email)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        self.finish_login(select_result, &attempt, meta).await
+    }
+    async fn resend_verification_email(&self, user_id: i32) -> Result<(), AuthError> {
+        let user = self.auth_repository
+            .get_verification_user(user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        if user.is_verified {
+            return Err(AuthError::AlreadyVerified);
+        }
+
+        // 待機時間中は再送信しない
+        if let Some(sent_at) = self.auth_repository.get_last_verification_sent_at(user.id).await? {
+            if Utc::now() < sent_at + self.verification_resend_cooldown() {
+                return Err(AuthError::TooManyRequests);
+            }
+        }
+
+        self.issue_verification_token(user.id, &user.email, preferred_or_current(user.locale.as_deref())).await
+    }
+
+    async fn verify_email(&self, token: &str) -> Result<(), AuthError> {
+        match self.auth_repository.verify_email_token(&hash_token(token)).await? {
+            Some(user_id) => {
+                info_log!("[auth_service] - [verify_email] user_id = {} verified", user_id);
+                Ok(())
+            }
+            None => Err(AuthError::InvalidToken),
+        }
+    }
+
+    async fn is_email_verified(&self, user_id: i32) -> Result<bool, AuthError> {
+        let user = self.auth_repository
+            .get_verification_user(user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        Ok(user.is_verified)
+    }
+    /// ユーザーの存在有無を呼び出し元に伝えないよう、登録されていないメールアドレスでも `Ok(())` を返す
+    ///
+    /// ゲストのメールアドレスにはメールを送信できないため、登録されていない場合と同じく扱う
+    /// 再送信の待機時間（`auth.password_reset_cooldown_secs`）中も、待機時間を伝えずに `Ok(())` を返す
+    /// 処理時間からも判別できないよう、`FORGOT_PASSWORD_MIN_DURATION` まで待ってから返す
+    async fn forgot_password(&self, email: &str) -> Result<(), AuthError> {
+        let started_at = tokio::time::Instant::now();
+        let result = self.send_password_reset(email).await;
+        tokio::time::sleep_until(started_at + FORGOT_PASSWORD_MIN_DURATION).await;
+
+        result
+    }
+
+    async fn reset_password(&self, token: &str, password: &str) -> Result<(), AuthError> {
+        let hashed_password = self.password_hasher.hash(password)?;
+
+        match self.auth_repository.reset_password(&hash_token(token), &hashed_password).await? {
+            Some(user_id) => {
+                info_log!("[auth_service] - [reset_password] user_id = {} password reset", user_id);
+                Ok(())
+            }
+            None => Err(AuthError::InvalidToken),
+        }
+    }
+
+    /// リフレッシュトークンを新しいトークンに入れ替え、アクセストークンを再発行
+    ///
+    /// 使用済みのトークンが再度使われた場合は、漏洩したものとみなしてセッションごと失効させる
+    async fn refresh_session(&self, refresh_token: &str, meta: &SessionMeta) -> Result<IssuedTokens, AuthError> {
+        let record = self.session_repository
+            .find_refresh_token(&hash_token(refresh_token))
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+        let session = &record.session;
+
+        if record.used_at.is_some() {
+            error_log!("[auth_service] - [refresh_session] - [message: Refresh token reuse detected] session_id = {}", session.public_id);
+            self.session_repository.revoke_session(session.id).await?;
+            return Err(AuthError::InvalidToken);
+        }
+
+        if !session.is_active(Utc::now()) {
+            return Err(AuthError::InvalidToken);
+        }
+
+        let user = self.auth_repository
+            .get_verification_user(session.user_id)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let (new_refresh_token, new_refresh_token_hash) = generate_token();
+        if !self.session_repository.rotate_refresh_token(record.id, session.id, meta, &new_refresh_token_hash).await? {
+            // 同時に同じトークンが使われた場合
+            error_log!("[auth_service] - [refresh_session] - [message: Refresh token reuse detected] session_id = {}", session.public_id);
+            self.session_repository.revoke_session(session.id).await?;
+            return Err(AuthError::InvalidToken);
+        }
+
+        // アクセストークンはセッション（ゲストの場合はゲスト）の有効期限を超えて発行しない
+        Ok(IssuedTokens {
+            access_token: self.jwt_keys.create_token_until(
+                &user.email,
+                &user.id,
+                &session.public_id.to_string(),
+                user.role.parse().unwrap_or_default(),
+                session.expires_at.timestamp() as u64
+            )?,
+            refresh_token: new_refresh_token,
+        })
+    }
+
+    /// リフレッシュトークンのセッション、またはアクセストークンのセッションを失効
+    async fn logout(&self, refresh_token: Option<&str>, claims: Option<&Claims>) -> Result<(), AuthError> {
+        if let Some(refresh_token) = refresh_token {
+            if let Some(record) = self.session_repository.find_refresh_token(&hash_token(refresh_token)).await? {
+                self.session_repository.revoke_session(record.session.id).await?;
+                return Ok(());
+            }
+        }
+
+        if let Some(claims) = claims {
+            if let Ok(public_id) = Uuid::parse_str(&claims.sid) {
+                self.session_repository.revoke_session_by_public_id(claims.id, public_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn logout_all(&self, user_id: i32) -> Result<u64, AuthError> {
+        let revoked = self.session_repository.revoke_all_sessions(user_id).await?;
+        info_log!("[auth_service] - [logout_all] user_id = {} revoked {} sessions", user_id, revoked);
+
+        Ok(revoked)
+    }
+
+    async fn get_sessions(&self, claims: &Claims) -> Result<Vec<SessionResponse>, AuthError> {
+        let sessions = self.session_repository.get_active_sessions(claims.id).await?;
+
+        Ok(sessions.into_iter().map(|session| SessionResponse {
+            current: session.public_id.to_string() == claims.sid,
+            id: session.public_id.to_string(),
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+        }).collect())
+    }
+
+    async fn revoke_session(&self, user_id: i32, session_id: &str) -> Result<(), AuthError> {
+        let public_id = Uuid::parse_str(session_id).map_err(|_| AuthError::SessionNotFound)?;
+
+        if self.session_repository.revoke_session_by_public_id(user_id, public_id).await? {
+            Ok(())
         } else {
-            error_log!("[auth_service] - [login] - [message: User Not Found]");
-            Err(AuthError::UserNotFound)
+            Err(AuthError::SessionNotFound)
         }
     }
+
+    /// 有効なセッションの状態（無効な場合は `None`）
+    ///
+    /// セッションが失効している、またはパスワード変更日時より前に発行された JWT は無効
+    async fn find_valid_session(&self, claims: &Claims) -> Result<Option<SessionStatus>, AuthError> {
+        // セッションを持たない旧形式のトークンは無効
+        let Ok(public_id) = Uuid::parse_str(&claims.sid) else {
+            return Ok(None);
+        };
+
+        let status = self.session_repository.get_session_status(claims.id, public_id).await?;
+
+        Ok(status.filter(|status| {
+            status.is_active
+                // ロールが変更された場合は、リフレッシュして新しいロールのトークンを取得する
+                && status.role == claims.role.as_str()
+                // `iat` は秒単位のため、変更と同じ秒に再発行したトークンも有効とする
+                && status
+                    .password_changed_at
+                    .is_none_or(|changed_at| claims.iat as i64 >= changed_at.timestamp())
+        }))
+    }
+
+    /// 2段階認証のコードを検証してログインを完了
+    ///
+    /// 正しくないコードはパスワードと同じくアカウント・IP アドレスの失敗として数え、上限に達した場合はロックします。
+    /// 1つのチャレンジで入力できる回数（`mfa.challenge_max_attempts`）を超えた場合は、パスワードの入力からやり直します。
+    ///
+    /// # 引数
+    ///
+    /// * `req`  - チャレンジのトークンと、TOTP のコードまたはリカバリーコード
+    /// * `meta` - ログイン端末の情報
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok((LoginResponse, IssuedTokens))` - ログインしたユーザーと発行したトークンを返します。
+    /// - `Err(AuthError)`                   - チャレンジが無効な場合は `InvalidToken`、コードが正しくない場合は `InvalidMfaCode` を返します。
+    async fn verify_mfa_login(&self, req: &MfaLoginRequest, meta: &SessionMeta) -> Result<(LoginResponse, IssuedTokens), AuthError> {
+        let challenge = self.mfa_repository
+            .find_challenge(&hash_token(&req.mfa_token))
+            .await?
+            .filter(|challenge| challenge.is_active(Utc::now(), self.mfa_settings.challenge_max_attempts))
+            .ok_or(AuthError::InvalidToken)?;
+
+        let attempt = LoginAttempt {
+            email: normalize_email(&challenge.email),
+            ip_address: meta.ip_address.clone(),
+            user_agent: meta.user_agent.clone(),
+        };
+
+        if !self.verify_second_factor(challenge.user_id, &req.code).await? {
+            self.mfa_repository.record_challenge_failure(challenge.id).await?;
+            self.reject_login(&attempt, Some(challenge.user_id), LoginFailureReason::WrongMfaCode).await?;
+
+            return Err(AuthError::InvalidMfaCode);
+        }
+
+        // 同時に同じチャレンジが使われた場合は、一方のみログインする
+        if !self.mfa_repository.consume_challenge(challenge.id).await? {
+            return Err(AuthError::InvalidToken);
+        }
+        self.login_guard_service.record_success(&attempt).await?;
+
+        let user = self.auth_repository
+            .get_user_by_email(&challenge.email)
+            .await?
+            .ok_or(AuthError::UserNotFound)?;
+
+        self.complete_login(user, meta).await
+    }
+
+    async fn get_mfa_status(&self, user_id: i32) -> Result<MfaStatusResponse, AuthError> {
+        let enabled_at = self.mfa_repository.get_totp(user_id).await?.and_then(|totp| totp.enabled_at);
+
+        let recovery_codes_remaining = match enabled_at {
+            Some(_) => self.mfa_repository.count_recovery_codes(user_id).await?,
+            None => 0,
+        };
+
+        Ok(MfaStatusResponse { enabled: enabled_at.is_some(), enabled_at, recovery_codes_remaining })
+    }
+
+    /// TOTP の登録
+    ///
+    /// シークレットを発行し、確認のコードを検証するまでは登録中として保存します。
+    /// 登録中に再度呼び出した場合は、新しいシークレットに入れ替えます。
+    /// 無効化・リカバリーコードの再発行はパスワードで本人を確認するため、パスワードを設定していないユーザーは登録できません。
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(TotpSetupResponse)` - シークレットと `otpauth://` URI を返します。
+    /// - `Err(AuthError)`        - 既に有効な場合は `MfaAlreadyEnabled`、パスワードを設定していない場合は `PasswordRequired` を返します。
+    async fn setup_totp(&self, claims: &Claims) -> Result<TotpSetupResponse, AuthError> {
+        if !self.auth_repository.has_password(claims.id).await? {
+            return Err(AuthError::PasswordRequired);
+        }
+        let secret = totp::generate_secret();
+
+        if !self.mfa_repository.save_pending_totp(claims.id, &secret).await? {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        Ok(TotpSetupResponse {
+            otpauth_uri: totp::otpauth_uri(&self.mfa_settings.issuer, &claims.sub, &secret),
+            secret,
+        })
+    }
+
+    /// TOTP の確認
+    ///
+    /// 認証アプリのコードを検証して2段階認証を有効にし、リカバリーコードを発行します。
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(RecoveryCodesResponse)` - リカバリーコードを返します（再度取得することはできません）。
+    /// - `Err(AuthError)`            - 登録していない場合は `MfaNotEnabled`、既に有効な場合は `MfaAlreadyEnabled`、
+    ///                                 コードが正しくない場合は `InvalidMfaCode` を返します。
+    async fn confirm_totp(&self, user_id: i32, code: &str) -> Result<RecoveryCodesResponse, AuthError> {
+        let user_totp = self.mfa_repository
+            .get_totp(user_id)
+            .await?
+            .ok_or(AuthError::MfaNotEnabled)?;
+
+        if user_totp.is_enabled() {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+
+        let step = totp::verify_code(&user_totp.secret, code, Utc::now().timestamp()).ok_or(AuthError::InvalidMfaCode)?;
+
+        let (recovery_codes, recovery_code_hashes) = self.generate_recovery_codes();
+        if !self.mfa_repository.enable_totp(user_id, step, &recovery_code_hashes).await? {
+            return Err(AuthError::MfaAlreadyEnabled);
+        }
+        info_log!("[auth_service] - [confirm_totp] user_id = {} mfa enabled", user_id);
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
+
+    /// 2段階認証を無効化
+    ///
+    /// パスワードで本人を確認し、TOTP のシークレット・リカバリーコードを削除します。
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(())`         - 無効にした場合。
+    /// - `Err(AuthError)` - パスワードが正しくない場合は `InvalidCredentials`、ロック中の場合は `RateLimited`、有効ではない場合は `MfaNotEnabled` を返します。
+    async fn disable_totp(&self, user_id: i32, password: &str, meta: &SessionMeta) -> Result<(), AuthError> {
+        self.confirm_password(user_id, password, meta).await?;
+
+        if !self.mfa_repository.disable_totp(user_id).await? {
+            return Err(AuthError::MfaNotEnabled);
+        }
+        info_log!("[auth_service] - [disable_totp] user_id = {} mfa disabled", user_id);
+
+        Ok(())
+    }
+
+    /// リカバリーコードの再発行
+    ///
+    /// パスワードで本人を確認し、以前のリカバリーコードを全て無効にして新しいコードを発行します。
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(RecoveryCodesResponse)` - 新しいリカバリーコードを返します。
+    /// - `Err(AuthError)`            - パスワードが正しくない場合は `InvalidCredentials`、ロック中の場合は `RateLimited`、有効ではない場合は `MfaNotEnabled` を返します。
+    async fn regenerate_recovery_codes(&self, user_id: i32, password: &str, meta: &SessionMeta) -> Result<RecoveryCodesResponse, AuthError> {
+        self.confirm_password(user_id, password, meta).await?;
+
+        if !self.mfa_repository.get_totp(user_id).await?.is_some_and(|totp| totp.is_enabled()) {
+            return Err(AuthError::MfaNotEnabled);
+        }
+
+        let (recovery_codes, recovery_code_hashes) = self.generate_recovery_codes();
+        self.mfa_repository.replace_recovery_codes(user_id, &recovery_code_hashes).await?;
+        info_log!("[auth_service] - [regenerate_recovery_codes] user_id = {} recovery codes regenerated", user_id);
+
+        Ok(RecoveryCodesResponse { recovery_codes })
+    }
 }
\ No newline at end of file
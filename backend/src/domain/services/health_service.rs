@@ -0,0 +1,178 @@
+//! # ヘルスチェックサービス
+//! 
+//! 生存確認（プロセスが応答するか）と準備完了確認（依存先を利用できるか）を行うサービス
+//! 準備完了確認では DB・マイグレーション・メール送信の各依存先を並行してチェックし、それぞれタイムアウトを設ける
+//! 
+//! | `[health]` のキー          | 既定値 | 説明                                         |
+//! | -------------------------- | ------ | -------------------------------------------- |
+//! | `check_timeout_ms`         | 2000   | 依存先ごとのチェックのタイムアウト（ミリ秒） |
+//! | `mail_check_interval_secs` | 60     | メール送信のチェック結果をキャッシュする秒数 |
+//! 
+//! SMTP サーバーへの接続は重いため、メール送信のチェック結果のみキャッシュする
+//! 
+//! ## メソッド
+//! 
+//! `liveness`  - 生存確認
+//! `readiness` - 準備完了確認
+
+use std::collections::BTreeMap;
+use std::env;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use tokio::time::timeout;
+
+use crate::{
+    app_log,
+    application::types::di_type::MailerArc,
+    domain::entities::health::{BuildInfo, ComponentCheck, HealthStatus, LivenessResponse, ReadinessResponse},
+    infrastructure::config::app_config::HealthSettings,
+    infrastructure::db::{connection::DbPool, migration},
+    warning_log
+};
+
+#[async_trait]
+pub trait HealthService: Send + Sync {
+    fn liveness(&self) -> LivenessResponse;
+    async fn readiness(&self) -> ReadinessResponse;
+}
+
+pub struct HealthServiceImpl {
+    pool: DbPool,
+    mailer: MailerArc,
+    started_at: Instant,
+    check_timeout: Duration,
+    mail_check_interval: Duration,
+    mail_check_cache: Mutex<Option<(Instant, ComponentCheck)>>,
+}
+
+impl HealthServiceImpl {
+    pub fn new(pool: DbPool, mailer: MailerArc, settings: &HealthSettings) -> Self {
+        HealthServiceImpl {
+            pool,
+            mailer,
+            started_at: Instant::now(),
+            check_timeout: Duration::from_millis(settings.check_timeout_ms),
+            mail_check_interval: Duration::from_secs(settings.mail_check_interval_secs),
+            mail_check_cache: Mutex::new(None),
+        }
+    }
+
+    fn build_info(&self) -> BuildInfo {
+        build_info(self.started_at)
+    }
+
+    /// DB の疎通確認（接続プールから接続を取得し、`SELECT 1` を実行）
+    async fn check_database(&self) -> ComponentCheck {
+        run_check(self.check_timeout, async {
+            let conn = self.pool.get().await.map_err(|err| err.to_string())?;
+            conn.simple_query("SELECT 1").await.map_err(|err| err.to_string())?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 未適用のマイグレーションがないか確認
+    async fn check_migrations(&self) -> ComponentCheck {
+        run_check(self.check_timeout, async {
+            let pending = migration::pending(&self.pool).await.map_err(|err| err.to_string())?;
+            if !pending.is_empty() {
+                return Err(format!("pending migrations: {}", pending.join(", ")));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// メール送信の疎通確認（`HEALTH_MAIL_CHECK_INTERVAL_SECS` の間は前回の結果を返す）
+    async fn check_mail(&self) -> ComponentCheck {
+        if let Some((checked_at, check)) = self.mail_check_cache.lock().unwrap().as_ref() {
+            if checked_at.elapsed() < self.mail_check_interval {
+                return check.clone();
+            }
+        }
+
+        let check = run_check(self.check_timeout, async {
+            self.mailer.check().await.map_err(|err| err.to_string())
+        })
+        .await;
+        *self.mail_check_cache.lock().unwrap() = Some((Instant::now(), check.clone()));
+
+        check
+    }
+}
+
+#[async_trait]
+impl HealthService for HealthServiceImpl {
+    /// 生存確認
+    /// 
+    /// 依存先はチェックしません。プロセスが応答できれば常に `ok` を返します。
+    /// 
+    /// # 戻り値
+    /// 
+    /// * `LivenessResponse` - 状態とビルド情報
+    fn liveness(&self) -> LivenessResponse {
+        LivenessResponse { status: HealthStatus::Ok, build: self.build_info() }
+    }
+
+    /// 準備完了確認
+    /// 
+    /// DB・マイグレーション・メール送信を並行してチェックします。
+    /// 
+    /// # 戻り値
+    /// 
+    /// * `ReadinessResponse` - 依存先ごとのチェック結果。1つでも失敗していれば `degraded`
+    async fn readiness(&self) -> ReadinessResponse {
+        let (database, migrations, mail) = tokio::join!(
+            self.check_database(),
+            self.check_migrations(),
+            self.check_mail()
+        );
+
+        let checks = BTreeMap::from([("database", database), ("migrations", migrations), ("mail", mail)]);
+        for (name, check) in checks.iter().filter(|(_, c)| c.error.is_some()) {
+            warning_log!("[health_service] - [readiness] {} check failed: {}", name, check.error.as_deref().unwrap_or_default());
+        }
+
+        ReadinessResponse::new(checks, self.build_info())
+    }
+}
+
+/// ビルド情報
+/// 
+/// # 引数
+/// 
+/// * `started_at` - 起動日時
+/// 
+/// # 戻り値
+/// 
+/// * `BuildInfo` - バージョン・コミット・起動してからの秒数
+pub fn build_info(started_at: Instant) -> BuildInfo {
+    let git_sha = option_env!("GIT_SHA")
+        .map(str::to_string)
+        .or_else(|| env::var("GIT_SHA").ok())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha,
+        uptime_secs: started_at.elapsed().as_secs(),
+    }
+}
+
+/// チェックをタイムアウト付きで実行し、かかった時間と結果を返す
+async fn run_check<F>(limit: Duration, check: F) -> ComponentCheck
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let started = Instant::now();
+    let result = timeout(limit, check).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(Ok(())) => ComponentCheck::ok(latency_ms),
+        Ok(Err(err)) => ComponentCheck::error(latency_ms, err),
+        Err(_) => ComponentCheck::error(latency_ms, format!("timed out after {} ms", limit.as_millis())),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,248 @@
+//! # ログイン保護サービス
+//!
+//! ログインの総当たり攻撃を防ぐサービス
+//! 接続元の IP アドレスと入力されたメールアドレス（アカウント）ごとに、リクエスト数・失敗回数を数える
+//! アカウントは未登録のメールアドレスも同じように数え、登録済みかどうかを推測できないようにする
+//!
+//! | 対策             | 内容                                                                 |
+//! | ---------------- | -------------------------------------------------------------------- |
+//! | 回数の制限       | `rate_window_secs` 秒間のリクエスト数が上限を超えると `429` を返す   |
+//! | 段階的な遅延     | 失敗が続いたアカウントは、失敗のレスポンスを指数的に遅らせる         |
+//! | 一時的なロック   | `failure_window_secs` 秒間の失敗回数が上限に達すると一定時間ロック   |
+//! | 失敗の履歴       | 失敗を `login_failures` に記録し、管理者が確認する                   |
+//!
+//! ## メソッド
+//!
+//! `check_request`  - ログインのリクエストを受け付けるか（ロック中・回数の上限を超えた場合は拒否）
+//! `record_failure` - 失敗を記録し、上限に達した場合はロック
+//! `record_success` - アカウントの失敗回数をリセット
+//! `get_failures`   - 失敗の履歴（管理者）
+//! `prune`          - 期限切れのカウンター・保存期間を過ぎた履歴を削除
+//!
+//! ## 関数
+//!
+//! `normalize_email`   - カウンターのキーに使用するメールアドレス
+//! `progressive_delay` - 失敗のレスポンスを遅らせる時間
+
+use std::time::Duration as StdDuration;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    application::metrics::registry::LOGIN_REJECTIONS_TOTAL,
+    application::types::di_type::{LoginFailureRepositoryArc, ThrottleStoreArc},
+    domain::entities::login_protection::{LoginAttempt, LoginFailureListQuery, LoginFailureListResponse, NewLoginFailure},
+    domain::entities::pagination::Pagination,
+    domain::enums::login_failure::LoginFailureReason,
+    infrastructure::config::app_config::LoginProtectionSettings,
+    {app_log, info_log, warning_log}
+};
+
+#[async_trait]
+pub trait LoginGuardService: Send + Sync {
+    async fn check_request(&self, ip_address: Option<&str>, email: Option<&str>) -> Result<(), AuthError>;
+    async fn record_failure(&self, attempt: &LoginAttempt, user_id: Option<i32>, reason: LoginFailureReason) -> Result<StdDuration, AuthError>;
+    async fn record_success(&self, attempt: &LoginAttempt) -> Result<(), AuthError>;
+    async fn get_failures(&self, query: &LoginFailureListQuery, pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError>;
+    async fn prune(&self) -> Result<(), AuthError>;
+}
+
+pub struct LoginGuardServiceImpl {
+    throttle_store: ThrottleStoreArc,
+    login_failure_repository: LoginFailureRepositoryArc,
+    settings: LoginProtectionSettings,
+}
+
+impl LoginGuardServiceImpl {
+    pub fn new(
+        throttle_store: ThrottleStoreArc,
+        login_failure_repository: LoginFailureRepositoryArc,
+        settings: LoginProtectionSettings
+    ) -> Self {
+        LoginGuardServiceImpl { throttle_store, login_failure_repository, settings }
+    }
+
+    /// 失敗回数を加算し、上限に達した場合はロック
+    ///
+    /// # 戻り値
+    ///
+    /// * `(i32, Option<DateTime<Utc>>)` - 期間内の失敗回数と、ロックした場合はその期限
+    async fn count_failure(&self, scope: &str, value: &str, max_failures: i32) -> Result<(i32, Option<DateTime<Utc>>), AuthError> {
+        let key = throttle_key("failure", scope, value);
+        let counter = self.throttle_store.increment(&key, Duration::seconds(self.settings.failure_window_secs)).await?;
+
+        if counter.count < max_failures {
+            return Ok((counter.count, None));
+        }
+
+        let until = Utc::now() + Duration::seconds(self.settings.lockout_secs);
+        self.throttle_store.lock(&key, until).await?;
+        warning_log!("[login_guard_service] - [record_failure] - [message: Login locked] scope = {}, failures = {}, until = {}", scope, counter.count, until);
+
+        Ok((counter.count, Some(until)))
+    }
+}
+
+/// カウンターのキー
+///
+/// * `kind`  - `request`（リクエスト数）/ `failure`（失敗回数・ロック）
+/// * `scope` - `ip` / `account`
+fn throttle_key(kind: &str, scope: &str, value: &str) -> String {
+    format!("{}:{}:{}", kind, scope, value)
+}
+
+/// 指定した日時までの秒数（1秒未満は切り上げ）
+fn seconds_until(until: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    let millis = (until - now).num_milliseconds().max(1) as u64;
+
+    millis.div_ceil(1000)
+}
+
+/// カウンターのキーに使用するメールアドレス
+///
+/// 大文字・小文字や前後の空白を変えて回数の制限を回避できないよう正規化する
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+/// 失敗のレスポンスを遅らせる時間
+///
+/// `delay_after` 回までは遅らせず、以降は失敗するたびに2倍にし、`max_ms` を上限とする
+///
+/// # 引数
+///
+/// * `failures`    - 今回を含む期間内の失敗回数
+/// * `delay_after` - 遅らせずに応答する失敗回数
+/// * `base_ms`     - 最初に遅らせる時間（ミリ秒）
+/// * `max_ms`      - 遅らせる時間の上限（ミリ秒）
+///
+/// # 戻り値
+///
+/// * `StdDuration` - 遅らせる時間
+pub fn progressive_delay(failures: i32, delay_after: i32, base_ms: u64, max_ms: u64) -> StdDuration {
+    if failures <= delay_after {
+        return StdDuration::ZERO;
+    }
+
+    let exponent = (failures - delay_after - 1).clamp(0, 32) as u32;
+
+    StdDuration::from_millis(base_ms.saturating_mul(1_u64 << exponent).min(max_ms))
+}
+
+#[async_trait]
+impl LoginGuardService for LoginGuardServiceImpl {
+    /// ログインのリクエストを受け付けるか
+    ///
+    /// IP アドレス・アカウントのどちらかがロック中、またはリクエスト数が上限を超えた場合は拒否します。
+    ///
+    /// # 引数
+    ///
+    /// * `ip_address` - 接続元の IP アドレス
+    /// * `email`      - 入力されたメールアドレス
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(())`                      - 受け付ける場合。
+    /// - `Err(AuthError::RateLimited)` - 拒否する場合、再試行できるまでの秒数を返します。
+    async fn check_request(&self, ip_address: Option<&str>, email: Option<&str>) -> Result<(), AuthError> {
+        let email = email.map(normalize_email).filter(|email| !email.is_empty());
+        let targets: Vec<(&str, &str, i32)> = [
+            ip_address.map(|ip| ("ip", ip, self.settings.ip_max_requests)),
+            email.as_deref().map(|email| ("account", email, self.settings.account_max_requests)),
+        ].into_iter().flatten().collect();
+
+        let now = Utc::now();
+        for (scope, value, _) in &targets {
+            let locked_until = self.throttle_store
+                .get(&throttle_key("failure", scope, value))
+                .await?
+                .and_then(|counter| counter.locked_until)
+                .filter(|until| *until > now);
+
+            if let Some(until) = locked_until {
+                info_log!("[login_guard_service] - [check_request] login rejected, scope = {}, reason = locked", scope);
+                LOGIN_REJECTIONS_TOTAL.with_label_values(&["locked"]).inc();
+                return Err(AuthError::RateLimited(seconds_until(until, now)));
+            }
+        }
+
+        let window = Duration::seconds(self.settings.rate_window_secs);
+        for (scope, value, max_requests) in &targets {
+            let counter = self.throttle_store.increment(&throttle_key("request", scope, value), window).await?;
+
+            if counter.count > *max_requests {
+                info_log!("[login_guard_service] - [check_request] login rejected, scope = {}, reason = rate_limited", scope);
+                LOGIN_REJECTIONS_TOTAL.with_label_values(&["rate_limited"]).inc();
+                return Err(AuthError::RateLimited(seconds_until(counter.window_started_at + window, now)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 失敗を記録
+    ///
+    /// アカウント・IP アドレスの失敗回数を加算し、上限に達した場合はロックします。
+    /// 失敗は履歴に記録します。
+    ///
+    /// # 引数
+    ///
+    /// * `attempt` - ログインの試行
+    /// * `user_id` - 登録済みのユーザーの場合はその ID
+    /// * `reason`  - 失敗した理由
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します:
+    ///
+    /// - `Ok(StdDuration)`  - 失敗のレスポンスを遅らせる時間を返します。
+    /// - `Err(AuthError)`   - 記録中にエラーが発生した場合、カスタムエラーを返します。
+    async fn record_failure(&self, attempt: &LoginAttempt, user_id: Option<i32>, reason: LoginFailureReason) -> Result<StdDuration, AuthError> {
+        let (failures, account_locked_until) = self
+            .count_failure("account", &attempt.email, self.settings.account_max_failures)
+            .await?;
+
+        let ip_locked_until = match &attempt.ip_address {
+            Some(ip) => self.count_failure("ip", ip, self.settings.ip_max_failures).await?.1,
+            None => None,
+        };
+
+        self.login_failure_repository.record_failure(&NewLoginFailure {
+            attempt,
+            user_id,
+            reason,
+            locked_until: account_locked_until.max(ip_locked_until),
+        }).await?;
+
+        Ok(progressive_delay(failures, self.settings.delay_after_failures, self.settings.delay_base_ms, self.settings.delay_max_ms))
+    }
+
+    /// アカウントの失敗回数をリセット
+    ///
+    /// IP アドレスの失敗回数は、他のアカウントへの試行を数えるためリセットしません。
+    async fn record_success(&self, attempt: &LoginAttempt) -> Result<(), AuthError> {
+        self.throttle_store.reset(&throttle_key("failure", "account", &attempt.email)).await
+    }
+
+    /// 失敗の履歴（管理者）
+    async fn get_failures(&self, query: &LoginFailureListQuery, pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError> {
+        self.login_failure_repository.get_failures(query, pagination).await
+    }
+
+    /// 期限切れのカウンター・保存期間を過ぎた履歴を削除
+    async fn prune(&self) -> Result<(), AuthError> {
+        let now = Utc::now();
+        let longest_window = self.settings.rate_window_secs.max(self.settings.failure_window_secs);
+
+        let counters = self.throttle_store.prune(now - Duration::seconds(longest_window)).await?;
+        let failures = self.login_failure_repository.prune(now - Duration::days(self.settings.history_retention_days)).await?;
+
+        if counters > 0 || failures > 0 {
+            info_log!("[login_guard_service] - [prune] pruned, counters = {}, failures = {}", counters, failures);
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,3 +1,7 @@
 pub mod auth_service;
+pub mod health_service;
+pub mod login_guard_service;
+pub mod oidc_service;
+pub mod outbox_service;
 pub mod task_service;
 pub mod user_service;
\ No newline at end of file
@@ -0,0 +1,249 @@
+//! # OpenID Connect サービス
+//!
+//! 外部の ID プロバイダー（Google・Discord・Twitch など）でのログインと、アカウントの連携を定義したサービス
+//!
+//! ログインでは、プロバイダーのアカウントを連携しているユーザーとしてログインする。
+//! 連携しているユーザーがいない場合は、メールアドレスが一致するユーザーに連携する（プロバイダーとこのサービスの両方で
+//! メールアドレスが確認済みの場合のみ）か、パスワードのないユーザーを作成する。
+//!
+//! 認可リクエストを開始したブラウザーには `state` のハッシュをクッキーで保存し、コールバックで照合する。
+//! 他のブラウザーで開始したログインのリダイレクト先を開かせて、攻撃者のアカウントでログインさせることはできない（ログイン CSRF）。
+//!
+//! ## 関数
+//!
+//! `state_binding`        - 認可リクエストを開始したブラウザーに保存する値
+//! `verify_state_binding` - コールバックの `state` とブラウザーに保存した値を照合
+//!
+//! ## メソッド
+//!
+//! `provider_names` - ログインに使用できるプロバイダーの一覧
+//! `authorize`      - 認可リクエストを作成（ログイン・連携）
+//! `callback`       - 認可コードを検証し、ログインまたはアカウントを連携
+//! `list_accounts`  - 連携しているアカウントの一覧
+//! `unlink_account` - アカウントの連携を解除
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use ring::constant_time::verify_slices_are_equal;
+use crate::{
+    app_log,
+    application::errors::auth_error::AuthError,
+    application::helpers::password::PasswordHasher,
+    application::helpers::token::{generate_token, hash_token},
+    application::metrics::registry::USER_REGISTRATIONS_TOTAL,
+    application::oidc::oidc_client::{OidcClient, OidcProviders},
+    application::types::di_type::{AuthRepositoryArc, AuthServiceArc, OidcRepositoryArc},
+    domain::entities::oidc::{
+        LinkedAccountsResponse, OidcAuthorizeResponse, OidcCallbackOutcome, OidcCallbackRequest, OidcIdentity, OidcState
+    },
+    domain::entities::session::SessionMeta,
+    error_log,
+    info_log,
+    infrastructure::config::app_config::OidcSettings
+};
+
+/// ユーザー名の最大文字数（`users.name`）
+const MAX_NAME_CHARS: usize = 255;
+
+/// 認可リクエストを開始したブラウザーに保存する値（`state` のハッシュ）
+///
+/// # 引数
+///
+/// * `state` - 認可リクエストの `state`
+///
+/// # 戻り値
+///
+/// * `String` - クッキーに保存する値
+pub fn state_binding(state: &str) -> String {
+    hash_token(state)
+}
+
+/// コールバックの `state` とブラウザーに保存した値を照合
+///
+/// # 引数
+///
+/// * `state`   - コールバックで受け取った `state`
+/// * `binding` - ブラウザーのクッキーの値（ない場合は `None`）
+///
+/// # 戻り値
+///
+/// * `bool` - 認可リクエストを開始したブラウザーの場合は `true`
+pub fn verify_state_binding(state: &str, binding: Option<&str>) -> bool {
+    binding.is_some_and(|binding| verify_slices_are_equal(state_binding(state).as_bytes(), binding.as_bytes()).is_ok())
+}
+
+#[async_trait]
+pub trait OidcService: Send + Sync {
+    fn provider_names(&self) -> Vec<String>;
+    async fn authorize(&self, provider: &str, user_id: Option<i32>) -> Result<(OidcAuthorizeResponse, String), AuthError>;
+    async fn callback(&self, provider: &str, req: &OidcCallbackRequest, binding: Option<&str>, user_id: Option<i32>, meta: &SessionMeta) -> Result<OidcCallbackOutcome, AuthError>;
+    async fn list_accounts(&self, user_id: i32) -> Result<LinkedAccountsResponse, AuthError>;
+    async fn unlink_account(&self, user_id: i32, provider: &str) -> Result<(), AuthError>;
+}
+
+pub struct OidcServiceImpl {
+    oidc_repository: OidcRepositoryArc,
+    auth_repository: AuthRepositoryArc,
+    auth_service: AuthServiceArc,
+    providers: Arc<OidcProviders>,
+    password_hasher: Arc<PasswordHasher>,
+    settings: OidcSettings,
+}
+
+impl OidcServiceImpl {
+    pub fn new(
+        oidc_repository: OidcRepositoryArc,
+        auth_repository: AuthRepositoryArc,
+        auth_service: AuthServiceArc,
+        providers: Arc<OidcProviders>,
+        password_hasher: Arc<PasswordHasher>,
+        settings: OidcSettings
+    ) -> Self {
+        OidcServiceImpl { oidc_repository, auth_repository, auth_service, providers, password_hasher, settings }
+    }
+
+    fn client(&self, provider: &str) -> Result<Arc<OidcClient>, AuthError> {
+        self.providers.get(provider).ok_or(AuthError::OidcProviderNotFound)
+    }
+
+    /// ログイン中のユーザーにアカウントを連携
+    ///
+    /// 他のユーザーが連携しているアカウント、または同じプロバイダーの別のアカウントを連携済みの場合はエラー
+    async fn link(&self, user_id: i32, provider: &str, identity: &OidcIdentity) -> Result<LinkedAccountsResponse, AuthError> {
+        let accounts = self.oidc_repository.list_accounts(user_id).await?;
+        if accounts.iter().any(|account| account.provider == provider && account.provider_account_id != identity.subject) {
+            return Err(AuthError::OidcProviderAlreadyLinked);
+        }
+        if !self.oidc_repository.link_account(user_id, provider, identity).await? {
+            return Err(AuthError::OidcAccountInUse);
+        }
+        info_log!("[oidc_service] - [callback] user_id = {} linked provider = {}", user_id, provider);
+
+        self.list_accounts(user_id).await
+    }
+
+    /// 連携しているユーザーがいないアカウントでログインした場合の、ログインするユーザーのメールアドレス
+    ///
+    /// メールアドレスが一致するユーザーには、プロバイダーとこのサービスの両方で確認済みの場合のみ連携する。
+    /// 一致するユーザーがいない場合は、パスワードのないユーザーを作成する
+    async fn find_or_create_user(&self, provider: &str, identity: &OidcIdentity) -> Result<String, AuthError> {
+        let email = identity.email.as_deref().ok_or(AuthError::OidcEmailRequired)?;
+
+        if let Some(user) = self.auth_repository.get_user_by_email(email).await? {
+            // 確認していないメールアドレスで、他のユーザーのアカウントに連携されないようにする
+            if !identity.email_verified || !user.is_verified {
+                error_log!("[oidc_service] - [callback] - [message: Email is not verified] user_id = {}, provider = {}", user.id, provider);
+                return Err(AuthError::OidcAccountInUse);
+            }
+            self.link(user.id, provider, identity).await.map_err(|err| match err {
+                AuthError::OidcProviderAlreadyLinked => AuthError::OidcAccountInUse,
+                err => err,
+            })?;
+
+            return Ok(user.email);
+        }
+
+        // パスワードでログインできないよう、ランダムな値のハッシュを保存する
+        let (random_password, _) = generate_token();
+        let password_hash = self.password_hasher.hash(&random_password)?;
+        let name: String = identity.name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| email.split('@').next().unwrap_or(email))
+            .chars()
+            .take(MAX_NAME_CHARS)
+            .collect();
+
+        let user_id = self.oidc_repository
+            .create_user_with_account(&name, email, &password_hash, provider, identity)
+            .await?;
+        USER_REGISTRATIONS_TOTAL.inc();
+        info_log!("[oidc_service] - [callback] user_id = {} registered with provider = {}", user_id, provider);
+
+        Ok(email.to_string())
+    }
+}
+
+#[async_trait]
+impl OidcService for OidcServiceImpl {
+    fn provider_names(&self) -> Vec<String> {
+        self.providers.names()
+    }
+
+    /// `user_id` を指定した場合は、コールバックでそのユーザーにアカウントを連携する
+    ///
+    /// DB には `state` のハッシュのみを保存する
+    /// 認可リクエストと、ブラウザーのクッキーに保存する値（`state_binding`）を返す
+    async fn authorize(&self, provider: &str, user_id: Option<i32>) -> Result<(OidcAuthorizeResponse, String), AuthError> {
+        let client = self.client(provider)?;
+        let request = client.authorization_request().await?;
+        let expires_at = Utc::now() + Duration::seconds(self.settings.state_ttl_secs);
+
+        let state = OidcState {
+            provider: provider.to_string(),
+            code_verifier: request.code_verifier,
+            nonce: request.nonce,
+            user_id,
+        };
+        self.oidc_repository.create_state(&hash_token(&request.state), &state, expires_at).await?;
+
+        Ok((OidcAuthorizeResponse { authorization_url: request.url, expires_at }, state_binding(&request.state)))
+    }
+
+    /// 認可リクエストを開始したブラウザー・ユーザーと、コールバックを送信したブラウザー・ユーザーが異なる場合は拒否する
+    async fn callback(&self, provider: &str, req: &OidcCallbackRequest, binding: Option<&str>, user_id: Option<i32>, meta: &SessionMeta) -> Result<OidcCallbackOutcome, AuthError> {
+        let client = self.client(provider)?;
+        if !verify_state_binding(&req.state, binding) {
+            error_log!("[oidc_service] - [callback] - [message: State was issued to another browser] provider = {}", provider);
+            return Err(AuthError::InvalidToken);
+        }
+        let state = self.oidc_repository
+            .consume_state(&hash_token(&req.state), provider)
+            .await?
+            .ok_or(AuthError::InvalidToken)?;
+        if state.user_id.is_some_and(|state_user_id| Some(state_user_id) != user_id) {
+            error_log!("[oidc_service] - [callback] - [message: State belongs to another user] provider = {}", provider);
+            return Err(AuthError::InvalidToken);
+        }
+
+        let identity = client.exchange_code(&req.code, &state.code_verifier, &state.nonce).await?;
+
+        if let Some(user_id) = state.user_id {
+            return Ok(OidcCallbackOutcome::Linked(self.link(user_id, provider, &identity).await?));
+        }
+
+        let email = match self.oidc_repository.find_account_owner(provider, &identity.subject).await? {
+            Some(owner) => {
+                // 最新のトークンを保存する
+                self.oidc_repository.link_account(owner.user_id, provider, &identity).await?;
+                owner.email
+            }
+            None => self.find_or_create_user(provider, &identity).await?,
+        };
+
+        Ok(OidcCallbackOutcome::Login(self.auth_service.login_verified_user(&email, meta).await?))
+    }
+
+    async fn list_accounts(&self, user_id: i32) -> Result<LinkedAccountsResponse, AuthError> {
+        Ok(LinkedAccountsResponse {
+            accounts: self.oidc_repository.list_accounts(user_id).await?,
+            has_password: self.auth_repository.has_password(user_id).await?,
+        })
+    }
+
+    /// パスワードがなく、最後に連携しているアカウントは解除できない
+    async fn unlink_account(&self, user_id: i32, provider: &str) -> Result<(), AuthError> {
+        if self.oidc_repository.unlink_account(user_id, provider).await? {
+            info_log!("[oidc_service] - [unlink_account] user_id = {} unlinked provider = {}", user_id, provider);
+            return Ok(());
+        }
+
+        let accounts = self.oidc_repository.list_accounts(user_id).await?;
+        if accounts.iter().any(|account| account.provider == provider) {
+            Err(AuthError::OidcLastLoginMethod)
+        } else {
+            Err(AuthError::OidcAccountNotFound)
+        }
+    }
+}
\ No newline at end of file
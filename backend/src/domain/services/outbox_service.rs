@@ -0,0 +1,77 @@
+//! # メール送信キューサービス
+//! 
+//! 管理者がメール送信キューを確認・再送信するサービス
+//! 送信はメール送信キューのワーカー（`outbox_worker`）が行う
+//! 
+//! ## メソッド
+//! 
+//! `get_entries` - メール一覧取得（管理者）
+//! `requeue`     - 再送信（管理者）
+
+use async_trait::async_trait;
+use crate::{
+    app_log,
+    application::{errors::outbox_error::OutboxError, types::di_type::OutboxRepositoryArc},
+    domain::entities::outbox::{OutboxListQuery, OutboxListResponse},
+    domain::entities::pagination::Pagination,
+    info_log
+};
+
+#[async_trait]
+pub trait OutboxService: Send + Sync {
+    async fn get_entries(&self, query: &OutboxListQuery, pagination: &Pagination) -> Result<OutboxListResponse, OutboxError>;
+    async fn requeue(&self, id: i64) -> Result<(), OutboxError>;
+}
+
+pub struct OutboxServiceImpl {
+    outbox_repository: OutboxRepositoryArc,
+}
+
+impl OutboxServiceImpl {
+    pub fn new(outbox_repository: OutboxRepositoryArc) -> Self {
+        OutboxServiceImpl { outbox_repository }
+    }
+}
+
+#[async_trait]
+impl OutboxService for OutboxServiceImpl {
+    /// メール一覧取得
+    /// 
+    /// # 引数
+    /// 
+    /// * `query`      - 状態の絞り込み
+    /// * `pagination` - ページ番号・1ページあたりの件数
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(OutboxListResponse)` - メール一覧と総件数を返します。
+    /// - `Err(OutboxError)`       - 取得処理中にエラーが発生した場合、カスタムエラーを返します。
+    async fn get_entries(&self, query: &OutboxListQuery, pagination: &Pagination) -> Result<OutboxListResponse, OutboxError> {
+        self.outbox_repository.get_entries(query, pagination).await
+    }
+
+    /// 再送信
+    /// 
+    /// 再送信の上限に達したメールの送信回数をリセットし、再送信待ちに戻します。
+    /// 
+    /// # 引数
+    /// 
+    /// * `id` - メールのID
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(())`           - 再送信待ちに戻した場合。
+    /// - `Err(OutboxError)` - メールが存在しない、または再送信の上限に達していない場合は `EntryNotFound` を返します。
+    async fn requeue(&self, id: i64) -> Result<(), OutboxError> {
+        if !self.outbox_repository.requeue(id).await? {
+            return Err(OutboxError::EntryNotFound);
+        }
+
+        info_log!("[outbox_service] - [requeue] id = {} requeued", id);
+        Ok(())
+    }
+}
\ No newline at end of file
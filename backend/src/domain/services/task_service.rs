@@ -5,6 +5,7 @@
 //! ## メソッド
 //! 
 //! `get_tasks`     - タスク一覧取得
+//! `get_task`      - タスク取得
 //! `create_task`   - タスク作成
 //! `update_task`   - タスク更新
 //! `delete_task`   - タスク削除
@@ -15,18 +16,20 @@ use crate::application::types::di_type::UserServiceArc;
 use crate::{
     application::errors::task_error::TaskError,
     application::jwt::jwt::Claims,
+    application::metrics::registry::{TASKS_COMPLETED_TOTAL, TASKS_CREATED_TOTAL},
     application::types::di_type::TaskRepositoryArc,
-    domain::entities::task::*,
+    domain::entities::{pagination::Pagination, task::*},
     {app_log, error_log}
 };
 
 #[async_trait]
 pub trait TaskService: Send + Sync {
-    async fn get_tasks(&self, user_id: i32) -> Result<TaskListResponse, TaskError>;
-    async fn create_task(&self, user: Claims, task_req: &RequestCreateTaskItem) -> Result<ResponseCreateTaskItem, TaskError>;
-    async fn update_task(&self, user: Claims, task_req: &RequestUpdateTaskItem) -> Result<(), TaskError>;
-    async fn delete_task(&self, user: Claims, task_req: &RequestDeleteTaskItem) -> Result<(), TaskError>;
-    async fn complete_task(&self, user: Claims, task_req: &RequestCompleteTaskItem) -> Result<(), TaskError>;
+    async fn get_tasks(&self, user_id: i32, query: &TaskListQuery, pagination: &Pagination) -> Result<TaskListResponse, TaskError>;
+    async fn get_task(&self, user: Claims, task_id: i32) -> Result<TaskItem, TaskError>;
+    async fn create_task(&self, user: Claims, task_req: &RequestCreateTaskItem) -> Result<TaskItem, TaskError>;
+    async fn update_task(&self, user: Claims, task_id: i32, task_req: &RequestUpdateTaskItem) -> Result<TaskItem, TaskError>;
+    async fn delete_task(&self, user: Claims, task_id: i32) -> Result<(), TaskError>;
+    async fn complete_task(&self, user: Claims, task_id: i32) -> Result<TaskItem, TaskError>;
 }
 
 pub struct TaskServiceImpl {
@@ -38,17 +41,29 @@ impl TaskServiceImpl {
     pub fn new(task_repository: TaskRepositoryArc, user_service: UserServiceArc) -> Self {
         TaskServiceImpl { task_repository, user_service }
     }
+
+    /// トークンのユーザーが存在することを確認し、ユーザーIDを返す
+    ///
+    /// 認証に失敗した場合は処理を続行せず、エラーを返す
+    async fn authorize(&self, user: &Claims, method: &str) -> Result<i32, TaskError> {
+        self.user_service.get_user_id(user).await.map_err(|err| {
+            error_log!("[task_service] - [{}] - [message: Authentication Failed] - Error: {:?}", method, err);
+            TaskError::from(err)
+        })
+    }
 }
 
 #[async_trait]
 impl TaskService for TaskServiceImpl {
     /// タスク一覧取得
     /// 
-    /// ユーザーが持つ全てのタスクを取得します。
+    /// ユーザーが持つタスクを検索条件・並び順・ページ指定で取得します。
     /// 
     /// # 引数
     /// 
-    /// * `user` - `Claims` 型のユーザーデータ
+    /// * `user_id`    - ユーザーID
+    /// * `query`      - `TaskListQuery` 型の検索条件
+    /// * `pagination` - ページ番号・1ページあたりの件数
     /// 
     /// # 戻り値
     /// 
@@ -56,10 +71,34 @@ impl TaskService for TaskServiceImpl {
     /// 
     /// - `Ok(TaskListResponse)` - タスクが取得できた場合、タスクのリストを返します。
     /// - `Err(TaskError)`    - タスク取得処理中にエラーが発生した場合、カスタムエラーを返します。
-    async fn get_tasks(&self, user_id: i32) -> Result<TaskListResponse, TaskError> {
+    async fn get_tasks(&self, user_id: i32, query: &TaskListQuery, pagination: &Pagination) -> Result<TaskListResponse, TaskError> {
         let task_repository = self.task_repository.clone();
 
-        task_repository.get_tasks(user_id).await
+        task_repository.get_tasks(user_id, query, pagination).await
+    }
+
+    /// タスク取得
+    /// 
+    /// ユーザーが持つタスクを1件取得します。
+    /// 
+    /// # 引数
+    /// 
+    /// * `user`    - `Claims` 型のユーザーデータ
+    /// * `task_id` - タスクID
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(TaskItem)`   - タスクが取得できた場合、タスクを返します。
+    /// - `Err(TaskError)` - タスクが存在しない、他ユーザーのタスク、または取得処理中にエラーが発生した場合、カスタムエラーを返します。
+    async fn get_task(&self, user: Claims, task_id: i32) -> Result<TaskItem, TaskError> {
+        let user_id = self.authorize(&user, "get_task").await?;
+
+        self.task_repository
+            .get_task(user_id, task_id)
+            .await?
+            .ok_or(TaskError::TaskNotFound)
     }
 
     /// タスクの新規作成
@@ -75,14 +114,15 @@ impl TaskService for TaskServiceImpl {
     /// 
     /// `Result` を返します:
     /// 
-    /// - `Ok(ResponseCreateTaskItem)` - タスクが作成された場合、作成されたタスクの情報を返します。
-    /// - `Err(TaskError)`             - タスク作成中にエラーが発生した場合、カスタムエラーを返します。
-    async fn create_task(&self, user: Claims, task_req: &RequestCreateTaskItem) -> Result<ResponseCreateTaskItem, TaskError> {
-        let task_repository = self.task_repository.clone();
-        let user_service = self.user_service.clone();
+    /// - `Ok(TaskItem)`   - タスクが作成された場合、作成されたタスクの情報を返します。
+    /// - `Err(TaskError)` - タスク作成中にエラーが発生した場合、カスタムエラーを返します。
+    async fn create_task(&self, user: Claims, task_req: &RequestCreateTaskItem) -> Result<TaskItem, TaskError> {
+        let user_id = self.authorize(&user, "create_task").await?;
 
-        let user_id = user_service.get_user_id(&user).await.map_err(TaskError::from)?;
-        task_repository.create_task(user_id, &task_req).await
+        let task = self.task_repository.create_task(user_id, task_req).await?;
+        TASKS_CREATED_TOTAL.inc();
+
+        Ok(task)
     }
 
     /// タスクの更新
@@ -91,24 +131,23 @@ impl TaskService for TaskServiceImpl {
     /// 
     /// # 引数
     /// 
-    /// * `user` - `Claims` 型のユーザーデータ
+    /// * `user`     - `Claims` 型のユーザーデータ
+    /// * `task_id`  - タスクID
     /// * `task_req` - `RequestUpdateTaskItem` 型のリクエストボディデータ
     /// 
     /// # 戻り値
     /// 
     /// `Result` を返します:
     /// 
-    /// - `Ok(())`                     - タスクが更新された場合。
-    /// - `Err(TaskError)`             - タスク更新中にエラーが発生した場合、カスタムエラーを返します。
-    async fn update_task(&self, user: Claims, task_req: &RequestUpdateTaskItem) -> Result<(), TaskError> {
-        let task_repository = self.task_repository.clone();
-        let user_service = self.user_service.clone();
+    /// - `Ok(TaskItem)`   - タスクが更新された場合、更新後のタスクを返します。
+    /// - `Err(TaskError)` - タスクが存在しない、他ユーザーのタスク、または更新中にエラーが発生した場合、カスタムエラーを返します。
+    async fn update_task(&self, user: Claims, task_id: i32, task_req: &RequestUpdateTaskItem) -> Result<TaskItem, TaskError> {
+        let user_id = self.authorize(&user, "update_task").await?;
 
-        if let Err(err) = user_service.get_user_id(&user).await.map_err(TaskError::from) {
-            error_log!("[task_service] - [update_task] - [message: Authentication Failed] - Error: {:?}", err);
-            return Ok(());
-        };
-        task_repository.update_task(&task_req).await
+        self.task_repository
+            .update_task(user_id, task_id, task_req)
+            .await?
+            .ok_or(TaskError::TaskNotFound)
     }
 
     /// タスクの削除
@@ -117,25 +156,23 @@ impl TaskService for TaskServiceImpl {
     /// 
     /// # 引数
     /// 
-    /// * `user` - `Claims` 型のユーザーデータ
-    /// * `task_req` - `RequestDeleteTaskItem` 型のリクエストボディデータ
+    /// * `user`    - `Claims` 型のユーザーデータ
+    /// * `task_id` - タスクID
     /// 
     /// # 戻り値
     /// 
     /// `Result` を返します:
     /// 
-    /// - `Ok(())` - 正常にタスクが削除された場合。
-    /// - `Err(TaskError)` - タスク削除中にエラーが発生した場合、カスタムエラーを返します。
-    async fn delete_task(&self, user: Claims, task_req: &RequestDeleteTaskItem) -> Result<(), TaskError> {
-        let task_repository = self.task_repository.clone();
-        let user_service = self.user_service.clone();
-
-        if let Err(err) = user_service.get_user_id(&user).await.map_err(TaskError::from) {
-            error_log!("[task_service] - [delete_task] - [message: Authentication Failed] - Error: {:?}", err);
-            return Ok(());
-        };
+    /// - `Ok(())`         - 正常にタスクが削除された場合。
+    /// - `Err(TaskError)` - タスクが存在しない、他ユーザーのタスク、または削除中にエラーが発生した場合、カスタムエラーを返します。
+    async fn delete_task(&self, user: Claims, task_id: i32) -> Result<(), TaskError> {
+        let user_id = self.authorize(&user, "delete_task").await?;
 
-        task_repository.delete_task(&task_req).await
+        if self.task_repository.delete_task(user_id, task_id).await? {
+            Ok(())
+        } else {
+            Err(TaskError::TaskNotFound)
+        }
     }
 
     /// タスクの完了
@@ -144,24 +181,24 @@ impl TaskService for TaskServiceImpl {
     /// 
     /// # 引数
     /// 
-    /// * `user` - `Claims` 型のユーザーデータ
-    /// * `task_req` - `RequestCompleteTaskItem` 型のリクエストボディデータ
+    /// * `user`    - `Claims` 型のユーザーデータ
+    /// * `task_id` - タスクID
     /// 
     /// # 戻り値
     /// 
     /// `Result` を返します:
     /// 
-    /// - `Ok(())` - タスクが完了としてマークされた場合。
-    /// - `Err(TaskError)` - タスク完了処理中にエラーが発生した場合、カスタムエラーを返します。
-    async fn complete_task(&self, user: Claims, task_req: &RequestCompleteTaskItem) -> Result<(), TaskError> {
-        let task_repository = self.task_repository.clone();
-        let user_service = self.user_service.clone();
+    /// - `Ok(TaskItem)`   - タスクが完了としてマークされた場合、完了後のタスクを返します。
+    /// - `Err(TaskError)` - タスクが存在しない、他ユーザーのタスク、または完了処理中にエラーが発生した場合、カスタムエラーを返します。
+    async fn complete_task(&self, user: Claims, task_id: i32) -> Result<TaskItem, TaskError> {
+        let user_id = self.authorize(&user, "complete_task").await?;
 
-        if let Err(err) = user_service.get_user_id(&user).await.map_err(TaskError::from) {
-            error_log!("[task_service] - [complete_task] - [message: Authentication Failed] - Error: {:?}", err);
-            return Ok(());
-        };
+        let task = self.task_repository
+            .complete_task(user_id, task_id)
+            .await?
+            .ok_or(TaskError::TaskNotFound)?;
+        TASKS_COMPLETED_TOTAL.inc();
 
-        task_repository.complete_task(&task_req).await
+        Ok(task)
     }
-}
\ No newline at end of file
+}
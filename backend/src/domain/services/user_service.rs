@@ -5,25 +5,60 @@
 //! ## メソッド
 //! 
 //! `get_user_id`     - ユーザーID取得
+//! `find_user_by_id` - ユーザー取得
+//! `update_profile`  - プロフィール更新
+//! `change_password` - パスワード変更
+//! `get_users`       - ユーザー一覧取得（管理者）
+//! `delete_user`     - ユーザー削除（管理者）
+//! `change_role`     - ロール変更（管理者）
 
+use std::sync::Arc;
 use async_trait::async_trait;
 use crate::{
-    app_log, application::{errors::user_error::UserError, jwt::jwt::Claims, types::di_type::UserRepositoryArc}, domain::entities::user::{UserRequest, UserResponse}, error_log
+    app_log,
+    application::{
+        errors::user_error::UserError,
+        helpers::password::PasswordHasher,
+        jwt::{jwt::Claims, jwt_keys::JwtKeys},
+        types::di_type::{LoginGuardServiceArc, UserRepositoryArc}
+    },
+    domain::entities::login_protection::LoginAttempt,
+    domain::entities::pagination::Pagination,
+    domain::entities::session::SessionMeta,
+    domain::entities::user::{ChangePasswordRequest, UpdateUserRequest, UserListResponse, UserRequest, UserResponse},
+    domain::enums::login_failure::LoginFailureReason,
+    domain::enums::role::Role,
+    domain::services::login_guard_service::normalize_email,
+    error_log,
+    info_log
 };
 
 #[async_trait]
 pub trait UserService: Send + Sync {
     async fn get_user_id(&self, user: &Claims) -> Result<i32, UserError>;
     async fn find_user_by_id(&self, req: &UserRequest) -> Result<Option<UserResponse>, UserError>;
+    async fn update_profile(&self, user_id: i32, req: &UpdateUserRequest) -> Result<UserResponse, UserError>;
+    async fn change_password(&self, user: &Claims, req: &ChangePasswordRequest, meta: &SessionMeta) -> Result<String, UserError>;
+    async fn get_users(&self, pagination: &Pagination) -> Result<UserListResponse, UserError>;
+    async fn delete_user(&self, admin: &Claims, user_id: i32) -> Result<(), UserError>;
+    async fn change_role(&self, admin: &Claims, user_id: i32, role: Role) -> Result<UserResponse, UserError>;
 }
 
 pub struct UserServiceImpl {
     user_repository: UserRepositoryArc,
+    login_guard_service: LoginGuardServiceArc,
+    password_hasher: Arc<PasswordHasher>,
+    jwt_keys: Arc<JwtKeys>,
 }
 
 impl UserServiceImpl {
-    pub fn new(user_repository: UserRepositoryArc) -> Self {
-        UserServiceImpl { user_repository }
+    pub fn new(
+        user_repository: UserRepositoryArc,
+        login_guard_service: LoginGuardServiceArc,
+        password_hasher: Arc<PasswordHasher>,
+        jwt_keys: Arc<JwtKeys>
+    ) -> Self {
+        UserServiceImpl { user_repository, login_guard_service, password_hasher, jwt_keys }
     }
 }
 
@@ -60,4 +95,135 @@ impl UserService for UserServiceImpl {
     async fn find_user_by_id(&self, req: &UserRequest) -> Result<Option<UserResponse>, UserError> {
         self.user_repository.find_user_by_id(&req.user_id).await
     }
+    /// プロフィール更新
+    /// 
+    /// 名前・自己紹介・写真のうち、指定された項目を更新します。
+    /// 
+    /// # 引数
+    /// 
+    /// * `user_id` - JWT から取得したユーザーID
+    /// * `req`     - `UpdateUserRequest` 型の更新内容
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(UserResponse)` - 更新後のユーザー情報を返します。
+    /// - `Err(UserError)`   - ユーザーが存在しない場合、または更新処理中にエラーが発生した場合、カスタムエラーを返します。
+    async fn update_profile(&self, user_id: i32, req: &UpdateUserRequest) -> Result<UserResponse, UserError> {
+        self.user_repository
+            .update_user(user_id, req)
+            .await?
+            .ok_or(UserError::UserNotFound)
+    }
+
+    /// パスワード変更
+    /// 
+    /// 現在のパスワードを検証したうえで新しいパスワードを保存します。
+    /// 現在のセッション以外は失効し、変更前に発行された JWT は無効になるため、新しいトークンを発行します。
+    /// 現在のパスワードの失敗はログインの失敗と同じく数え、アカウントがロック中の場合は拒否します。
+    /// 
+    /// # 引数
+    /// 
+    /// * `user` - `Claims` 型のユーザーデータ
+    /// * `req`  - `ChangePasswordRequest` 型の現在のパスワードと新しいパスワード
+    /// * `meta` - 接続元の IP アドレス・User-Agent
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(String)`     - 新しく発行した JWT を返します。
+    /// - `Err(UserError)` - 現在のパスワードが正しくない場合は `InvalidCredentials`、ロック中の場合は `LoginGuardError`、その他はカスタムエラーを返します。
+    async fn change_password(&self, user: &Claims, req: &ChangePasswordRequest, meta: &SessionMeta) -> Result<String, UserError> {
+        let attempt = LoginAttempt {
+            email: normalize_email(&user.sub),
+            ip_address: meta.ip_address.clone(),
+            user_agent: meta.user_agent.clone(),
+        };
+        self.login_guard_service.check_request(attempt.ip_address.as_deref(), Some(&attempt.email)).await?;
+
+        let current_hash = self.user_repository
+            .get_password_hash(user.id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+
+        if !self.password_hasher.verify(&req.current_password, &current_hash)? {
+            error_log!("[user_service] - [change_password] - [message: Authentication Failed] user_id = {}", user.id);
+            let delay = self.login_guard_service.record_failure(&attempt, Some(user.id), LoginFailureReason::WrongPassword).await?;
+            tokio::time::sleep(delay).await;
+
+            return Err(UserError::InvalidCredentials);
+        }
+        self.login_guard_service.record_success(&attempt).await?;
+
+        let new_hash = self.password_hasher.hash(&req.new_password)?;
+        if !self.user_repository.update_password(user.id, &new_hash, &user.sid).await? {
+            return Err(UserError::UserNotFound);
+        }
+
+        Ok(self.jwt_keys.create_token(&user.sub, &user.id, &user.sid, user.role)?)
+    }
+    async fn get_users(&self, pagination: &Pagination) -> Result<UserListResponse, UserError> {
+        self.user_repository.get_users(pagination).await
+    }
+
+    /// ユーザー削除
+    /// 
+    /// 管理者が自分自身を削除することはできません。
+    /// 
+    /// # 引数
+    /// 
+    /// * `admin`   - 操作する管理者の `Claims`
+    /// * `user_id` - 削除するユーザーID
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(())`         - ユーザーを削除した場合。
+    /// - `Err(UserError)` - 自分自身の場合は `CannotModifySelf`、ユーザーが存在しない場合は `UserNotFound` を返します。
+    async fn delete_user(&self, admin: &Claims, user_id: i32) -> Result<(), UserError> {
+        if admin.id == user_id {
+            return Err(UserError::CannotModifySelf);
+        }
+
+        if !self.user_repository.delete_user(user_id).await? {
+            return Err(UserError::UserNotFound);
+        }
+
+        info_log!("[user_service] - [delete_user] user_id = {} deleted by admin_id = {}", user_id, admin.id);
+        Ok(())
+    }
+
+    /// ロール変更
+    /// 
+    /// ロールを変更し、監査ログに記録します。管理者がいなくならないよう、自分自身のロールは変更できません。
+    /// 変更前のロールで発行された JWT は無効になり、リフレッシュ時に新しいロールで再発行されます。
+    /// 
+    /// # 引数
+    /// 
+    /// * `admin`   - 操作する管理者の `Claims`
+    /// * `user_id` - 変更するユーザーID
+    /// * `role`    - 新しいロール
+    /// 
+    /// # 戻り値
+    /// 
+    /// `Result` を返します:
+    /// 
+    /// - `Ok(UserResponse)` - 変更後のユーザー情報を返します。
+    /// - `Err(UserError)`   - 自分自身の場合は `CannotModifySelf`、ユーザーが存在しない場合は `UserNotFound` を返します。
+    async fn change_role(&self, admin: &Claims, user_id: i32, role: Role) -> Result<UserResponse, UserError> {
+        if admin.id == user_id {
+            return Err(UserError::CannotModifySelf);
+        }
+
+        let user = self.user_repository
+            .update_role(user_id, role, admin.id)
+            .await?
+            .ok_or(UserError::UserNotFound)?;
+
+        info_log!("[user_service] - [change_role] user_id = {} role = {} changed by admin_id = {}", user_id, role.as_str(), admin.id);
+        Ok(user)
+    }
 }
\ No newline at end of file
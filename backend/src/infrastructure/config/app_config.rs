@@ -0,0 +1,977 @@
+//! アプリケーション設定
+//!
+//! 起動時に一度だけ読み込み、`AppState` を通じて各サービスに渡す
+//! 値は次の順に上書きする
+//!
+//! 1. 既定値
+//! 2. 設定ファイル（環境変数 `APP_CONFIG_FILE` で指定した TOML。未指定の場合は読み込まない）
+//! 3. 環境変数
+//!
+//! 読み込んだ後に全ての値を検証し、見つかったエラーはまとめて `ConfigError` で返す
+//! 設定ファイルのキーは `[database]` の `host` のようにセクションとキーで指定し、環境変数との対応は README を参照
+//!
+//! # 関数
+//!
+//! * `install` - 読み込んだ設定をプロセス全体で共有
+//! * `current` - 共有している設定（トークンの有効期限・リンクなど、リクエストの外からも参照する値に使用）
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+use lazy_static::lazy_static;
+use serde::de::{value::StrDeserializer, DeserializeOwned, IntoDeserializer};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::EnvFilter;
+
+use crate::application::errors::config_error::ConfigError;
+use crate::application::helpers::password::PasswordHasher;
+use crate::application::helpers::redact::REDACTED;
+use crate::application::jwt::jwt_keys::{JwtKeys, KeyAlgorithm};
+use crate::domain::enums::locale::Locale;
+use crate::infrastructure::config::db_config::{make_tls_connector, PgSslMode};
+use crate::infrastructure::config::log_config::{LogFormat, LogRotation};
+use crate::infrastructure::config::mail_config::{MailConfig, MailTransportKind, SmtpTls};
+use crate::PROJECT_PATH;
+
+static CURRENT: OnceLock<Arc<AppConfig>> = OnceLock::new();
+
+lazy_static! {
+    /// `install` する前（テストなど）に使用する既定の設定
+    static ref DEFAULT: AppConfig = AppConfig::default();
+}
+
+/// アプリケーション設定
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    pub server: ServerSettings,
+    pub app: AppSettings,
+    pub database: DatabaseSettings,
+    pub jwt: JwtSettings,
+    pub auth: AuthSettings,
+    pub password: PasswordSettings,
+    pub mail: MailSettings,
+    pub log: LogSettings,
+    pub outbox: OutboxSettings,
+    pub task_reminder: TaskReminderSettings,
+    pub health: HealthSettings,
+    pub metrics: MetricsSettings,
+    pub login_protection: LoginProtectionSettings,
+    pub mfa: MfaSettings,
+    pub oidc: OidcSettings,
+    pub guest: GuestSettings,
+}
+
+/// Web サーバー
+///
+/// * `cors_allowed_origins`  - クッキー付きのリクエストを許可するオリジン（未設定の場合は `app.url` のオリジンのみ）
+/// * `auto_migrate`          - 起動時にマイグレーションを適用するか
+/// * `shutdown_timeout_secs` - 停止の要求から、処理中のリクエスト・ジョブの終了を待つ秒数
+/// * `trust_proxy_headers`   - 接続元の IP アドレスをリバースプロキシのヘッダー（`X-Real-IP` / `X-Forwarded-For`）から取得するか
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub cors_max_age: usize,
+    pub cors_allowed_origins: Vec<String>,
+    pub auto_migrate: bool,
+    pub shutdown_timeout_secs: u64,
+    pub trust_proxy_headers: bool,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        ServerSettings {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            cors_max_age: 3600,
+            cors_allowed_origins: Vec::new(),
+            auto_migrate: true,
+            shutdown_timeout_secs: 30,
+            trust_proxy_headers: false,
+        }
+    }
+}
+
+/// アプリケーション全体
+///
+/// * `url`                    - フロントエンドの URL（メールのリンクに使用）
+/// * `require_verified_email` - メールアドレスが未認証のユーザーのタスク操作を拒否するか
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppSettings {
+    pub url: String,
+    pub default_locale: Locale,
+    pub require_verified_email: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings { url: "http://localhost:3000".to_string(), default_locale: Locale::default(), require_verified_email: false }
+    }
+}
+
+/// DB 接続・接続プール
+///
+/// * `idle_timeout_secs` - アイドル状態の接続を開放するまでの秒数
+/// * `ssl_mode`          - TLS のモード（`disable` / `prefer` / `require` / `verify-ca` / `verify-full`）
+/// * `ssl_root_cert`     - サーバー証明書を検証する CA 証明書 (PEM)
+/// * `ssl_cert`          - クライアント証明書 (PEM)
+/// * `ssl_key`           - クライアント証明書の秘密鍵 (PKCS#8 の PEM)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub name: String,
+    pub max_pool_size: u32,
+    pub min_idle: u32,
+    pub idle_timeout_secs: u64,
+    pub ssl_mode: PgSslMode,
+    pub ssl_root_cert: Option<PathBuf>,
+    pub ssl_cert: Option<PathBuf>,
+    pub ssl_key: Option<PathBuf>,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        DatabaseSettings {
+            host: String::new(),
+            port: 5432,
+            user: String::new(),
+            password: String::new(),
+            name: String::new(),
+            max_pool_size: 100,
+            min_idle: 5,
+            idle_timeout_secs: 60,
+            ssl_mode: PgSslMode::Prefer,
+            ssl_root_cert: None,
+            ssl_cert: None,
+            ssl_key: None,
+        }
+    }
+}
+
+/// JWT の鍵・トークンの有効期限
+///
+/// * `secret`               - HS256 の共通鍵
+/// * `private_key_path`     - RS256 / EdDSA の秘密鍵 (PEM) のパス
+/// * `previous_secrets`     - ローテーション前の共通鍵（`kid` ごと）
+/// * `previous_public_keys` - ローテーション前の公開鍵 (PEM) のパス（`kid` ごと）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JwtSettings {
+    pub algorithm: KeyAlgorithm,
+    pub key_id: String,
+    pub secret: Option<String>,
+    pub private_key_path: Option<PathBuf>,
+    pub previous_secrets: BTreeMap<String, String>,
+    pub previous_public_keys: BTreeMap<String, PathBuf>,
+    pub issuer: Option<String>,
+    pub audience: Vec<String>,
+    pub leeway_secs: u64,
+    pub access_token_ttl_minutes: u64,
+    pub refresh_token_ttl_days: i64,
+}
+
+impl Default for JwtSettings {
+    fn default() -> Self {
+        JwtSettings {
+            algorithm: KeyAlgorithm::HS256,
+            key_id: "default".to_string(),
+            secret: None,
+            private_key_path: None,
+            previous_secrets: BTreeMap::new(),
+            previous_public_keys: BTreeMap::new(),
+            issuer: None,
+            audience: Vec::new(),
+            leeway_secs: 0,
+            access_token_ttl_minutes: 15,
+            refresh_token_ttl_days: 30,
+        }
+    }
+}
+
+/// メール認証・パスワードリセットのトークン
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    pub verification_token_ttl_hours: i64,
+    pub verification_resend_cooldown_secs: i64,
+    pub password_reset_token_ttl_minutes: i64,
+    pub password_reset_cooldown_secs: i64,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        AuthSettings {
+            verification_token_ttl_hours: 24,
+            verification_resend_cooldown_secs: 60,
+            password_reset_token_ttl_minutes: 60,
+            password_reset_cooldown_secs: 60,
+        }
+    }
+}
+
+/// パスワードのハッシュ（argon2id のコスト）
+///
+/// 変更すると、以前の設定のハッシュは次のログインで作り直す
+///
+/// * `argon2_memory_kib`  - 使用するメモリ（KiB）
+/// * `argon2_iterations`  - 反復回数
+/// * `argon2_parallelism` - 並列数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordSettings {
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+}
+
+impl Default for PasswordSettings {
+    fn default() -> Self {
+        PasswordSettings { argon2_memory_kib: 19456, argon2_iterations: 2, argon2_parallelism: 1 }
+    }
+}
+
+/// メール送信
+///
+/// * `file_dir` - `file` の出力先ディレクトリ
+/// * `smtp_tls` - 未指定の場合はポート 465 なら `tls`、それ以外は `starttls`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailSettings {
+    pub transport: MailTransportKind,
+    pub from: String,
+    pub file_dir: PathBuf,
+    pub smtp_server: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_tls: Option<SmtpTls>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+}
+
+impl Default for MailSettings {
+    fn default() -> Self {
+        MailSettings {
+            transport: MailTransportKind::Smtp,
+            from: "no-reply@example.com".to_string(),
+            file_dir: PathBuf::from(PROJECT_PATH).join("mail"),
+            smtp_server: None,
+            smtp_port: 587,
+            smtp_tls: None,
+            smtp_username: None,
+            smtp_password: None,
+        }
+    }
+}
+
+/// ログ
+///
+/// * `filter`      - モジュールごとのログレベル（`RUST_LOG` と同じ形式）
+/// * `dir`         - ログファイルの出力先ディレクトリ。空の場合はファイルに出力しない
+/// * `max_size_mb` - サイズによるローテーション（0 の場合はサイズでローテーションしない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSettings {
+    pub filter: String,
+    pub format: LogFormat,
+    pub dir: String,
+    pub file_name: String,
+    pub rotation: LogRotation,
+    pub max_size_mb: u64,
+    pub max_files: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        LogSettings {
+            filter: "info".to_string(),
+            format: LogFormat::Json,
+            dir: format!("{}/log", PROJECT_PATH),
+            file_name: "actix.log".to_string(),
+            rotation: LogRotation::Daily,
+            max_size_mb: 50,
+            max_files: 14,
+        }
+    }
+}
+
+/// メール送信キューのワーカー
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboxSettings {
+    pub poll_interval_secs: u64,
+    pub batch_size: i64,
+    pub max_attempts: i32,
+    pub backoff_base_secs: i64,
+    pub backoff_max_secs: i64,
+    pub lease_secs: i64,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        OutboxSettings {
+            poll_interval_secs: 5,
+            batch_size: 20,
+            max_attempts: 8,
+            backoff_base_secs: 30,
+            backoff_max_secs: 3600,
+            lease_secs: 300,
+        }
+    }
+}
+
+/// タスクのリマインダーのワーカー
+///
+/// * `lead_hours` - 期限の何時間前に送信するか
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TaskReminderSettings {
+    pub lead_hours: i64,
+    pub interval_secs: u64,
+}
+
+impl Default for TaskReminderSettings {
+    fn default() -> Self {
+        TaskReminderSettings { lead_hours: 24, interval_secs: 60 }
+    }
+}
+
+/// ヘルスチェック
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthSettings {
+    pub check_timeout_ms: u64,
+    pub mail_check_interval_secs: u64,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        HealthSettings { check_timeout_ms: 2000, mail_check_interval_secs: 60 }
+    }
+}
+
+/// メトリクス
+///
+/// * `token` - 設定した場合は `Authorization: Bearer {token}` のリクエストのみ許可
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsSettings {
+    pub token: Option<String>,
+}
+
+/// ログイン試行の回数を記録する場所（設定値）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThrottleStoreKind {
+    /// プロセスのメモリ（再起動でリセットされ、複数のプロセスでは共有しない）
+    Memory,
+    /// DB の `login_throttle` テーブル
+    Postgres,
+}
+
+/// ログインの総当たり攻撃対策
+///
+/// * `ip_max_requests` / `account_max_requests` - `rate_window_secs` 秒間に受け付けるログインのリクエスト数
+/// * `ip_max_failures` / `account_max_failures` - `failure_window_secs` 秒間にこの回数失敗するとロックする
+/// * `lockout_secs`                             - ロックする秒数
+/// * `delay_after_failures`                     - この回数を超えて失敗したアカウントは、失敗のレスポンスを遅らせる
+/// * `history_retention_days`                   - 失敗の履歴を保存する日数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoginProtectionSettings {
+    pub store: ThrottleStoreKind,
+    pub rate_window_secs: i64,
+    pub ip_max_requests: i32,
+    pub account_max_requests: i32,
+    pub failure_window_secs: i64,
+    pub ip_max_failures: i32,
+    pub account_max_failures: i32,
+    pub lockout_secs: i64,
+    pub delay_after_failures: i32,
+    pub delay_base_ms: u64,
+    pub delay_max_ms: u64,
+    pub history_retention_days: i64,
+}
+
+impl Default for LoginProtectionSettings {
+    fn default() -> Self {
+        LoginProtectionSettings {
+            store: ThrottleStoreKind::Memory,
+            rate_window_secs: 60,
+            ip_max_requests: 30,
+            account_max_requests: 10,
+            failure_window_secs: 900,
+            ip_max_failures: 50,
+            account_max_failures: 5,
+            lockout_secs: 900,
+            delay_after_failures: 2,
+            delay_base_ms: 500,
+            delay_max_ms: 4000,
+            history_retention_days: 90,
+        }
+    }
+}
+
+/// 2段階認証（TOTP）
+///
+/// * `issuer`                 - 認証アプリに表示するサービス名
+/// * `challenge_ttl_secs`     - パスワードを検証してから TOTP のコードを入力するまでの有効期限（秒）
+/// * `challenge_max_attempts` - 1回のログインでコードを入力できる回数
+/// * `recovery_code_count`    - 発行するリカバリーコードの数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MfaSettings {
+    pub issuer: String,
+    pub challenge_ttl_secs: i64,
+    pub challenge_max_attempts: i32,
+    pub recovery_code_count: usize,
+}
+
+impl Default for MfaSettings {
+    fn default() -> Self {
+        MfaSettings {
+            issuer: "Gamernage".to_string(),
+            challenge_ttl_secs: 300,
+            challenge_max_attempts: 5,
+            recovery_code_count: 10,
+        }
+    }
+}
+
+/// OpenID Connect のログイン
+///
+/// * `redirect_base_url` - 認可レスポンスのリダイレクト先（`{redirect_base_url}/{provider}`）。未指定の場合は `{app.url}/auth/callback`
+/// * `state_ttl_secs`    - 認可リクエストからコールバックまでの有効期限（秒）
+/// * `http_timeout_secs` - プロバイダーへのリクエストのタイムアウト（秒）
+/// * `providers`         - プロバイダー（キーは URL と `accounts.provider` に使用する名前）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcSettings {
+    pub redirect_base_url: Option<String>,
+    pub state_ttl_secs: i64,
+    pub http_timeout_secs: u64,
+    pub providers: BTreeMap<String, OidcProviderSettings>,
+}
+
+impl Default for OidcSettings {
+    fn default() -> Self {
+        OidcSettings { redirect_base_url: None, state_ttl_secs: 600, http_timeout_secs: 10, providers: BTreeMap::new() }
+    }
+}
+
+/// OpenID Connect のプロバイダー
+///
+/// * `issuer`        - 発行者の URL（`{issuer}/.well-known/openid-configuration` からエンドポイントを取得）
+/// * `client_secret` - 未指定の場合は公開クライアントとして PKCE のみで認可コードを交換する
+/// * `scopes`        - 要求するスコープ（`openid` は常に含める）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OidcProviderSettings {
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl Default for OidcProviderSettings {
+    fn default() -> Self {
+        OidcProviderSettings {
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: None,
+            scopes: vec!["openid".to_string(), "email".to_string(), "profile".to_string()],
+        }
+    }
+}
+
+/// ゲストログイン
+///
+/// * `enabled`               - ゲストログインを許可するか
+/// * `ttl_hours`             - ゲストの有効期限（時間）。セッション・JWT もこの期限を超えて発行しない
+/// * `cleanup_interval_secs` - 期限切れのゲストを削除する間隔（秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GuestSettings {
+    pub enabled: bool,
+    pub ttl_hours: i64,
+    pub cleanup_interval_secs: u64,
+}
+
+impl Default for GuestSettings {
+    fn default() -> Self {
+        GuestSettings { enabled: true, ttl_hours: 72, cleanup_interval_secs: 3600 }
+    }
+}
+
+impl AppConfig {
+    /// 既定値・設定ファイル・環境変数から設定を読み込み、検証
+    ///
+    /// # 戻り値
+    ///
+    /// * `Result<AppConfig, ConfigError>` - 設定（エラーがある場合は全てのエラー）
+    pub fn load() -> Result<AppConfig, ConfigError> {
+        let mut errors = ConfigError::default();
+
+        let mut config = match env::var("APP_CONFIG_FILE") {
+            Ok(path) => AppConfig::from_file(Path::new(&path)).unwrap_or_else(|err| {
+                errors.push(err);
+                AppConfig::default()
+            }),
+            Err(_) => AppConfig::default(),
+        };
+        config.apply_env(|key| env::var(key).ok(), &mut errors);
+        config.validate(&mut errors);
+
+        errors.into_result(config)
+    }
+
+    /// 設定ファイル (TOML) を読み込み（ファイルにないキーは既定値）
+    pub fn from_file(path: &Path) -> Result<AppConfig, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("設定ファイル `{}` を読み込めません: {}", path.display(), err))?;
+
+        AppConfig::from_toml(&content).map_err(|err| format!("設定ファイル `{}` が不正です: {}", path.display(), err))
+    }
+
+    /// TOML の文字列から設定を作成（ないキーは既定値）
+    pub fn from_toml(content: &str) -> Result<AppConfig, String> {
+        toml::from_str(content).map_err(|err| err.message().to_string())
+    }
+
+    /// 環境変数で上書き
+    ///
+    /// # 引数
+    ///
+    /// * `lookup` - 環境変数の値を取得する関数
+    /// * `errors` - 値を変換できない環境変数のエラーを追加する
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>, errors: &mut ConfigError) {
+        let mut env = EnvOverrides { lookup: &lookup, errors };
+
+        env.parse("HOST_NAME", &mut self.server.host);
+        env.parse("BACKEND_PORT", &mut self.server.port);
+        env.parse("CORS_MAX_AGE", &mut self.server.cors_max_age);
+        env.list("CORS_ALLOWED_ORIGINS", &mut self.server.cors_allowed_origins);
+        env.parse("AUTO_MIGRATE", &mut self.server.auto_migrate);
+        env.parse("SHUTDOWN_TIMEOUT_SECS", &mut self.server.shutdown_timeout_secs);
+        env.parse("TRUST_PROXY_HEADERS", &mut self.server.trust_proxy_headers);
+
+        env.parse("APP_URL", &mut self.app.url);
+        env.parse("DEFAULT_LOCALE", &mut self.app.default_locale);
+        env.parse("REQUIRE_VERIFIED_EMAIL", &mut self.app.require_verified_email);
+
+        env.parse("DATABASE_HOST", &mut self.database.host);
+        env.parse("DATABASE_PORT", &mut self.database.port);
+        env.parse("DATABASE_USER", &mut self.database.user);
+        env.parse("DATABASE_PASSWORD", &mut self.database.password);
+        env.parse("DATABASE_NAME", &mut self.database.name);
+        env.parse("DATABASE_MAX_POOL_SIZE", &mut self.database.max_pool_size);
+        env.parse("MIN_IDLE_CONNECTION", &mut self.database.min_idle);
+        env.parse("DATABASE_CONNECT_TIMEOUT", &mut self.database.idle_timeout_secs);
+        env.variant("DATABASE_SSL_MODE", &mut self.database.ssl_mode);
+        env.parse_opt("DATABASE_SSL_ROOT_CERT", &mut self.database.ssl_root_cert);
+        env.parse_opt("DATABASE_SSL_CERT", &mut self.database.ssl_cert);
+        env.parse_opt("DATABASE_SSL_KEY", &mut self.database.ssl_key);
+
+        env.parse("JWT_ALGORITHM", &mut self.jwt.algorithm);
+        env.parse("JWT_KEY_ID", &mut self.jwt.key_id);
+        env.parse_opt("JWT_SECRET", &mut self.jwt.secret);
+        env.parse_opt("JWT_PRIVATE_KEY_PATH", &mut self.jwt.private_key_path);
+        env.key_values("JWT_PREVIOUS_SECRETS", &mut self.jwt.previous_secrets);
+        env.key_values("JWT_PREVIOUS_PUBLIC_KEYS", &mut self.jwt.previous_public_keys);
+        env.parse_opt("JWT_ISSUER", &mut self.jwt.issuer);
+        env.list("JWT_AUDIENCE", &mut self.jwt.audience);
+        env.parse("JWT_LEEWAY_SECS", &mut self.jwt.leeway_secs);
+        env.parse("ACCESS_TOKEN_TTL_MINUTES", &mut self.jwt.access_token_ttl_minutes);
+        env.parse("REFRESH_TOKEN_TTL_DAYS", &mut self.jwt.refresh_token_ttl_days);
+
+        env.parse("VERIFICATION_TOKEN_TTL_HOURS", &mut self.auth.verification_token_ttl_hours);
+        env.parse("VERIFICATION_RESEND_COOLDOWN_SECS", &mut self.auth.verification_resend_cooldown_secs);
+        env.parse("PASSWORD_RESET_TOKEN_TTL_MINUTES", &mut self.auth.password_reset_token_ttl_minutes);
+        env.parse("PASSWORD_RESET_COOLDOWN_SECS", &mut self.auth.password_reset_cooldown_secs);
+        env.parse("PASSWORD_ARGON2_MEMORY_KIB", &mut self.password.argon2_memory_kib);
+        env.parse("PASSWORD_ARGON2_ITERATIONS", &mut self.password.argon2_iterations);
+        env.parse("PASSWORD_ARGON2_PARALLELISM", &mut self.password.argon2_parallelism);
+
+        env.variant("MAIL_TRANSPORT", &mut self.mail.transport);
+        env.parse("MAIL_FROM", &mut self.mail.from);
+        env.parse("MAIL_FILE_DIR", &mut self.mail.file_dir);
+        env.parse_opt("SMTP_SERVER", &mut self.mail.smtp_server);
+        env.parse("SMTP_PORT", &mut self.mail.smtp_port);
+        env.variant_opt("SMTP_TLS", &mut self.mail.smtp_tls);
+        env.parse_opt("SMTP_USERNAME", &mut self.mail.smtp_username);
+        env.parse_opt("SMTP_PASSWORD", &mut self.mail.smtp_password);
+
+        env.parse("RUST_LOG", &mut self.log.filter);
+        env.variant("LOG_FORMAT", &mut self.log.format);
+        env.parse("LOG_DIR", &mut self.log.dir);
+        env.parse("LOG_FILE_NAME", &mut self.log.file_name);
+        env.variant("LOG_ROTATION", &mut self.log.rotation);
+        env.parse("LOG_MAX_SIZE_MB", &mut self.log.max_size_mb);
+        env.parse("LOG_MAX_FILES", &mut self.log.max_files);
+
+        env.parse("OUTBOX_POLL_INTERVAL_SECS", &mut self.outbox.poll_interval_secs);
+        env.parse("OUTBOX_BATCH_SIZE", &mut self.outbox.batch_size);
+        env.parse("OUTBOX_MAX_ATTEMPTS", &mut self.outbox.max_attempts);
+        env.parse("OUTBOX_BACKOFF_BASE_SECS", &mut self.outbox.backoff_base_secs);
+        env.parse("OUTBOX_BACKOFF_MAX_SECS", &mut self.outbox.backoff_max_secs);
+        env.parse("OUTBOX_LEASE_SECS", &mut self.outbox.lease_secs);
+
+        env.parse("TASK_REMINDER_LEAD_HOURS", &mut self.task_reminder.lead_hours);
+        env.parse("TASK_REMINDER_INTERVAL_SECS", &mut self.task_reminder.interval_secs);
+
+        env.parse("HEALTH_CHECK_TIMEOUT_MS", &mut self.health.check_timeout_ms);
+        env.parse("HEALTH_MAIL_CHECK_INTERVAL_SECS", &mut self.health.mail_check_interval_secs);
+
+        env.parse_opt("METRICS_TOKEN", &mut self.metrics.token);
+
+        env.variant("LOGIN_THROTTLE_STORE", &mut self.login_protection.store);
+        env.parse("LOGIN_RATE_WINDOW_SECS", &mut self.login_protection.rate_window_secs);
+        env.parse("LOGIN_IP_MAX_REQUESTS", &mut self.login_protection.ip_max_requests);
+        env.parse("LOGIN_ACCOUNT_MAX_REQUESTS", &mut self.login_protection.account_max_requests);
+        env.parse("LOGIN_FAILURE_WINDOW_SECS", &mut self.login_protection.failure_window_secs);
+        env.parse("LOGIN_IP_MAX_FAILURES", &mut self.login_protection.ip_max_failures);
+        env.parse("LOGIN_ACCOUNT_MAX_FAILURES", &mut self.login_protection.account_max_failures);
+        env.parse("LOGIN_LOCKOUT_SECS", &mut self.login_protection.lockout_secs);
+        env.parse("LOGIN_DELAY_AFTER_FAILURES", &mut self.login_protection.delay_after_failures);
+        env.parse("LOGIN_DELAY_BASE_MS", &mut self.login_protection.delay_base_ms);
+        env.parse("LOGIN_DELAY_MAX_MS", &mut self.login_protection.delay_max_ms);
+        env.parse("LOGIN_HISTORY_RETENTION_DAYS", &mut self.login_protection.history_retention_days);
+
+        env.parse("MFA_ISSUER", &mut self.mfa.issuer);
+        env.parse("MFA_CHALLENGE_TTL_SECS", &mut self.mfa.challenge_ttl_secs);
+        env.parse("MFA_CHALLENGE_MAX_ATTEMPTS", &mut self.mfa.challenge_max_attempts);
+        env.parse("MFA_RECOVERY_CODE_COUNT", &mut self.mfa.recovery_code_count);
+        env.parse_opt("OIDC_REDIRECT_BASE_URL", &mut self.oidc.redirect_base_url);
+        env.parse("OIDC_STATE_TTL_SECS", &mut self.oidc.state_ttl_secs);
+        env.parse("OIDC_HTTP_TIMEOUT_SECS", &mut self.oidc.http_timeout_secs);
+        // `OIDC_PROVIDERS=google,twitch` の場合は `OIDC_GOOGLE_*` / `OIDC_TWITCH_*` を読み込む
+        let mut provider_names = Vec::new();
+        env.list("OIDC_PROVIDERS", &mut provider_names);
+        for name in provider_names {
+            let prefix = format!("OIDC_{}", name.to_uppercase().replace('-', "_"));
+            let provider = self.oidc.providers.entry(name).or_default();
+            env.parse(&format!("{}_ISSUER", prefix), &mut provider.issuer);
+            env.parse(&format!("{}_CLIENT_ID", prefix), &mut provider.client_id);
+            env.parse_opt(&format!("{}_CLIENT_SECRET", prefix), &mut provider.client_secret);
+            env.list(&format!("{}_SCOPES", prefix), &mut provider.scopes);
+        }
+
+        env.parse("GUEST_ENABLED", &mut self.guest.enabled);
+        env.parse("GUEST_TTL_HOURS", &mut self.guest.ttl_hours);
+        env.parse("GUEST_CLEANUP_INTERVAL_SECS", &mut self.guest.cleanup_interval_secs);
+    }
+
+    /// 値を検証
+    ///
+    /// 最初のエラーで止めず、全てのエラーを `errors` に追加する
+    pub fn validate(&self, errors: &mut ConfigError) {
+        let mut required = |value: &str, key: &str, env: &str| {
+            if value.trim().is_empty() {
+                errors.push(format!("{}は設定する必要があります。", field(key, env)));
+            }
+        };
+        required(&self.server.host, "server.host", "HOST_NAME");
+        required(&self.database.host, "database.host", "DATABASE_HOST");
+        required(&self.database.user, "database.user", "DATABASE_USER");
+        required(&self.database.name, "database.name", "DATABASE_NAME");
+
+        let mut positive = |value: i64, key: &str, env: &str| {
+            if value < 1 {
+                errors.push(format!("{}は 1 以上を設定する必要があります: {}", field(key, env), value));
+            }
+        };
+        positive(self.server.shutdown_timeout_secs as i64, "server.shutdown_timeout_secs", "SHUTDOWN_TIMEOUT_SECS");
+        positive(self.database.max_pool_size as i64, "database.max_pool_size", "DATABASE_MAX_POOL_SIZE");
+        positive(self.jwt.access_token_ttl_minutes as i64, "jwt.access_token_ttl_minutes", "ACCESS_TOKEN_TTL_MINUTES");
+        positive(self.jwt.refresh_token_ttl_days, "jwt.refresh_token_ttl_days", "REFRESH_TOKEN_TTL_DAYS");
+        positive(self.auth.verification_token_ttl_hours, "auth.verification_token_ttl_hours", "VERIFICATION_TOKEN_TTL_HOURS");
+        positive(self.auth.password_reset_token_ttl_minutes, "auth.password_reset_token_ttl_minutes", "PASSWORD_RESET_TOKEN_TTL_MINUTES");
+        positive(self.outbox.poll_interval_secs as i64, "outbox.poll_interval_secs", "OUTBOX_POLL_INTERVAL_SECS");
+        positive(self.outbox.batch_size, "outbox.batch_size", "OUTBOX_BATCH_SIZE");
+        positive(self.outbox.max_attempts as i64, "outbox.max_attempts", "OUTBOX_MAX_ATTEMPTS");
+        positive(self.outbox.backoff_base_secs, "outbox.backoff_base_secs", "OUTBOX_BACKOFF_BASE_SECS");
+        positive(self.outbox.lease_secs, "outbox.lease_secs", "OUTBOX_LEASE_SECS");
+        positive(self.task_reminder.lead_hours, "task_reminder.lead_hours", "TASK_REMINDER_LEAD_HOURS");
+        positive(self.task_reminder.interval_secs as i64, "task_reminder.interval_secs", "TASK_REMINDER_INTERVAL_SECS");
+        positive(self.health.check_timeout_ms as i64, "health.check_timeout_ms", "HEALTH_CHECK_TIMEOUT_MS");
+        positive(self.login_protection.rate_window_secs, "login_protection.rate_window_secs", "LOGIN_RATE_WINDOW_SECS");
+        positive(self.login_protection.ip_max_requests as i64, "login_protection.ip_max_requests", "LOGIN_IP_MAX_REQUESTS");
+        positive(self.login_protection.account_max_requests as i64, "login_protection.account_max_requests", "LOGIN_ACCOUNT_MAX_REQUESTS");
+        positive(self.login_protection.failure_window_secs, "login_protection.failure_window_secs", "LOGIN_FAILURE_WINDOW_SECS");
+        positive(self.login_protection.ip_max_failures as i64, "login_protection.ip_max_failures", "LOGIN_IP_MAX_FAILURES");
+        positive(self.login_protection.account_max_failures as i64, "login_protection.account_max_failures", "LOGIN_ACCOUNT_MAX_FAILURES");
+        positive(self.login_protection.lockout_secs, "login_protection.lockout_secs", "LOGIN_LOCKOUT_SECS");
+        positive(self.login_protection.history_retention_days, "login_protection.history_retention_days", "LOGIN_HISTORY_RETENTION_DAYS");
+        positive(self.mfa.challenge_ttl_secs, "mfa.challenge_ttl_secs", "MFA_CHALLENGE_TTL_SECS");
+        positive(self.mfa.challenge_max_attempts as i64, "mfa.challenge_max_attempts", "MFA_CHALLENGE_MAX_ATTEMPTS");
+        positive(self.mfa.recovery_code_count as i64, "mfa.recovery_code_count", "MFA_RECOVERY_CODE_COUNT");
+        positive(self.oidc.state_ttl_secs, "oidc.state_ttl_secs", "OIDC_STATE_TTL_SECS");
+        positive(self.oidc.http_timeout_secs as i64, "oidc.http_timeout_secs", "OIDC_HTTP_TIMEOUT_SECS");
+        positive(self.guest.ttl_hours, "guest.ttl_hours", "GUEST_TTL_HOURS");
+        positive(self.guest.cleanup_interval_secs as i64, "guest.cleanup_interval_secs", "GUEST_CLEANUP_INTERVAL_SECS");
+
+        if self.database.min_idle > self.database.max_pool_size {
+            errors.push(format!(
+                "{}は{}以下を設定する必要があります: {}",
+                field("database.min_idle", "MIN_IDLE_CONNECTION"),
+                field("database.max_pool_size", "DATABASE_MAX_POOL_SIZE"),
+                self.database.min_idle
+            ));
+        }
+        if self.outbox.backoff_max_secs < self.outbox.backoff_base_secs {
+            errors.push(format!(
+                "{}は{}以上を設定する必要があります: {}",
+                field("outbox.backoff_max_secs", "OUTBOX_BACKOFF_MAX_SECS"),
+                field("outbox.backoff_base_secs", "OUTBOX_BACKOFF_BASE_SECS"),
+                self.outbox.backoff_max_secs
+            ));
+        }
+        if self.login_protection.delay_max_ms < self.login_protection.delay_base_ms {
+            errors.push(format!(
+                "{}は{}以上を設定する必要があります: {}",
+                field("login_protection.delay_max_ms", "LOGIN_DELAY_MAX_MS"),
+                field("login_protection.delay_base_ms", "LOGIN_DELAY_BASE_MS"),
+                self.login_protection.delay_max_ms
+            ));
+        }
+        // `otpauth://totp/{issuer}:{account}` の区切りと区別できないため、`:` は使用できない
+        if self.mfa.issuer.trim().is_empty() || self.mfa.issuer.contains(':') {
+            errors.push(format!("{}は `:` を含まない空でない文字列を設定する必要があります: {}", field("mfa.issuer", "MFA_ISSUER"), self.mfa.issuer));
+        }
+        if let Some(url) = self.oidc.redirect_base_url.as_deref().filter(|url| !is_http_url(url)) {
+            errors.push(format!("{}は `http://` または `https://` で始まる URL を設定する必要があります: {}", field("oidc.redirect_base_url", "OIDC_REDIRECT_BASE_URL"), url));
+        }
+        for (name, provider) in &self.oidc.providers {
+            let env_prefix = format!("OIDC_{}", name.to_uppercase().replace('-', "_"));
+            // プロバイダー名は URL のパスに使用する
+            if name.is_empty() || !name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_') {
+                errors.push(format!("`oidc.providers` のキーは英小文字・数字・`-`・`_` のみ使用できます: {}", name));
+            }
+            if !is_http_url(&provider.issuer) {
+                errors.push(format!(
+                    "{}は `http://` または `https://` で始まる URL を設定する必要があります: {}",
+                    field(&format!("oidc.providers.{}.issuer", name), &format!("{}_ISSUER", env_prefix)),
+                    provider.issuer
+                ));
+            }
+            if provider.client_id.is_empty() {
+                errors.push(format!("{}は設定する必要があります。", field(&format!("oidc.providers.{}.client_id", name), &format!("{}_CLIENT_ID", env_prefix))));
+            }
+        }
+        if !is_http_url(&self.app.url) {
+            errors.push(format!("{}は `http://` または `https://` で始まる URL を設定する必要があります: {}", field("app.url", "APP_URL"), self.app.url));
+        }
+        for origin in self.server.cors_allowed_origins.iter().filter(|origin| !is_origin(origin)) {
+            errors.push(format!(
+                "{}はパスを含まない `http://` または `https://` のオリジンを設定する必要があります（`*` は使用できません）: {}",
+                field("server.cors_allowed_origins", "CORS_ALLOWED_ORIGINS"),
+                origin
+            ));
+        }
+        if let Err(err) = EnvFilter::try_new(&self.log.filter) {
+            errors.push(format!("{}が不正です: {}", field("log.filter", "RUST_LOG"), err));
+        }
+        if let Err(err) = PasswordHasher::from_settings(&self.password) {
+            errors.push(format!(
+                "{}・{}・{}の組み合わせが不正です: {}",
+                field("password.argon2_memory_kib", "PASSWORD_ARGON2_MEMORY_KIB"),
+                field("password.argon2_iterations", "PASSWORD_ARGON2_ITERATIONS"),
+                field("password.argon2_parallelism", "PASSWORD_ARGON2_PARALLELISM"),
+                err
+            ));
+        }
+
+        // 証明書・鍵ファイル・メールアドレスは実際に読み込んで検証する
+        if let Err(err) = make_tls_connector(&self.database) {
+            errors.push(err);
+        }
+        if let Err(err) = JwtKeys::from_settings(&self.jwt) {
+            errors.push(err.to_string());
+        }
+        if let Err(err) = MailConfig::from_settings(&self.mail) {
+            errors.push(err.to_string());
+        }
+    }
+
+    /// シークレット（パスワード・鍵・トークン）を `[REDACTED]` に置き換えた設定
+    ///
+    /// `--print-config` の出力に使用する
+    pub fn redacted(&self) -> AppConfig {
+        let mut config = self.clone();
+        let redact = |value: &mut String| {
+            if !value.is_empty() {
+                *value = REDACTED.to_string();
+            }
+        };
+
+        redact(&mut config.database.password);
+        config.jwt.secret.as_mut().map(redact);
+        config.jwt.previous_secrets.values_mut().for_each(redact);
+        config.mail.smtp_password.as_mut().map(redact);
+        config.metrics.token.as_mut().map(redact);
+        config.oidc.providers.values_mut().for_each(|provider| {
+            provider.client_secret.as_mut().map(redact);
+        });
+
+        config
+    }
+
+    /// CORS でクッキー付きのリクエストを許可するオリジン
+    ///
+    /// `server.cors_allowed_origins` が未設定の場合は、フロントエンド（`app.url`）のオリジンのみ許可する
+    pub fn cors_origins(&self) -> Vec<String> {
+        if !self.server.cors_allowed_origins.is_empty() {
+            return self.server.cors_allowed_origins.clone();
+        }
+
+        vec![origin_of(&self.app.url)]
+    }
+
+    /// CORS で許可したオリジンか
+    ///
+    /// * `url` - `Origin` / `Referer` ヘッダーの値（パスは無視する）
+    pub fn is_allowed_origin(&self, url: &str) -> bool {
+        is_http_url(url) && self.cors_origins().contains(&origin_of(url))
+    }
+
+    /// TOML 形式の文字列（シークレットは伏せる）
+    pub fn to_redacted_toml(&self) -> String {
+        toml::to_string_pretty(&self.redacted()).unwrap_or_else(|err| format!("# {}", err))
+    }
+}
+
+/// 読み込んだ設定をプロセス全体で共有
+///
+/// 2回目以降の呼び出しでは共有している設定を変更しない
+///
+/// # 戻り値
+///
+/// * `Arc<AppConfig>` - `AppState` に渡す設定
+pub fn install(config: AppConfig) -> Arc<AppConfig> {
+    CURRENT.get_or_init(|| Arc::new(config)).clone()
+}
+
+/// 共有している設定（`install` する前は既定値）
+pub fn current() -> &'static AppConfig {
+    CURRENT.get().map(Arc::as_ref).unwrap_or(&DEFAULT)
+}
+
+/// エラーメッセージに表示するキー
+fn field(key: &str, env: &str) -> String {
+    format!("`{}`（環境変数 `{}`）", key, env)
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// パスを含まない `scheme://host[:port]` か
+fn is_origin(origin: &str) -> bool {
+    is_http_url(origin) && origin_of(origin) == origin && !origin.ends_with("://")
+}
+
+/// URL のオリジン（`scheme://host[:port]`）
+fn origin_of(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => format!("{}://{}", scheme, rest.split(['/', '?', '#']).next().unwrap_or_default()),
+        None => url.to_string(),
+    }
+}
+
+/// 環境変数による上書き
+///
+/// 変換できない値は既定値・設定ファイルの値のまま、エラーを追加する
+struct EnvOverrides<'a, F: Fn(&str) -> Option<String>> {
+    lookup: &'a F,
+    errors: &'a mut ConfigError,
+}
+
+impl<F: Fn(&str) -> Option<String>> EnvOverrides<'_, F> {
+    /// `FromStr` で変換する値
+    fn parse<T: FromStr>(&mut self, key: &str, target: &mut T)
+    where
+        T::Err: Display,
+    {
+        if let Some(value) = self.value(key, |v| v.parse::<T>().map_err(|err| err.to_string())) {
+            *target = value;
+        }
+    }
+
+    fn parse_opt<T: FromStr>(&mut self, key: &str, target: &mut Option<T>)
+    where
+        T::Err: Display,
+    {
+        if let Some(value) = self.value(key, |v| v.parse::<T>().map_err(|err| err.to_string())) {
+            *target = Some(value);
+        }
+    }
+
+    /// 設定ファイルと同じ文字列（`json` / `text` など）で指定する列挙型
+    fn variant<T: DeserializeOwned>(&mut self, key: &str, target: &mut T) {
+        if let Some(value) = self.value(key, parse_variant) {
+            *target = value;
+        }
+    }
+
+    fn variant_opt<T: DeserializeOwned>(&mut self, key: &str, target: &mut Option<T>) {
+        if let Some(value) = self.value(key, parse_variant) {
+            *target = Some(value);
+        }
+    }
+
+    /// カンマ区切りのリスト
+    fn list(&mut self, key: &str, target: &mut Vec<String>) {
+        if let Some(value) = (self.lookup)(key) {
+            *target = value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+        }
+    }
+
+    /// `kid=value` のカンマ区切りのリスト
+    fn key_values<T: From<String>>(&mut self, key: &str, target: &mut BTreeMap<String, T>) {
+        let value = match (self.lookup)(key) {
+            Some(value) => value,
+            None => return,
+        };
+
+        let mut entries = BTreeMap::new();
+        for entry in value.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            match entry.split_once('=') {
+                Some((kid, value)) if !kid.trim().is_empty() => {
+                    entries.insert(kid.trim().to_string(), T::from(value.trim().to_string()));
+                }
+                _ => {
+                    self.errors.push(format!("環境変数 `{}` は `kid=value` のカンマ区切りで設定する必要があります。", key));
+                    return;
+                }
+            }
+        }
+        *target = entries;
+    }
+
+    fn value<T>(&mut self, key: &str, parse: impl Fn(&str) -> Result<T, String>) -> Option<T> {
+        let value = (self.lookup)(key)?;
+
+        match parse(&value) {
+            Ok(parsed) => Some(parsed),
+            Err(err) => {
+                self.errors.push(format!("環境変数 `{}` の値 `{}` が不正です: {}", key, value, err));
+                None
+            }
+        }
+    }
+}
+
+/// 列挙型を設定ファイルと同じ文字列から変換
+fn parse_variant<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    let deserializer: StrDeserializer<serde::de::value::Error> = value.into_deserializer();
+    T::deserialize(deserializer).map_err(|err| err.to_string())
+}
\ No newline at end of file
@@ -1,25 +1,126 @@
 //! DB接続設定
 //! 
-//! DB接続に必要な値を環境変数として取得し、Config に設定
+//! DB接続に必要な値を `AppConfig` の `[database]` から取得し、Config に設定
 //! 
 //! # 関数
 //! 
-//! * `get_config` - `tokio_postgres::Config` を作成し、各環境変数を設定
+//! * `get_config`          - `tokio_postgres::Config` を作成し、各設定値を設定
+//! * `make_tls_connector` - `ssl_mode` と証明書から TLS の接続を作成
+//! 
+//! # TLS のモード
+//! 
+//! | `ssl_mode`    | 暗号化           | サーバー証明書の検証           |
+//! | ------------- | ---------------- | ------------------------------ |
+//! | `disable`     | しない           | -                              |
+//! | `prefer`      | 可能であれば     | しない                         |
+//! | `require`     | 必須             | しない（※）                   |
+//! | `verify-ca`   | 必須             | CA のみ（ホスト名は検証しない） |
+//! | `verify-full` | 必須             | CA とホスト名                  |
+//! 
+//! `ssl_root_cert` を指定した場合はその CA（複数の証明書を連結したファイルも可）のみを信頼し、指定しない場合は OS の CA を使用する
+//! ※ libpq と同じく、`require` で `ssl_root_cert` を指定した場合は `verify-ca` と同じく CA を検証する
 
-use std::env;
+use std::fs;
+use std::path::Path;
+use native_tls::{Certificate, Identity, TlsConnector};
+use postgres_native_tls::MakeTlsConnector;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::config::SslMode;
 
-// SSL証明書発行後に設定
-// use postgres::config::SslMode;
+use crate::infrastructure::config::app_config::DatabaseSettings;
 
-pub fn get_config () -> tokio_postgres::Config {
-    let mut config = tokio_postgres::Config::new();
-    config.host(&env::var("DATABASE_HOST").expect("環境変数 `DATABASE_HOST` は設定する必要があります。"));
-    config.user(&env::var("DATABASE_USER").expect("環境変数 `DATABASE_USER` は設定する必要があります。"));
-    config.password(&env::var("DATABASE_PASSWORD").expect("環境変数 `DATABASE_PASSWORD` は設定する必要があります。"));
-    config.dbname(&env::var("DATABASE_NAME").expect("環境変数 `DATABASE_NAME` は設定する必要があります。"));
+/// TLS のモード（libpq の `sslmode` と同じ名前）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PgSslMode {
+    Disable,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
 
-    // // SSL接続を有効にする
-    // config.ssl_mode(SslMode::Require);
+pub fn get_config (settings: &DatabaseSettings) -> tokio_postgres::Config {
+    let mut config = tokio_postgres::Config::new();
+    config.host(&settings.host);
+    config.port(settings.port);
+    config.user(&settings.user);
+    config.password(&settings.password);
+    config.dbname(&settings.name);
+    config.ssl_mode(match settings.ssl_mode {
+        PgSslMode::Disable => SslMode::Disable,
+        PgSslMode::Prefer => SslMode::Prefer,
+        PgSslMode::Require | PgSslMode::VerifyCa | PgSslMode::VerifyFull => SslMode::Require,
+    });
 
     config
+}
+
+/// TLS の接続を作成
+/// 
+/// `disable` の場合も接続プールの型を共通にするため作成する（TLS は使用しない）
+/// 
+/// # 引数
+/// 
+/// * `settings` - `[database]` の設定
+/// 
+/// # 戻り値
+/// 
+/// * `Result<MakeTlsConnector, String>` - 証明書を読み込めない場合はエラー
+pub fn make_tls_connector(settings: &DatabaseSettings) -> Result<MakeTlsConnector, String> {
+    let mut builder = TlsConnector::builder();
+
+    match (settings.ssl_mode, &settings.ssl_root_cert) {
+        (PgSslMode::Disable | PgSslMode::Prefer, _) | (PgSslMode::Require, None) => {
+            builder.danger_accept_invalid_certs(true);
+        }
+        (PgSslMode::Require, Some(_)) | (PgSslMode::VerifyCa, _) => {
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        (PgSslMode::VerifyFull, _) => {}
+    }
+
+    if let Some(path) = &settings.ssl_root_cert {
+        let certificates = read_certificates(path)?;
+        builder.disable_built_in_roots(true);
+        for certificate in certificates {
+            builder.add_root_certificate(certificate);
+        }
+    }
+
+    match (&settings.ssl_cert, &settings.ssl_key) {
+        (Some(cert), Some(key)) => {
+            let identity = Identity::from_pkcs8(&read(cert)?, &read(key)?).map_err(|err| {
+                format!("クライアント証明書 `{}` / 秘密鍵 `{}` を読み込めません（秘密鍵は PKCS#8 の PEM）: {}", cert.display(), key.display(), err)
+            })?;
+            builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => {
+            return Err("`database.ssl_cert`（環境変数 `DATABASE_SSL_CERT`）と `database.ssl_key`（環境変数 `DATABASE_SSL_KEY`）は両方設定する必要があります。".to_string());
+        }
+    }
+
+    let connector = builder.build().map_err(|err| format!("DB の TLS 設定が不正です: {}", err))?;
+
+    Ok(MakeTlsConnector::new(connector))
+}
+
+/// PEM ファイルの全ての証明書を読み込み
+fn read_certificates(path: &Path) -> Result<Vec<Certificate>, String> {
+    let certificates = pem::parse_many(read(path)?)
+        .into_iter()
+        .filter(|pem| pem.tag == "CERTIFICATE")
+        .map(|pem| Certificate::from_der(&pem.contents).map_err(|err| format!("CA 証明書 `{}` が不正です: {}", path.display(), err)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if certificates.is_empty() {
+        return Err(format!("CA 証明書 `{}` に証明書がありません。", path.display()));
+    }
+
+    Ok(certificates)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|err| format!("`{}` を読み込めません: {}", path.display(), err))
 }
\ No newline at end of file
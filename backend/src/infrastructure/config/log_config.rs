@@ -0,0 +1,62 @@
+//! ログ設定
+//!
+//! `AppConfig` の `[log]` から、ログの出力形式・出力先・ローテーションを作成
+
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::infrastructure::config::app_config::LogSettings;
+
+/// 標準出力の形式（ファイルは常に `json`）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Text,
+}
+
+/// 時間によるローテーションの間隔
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Daily,
+    Hourly,
+    Never,
+}
+
+/// ログファイルの設定
+#[derive(Clone, Debug)]
+pub struct LogFileConfig {
+    pub dir: PathBuf,
+    pub file_name: String,
+    pub rotation: LogRotation,
+    pub max_bytes: u64,
+    pub max_files: usize,
+}
+
+/// ログ設定
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    pub filter: String,
+    pub format: LogFormat,
+    pub file: Option<LogFileConfig>,
+}
+
+impl LogConfig {
+    /// `[log]` の設定から作成（`dir` が空の場合はファイルに出力しない）
+    pub fn from_settings(settings: &LogSettings) -> Self {
+        let file = (!settings.dir.is_empty()).then(|| LogFileConfig {
+            dir: PathBuf::from(&settings.dir),
+            file_name: settings.file_name.clone(),
+            rotation: settings.rotation,
+            max_bytes: settings.max_size_mb * 1024 * 1024,
+            max_files: settings.max_files,
+        });
+
+        LogConfig {
+            filter: settings.filter.clone(),
+            format: settings.format,
+            file,
+        }
+    }
+}
\ No newline at end of file
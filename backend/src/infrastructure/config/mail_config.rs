@@ -0,0 +1,92 @@
+//! メール送信設定
+//!
+//! `AppConfig` の `[mail]` から、メールの送信方法と送信元を作成
+
+use std::path::PathBuf;
+use lettre::message::Mailbox;
+use serde::{Deserialize, Serialize};
+
+use crate::application::errors::mail_error::MailError;
+use crate::infrastructure::config::app_config::MailSettings;
+
+/// SMTP の暗号化方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    StartTls,
+    Tls,
+    None,
+}
+
+/// メールの送信方法（設定値）
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MailTransportKind {
+    Smtp,
+    File,
+    Log,
+}
+
+/// メールの送信方法
+#[derive(Clone, Debug)]
+pub enum MailTransport {
+    /// SMTP サーバーで送信
+    Smtp {
+        server: String,
+        port: u16,
+        tls: SmtpTls,
+        credentials: Option<(String, String)>,
+    },
+    /// `.eml` ファイルとしてディレクトリに出力（開発用）
+    File { dir: PathBuf },
+    /// ログに出力（開発用）
+    Log,
+}
+
+/// メール送信設定
+#[derive(Clone, Debug)]
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub from: Mailbox,
+}
+
+impl MailConfig {
+    /// `[mail]` の設定から作成
+    ///
+    /// 送信元のメールアドレスが不正な場合、`smtp` で `smtp_server` が未設定の場合はエラー
+    pub fn from_settings(settings: &MailSettings) -> Result<Self, MailError> {
+        let from = settings.from.parse().map_err(|_| {
+            MailError::Config(format!(
+                "`mail.from`（環境変数 `MAIL_FROM`）は正しいメールアドレスで設定する必要があります: {}",
+                settings.from
+            ))
+        })?;
+
+        let transport = match settings.transport {
+            MailTransportKind::Smtp => smtp_transport(settings)?,
+            MailTransportKind::File => MailTransport::File { dir: settings.file_dir.clone() },
+            MailTransportKind::Log => MailTransport::Log,
+        };
+
+        Ok(MailConfig { transport, from })
+    }
+}
+
+fn smtp_transport(settings: &MailSettings) -> Result<MailTransport, MailError> {
+    let server = settings.smtp_server.clone().filter(|server| !server.is_empty()).ok_or_else(|| {
+        MailError::Config("`mail.smtp_server`（環境変数 `SMTP_SERVER`）は設定する必要があります。".to_string())
+    })?;
+
+    let tls = match settings.smtp_tls {
+        Some(tls) => tls,
+        None if settings.smtp_port == 465 => SmtpTls::Tls,
+        None => SmtpTls::StartTls,
+    };
+
+    let credentials = match (&settings.smtp_username, &settings.smtp_password) {
+        (Some(username), Some(password)) => Some((username.clone(), password.clone())),
+        _ => None,
+    };
+
+    Ok(MailTransport::Smtp { server, port: settings.smtp_port, tls, credentials })
+}
\ No newline at end of file
@@ -1 +1,4 @@
-pub mod db_config;
\ No newline at end of file
+pub mod app_config;
+pub mod db_config;
+pub mod log_config;
+pub mod mail_config;
\ No newline at end of file
@@ -1,46 +1,103 @@
 //! DBプール設定
 //! 
-//! DB接続に必要な値を環境変数として取得し、Config に設定
+//! DB接続に必要な値を `AppConfig` の `[database]` から取得し、Config に設定
 //! 
 //! # 関数
 //! 
-//! * `get_config` - `tokio_postgres::Config` を作成し、各環境変数を設定
-
-use std::time::Duration;
-use std::env;
-use bb8_postgres::{PostgresConnectionManager, bb8::Pool};
-use tokio_postgres::NoTls;
-
-use crate::infrastructure::config::db_config::get_config;
-
-pub async fn get_db_pool () -> Pool<PostgresConnectionManager<NoTls>> {
-    let max_pool_size: u32 = env::var("DATABASE_MAX_POOL_SIZE")
-        .unwrap_or_else(|_| "100".to_string())
-        .parse()
-        .expect("環境変数 `DATABASE_MAX_POOL_SIZE` は正しい整数値で設定する必要があります。");
-
-    let min_idle_connect: u32 = env::var("MIN_IDLE_CONNECTION")
-        .unwrap_or_else(|_| "5".to_string())
-        .parse()
-        .expect("環境変数 `MIN_IDLE_CONNECTION` は正しい整数値で設定する必要があります。");
-
-    let connect_timeout: u64 = env::var("DATABASE_CONNECT_TIMEOUT")
-        .unwrap_or_else(|_| "60".to_string()) 
-        .parse()
-        .expect("環境変数 `DATABASE_CONNECT_TIMEOUT` は正しい整数値で設定する必要があります。");
-
-    // DB接続設定
-    let config = get_config();
-    let pg_mgr = PostgresConnectionManager::new(config, NoTls);
-
-    // 接続プールの作成
-    let pool = Pool::builder()
-        .max_size(max_pool_size) // 最大接続数
-        .min_idle(Some(min_idle_connect)) // 最小アイドル接続
-        .idle_timeout(Some(Duration::from_secs(connect_timeout))) // 指定した時間を超えると接続を開放
-        .build(pg_mgr)
-        .await
-        .expect("DB接続プールの作成に失敗しました");
-
-    pool
+//! * `get_db_pool` - 接続プールを作成
+//! 
+//! # 構造体
+//! 
+//! * `TlsDbPool` - 接続の取得にかかった時間・プールの状態をメトリクスに記録する接続プール（TLS の実装ごと）
+//! 
+//! # 型
+//! 
+//! * `DbPool`       - アプリケーションで使用する接続プール（各リポジトリはこの型を使用する）
+//! * `DbConnection` - 接続プールから取得した接続
+
+use std::time::{Duration, Instant};
+use bb8_postgres::{PostgresConnectionManager, bb8::{ManageConnection, Pool, PooledConnection, RunError}};
+use postgres_native_tls::MakeTlsConnector;
+use tokio_postgres::tls::MakeTlsConnect;
+use tokio_postgres::{Client, Socket};
+
+use crate::application::metrics::registry::{
+    DB_POOL_CONNECTIONS, DB_POOL_ERRORS_TOTAL, DB_POOL_IDLE_CONNECTIONS, DB_POOL_MAX_CONNECTIONS, DB_POOL_WAIT_SECONDS,
+};
+use crate::infrastructure::config::app_config::DatabaseSettings;
+use crate::infrastructure::config::db_config::{get_config, make_tls_connector};
+
+/// アプリケーションで使用する接続プール
+/// 
+/// TLS を使用しない場合（`ssl_mode = "disable"`）も同じ型で接続する
+pub type DbPool = TlsDbPool<MakeTlsConnector>;
+
+/// 接続プールから取得した接続
+pub type DbConnection<'a, Tls = MakeTlsConnector> = PooledConnection<'a, PostgresConnectionManager<Tls>>;
+
+/// 接続プール
+/// 
+/// `bb8` は接続の待機時間を記録しないため、`get` で計測してメトリクスに記録する
+#[derive(Clone)]
+pub struct TlsDbPool<Tls>
+where
+    Tls: MakeTlsConnect<Socket>,
+    PostgresConnectionManager<Tls>: ManageConnection,
+{
+    inner: Pool<PostgresConnectionManager<Tls>>,
+    max_size: u32,
+}
+
+impl<Tls> TlsDbPool<Tls>
+where
+    Tls: MakeTlsConnect<Socket>,
+    PostgresConnectionManager<Tls>: ManageConnection<Connection = Client, Error = tokio_postgres::Error>,
+{
+    /// 接続プールを作成
+    /// 
+    /// # 引数
+    /// 
+    /// * `settings` - `[database]` の設定
+    /// * `tls`      - TLS の接続
+    pub async fn build(settings: &DatabaseSettings, tls: Tls) -> Result<Self, tokio_postgres::Error> {
+        let pg_mgr = PostgresConnectionManager::new(get_config(settings), tls);
+
+        let pool = Pool::builder()
+            .max_size(settings.max_pool_size) // 最大接続数
+            .min_idle(Some(settings.min_idle)) // 最小アイドル接続
+            .idle_timeout(Some(Duration::from_secs(settings.idle_timeout_secs))) // 指定した時間を超えると接続を開放
+            .build(pg_mgr)
+            .await?;
+
+        Ok(TlsDbPool { inner: pool, max_size: settings.max_pool_size })
+    }
+
+    /// 接続を取得
+    pub async fn get(&self) -> Result<DbConnection<'_, Tls>, RunError<tokio_postgres::Error>> {
+        let started = Instant::now();
+        let result = self.inner.get().await;
+
+        DB_POOL_WAIT_SECONDS.observe(started.elapsed().as_secs_f64());
+        if result.is_err() {
+            DB_POOL_ERRORS_TOTAL.inc();
+        }
+
+        result
+    }
+
+    /// プールの状態（接続数・アイドル接続数）をメトリクスに記録
+    pub fn record_state(&self) {
+        let state = self.inner.state();
+
+        DB_POOL_CONNECTIONS.set(state.connections as i64);
+        DB_POOL_IDLE_CONNECTIONS.set(state.idle_connections as i64);
+        DB_POOL_MAX_CONNECTIONS.set(self.max_size as i64);
+    }
+}
+
+pub async fn get_db_pool (settings: &DatabaseSettings) -> DbPool {
+    // 証明書は起動時の設定の検証で読み込み済み
+    let tls = make_tls_connector(settings).expect("DB の TLS 設定が不正です");
+
+    DbPool::build(settings, tls).await.expect("DB接続プールの作成に失敗しました")
 }
\ No newline at end of file
@@ -0,0 +1,288 @@
+//! DBマイグレーション
+//!
+//! `migrations/` 配下の SQL をバイナリに埋め込み、バージョン順に適用する
+//! 適用済みのバージョンとチェックサムは `schema_migrations` テーブルで管理
+//!
+//! 複数のインスタンスが同時に起動しても二重に適用されないよう、
+//! 実行中は PostgreSQL のアドバイザリロックを取得する
+//!
+//! # 関数
+//!
+//! * `run_pending`   - 未適用のマイグレーションを全て適用
+//! * `rollback_last` - 最後に適用したマイグレーションを1件取り消す
+//! * `status`        - 各マイグレーションの適用状況を取得
+//! * `pending`       - 未適用のマイグレーションを取得（ヘルスチェック用。テーブルの作成・ロックはしない）
+
+use crate::infrastructure::db::connection::DbPool;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+use tokio_postgres::Client;
+
+use crate::application::errors::migration_error::MigrationError;
+use crate::{app_log, info_log, success_log};
+
+/// マイグレーション実行中に取得するアドバイザリロックのキー
+const MIGRATION_LOCK_KEY: i64 = 0x6761_6d65_726e_6167;
+
+/// マイグレーション
+///
+/// * `version` - バージョン（昇順に適用）
+/// * `name`    - 名前
+/// * `up`      - 適用する SQL
+/// * `down`    - 取り消す SQL
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// 適用する SQL の SHA-256 チェックサム
+    pub fn checksum(&self) -> String {
+        format!("{:x}", Sha256::digest(self.up.as_bytes()))
+    }
+}
+
+macro_rules! migration {
+    ($version:expr, $name:expr) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/", $name, ".up.sql")),
+            down: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+/// 埋め込み済みのマイグレーション（バージョン昇順）
+pub static MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_initial_schema"),
+    migration!(2, "0002_task_user_index"),
+    migration!(3, "0003_verification_token_index"),
+    migration!(4, "0004_password_reset"),
+    migration!(5, "0005_sessions"),
+    migration!(6, "0006_roles"),
+    migration!(7, "0007_user_locale"),
+    migration!(8, "0008_email_outbox"),
+    migration!(9, "0009_login_protection"),
+    migration!(10, "0010_mfa"),
+    migration!(11, "0011_oidc_accounts"),
+    migration!(12, "0012_guest_users"),
+    migration!(13, "0013_email_outbox_retention"),
+];
+
+/// マイグレーションの適用状況
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: &'static str,
+    pub applied: bool,
+}
+
+/// `schema_migrations` に記録された適用済みマイグレーション
+struct AppliedMigration {
+    version: i64,
+    name: String,
+    checksum: String,
+}
+
+/// 未適用のマイグレーションを全て適用
+///
+/// # 戻り値
+///
+/// * `Result<Vec<i64>, MigrationError>` - 適用したバージョン
+pub async fn run_pending(pool: &DbPool) -> Result<Vec<i64>, MigrationError> {
+    let mut conn = pool.get().await?;
+
+    lock(&conn).await?;
+    let result = apply_pending(&mut conn).await;
+    unlock(&conn).await?;
+
+    result
+}
+
+/// 最後に適用したマイグレーションを1件取り消す
+///
+/// # 戻り値
+///
+/// * `Result<Option<i64>, MigrationError>` - 取り消したバージョン（適用済みがない場合は `None`）
+pub async fn rollback_last(pool: &DbPool) -> Result<Option<i64>, MigrationError> {
+    let mut conn = pool.get().await?;
+
+    lock(&conn).await?;
+    let result = revert_last(&mut conn).await;
+    unlock(&conn).await?;
+
+    result
+}
+
+/// 各マイグレーションの適用状況を取得
+///
+/// 適用済みのマイグレーションのチェックサムも検証する
+pub async fn status(pool: &DbPool) -> Result<Vec<MigrationStatus>, MigrationError> {
+    let conn = pool.get().await?;
+
+    create_table(&conn).await?;
+    let applied = verify_applied(&conn).await?;
+
+    Ok(MIGRATIONS.iter().map(|m| MigrationStatus {
+        version: m.version,
+        name: m.name,
+        applied: applied.iter().any(|a| a.version == m.version),
+    }).collect())
+}
+
+/// 未適用のマイグレーションを取得
+///
+/// ヘルスチェックから頻繁に呼ばれるため、`schema_migrations` の作成やロックの取得はしない
+/// 適用済みのマイグレーションのチェックサムは検証する
+///
+/// # 戻り値
+///
+/// * `Result<Vec<&'static str>, MigrationError>` - 未適用のマイグレーションの名前
+pub async fn pending(pool: &DbPool) -> Result<Vec<&'static str>, MigrationError> {
+    let conn = pool.get().await?;
+
+    let exists: bool = conn
+        .query_one("SELECT to_regclass('schema_migrations') IS NOT NULL", &[])
+        .await?
+        .get(0);
+    let applied = if exists { verify_applied(&conn).await? } else { Vec::new() };
+
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .map(|m| m.name)
+        .collect())
+}
+
+/// アドバイザリロックを取得（他のインスタンスが実行中の場合は解放まで待機）
+async fn lock(client: &Client) -> Result<(), MigrationError> {
+    client.execute("SELECT pg_advisory_lock($1)", &[&MIGRATION_LOCK_KEY]).await?;
+    Ok(())
+}
+
+/// アドバイザリロックを解放
+async fn unlock(client: &Client) -> Result<(), MigrationError> {
+    client.execute("SELECT pg_advisory_unlock($1)", &[&MIGRATION_LOCK_KEY]).await?;
+    Ok(())
+}
+
+async fn apply_pending(client: &mut Client) -> Result<Vec<i64>, MigrationError> {
+    create_table(client).await?;
+    let applied = verify_applied(client).await?;
+    let mut applied_versions = Vec::new();
+
+    for migration in MIGRATIONS.iter().filter(|m| !applied.iter().any(|a| a.version == m.version)) {
+        let started = Instant::now();
+        let tx = client.transaction().await?;
+
+        tx.batch_execute(migration.up).await?;
+        tx.execute(
+            r#"
+                INSERT INTO schema_migrations (
+                    version,
+                    name,
+                    checksum,
+                    execution_ms
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4
+                )
+            "#,
+            &[&migration.version, &migration.name, &migration.checksum(), &(started.elapsed().as_millis() as i64)]
+        ).await?;
+        tx.commit().await?;
+
+        success_log!("[migration] - [run_pending] applied {} ({} ms)", migration.name, started.elapsed().as_millis());
+        applied_versions.push(migration.version);
+    }
+
+    if applied_versions.is_empty() {
+        info_log!("[migration] - [run_pending] schema is up to date");
+    }
+
+    Ok(applied_versions)
+}
+
+async fn revert_last(client: &mut Client) -> Result<Option<i64>, MigrationError> {
+    create_table(client).await?;
+    let applied = verify_applied(client).await?;
+
+    let Some(last) = applied.last() else {
+        info_log!("[migration] - [rollback_last] no migration to roll back");
+        return Ok(None);
+    };
+
+    let migration = find(last.version)?;
+    let tx = client.transaction().await?;
+
+    tx.batch_execute(migration.down).await?;
+    tx.execute("DELETE FROM schema_migrations WHERE version = $1", &[&migration.version]).await?;
+    tx.commit().await?;
+
+    success_log!("[migration] - [rollback_last] rolled back {}", migration.name);
+    Ok(Some(migration.version))
+}
+
+/// `schema_migrations` テーブルを作成
+async fn create_table(client: &Client) -> Result<(), MigrationError> {
+    client.batch_execute(
+        r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                execution_ms BIGINT NOT NULL DEFAULT 0,
+                applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+        "#
+    ).await?;
+
+    Ok(())
+}
+
+/// 適用済みのマイグレーションを取得し、埋め込み済みの内容と一致するか検証
+///
+/// * 適用後に SQL が変更されている場合は `ChecksumMismatch`
+/// * アプリケーションが知らないバージョンが適用されている場合は `UnknownVersion`
+async fn verify_applied(client: &Client) -> Result<Vec<AppliedMigration>, MigrationError> {
+    let rows = client.query(
+        r#"
+            SELECT
+                version,
+                name,
+                checksum
+            FROM
+                schema_migrations
+            ORDER BY
+                version
+        "#,
+        &[]
+    ).await?;
+
+    let applied: Vec<AppliedMigration> = rows.iter().map(|row| AppliedMigration {
+        version: row.get("version"),
+        name: row.get("name"),
+        checksum: row.get("checksum"),
+    }).collect();
+
+    for a in &applied {
+        let migration = find(a.version)?;
+        if migration.checksum() != a.checksum {
+            return Err(MigrationError::ChecksumMismatch { version: a.version, name: a.name.clone() });
+        }
+    }
+
+    Ok(applied)
+}
+
+fn find(version: i64) -> Result<&'static Migration, MigrationError> {
+    MIGRATIONS
+        .iter()
+        .find(|m| m.version == version)
+        .ok_or(MigrationError::UnknownVersion(version))
+}
@@ -0,0 +1,2 @@
+pub mod redacting_writer;
+pub mod rolling_file;
\ No newline at end of file
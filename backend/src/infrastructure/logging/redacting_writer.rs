@@ -0,0 +1,32 @@
+//! # 秘匿情報をマスクする出力先
+//!
+//! 出力先（標準出力・ログファイル）に書き込む前に `redact` で秘匿情報をマスクする
+//! `tracing` のイベントは1行ずつ書き込まれるため、行単位でマスクできる
+
+use std::io::{self, Write};
+
+use crate::application::helpers::redact::redact;
+
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        RedactingWriter { inner }
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let line = String::from_utf8_lossy(buf);
+        self.inner.write_all(redact(&line).as_bytes())?;
+
+        // 呼び出し元には受け取ったバイト数を返す（マスクで長さが変わっても再送信させない）
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
\ No newline at end of file
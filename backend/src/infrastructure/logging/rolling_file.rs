@@ -0,0 +1,164 @@
+//! # ログファイルのローテーション
+//!
+//! 出力中のファイル（`actix.log`）が指定サイズを超えた場合、または日付・時刻が変わった場合に
+//! `actix.{YYYYMMDD-HHMMSS}-{連番}.log` へ名前を変更し、新しいファイルに出力する
+//! ローテーション済みのファイルは新しいものから `max_files` 件を保持し、それより古いものは削除する
+//!
+//! 書き込みは `tracing_appender::non_blocking` のワーカースレッドから行うため、リクエストの処理を待たせない
+//!
+//! ## メソッド
+//!
+//! - `open`:  ログファイルを開く（ディレクトリがない場合は作成）
+//! - `write`: ローテーションが必要な場合はローテーションしてから書き込む
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use chrono::{DateTime, Local};
+
+use crate::infrastructure::config::log_config::{LogFileConfig, LogRotation};
+
+pub struct RollingFileWriter {
+    config: LogFileConfig,
+    file: File,
+    size: u64,
+    period: Option<String>,
+    last_archive: Option<(String, u32)>,
+}
+
+impl RollingFileWriter {
+    /// ログファイルを開く
+    ///
+    /// 既存のファイルが前の期間に書き込まれたものである場合は、すぐにローテーションする
+    ///
+    /// # 引数
+    ///
+    /// * `config` - ログファイルの設定
+    pub fn open(config: LogFileConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+
+        let path = config.dir.join(&config.file_name);
+        let (size, period) = match fs::metadata(&path) {
+            Ok(metadata) => {
+                let modified: DateTime<Local> = metadata.modified()?.into();
+                (metadata.len(), period_of(config.rotation, modified))
+            }
+            Err(_) => (0, period_of(config.rotation, Local::now())),
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut writer = RollingFileWriter { config, file, size, period, last_archive: None };
+
+        if writer.period != period_of(writer.config.rotation, Local::now()) {
+            writer.rotate()?;
+        }
+
+        Ok(writer)
+    }
+
+    /// 出力中のファイルのパス
+    fn active_path(&self) -> PathBuf {
+        self.config.dir.join(&self.config.file_name)
+    }
+
+    /// ファイル名の拡張子の前に付ける区切り（`actix.log` の場合は `actix.` と `.log`）
+    fn name_parts(&self) -> (&str, &str) {
+        match self.config.file_name.rsplit_once('.') {
+            Some((stem, ext)) => (stem, ext),
+            None => (self.config.file_name.as_str(), ""),
+        }
+    }
+
+    /// 出力中のファイルをローテーション済みにし、新しいファイルを開く
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let (stem, ext) = self.name_parts();
+        let timestamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        // 同じ秒に複数回ローテーションした場合も名前順が日時順になるよう、前回より大きい連番を付ける
+        let start = match &self.last_archive {
+            Some((last_timestamp, last_index)) if *last_timestamp == timestamp => last_index + 1,
+            _ => 0,
+        };
+        let (index, archive) = (start..)
+            .map(|index| (index, self.config.dir.join(format!("{}.{}-{:03}.{}", stem, timestamp, index, ext))))
+            .find(|(_, path)| !path.exists())
+            .unwrap_or_default();
+
+        if self.size > 0 {
+            fs::rename(self.active_path(), &archive)?;
+            self.last_archive = Some((timestamp, index));
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(self.active_path())?;
+        self.size = 0;
+        self.period = period_of(self.config.rotation, Local::now());
+
+        self.prune()
+    }
+
+    /// 保持するファイル数を超えたローテーション済みのファイルを削除
+    ///
+    /// ファイル名の日時の順に並ぶため、名前順で古いものから削除する
+    fn prune(&self) -> io::Result<()> {
+        let (stem, ext) = self.name_parts();
+        let prefix = format!("{}.", stem);
+        let suffix = format!(".{}", ext);
+
+        let mut archives: Vec<PathBuf> = fs::read_dir(&self.config.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map(|name| name != self.config.file_name && name.starts_with(&prefix) && name.ends_with(&suffix))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        if archives.len() <= self.config.max_files {
+            return Ok(());
+        }
+
+        archives.sort();
+        let excess = archives.len() - self.config.max_files;
+        for path in archives.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// ローテーションが必要かどうか
+    fn should_rotate(&self, incoming: usize) -> bool {
+        let over_size = self.config.max_bytes > 0 && self.size > 0 && self.size + incoming as u64 > self.config.max_bytes;
+
+        over_size || self.period != period_of(self.config.rotation, Local::now())
+    }
+}
+
+impl Write for RollingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// ローテーションの期間（同じ期間のログは同じファイルに出力する）
+fn period_of(rotation: LogRotation, time: DateTime<Local>) -> Option<String> {
+    match rotation {
+        LogRotation::Daily => Some(time.format("%Y%m%d").to_string()),
+        LogRotation::Hourly => Some(time.format("%Y%m%d%H").to_string()),
+        LogRotation::Never => None,
+    }
+}
\ No newline at end of file
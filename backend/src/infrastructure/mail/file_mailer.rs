@@ -0,0 +1,56 @@
+//! # ファイル出力
+//!
+//! メールを送信せず、`.eml` ファイルとしてディレクトリに出力（開発用）
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::{AsyncFileTransport, AsyncTransport, Tokio1Executor};
+
+use crate::application::errors::mail_error::MailError;
+use crate::application::mail::mailer::{Mail, Mailer};
+use crate::{app_log, info_log};
+
+pub struct FileMailer {
+    transport: AsyncFileTransport<Tokio1Executor>,
+    dir: PathBuf,
+    from: Mailbox,
+}
+
+impl FileMailer {
+    /// 出力先ディレクトリを作成
+    ///
+    /// # 引数
+    ///
+    /// * `dir`  - 出力先ディレクトリ
+    /// * `from` - 送信元
+    pub fn new(dir: &Path, from: Mailbox) -> Result<Self, MailError> {
+        fs::create_dir_all(dir)
+            .map_err(|err| MailError::Config(format!("{} を作成できません: {}", dir.display(), err)))?;
+
+        Ok(FileMailer { transport: AsyncFileTransport::new(dir), dir: dir.to_path_buf(), from })
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        let id = self.transport.send(mail.to_message(&self.from)?).await?;
+
+        info_log!("[file_mailer] - [send] message: mail written, id = {}", id);
+        Ok(())
+    }
+
+    /// 出力先ディレクトリが存在し、書き込みできるか確認
+    async fn check(&self) -> Result<(), MailError> {
+        let metadata = fs::metadata(&self.dir)
+            .map_err(|err| MailError::Unavailable(format!("{}: {}", self.dir.display(), err)))?;
+
+        if !metadata.is_dir() || metadata.permissions().readonly() {
+            return Err(MailError::Unavailable(format!("{} is not a writable directory", self.dir.display())));
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+//! # ログ出力
+//!
+//! メールを送信せず、件名とテキスト形式の本文をログに出力（開発用）
+
+use async_trait::async_trait;
+
+use crate::application::errors::mail_error::MailError;
+use crate::application::mail::mailer::{Mail, Mailer};
+use crate::{app_log, info_log};
+
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        info_log!("[log_mailer] - [send] message: to = {}, subject = {}\n{}", mail.to, mail.subject, mail.text);
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+//! # メモリ
+//!
+//! 送信したメールをメモリに保存（テスト用）
+
+use std::sync::Mutex;
+use async_trait::async_trait;
+
+use crate::application::errors::mail_error::MailError;
+use crate::application::mail::mailer::{Mail, Mailer};
+
+#[derive(Default)]
+pub struct MemoryMailer {
+    sent: Mutex<Vec<Mail>>,
+}
+
+impl MemoryMailer {
+    /// 送信したメール（送信順）
+    pub fn sent(&self) -> Vec<Mail> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        self.sent.lock().unwrap().push(mail.clone());
+
+        Ok(())
+    }
+}
\ No newline at end of file
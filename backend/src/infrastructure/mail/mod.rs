@@ -0,0 +1,6 @@
+pub mod file_mailer;
+pub mod log_mailer;
+#[cfg(test)]
+pub mod memory_mailer;
+pub mod smtp_mailer;
+pub mod transport;
\ No newline at end of file
@@ -0,0 +1,68 @@
+//! # SMTP
+//!
+//! SMTP サーバーでメールを非同期に送信
+//! 接続はプールし、送信ごとに再接続しない
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+use crate::application::errors::mail_error::MailError;
+use crate::application::mail::mailer::{Mail, Mailer};
+use crate::infrastructure::config::mail_config::SmtpTls;
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// SMTP サーバーの設定から作成
+    ///
+    /// # 引数
+    ///
+    /// * `server`      - SMTP サーバー
+    /// * `port`        - SMTP ポート
+    /// * `tls`         - 暗号化方式
+    /// * `credentials` - SMTP 認証のユーザー名とパスワード
+    /// * `from`        - 送信元
+    pub fn new(
+        server: &str,
+        port: u16,
+        tls: SmtpTls,
+        credentials: Option<(String, String)>,
+        from: Mailbox,
+    ) -> Result<Self, MailError> {
+        let mut builder = match tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(server)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(server)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(server),
+        }
+        .port(port);
+
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(SmtpMailer { transport: builder.build(), from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        self.transport.send(mail.to_message(&self.from)?).await?;
+
+        Ok(())
+    }
+
+    /// SMTP サーバーに接続し、`NOOP` に応答するか確認
+    async fn check(&self) -> Result<(), MailError> {
+        if !self.transport.test_connection().await? {
+            return Err(MailError::Unavailable("SMTP server did not respond to NOOP".to_string()));
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
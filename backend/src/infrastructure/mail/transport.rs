@@ -0,0 +1,35 @@
+//! # メールの送信方法
+//!
+//! ## 関数
+//!
+//! - `create_mailer`: 設定の送信方法で `Mailer` を作成
+
+use std::sync::Arc;
+
+use crate::application::errors::mail_error::MailError;
+use crate::application::types::di_type::MailerArc;
+use crate::infrastructure::config::mail_config::{MailConfig, MailTransport};
+use crate::infrastructure::mail::file_mailer::FileMailer;
+use crate::infrastructure::mail::log_mailer::LogMailer;
+use crate::infrastructure::mail::smtp_mailer::SmtpMailer;
+
+/// 設定の送信方法で `Mailer` を作成
+///
+/// # 引数
+///
+/// * `config` - メール送信設定
+///
+/// # 戻り値
+///
+/// * `MailerArc` - メール送信
+pub fn create_mailer(config: &MailConfig) -> Result<MailerArc, MailError> {
+    let mailer: MailerArc = match &config.transport {
+        MailTransport::Smtp { server, port, tls, credentials } => {
+            Arc::new(SmtpMailer::new(server, *port, *tls, credentials.clone(), config.from.clone())?)
+        }
+        MailTransport::File { dir } => Arc::new(FileMailer::new(dir, config.from.clone())?),
+        MailTransport::Log => Arc::new(LogMailer),
+    };
+
+    Ok(mailer)
+}
\ No newline at end of file
@@ -1,3 +1,5 @@
 pub mod config;
 pub mod db;
+pub mod logging;
+pub mod mail;
 pub mod repositories;
\ No newline at end of file
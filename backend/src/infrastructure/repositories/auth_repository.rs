@@ -4,33 +4,62 @@
 //! 
 //! ## メソッド
 //! 
-//! `guest_login`             - ゲストログイン
-//! `signup`                  - 新規登録
-//! `get_user_by_email`       - ユーザー検索
+//! `create_guest`                    - ゲストユーザーの作成
+//! `convert_guest`                   - ゲストのアカウント登録（セッションの失効を含む）
+//! `delete_expired_guests`           - 有効期限切れのゲストの削除
+//! `signup`                          - 新規登録（メール認証トークンの保存を含む）
+//! `get_user_by_email`               - ユーザー検索
+//! `get_verification_user`           - メール認証状態の取得
+//! `get_password_hash`               - パスワードのハッシュ（本人確認に使用）
+//! `has_password`                    - ユーザーがパスワードを設定しているか
+//! `update_password_hash`            - パスワードのハッシュを作り直したハッシュに置き換え
+//! `create_verification_token`       - メール認証トークンの保存
+//! `get_last_verification_sent_at`   - 最後にメール認証トークンを発行した日時
+//! `verify_email_token`              - メール認証トークンの検証
+//! `create_password_reset_token`     - パスワードリセットトークンの保存
+//! `get_last_password_reset_sent_at` - 最後にパスワードリセットトークンを発行した日時
+//! `reset_password`                  - パスワードリセットトークンの検証とパスワード更新
 
 use async_trait::async_trait;
-use tokio_postgres::NoTls;
-use bb8_postgres::{PostgresConnectionManager, bb8::Pool};
+use chrono::{DateTime, Utc};
+use crate::infrastructure::db::connection::DbPool;
 use crate::{
-    application::errors::auth_error::AuthError, domain::{entities::auth::{LoginSelectResult, SignupInsertResult}, repositories::auth_repository::AuthRepository}, app_log, info_log
+    application::errors::auth_error::AuthError,
+    application::mail::mailer::Mail,
+    domain::{
+        entities::auth::{LoginSelectResult, SignupInsertResult, VerificationSelectResult},
+        repositories::auth_repository::AuthRepository
+    },
+    infrastructure::repositories::outbox_repository::enqueue_mail,
+    app_log, info_log
 };
 
 pub struct AuthRepositoryImpl {
-    pool: Pool<PostgresConnectionManager<NoTls>>
+    pool: DbPool
 }
 
 impl AuthRepositoryImpl {
-    pub fn new(pool: Pool<PostgresConnectionManager<NoTls>>) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         AuthRepositoryImpl { pool }
     }
 }
 
 #[async_trait]
 impl AuthRepository for AuthRepositoryImpl {
-    async fn register_user(&self, name: &str, email: &str, password: &str) -> Result<SignupInsertResult, AuthError> {
-        let conn = self.pool.get().await?;
+    /// ユーザーとメール認証トークンを作成し、認証メールをメール送信キューに登録する
+    async fn register_user(
+        &self,
+        name: &str,
+        email: &str,
+        password: &str,
+        verification_token_hash: &str,
+        expires_at: DateTime<Utc>,
+        verification_mail: &Mail,
+    ) -> Result<SignupInsertResult, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
 
-        let row = conn.query_one(
+        let row = tx.query_one(
             r#"
                 INSERT INTO users (
                     name,
@@ -46,6 +75,63 @@ impl AuthRepository for AuthRepositoryImpl {
             &[&name, &email, &password]
         ).await?;
 
+        let user_id: i32 = row.get("id");
+
+        tx.execute(
+            r#"
+                INSERT INTO tokens (
+                    user_id,
+                    verification_token,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3
+                );
+            "#,
+            &[&user_id, &verification_token_hash, &expires_at]
+        ).await?;
+
+        enqueue_mail(&tx, verification_mail, Some(expires_at)).await?;
+        tx.commit().await?;
+
+        Ok(SignupInsertResult {
+            id: user_id,
+            name: row.get("name"),
+            email: row.get("email"),
+            role: row.get("role"),
+            photo: row.get("photo"),
+            bio: row.get("bio"),
+            is_verified: row.get("is_verified"),
+        })
+    }
+
+    /// パスワードでログインできないよう、`password` にはランダムな値のハッシュを受け取る
+    async fn create_guest(&self, name: &str, email: &str, password: &str, expires_at: DateTime<Utc>) -> Result<SignupInsertResult, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_one(
+            r#"
+                INSERT INTO users (
+                    name,
+                    email,
+                    password,
+                    role,
+                    has_password,
+                    guest_expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    'guest',
+                    FALSE,
+                    $4
+                )
+                RETURNING *;
+            "#,
+            &[&name, &email, &password, &expires_at]
+        ).await?;
+
         Ok(SignupInsertResult {
             id: row.get("id"),
             name: row.get("name"),
@@ -57,6 +143,84 @@ impl AuthRepository for AuthRepositoryImpl {
         })
     }
 
+    /// 有効期限内のゲストに名前・メールアドレス・パスワードを設定し、一般ユーザーにする
+    ///
+    /// ユーザー ID は変わらないため、ゲストで作成したデータはそのまま引き継ぐ。
+    /// ゲストのセッションは全て失効させる。ゲストではない場合は `None` を返す
+    async fn convert_guest(&self, user_id: i32, name: &str, email: &str, password: &str) -> Result<Option<SignupInsertResult>, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let row_opt = tx.query_opt(
+            r#"
+                UPDATE
+                    users
+                SET
+                    name = $2,
+                    email = $3,
+                    password = $4,
+                    role = 'user',
+                    has_password = TRUE,
+                    guest_expires_at = NULL,
+                    password_changed_at = CURRENT_TIMESTAMP,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                    AND role = 'guest'
+                    AND guest_expires_at > CURRENT_TIMESTAMP
+                RETURNING *;
+            "#,
+            &[&user_id, &name, &email, &password]
+        ).await?;
+
+        let Some(row) = row_opt else {
+            return Ok(None);
+        };
+
+        tx.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_id = $1
+                    AND revoked_at IS NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(SignupInsertResult {
+            id: user_id,
+            name: row.get("name"),
+            email: row.get("email"),
+            role: row.get("role"),
+            photo: row.get("photo"),
+            bio: row.get("bio"),
+            is_verified: row.get("is_verified"),
+        }))
+    }
+
+    /// タスク・セッションなど、ゲストのデータは外部キーの `ON DELETE CASCADE` で合わせて削除する
+    async fn delete_expired_guests(&self) -> Result<u64, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let deleted = conn.execute(
+            r#"
+                DELETE FROM
+                    users
+                WHERE
+                    role = 'guest'
+                    AND guest_expires_at <= CURRENT_TIMESTAMP;
+            "#,
+            &[]
+        ).await?;
+
+        Ok(deleted)
+    }
+
     async fn get_user_by_email(&self, email: &str) -> Result<Option<LoginSelectResult>, AuthError> {
         info_log!("[auth_repository] - [get_user_by_email] get_user_by_email called");
 
@@ -68,10 +232,12 @@ impl AuthRepository for AuthRepositoryImpl {
                     id,
                     name,
                     email,
+                    password,
                     role,
                     photo,
                     bio,
-                    is_verified
+                    is_verified,
+                    locale
                 FROM
                     users
                 WHERE
@@ -91,10 +257,333 @@ impl AuthRepository for AuthRepositoryImpl {
                     photo: row.get("photo"),
                     bio: row.get("bio"),
                     is_verified: row.get("is_verified"),
+                    locale: row.get("locale"),
                 }))
             },
             Ok(None) => Ok(None),
-            Err(err) => Err(AuthError::DatabaseError(err.into())),
+            Err(err) => Err(AuthError::DatabaseError(err)),
         }
     }
+    async fn get_verification_user(&self, user_id: i32) -> Result<Option<VerificationSelectResult>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    id,
+                    email,
+                    role,
+                    is_verified,
+                    locale
+                FROM
+                    users
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| VerificationSelectResult {
+            id: row.get("id"),
+            email: row.get("email"),
+            role: row.get("role"),
+            is_verified: row.get::<_, Option<bool>>("is_verified").unwrap_or(false),
+            locale: row.get("locale"),
+        }))
+    }
+
+    async fn get_password_hash(&self, user_id: i32) -> Result<Option<String>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    password
+                FROM
+                    users
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| row.get("password")))
+    }
+
+    async fn has_password(&self, user_id: i32) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    has_password
+                FROM
+                    users
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        row_opt.map(|row| row.get("has_password")).ok_or(AuthError::UserNotFound)
+    }
+
+    /// 読み込んだ後にパスワードが変更されていた場合は置き換えずに `false` を返す
+    ///
+    /// パスワードは変わらないため、`password_changed_at` は更新せず、発行済みの JWT も無効にしない
+    async fn update_password_hash(&self, user_id: i32, current_hash: &str, new_hash: &str) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let updated = conn.execute(
+            r#"
+                UPDATE
+                    users
+                SET
+                    password = $3
+                WHERE
+                    id = $1
+                    AND password = $2;
+            "#,
+            &[&user_id, &current_hash, &new_hash]
+        ).await?;
+
+        Ok(updated > 0)
+    }
+
+    /// 認証メールをメール送信キューに登録する
+    async fn create_verification_token(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        mail: &Mail,
+    ) -> Result<(), AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            r#"
+                INSERT INTO tokens (
+                    user_id,
+                    verification_token,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3
+                );
+            "#,
+            &[&user_id, &token_hash, &expires_at]
+        ).await?;
+
+        enqueue_mail(&tx, mail, Some(expires_at)).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_last_verification_sent_at(&self, user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_one(
+            r#"
+                SELECT
+                    MAX(created_at) AS sent_at
+                FROM
+                    tokens
+                WHERE
+                    user_id = $1
+                    AND verification_token IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row.get("sent_at"))
+    }
+
+    /// 有効期限内のトークンであれば使用済みとして削除し、ユーザーを認証済みにする
+    ///
+    /// 同じユーザーに発行した他の認証トークンも合わせて削除する
+    async fn verify_email_token(&self, token_hash: &str) -> Result<Option<i32>, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let row_opt = tx.query_opt(
+            r#"
+                DELETE FROM
+                    tokens
+                WHERE
+                    verification_token = $1
+                    AND expires_at > CURRENT_TIMESTAMP
+                RETURNING
+                    user_id;
+            "#,
+            &[&token_hash]
+        ).await?;
+
+        let Some(row) = row_opt else {
+            return Ok(None);
+        };
+        let user_id: i32 = row.get("user_id");
+
+        tx.execute(
+            r#"
+                UPDATE
+                    users
+                SET
+                    is_verified = TRUE,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    tokens
+                WHERE
+                    user_id = $1
+                    AND verification_token IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user_id))
+    }
+    /// 未使用のリセットトークンは削除し、最新のトークンのみ有効にする
+    async fn create_password_reset_token(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        mail: &Mail,
+    ) -> Result<(), AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    tokens
+                WHERE
+                    user_id = $1
+                    AND password_reset_token IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.execute(
+            r#"
+                INSERT INTO tokens (
+                    user_id,
+                    password_reset_token,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3
+                );
+            "#,
+            &[&user_id, &token_hash, &expires_at]
+        ).await?;
+
+        enqueue_mail(&tx, mail, Some(expires_at)).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 最後にパスワードリセットトークンを発行した日時
+    async fn get_last_password_reset_sent_at(&self, user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_one(
+            r#"
+                SELECT
+                    MAX(created_at) AS sent_at
+                FROM
+                    tokens
+                WHERE
+                    user_id = $1
+                    AND password_reset_token IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row.get("sent_at"))
+    }
+
+    /// 有効期限内のトークンであれば使用済みとして削除し、パスワードを更新する
+    ///
+    /// パスワード変更日時を更新し、全てのセッションとそれ以前に発行した JWT を無効にする
+    async fn reset_password(&self, token_hash: &str, password_hash: &str) -> Result<Option<i32>, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let row_opt = tx.query_opt(
+            r#"
+                DELETE FROM
+                    tokens
+                WHERE
+                    password_reset_token = $1
+                    AND expires_at > CURRENT_TIMESTAMP
+                RETURNING
+                    user_id;
+            "#,
+            &[&token_hash]
+        ).await?;
+
+        let Some(row) = row_opt else {
+            return Ok(None);
+        };
+        let user_id: i32 = row.get("user_id");
+
+        tx.execute(
+            r#"
+                UPDATE
+                    users
+                SET
+                    password = $2,
+                    password_changed_at = CURRENT_TIMESTAMP,
+                    has_password = TRUE,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id, &password_hash]
+        ).await?;
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    tokens
+                WHERE
+                    user_id = $1
+                    AND password_reset_token IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        // 全端末のセッションを失効
+        tx.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_id = $1
+                    AND revoked_at IS NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user_id))
+    }
 }
\ No newline at end of file
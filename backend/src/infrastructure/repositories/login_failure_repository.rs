@@ -0,0 +1,146 @@
+//! # ログインの失敗の履歴リポジトリ
+//!
+//! `login_failures` テーブルに失敗を記録し、管理者が確認する
+//!
+//! ## メソッド
+//!
+//! `record_failure` - 失敗を記録
+//! `get_failures`   - 失敗の履歴（管理者）
+//! `prune`          - 保存期間を過ぎた履歴を削除
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::Row;
+use crate::infrastructure::db::connection::DbPool;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{
+        entities::login_protection::{LoginFailureListQuery, LoginFailureListResponse, LoginFailureResponse, NewLoginFailure},
+        entities::pagination::Pagination,
+        enums::login_failure::LoginFailureReason,
+        repositories::login_failure_repository::LoginFailureRepository
+    }
+};
+
+pub struct LoginFailureRepositoryImpl {
+    pool: DbPool
+}
+
+impl LoginFailureRepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        LoginFailureRepositoryImpl { pool }
+    }
+}
+
+/// DB の行を履歴の1件に変換
+fn row_to_failure(row: &Row) -> LoginFailureResponse {
+    LoginFailureResponse {
+        id: row.get("id"),
+        email: row.get("email"),
+        user_id: row.get("user_id"),
+        ip_address: row.get("ip_address"),
+        user_agent: row.get("user_agent"),
+        reason: row.get::<_, String>("reason").parse().unwrap_or(LoginFailureReason::WrongPassword),
+        locked_until: row.get("locked_until"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl LoginFailureRepository for LoginFailureRepositoryImpl {
+    async fn record_failure(&self, failure: &NewLoginFailure<'_>) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                INSERT INTO login_failures (
+                    email,
+                    user_id,
+                    ip_address,
+                    user_agent,
+                    reason,
+                    locked_until
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6
+                );
+            "#,
+            &[
+                &failure.attempt.email,
+                &failure.user_id,
+                &failure.attempt.ip_address,
+                &failure.attempt.user_agent,
+                &failure.reason.as_str(),
+                &failure.locked_until
+            ]
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 新しい順に取得する
+    async fn get_failures(&self, query: &LoginFailureListQuery, pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError> {
+        let conn = self.pool.get().await?;
+        let email = query.email.as_ref().map(|email| email.trim().to_lowercase());
+        let per_page = pagination.per_page();
+
+        let total: i64 = conn
+            .query_one(
+                r#"
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        login_failures
+                    WHERE
+                        ($1::varchar IS NULL OR email = $1)
+                        AND ($2::text IS NULL OR ip_address = $2);
+                "#,
+                &[&email, &query.ip_address]
+            )
+            .await?
+            .get(0);
+
+        let rows = conn.query(
+            r#"
+                SELECT
+                    id,
+                    email,
+                    user_id,
+                    ip_address,
+                    user_agent,
+                    reason,
+                    locked_until,
+                    created_at
+                FROM
+                    login_failures
+                WHERE
+                    ($1::varchar IS NULL OR email = $1)
+                    AND ($2::text IS NULL OR ip_address = $2)
+                ORDER BY
+                    id DESC
+                LIMIT $3
+                OFFSET $4;
+            "#,
+            &[&email, &query.ip_address, &per_page, &pagination.offset()]
+        ).await?;
+
+        Ok(LoginFailureListResponse {
+            entries: rows.iter().map(row_to_failure).collect(),
+            total,
+            page: pagination.page(),
+            per_page,
+        })
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let deleted = conn.execute("DELETE FROM login_failures WHERE created_at < $1;", &[&before]).await?;
+
+        Ok(deleted)
+    }
+}
\ No newline at end of file
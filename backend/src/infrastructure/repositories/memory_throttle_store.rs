@@ -0,0 +1,73 @@
+//! # ログイン試行のカウンター（メモリ）
+//!
+//! プロセスのメモリに保存する。再起動でリセットされ、複数のプロセスでは共有しない
+//! 複数のプロセスで実行する場合は `PgThrottleStore` を使用する
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{entities::login_protection::ThrottleCounter, repositories::throttle_store::ThrottleStore}
+};
+
+#[derive(Default)]
+pub struct MemoryThrottleStore {
+    counters: Mutex<HashMap<String, ThrottleCounter>>
+}
+
+impl MemoryThrottleStore {
+    pub fn new() -> Self {
+        MemoryThrottleStore::default()
+    }
+}
+
+#[async_trait]
+impl ThrottleStore for MemoryThrottleStore {
+    async fn increment(&self, key: &str, window: Duration) -> Result<ThrottleCounter, AuthError> {
+        let now = Utc::now();
+        let mut counters = self.counters.lock().unwrap();
+
+        let counter = counters.entry(key.to_string()).or_insert(ThrottleCounter { count: 0, window_started_at: now, locked_until: None });
+        if counter.window_started_at + window <= now {
+            counter.count = 0;
+            counter.window_started_at = now;
+        }
+        counter.count += 1;
+
+        Ok(counter.clone())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<ThrottleCounter>, AuthError> {
+        Ok(self.counters.lock().unwrap().get(key).cloned())
+    }
+
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut counters = self.counters.lock().unwrap();
+
+        counters.entry(key.to_string())
+            .or_insert(ThrottleCounter { count: 0, window_started_at: Utc::now(), locked_until: None })
+            .locked_until = Some(until);
+
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), AuthError> {
+        self.counters.lock().unwrap().remove(key);
+
+        Ok(())
+    }
+
+    async fn prune(&self, window_started_before: DateTime<Utc>) -> Result<u64, AuthError> {
+        let now = Utc::now();
+        let mut counters = self.counters.lock().unwrap();
+        let before = counters.len();
+
+        counters.retain(|_, counter| {
+            counter.window_started_at >= window_started_before || counter.locked_until.is_some_and(|until| until > now)
+        });
+
+        Ok((before - counters.len()) as u64)
+    }
+}
\ No newline at end of file
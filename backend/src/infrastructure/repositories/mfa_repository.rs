@@ -0,0 +1,378 @@
+//! # 2段階認証リポジトリ
+//!
+//! TOTP のシークレット（`user_totp`）・リカバリーコード（`recovery_codes`）・ログインのチャレンジ（`mfa_challenges`）を管理
+//!
+//! ## メソッド
+//!
+//! `get_totp`                 - ユーザーの TOTP
+//! `save_pending_totp`        - 登録中のシークレットを保存（有効な場合は保存しない）
+//! `enable_totp`              - 登録中の TOTP を有効にし、リカバリーコードを保存
+//! `use_totp_step`            - 使用したコードのステップを記録（再利用の場合は拒否）
+//! `disable_totp`             - TOTP・リカバリーコード・チャレンジを削除
+//! `replace_recovery_codes`   - リカバリーコードを入れ替え
+//! `use_recovery_code`        - リカバリーコードを使用済みにする
+//! `count_recovery_codes`     - 未使用のリカバリーコードの数
+//! `create_challenge`         - ログインのチャレンジを作成
+//! `find_challenge`           - トークンのハッシュでチャレンジを検索
+//! `record_challenge_failure` - チャレンジの入力回数を加算
+//! `consume_challenge`        - チャレンジを使用済みにする
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::Transaction;
+use crate::infrastructure::db::connection::DbPool;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{
+        entities::mfa::{MfaChallenge, UserTotp},
+        repositories::mfa_repository::MfaRepository
+    }
+};
+
+pub struct MfaRepositoryImpl {
+    pool: DbPool
+}
+
+impl MfaRepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        MfaRepositoryImpl { pool }
+    }
+}
+
+/// ユーザーのリカバリーコードを全て削除し、新しいコードを保存
+async fn insert_recovery_codes(tx: &Transaction<'_>, user_id: i32, recovery_code_hashes: &[String]) -> Result<(), AuthError> {
+    tx.execute(
+        r#"
+            DELETE FROM
+                recovery_codes
+            WHERE
+                user_id = $1;
+        "#,
+        &[&user_id]
+    ).await?;
+
+    tx.execute(
+        r#"
+            INSERT INTO recovery_codes (
+                user_id,
+                code_hash
+            )
+            SELECT
+                $1,
+                UNNEST($2::TEXT[]);
+        "#,
+        &[&user_id, &recovery_code_hashes]
+    ).await?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl MfaRepository for MfaRepositoryImpl {
+    async fn get_totp(&self, user_id: i32) -> Result<Option<UserTotp>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    secret,
+                    enabled_at,
+                    last_used_step
+                FROM
+                    user_totp
+                WHERE
+                    user_id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| UserTotp {
+            secret: row.get("secret"),
+            enabled_at: row.get("enabled_at"),
+            last_used_step: row.get("last_used_step"),
+        }))
+    }
+
+    /// 登録をやり直した場合は、登録中のシークレットを入れ替える
+    ///
+    /// 既に有効な場合は `false` を返す
+    async fn save_pending_totp(&self, user_id: i32, secret: &str) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let saved = conn.execute(
+            r#"
+                INSERT INTO user_totp (
+                    user_id,
+                    secret
+                ) VALUES (
+                    $1,
+                    $2
+                )
+                ON CONFLICT (user_id) DO UPDATE SET
+                    secret = EXCLUDED.secret,
+                    last_used_step = NULL,
+                    created_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_totp.enabled_at IS NULL;
+            "#,
+            &[&user_id, &secret]
+        ).await?;
+
+        Ok(saved > 0)
+    }
+
+    /// 確認に使用したコードのステップを記録し、同じコードでのログインを拒否する
+    ///
+    /// 登録中の TOTP がない、または既に有効な場合は `false` を返す
+    async fn enable_totp(&self, user_id: i32, step: i64, recovery_code_hashes: &[String]) -> Result<bool, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let enabled = tx.execute(
+            r#"
+                UPDATE
+                    user_totp
+                SET
+                    enabled_at = CURRENT_TIMESTAMP,
+                    last_used_step = $2
+                WHERE
+                    user_id = $1
+                    AND enabled_at IS NULL;
+            "#,
+            &[&user_id, &step]
+        ).await?;
+
+        if enabled == 0 {
+            return Ok(false);
+        }
+
+        insert_recovery_codes(&tx, user_id, recovery_code_hashes).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// 最後に使用したステップより後のステップの場合のみ記録する
+    ///
+    /// 同時に同じコードが使われた場合も、一方のみ `true` を返す
+    async fn use_totp_step(&self, user_id: i32, step: i64) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let updated = conn.execute(
+            r#"
+                UPDATE
+                    user_totp
+                SET
+                    last_used_step = $2
+                WHERE
+                    user_id = $1
+                    AND enabled_at IS NOT NULL
+                    AND (last_used_step IS NULL OR last_used_step < $2);
+            "#,
+            &[&user_id, &step]
+        ).await?;
+
+        Ok(updated > 0)
+    }
+
+    /// 有効な TOTP がない場合は `false` を返す
+    async fn disable_totp(&self, user_id: i32) -> Result<bool, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let deleted = tx.execute(
+            r#"
+                DELETE FROM
+                    user_totp
+                WHERE
+                    user_id = $1
+                    AND enabled_at IS NOT NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        if deleted == 0 {
+            return Ok(false);
+        }
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    recovery_codes
+                WHERE
+                    user_id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    mfa_challenges
+                WHERE
+                    user_id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// 以前のリカバリーコードは使用済みかどうかにかかわらず無効になる
+    async fn replace_recovery_codes(&self, user_id: i32, recovery_code_hashes: &[String]) -> Result<(), AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        insert_recovery_codes(&tx, user_id, recovery_code_hashes).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 未使用のコードの場合のみ使用済みにし、`true` を返す
+    async fn use_recovery_code(&self, user_id: i32, code_hash: &str) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let updated = conn.execute(
+            r#"
+                UPDATE
+                    recovery_codes
+                SET
+                    used_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_id = $1
+                    AND code_hash = $2
+                    AND used_at IS NULL;
+            "#,
+            &[&user_id, &code_hash]
+        ).await?;
+
+        Ok(updated > 0)
+    }
+
+    async fn count_recovery_codes(&self, user_id: i32) -> Result<i64, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_one(
+            r#"
+                SELECT
+                    COUNT(*) AS remaining
+                FROM
+                    recovery_codes
+                WHERE
+                    user_id = $1
+                    AND used_at IS NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row.get("remaining"))
+    }
+
+    /// 有効期限を過ぎたチャレンジは、作成のたびに削除する
+    async fn create_challenge(&self, user_id: i32, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            r#"
+                DELETE FROM
+                    mfa_challenges
+                WHERE
+                    expires_at < CURRENT_TIMESTAMP;
+            "#,
+            &[]
+        ).await?;
+
+        tx.execute(
+            r#"
+                INSERT INTO mfa_challenges (
+                    user_id,
+                    token_hash,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3
+                );
+            "#,
+            &[&user_id, &token_hash, &expires_at]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_challenge(&self, token_hash: &str) -> Result<Option<MfaChallenge>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    c.id,
+                    c.user_id,
+                    u.email,
+                    c.attempts,
+                    c.expires_at,
+                    c.used_at
+                FROM
+                    mfa_challenges c
+                    INNER JOIN users u ON u.id = c.user_id
+                WHERE
+                    c.token_hash = $1;
+            "#,
+            &[&token_hash]
+        ).await?;
+
+        Ok(row_opt.map(|row| MfaChallenge {
+            id: row.get("id"),
+            user_id: row.get("user_id"),
+            email: row.get("email"),
+            attempts: row.get("attempts"),
+            expires_at: row.get("expires_at"),
+            used_at: row.get("used_at"),
+        }))
+    }
+
+    async fn record_challenge_failure(&self, challenge_id: i32) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                UPDATE
+                    mfa_challenges
+                SET
+                    attempts = attempts + 1
+                WHERE
+                    id = $1;
+            "#,
+            &[&challenge_id]
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 未使用・有効期限内の場合のみ使用済みにし、`true` を返す
+    async fn consume_challenge(&self, challenge_id: i32) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let updated = conn.execute(
+            r#"
+                UPDATE
+                    mfa_challenges
+                SET
+                    used_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                    AND used_at IS NULL
+                    AND expires_at > CURRENT_TIMESTAMP;
+            "#,
+            &[&challenge_id]
+        ).await?;
+
+        Ok(updated > 0)
+    }
+}
\ No newline at end of file
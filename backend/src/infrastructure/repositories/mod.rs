@@ -1,3 +1,10 @@
 pub mod auth_repository;
+pub mod login_failure_repository;
+pub mod memory_throttle_store;
+pub mod mfa_repository;
+pub mod oidc_repository;
+pub mod outbox_repository;
+pub mod pg_throttle_store;
+pub mod session_repository;
 pub mod task_repository;
 pub mod user_repository;
\ No newline at end of file
@@ -0,0 +1,298 @@
+//! # OpenID Connect リポジトリ
+//!
+//! 連携しているアカウント（`accounts`）・認可リクエストの状態（`oidc_states`）を管理
+//!
+//! ## メソッド
+//!
+//! `create_state`             - 認可リクエストの状態を保存
+//! `consume_state`            - 有効期限内の状態を削除して取得
+//! `find_account_owner`       - プロバイダーのアカウントを連携しているユーザー
+//! `link_account`             - アカウントを連携（連携済みの場合はトークンを更新）
+//! `create_user_with_account` - パスワードのないユーザーを作成し、アカウントを連携
+//! `list_accounts`            - ユーザーが連携しているアカウントの一覧
+//! `unlink_account`           - アカウントの連携を解除（ログインできなくなる場合は解除しない）
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::GenericClient;
+use crate::infrastructure::db::connection::DbPool;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{
+        entities::oidc::{AccountOwner, LinkedAccount, OidcIdentity, OidcState},
+        repositories::oidc_repository::OidcRepository
+    }
+};
+
+pub struct OidcRepositoryImpl {
+    pool: DbPool
+}
+
+impl OidcRepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        OidcRepositoryImpl { pool }
+    }
+}
+
+/// アカウントを連携し、トークンを保存
+///
+/// 他のユーザーが連携しているアカウントは更新せず、`0` を返す
+async fn upsert_account<C: GenericClient>(client: &C, user_id: i32, provider: &str, identity: &OidcIdentity) -> Result<u64, AuthError> {
+    let tokens = &identity.tokens;
+
+    let upserted = client.execute(
+        r#"
+            INSERT INTO accounts (
+                user_id,
+                provider,
+                provider_account_id,
+                email,
+                access_token,
+                refresh_token,
+                expires_at,
+                token_type,
+                scope,
+                id_token
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $8,
+                $9,
+                $10
+            )
+            ON CONFLICT (provider, provider_account_id) DO UPDATE SET
+                email = EXCLUDED.email,
+                access_token = EXCLUDED.access_token,
+                refresh_token = COALESCE(EXCLUDED.refresh_token, accounts.refresh_token),
+                expires_at = EXCLUDED.expires_at,
+                token_type = EXCLUDED.token_type,
+                scope = EXCLUDED.scope,
+                id_token = EXCLUDED.id_token,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE
+                accounts.user_id = EXCLUDED.user_id;
+        "#,
+        &[
+            &user_id,
+            &provider,
+            &identity.subject,
+            &identity.email,
+            &tokens.access_token,
+            &tokens.refresh_token,
+            &tokens.expires_at,
+            &tokens.token_type,
+            &tokens.scope,
+            &tokens.id_token
+        ]
+    ).await?;
+
+    Ok(upserted)
+}
+
+#[async_trait]
+impl OidcRepository for OidcRepositoryImpl {
+    /// 有効期限切れの状態は、新しい状態を保存する時に削除する
+    async fn create_state(&self, state_hash: &str, state: &OidcState, expires_at: DateTime<Utc>) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                DELETE FROM
+                    oidc_states
+                WHERE
+                    expires_at <= CURRENT_TIMESTAMP;
+            "#,
+            &[]
+        ).await?;
+
+        conn.execute(
+            r#"
+                INSERT INTO oidc_states (
+                    state_hash,
+                    provider,
+                    code_verifier,
+                    nonce,
+                    user_id,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6
+                );
+            "#,
+            &[&state_hash, &state.provider, &state.code_verifier, &state.nonce, &state.user_id, &expires_at]
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 状態は一度だけ使用できる
+    async fn consume_state(&self, state_hash: &str, provider: &str) -> Result<Option<OidcState>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                DELETE FROM
+                    oidc_states
+                WHERE
+                    state_hash = $1
+                    AND provider = $2
+                    AND expires_at > CURRENT_TIMESTAMP
+                RETURNING
+                    provider,
+                    code_verifier,
+                    nonce,
+                    user_id;
+            "#,
+            &[&state_hash, &provider]
+        ).await?;
+
+        Ok(row_opt.map(|row| OidcState {
+            provider: row.get("provider"),
+            code_verifier: row.get("code_verifier"),
+            nonce: row.get("nonce"),
+            user_id: row.get("user_id"),
+        }))
+    }
+
+    async fn find_account_owner(&self, provider: &str, provider_account_id: &str) -> Result<Option<AccountOwner>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    u.id,
+                    u.email
+                FROM
+                    accounts a
+                    INNER JOIN users u ON u.id = a.user_id
+                WHERE
+                    a.provider = $1
+                    AND a.provider_account_id = $2;
+            "#,
+            &[&provider, &provider_account_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| AccountOwner {
+            user_id: row.get("id"),
+            email: row.get("email"),
+        }))
+    }
+
+    /// 他のユーザーが連携しているアカウントの場合は `false` を返す
+    async fn link_account(&self, user_id: i32, provider: &str, identity: &OidcIdentity) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        Ok(upsert_account(&*conn, user_id, provider, identity).await? > 0)
+    }
+
+    /// プロバイダーがメールアドレスを確認済みの場合は、メール認証済みのユーザーとして作成する
+    async fn create_user_with_account(&self, name: &str, email: &str, password_hash: &str, provider: &str, identity: &OidcIdentity) -> Result<i32, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let row = tx.query_one(
+            r#"
+                INSERT INTO users (
+                    name,
+                    email,
+                    password,
+                    photo,
+                    is_verified,
+                    has_password
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    FALSE
+                )
+                RETURNING
+                    id;
+            "#,
+            &[&name, &email, &password_hash, &identity.picture, &identity.email_verified]
+        ).await?;
+        let user_id: i32 = row.get("id");
+
+        upsert_account(&tx, user_id, provider, identity).await?;
+        tx.commit().await?;
+
+        Ok(user_id)
+    }
+
+    async fn list_accounts(&self, user_id: i32) -> Result<Vec<LinkedAccount>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let rows = conn.query(
+            r#"
+                SELECT
+                    provider,
+                    provider_account_id,
+                    email,
+                    created_at
+                FROM
+                    accounts
+                WHERE
+                    user_id = $1
+                ORDER BY
+                    provider;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(rows.iter().map(|row| LinkedAccount {
+            provider: row.get("provider"),
+            provider_account_id: row.get("provider_account_id"),
+            email: row.get("email"),
+            created_at: row.get("created_at"),
+        }).collect())
+    }
+
+    /// パスワードがなく、最後に連携しているアカウントの場合は解除せず、`false` を返す
+    ///
+    /// 同時に解除した場合も全てのアカウントを解除しないよう、ユーザーの行をロックする
+    async fn unlink_account(&self, user_id: i32, provider: &str) -> Result<bool, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            r#"
+                SELECT
+                    id
+                FROM
+                    users
+                WHERE
+                    id = $1
+                FOR UPDATE;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        let deleted = tx.execute(
+            r#"
+                DELETE FROM
+                    accounts
+                WHERE
+                    user_id = $1
+                    AND provider = $2
+                    AND (
+                        (SELECT has_password FROM users WHERE id = $1)
+                        OR (SELECT COUNT(*) FROM accounts WHERE user_id = $1) > 1
+                    );
+            "#,
+            &[&user_id, &provider]
+        ).await?;
+        tx.commit().await?;
+
+        Ok(deleted > 0)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,344 @@
+//! # メール送信キューリポジトリ
+//!
+//! `email_outbox` テーブルのメールを登録・取得・更新するリポジトリ
+//!
+//! ワーカーは `FOR UPDATE SKIP LOCKED` で他のワーカーが取得中の行を読み飛ばし、
+//! 取得した行の `next_attempt_at` を処理期限まで延ばす。処理中にプロセスが停止した場合は、期限後に再送信される。
+//!
+//! ## 関数
+//!
+//! `enqueue_mail`           - トランザクション内でメールを登録
+//!
+//! ## メソッド
+//!
+//! `claim_due`              - 送信日時を過ぎたメールを取得し、処理期限を設定
+//! `mark_sent`              - 送信済みにし、本文を削除する
+//! `mark_failed`            - 送信に失敗したメールを再送信待ち、または再送信の上限に達した状態にする
+//! `discard_expired`        - 本文のリンクの有効期限を過ぎたメールを送信せず、本文を削除する
+//! `get_entries`            - メール一覧（管理者）
+//! `requeue`                - 再送信の上限に達したメールを再送信待ちに戻す（管理者）
+//! `enqueue_task_reminders` - 期限が近いタスクのリマインダーを登録し、タスクを送信済みにする
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::{Row, Transaction};
+use crate::infrastructure::db::connection::DbPool;
+use crate::{
+    application::errors::outbox_error::OutboxError,
+    application::mail::mailer::Mail,
+    domain::{
+        entities::outbox::{OutboxEntryResponse, OutboxListQuery, OutboxListResponse, OutboxMessage, TaskReminder},
+        entities::pagination::Pagination,
+        enums::outbox::OutboxStatus,
+        repositories::outbox_repository::OutboxRepository
+    }
+};
+
+pub struct OutboxRepositoryImpl {
+    pool: DbPool
+}
+
+impl OutboxRepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        OutboxRepositoryImpl { pool }
+    }
+}
+
+/// トランザクション内でメールを登録
+///
+/// # 引数
+///
+/// * `tx`         - 送信のきっかけとなる変更のトランザクション
+/// * `mail`       - 送信するメール
+/// * `expires_at` - 本文のリンクの有効期限（トークンを含まないメールは `None`）
+pub async fn enqueue_mail(tx: &Transaction<'_>, mail: &Mail, expires_at: Option<DateTime<Utc>>) -> Result<(), tokio_postgres::Error> {
+    tx.execute(
+        r#"
+            INSERT INTO email_outbox (
+                recipient,
+                subject,
+                text_body,
+                html_body,
+                expires_at
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5
+            );
+        "#,
+        &[&mail.to, &mail.subject, &mail.text, &mail.html, &expires_at]
+    ).await?;
+
+    Ok(())
+}
+
+/// DB の行を一覧のメールに変換
+fn row_to_entry(row: &Row) -> OutboxEntryResponse {
+    OutboxEntryResponse {
+        id: row.get("id"),
+        recipient: row.get("recipient"),
+        subject: row.get("subject"),
+        status: row.get::<_, String>("status").parse().unwrap_or(OutboxStatus::Pending),
+        attempts: row.get("attempts"),
+        last_error: row.get("last_error"),
+        next_attempt_at: row.get("next_attempt_at"),
+        created_at: row.get("created_at"),
+        sent_at: row.get("sent_at"),
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    /// 取得した時点で送信回数を加算する
+    /// 本文のリンクの有効期限を過ぎたメールは取得しない（`discard_expired` を参照）
+    async fn claim_due(&self, limit: i64, lease_until: DateTime<Utc>) -> Result<Vec<OutboxMessage>, OutboxError> {
+        let conn = self.pool.get().await?;
+
+        let rows = conn.query(
+            r#"
+                UPDATE
+                    email_outbox
+                SET
+                    attempts = attempts + 1,
+                    next_attempt_at = $2
+                WHERE
+                    id IN (
+                        SELECT
+                            id
+                        FROM
+                            email_outbox
+                        WHERE
+                            status = 'pending'
+                            AND next_attempt_at <= CURRENT_TIMESTAMP
+                            AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP)
+                        ORDER BY
+                            next_attempt_at,
+                            id
+                        LIMIT $1
+                        FOR UPDATE SKIP LOCKED
+                    )
+                RETURNING
+                    id,
+                    recipient,
+                    subject,
+                    text_body,
+                    html_body,
+                    attempts;
+            "#,
+            &[&limit, &lease_until]
+        ).await?;
+
+        Ok(rows.iter().map(|row| OutboxMessage {
+            id: row.get("id"),
+            mail: Mail {
+                to: row.get("recipient"),
+                subject: row.get("subject"),
+                text: row.get::<_, Option<String>>("text_body").unwrap_or_default(),
+                html: row.get::<_, Option<String>>("html_body").unwrap_or_default(),
+            },
+            attempts: row.get("attempts"),
+        }).collect())
+    }
+
+    /// 本文にはトークンを含むリンクがあるため、送信後は保存しない
+    async fn mark_sent(&self, id: i64) -> Result<(), OutboxError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                UPDATE
+                    email_outbox
+                SET
+                    status = 'sent',
+                    text_body = NULL,
+                    html_body = NULL,
+                    last_error = NULL,
+                    sent_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1;
+            "#,
+            &[&id]
+        ).await?;
+
+        Ok(())
+    }
+
+    /// `retry_at` が `None` の場合は再送信の上限に達した状態にする
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), OutboxError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                UPDATE
+                    email_outbox
+                SET
+                    status = CASE WHEN $3::timestamptz IS NULL THEN 'dead' ELSE 'pending' END,
+                    last_error = $2,
+                    next_attempt_at = COALESCE($3, next_attempt_at)
+                WHERE
+                    id = $1;
+            "#,
+            &[&id, &error, &retry_at]
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 送信待ち・再送信の上限に達したメールが対象。削除したメールは再送信できない
+    async fn discard_expired(&self) -> Result<u64, OutboxError> {
+        let conn = self.pool.get().await?;
+
+        let discarded = conn.execute(
+            r#"
+                UPDATE
+                    email_outbox
+                SET
+                    status = 'dead',
+                    text_body = NULL,
+                    html_body = NULL,
+                    last_error = 'link expired before delivery'
+                WHERE
+                    status IN ('pending', 'dead')
+                    AND expires_at <= CURRENT_TIMESTAMP
+                    AND text_body IS NOT NULL;
+            "#,
+            &[]
+        ).await?;
+
+        Ok(discarded)
+    }
+
+    /// 新しい順に取得する
+    async fn get_entries(&self, query: &OutboxListQuery, pagination: &Pagination) -> Result<OutboxListResponse, OutboxError> {
+        let conn = self.pool.get().await?;
+        let status = query.status.map(|status| status.as_str());
+        let per_page = pagination.per_page();
+
+        let total: i64 = conn
+            .query_one(
+                "SELECT COUNT(*) FROM email_outbox WHERE ($1::varchar IS NULL OR status = $1);",
+                &[&status]
+            )
+            .await?
+            .get(0);
+
+        let rows = conn.query(
+            r#"
+                SELECT
+                    id,
+                    recipient,
+                    subject,
+                    status,
+                    attempts,
+                    last_error,
+                    next_attempt_at,
+                    created_at,
+                    sent_at
+                FROM
+                    email_outbox
+                WHERE
+                    ($1::varchar IS NULL OR status = $1)
+                ORDER BY
+                    id DESC
+                LIMIT $2
+                OFFSET $3;
+            "#,
+            &[&status, &per_page, &pagination.offset()]
+        ).await?;
+
+        Ok(OutboxListResponse {
+            entries: rows.iter().map(row_to_entry).collect(),
+            total,
+            page: pagination.page(),
+            per_page,
+        })
+    }
+
+    /// 送信回数をリセットし、すぐに送信する
+    /// 本文を削除したメール（本文のリンクの有効期限切れ）は再送信できない
+    async fn requeue(&self, id: i64) -> Result<bool, OutboxError> {
+        let conn = self.pool.get().await?;
+
+        let updated = conn.execute(
+            r#"
+                UPDATE
+                    email_outbox
+                SET
+                    status = 'pending',
+                    attempts = 0,
+                    next_attempt_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                    AND status = 'dead'
+                    AND text_body IS NOT NULL
+                    AND (expires_at IS NULL OR expires_at > CURRENT_TIMESTAMP);
+            "#,
+            &[&id]
+        ).await?;
+
+        Ok(updated == 1)
+    }
+
+    /// リマインダーの登録とタスクの更新は同じトランザクションで行い、二重に送信しない
+    /// ゲストのメールアドレスには送信できないため、ゲストのタスクは対象外（登録後に送信する）
+    async fn enqueue_task_reminders(
+        &self,
+        due_before: DateTime<Utc>,
+        limit: i64,
+        compose: &(dyn for<'r> Fn(&'r TaskReminder) -> Mail + Send + Sync),
+    ) -> Result<usize, OutboxError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let rows = tx.query(
+            r#"
+                SELECT
+                    t.id,
+                    t.title,
+                    t.due_date,
+                    u.email,
+                    u.locale
+                FROM
+                    tasks t
+                    INNER JOIN users u ON u.id = t.user_id
+                WHERE
+                    t.reminder_sent_at IS NULL
+                    AND t.is_completed = FALSE
+                    AND t.deleted_at IS NULL
+                    AND t.due_date > CURRENT_TIMESTAMP
+                    AND t.due_date <= $1
+                    AND u.role <> 'guest'
+                ORDER BY
+                    t.due_date
+                LIMIT $2
+                FOR UPDATE OF t SKIP LOCKED;
+            "#,
+            &[&due_before, &limit]
+        ).await?;
+
+        let mut task_ids: Vec<i32> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let reminder = TaskReminder {
+                task_id: row.get("id"),
+                title: row.get("title"),
+                due_date: row.get("due_date"),
+                email: row.get("email"),
+                locale: row.get("locale"),
+            };
+
+            enqueue_mail(&tx, &compose(&reminder), None).await?;
+            task_ids.push(reminder.task_id);
+        }
+
+        tx.execute(
+            "UPDATE tasks SET reminder_sent_at = CURRENT_TIMESTAMP WHERE id = ANY($1);",
+            &[&task_ids]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(task_ids.len())
+    }
+}
\ No newline at end of file
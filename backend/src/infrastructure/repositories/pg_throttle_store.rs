@@ -0,0 +1,126 @@
+//! # ログイン試行のカウンター（DB）
+//!
+//! `login_throttle` テーブルに保存し、複数のプロセスで共有する
+//! 回数の加算は `INSERT ... ON CONFLICT` の1文で行い、同時のリクエストでも数え漏らさない
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use tokio_postgres::Row;
+use crate::infrastructure::db::connection::DbPool;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{entities::login_protection::ThrottleCounter, repositories::throttle_store::ThrottleStore}
+};
+
+pub struct PgThrottleStore {
+    pool: DbPool
+}
+
+impl PgThrottleStore {
+    pub fn new(pool: DbPool) -> Self {
+        PgThrottleStore { pool }
+    }
+}
+
+/// DB の行をカウンターに変換
+fn row_to_counter(row: &Row) -> ThrottleCounter {
+    ThrottleCounter {
+        count: row.get("count"),
+        window_started_at: row.get("window_started_at"),
+        locked_until: row.get("locked_until"),
+    }
+}
+
+#[async_trait]
+impl ThrottleStore for PgThrottleStore {
+    async fn increment(&self, key: &str, window: Duration) -> Result<ThrottleCounter, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_one(
+            r#"
+                INSERT INTO login_throttle (
+                    key,
+                    count,
+                    window_started_at
+                ) VALUES (
+                    $1,
+                    1,
+                    CURRENT_TIMESTAMP
+                )
+                ON CONFLICT (key) DO UPDATE SET
+                    count = CASE
+                        WHEN login_throttle.window_started_at <= CURRENT_TIMESTAMP - make_interval(secs => $2) THEN 1
+                        ELSE login_throttle.count + 1
+                    END,
+                    window_started_at = CASE
+                        WHEN login_throttle.window_started_at <= CURRENT_TIMESTAMP - make_interval(secs => $2) THEN CURRENT_TIMESTAMP
+                        ELSE login_throttle.window_started_at
+                    END
+                RETURNING
+                    count,
+                    window_started_at,
+                    locked_until;
+            "#,
+            &[&key, &(window.num_seconds() as f64)]
+        ).await?;
+
+        Ok(row_to_counter(&row))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<ThrottleCounter>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row = conn.query_opt(
+            "SELECT count, window_started_at, locked_until FROM login_throttle WHERE key = $1;",
+            &[&key]
+        ).await?;
+
+        Ok(row.as_ref().map(row_to_counter))
+    }
+
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                INSERT INTO login_throttle (
+                    key,
+                    locked_until
+                ) VALUES (
+                    $1,
+                    $2
+                )
+                ON CONFLICT (key) DO UPDATE SET
+                    locked_until = $2;
+            "#,
+            &[&key, &until]
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute("DELETE FROM login_throttle WHERE key = $1;", &[&key]).await?;
+
+        Ok(())
+    }
+
+    async fn prune(&self, window_started_before: DateTime<Utc>) -> Result<u64, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let deleted = conn.execute(
+            r#"
+                DELETE FROM
+                    login_throttle
+                WHERE
+                    window_started_at < $1
+                    AND (locked_until IS NULL OR locked_until <= CURRENT_TIMESTAMP);
+            "#,
+            &[&window_started_before]
+        ).await?;
+
+        Ok(deleted)
+    }
+}
\ No newline at end of file
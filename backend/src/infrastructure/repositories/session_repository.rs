@@ -0,0 +1,318 @@
+//! # セッションリポジトリ
+//!
+//! ログイン端末ごとのセッションとリフレッシュトークンを管理するリポジトリ
+//!
+//! ## メソッド
+//!
+//! `create_session`              - セッションと最初のリフレッシュトークンを作成
+//! `find_refresh_token`          - リフレッシュトークンとセッションの取得
+//! `rotate_refresh_token`        - リフレッシュトークンを使用済みにし、新しいトークンを保存
+//! `revoke_session`              - セッションを失効
+//! `revoke_session_by_public_id` - ユーザーのセッションを公開IDで失効
+//! `revoke_all_sessions`         - ユーザーの全セッションを失効
+//! `get_active_sessions`         - ユーザーの有効なセッション一覧
+//! `get_session_status`          - JWT の検証に必要なセッションの状態
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio_postgres::Row;
+use crate::infrastructure::db::connection::DbPool;
+use uuid::Uuid;
+use crate::{
+    application::errors::auth_error::AuthError,
+    domain::{
+        entities::session::{RefreshTokenSelectResult, SessionMeta, SessionSelectResult, SessionStatus},
+        repositories::session_repository::SessionRepository
+    }
+};
+
+pub struct SessionRepositoryImpl {
+    pool: DbPool
+}
+
+impl SessionRepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        SessionRepositoryImpl { pool }
+    }
+}
+
+/// DB の行をセッションに変換
+fn row_to_session(row: &Row) -> SessionSelectResult {
+    SessionSelectResult {
+        id: row.get("id"),
+        public_id: row.get("public_id"),
+        user_id: row.get("user_id"),
+        user_agent: row.get("user_agent"),
+        ip_address: row.get("ip_address"),
+        expires_at: row.get("expires_at"),
+        revoked_at: row.get("revoked_at"),
+        last_used_at: row.get("last_used_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SessionRepositoryImpl {
+    async fn create_session(
+        &self,
+        user_id: i32,
+        public_id: Uuid,
+        meta: &SessionMeta,
+        expires_at: DateTime<Utc>,
+        token_hash: &str,
+    ) -> Result<(), AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let row = tx.query_one(
+            r#"
+                INSERT INTO sessions (
+                    public_id,
+                    user_id,
+                    user_agent,
+                    ip_address,
+                    expires_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5
+                )
+                RETURNING
+                    id;
+            "#,
+            &[&public_id, &user_id, &meta.user_agent, &meta.ip_address, &expires_at]
+        ).await?;
+        let session_id: i32 = row.get("id");
+
+        tx.execute(
+            r#"
+                INSERT INTO refresh_tokens (
+                    session_id,
+                    token_hash
+                ) VALUES (
+                    $1,
+                    $2
+                );
+            "#,
+            &[&session_id, &token_hash]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenSelectResult>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    rt.id AS token_id,
+                    rt.used_at,
+                    s.id,
+                    s.public_id,
+                    s.user_id,
+                    s.user_agent,
+                    s.ip_address,
+                    s.expires_at,
+                    s.revoked_at,
+                    s.last_used_at,
+                    s.created_at
+                FROM
+                    refresh_tokens rt
+                    INNER JOIN sessions s ON s.id = rt.session_id
+                WHERE
+                    rt.token_hash = $1;
+            "#,
+            &[&token_hash]
+        ).await?;
+
+        Ok(row_opt.map(|row| RefreshTokenSelectResult {
+            id: row.get("token_id"),
+            used_at: row.get("used_at"),
+            session: row_to_session(&row),
+        }))
+    }
+
+    /// 同じトークンで同時にリクエストされた場合は、先に使用済みにした方のみ成功する
+    async fn rotate_refresh_token(
+        &self,
+        token_id: i32,
+        session_id: i32,
+        meta: &SessionMeta,
+        new_token_hash: &str,
+    ) -> Result<bool, AuthError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let used = tx.execute(
+            r#"
+                UPDATE
+                    refresh_tokens
+                SET
+                    used_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                    AND used_at IS NULL;
+            "#,
+            &[&token_id]
+        ).await?;
+
+        if used != 1 {
+            return Ok(false);
+        }
+
+        tx.execute(
+            r#"
+                INSERT INTO refresh_tokens (
+                    session_id,
+                    token_hash
+                ) VALUES (
+                    $1,
+                    $2
+                );
+            "#,
+            &[&session_id, &new_token_hash]
+        ).await?;
+
+        tx.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    user_agent = COALESCE($2, user_agent),
+                    ip_address = COALESCE($3, ip_address),
+                    last_used_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1;
+            "#,
+            &[&session_id, &meta.user_agent, &meta.ip_address]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    async fn revoke_session(&self, session_id: i32) -> Result<(), AuthError> {
+        let conn = self.pool.get().await?;
+
+        conn.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                    AND revoked_at IS NULL;
+            "#,
+            &[&session_id]
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn revoke_session_by_public_id(&self, user_id: i32, public_id: Uuid) -> Result<bool, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let revoked = conn.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    public_id = $1
+                    AND user_id = $2
+                    AND revoked_at IS NULL;
+            "#,
+            &[&public_id, &user_id]
+        ).await?;
+
+        Ok(revoked == 1)
+    }
+
+    async fn revoke_all_sessions(&self, user_id: i32) -> Result<u64, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let revoked = conn.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_id = $1
+                    AND revoked_at IS NULL;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(revoked)
+    }
+
+    async fn get_active_sessions(&self, user_id: i32) -> Result<Vec<SessionSelectResult>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let rows = conn.query(
+            r#"
+                SELECT
+                    id,
+                    public_id,
+                    user_id,
+                    user_agent,
+                    ip_address,
+                    expires_at,
+                    revoked_at,
+                    last_used_at,
+                    created_at
+                FROM
+                    sessions
+                WHERE
+                    user_id = $1
+                    AND revoked_at IS NULL
+                    AND expires_at > CURRENT_TIMESTAMP
+                ORDER BY
+                    last_used_at DESC;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(rows.iter().map(row_to_session).collect())
+    }
+
+    /// ユーザーが存在しない場合は `None`
+    async fn get_session_status(&self, user_id: i32, public_id: Uuid) -> Result<Option<SessionStatus>, AuthError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    u.password_changed_at,
+                    u.role,
+                    u.locale,
+                    s.id IS NOT NULL AS is_active
+                FROM
+                    users u
+                    LEFT JOIN sessions s ON s.user_id = u.id
+                        AND s.public_id = $2
+                        AND s.revoked_at IS NULL
+                        AND s.expires_at > CURRENT_TIMESTAMP
+                WHERE
+                    u.id = $1;
+            "#,
+            &[&user_id, &public_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| SessionStatus {
+            password_changed_at: row.get("password_changed_at"),
+            is_active: row.get("is_active"),
+            role: row.get("role"),
+            locale: row.get("locale"),
+        }))
+    }
+}
\ No newline at end of file
@@ -1,137 +1,375 @@
 //! # タスクリポジトリ
-//! 
+//!
 //! タスク処理を定義したリポジトリ
-//! 
+//!
+//! 更新系のクエリは全て `id` と `user_id` の両方で絞り込み、
+//! 他ユーザーのタスクを ID 指定で操作できないようにする
+//!
 //! ## メソッド
-//! 
-/// `get_tasks`     - ユーザーが持つタスク一覧を取得します。  
-/// `create_task`   - 新規タスクを作成します。  
-/// `update_task`   - 既存のタスクを更新します。  
-/// `delete_task`   - タスクを削除します。  
-/// `complete_task` - タスクを完了状態にします。
+//!
+//! `get_tasks`     - ユーザーが持つタスク一覧を検索条件・並び順・ページ指定で取得します。
+//! `get_task`      - ユーザーが持つタスクを1件取得します。
+//! `create_task`   - 新規タスクを作成します。
+//! `update_task`   - 既存のタスクを更新します。
+//! `delete_task`   - タスクを削除します。
+//! `complete_task` - タスクを完了状態にします。
 
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use std::time::{SystemTime, UNIX_EPOCH};
 use std::str::FromStr;
-use tokio_postgres::NoTls;
-use bb8_postgres::{PostgresConnectionManager, bb8::Pool};
-use crate::domain::enums::task::Priority;
+use tokio_postgres::Row;
+use tokio_postgres::types::ToSql;
+use crate::infrastructure::db::connection::DbPool;
+use crate::domain::enums::task::{Priority, SortOrder, TaskSortField};
 use crate::{
     application::errors::task_error::TaskError,
-    domain::{entities::task::*, enums::task::Status, repositories::task_repository::TaskRepository},
+    domain::{entities::{pagination::Pagination, task::*}, enums::task::Status, repositories::task_repository::TaskRepository},
 };
 
 pub struct TaskRepositoryImpl {
-    pool: Pool<PostgresConnectionManager<NoTls>>,
+    pool: DbPool,
 }
 
 impl TaskRepositoryImpl {
-    pub fn new(pool: Pool<PostgresConnectionManager<NoTls>>) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         TaskRepositoryImpl { pool }
     }
 }
 
+/// DB の行を `TaskItem` に変換
+///
+/// タイムスタンプは `TIMESTAMP WITH TIME ZONE` として取得し、UTC の `NaiveDateTime` に変換します。
+fn row_to_task(row: &Row) -> TaskItem {
+    let status: Option<String> = row.get("status");
+    let priority: Option<String> = row.get("priority");
+    let due_date: Option<DateTime<Utc>> = row.get("due_date");
+    let created_at: DateTime<Utc> = row.get("created_at");
+    let updated_at: DateTime<Utc> = row.get("updated_at");
+
+    TaskItem {
+        id: row.get("id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        due_date: due_date.map(|d| d.naive_utc()),
+        status: status.and_then(|s| Status::from_str(&s).ok()),
+        completed: row.get("is_completed"),
+        priority: priority.and_then(|p| Priority::from_str(&p).ok()),
+        user_id: row.get("user_id"),
+        created_at: created_at.naive_utc(),
+        updated_at: updated_at.naive_utc(),
+    }
+}
+
+/// タスク一覧の検索クエリビルダー
+///
+/// 条件は全てプレースホルダ（`$n`）経由でバインドし、
+/// 並び替え項目と並び順は列挙子から固定の SQL 断片に変換するため、
+/// リクエストの値が SQL 文字列に直接埋め込まれることはない
+pub struct TaskQueryBuilder {
+    conditions: Vec<String>,
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+    sort: TaskSortField,
+    order: SortOrder,
+}
+
+impl TaskQueryBuilder {
+    /// ユーザーが持つ未削除のタスクを対象とするビルダーを作成
+    pub fn new(user_id: i32) -> Self {
+        let mut builder = TaskQueryBuilder {
+            conditions: Vec::new(),
+            params: Vec::new(),
+            sort: TaskSortField::default(),
+            order: SortOrder::default(),
+        };
+        let placeholder = builder.bind(user_id);
+        builder.conditions.push(format!("user_id = {}", placeholder));
+        builder.conditions.push("deleted_at IS NULL".to_string());
+        builder
+    }
+
+    /// 値をパラメータに追加し、対応するプレースホルダを返す
+    fn bind<T: ToSql + Sync + Send + 'static>(&mut self, value: T) -> String {
+        self.params.push(Box::new(value));
+        format!("${}", self.params.len())
+    }
+
+    /// `column op $n` 形式の条件を追加
+    fn filter<T: ToSql + Sync + Send + 'static>(mut self, column: &str, op: &str, value: T) -> Self {
+        let placeholder = self.bind(value);
+        self.conditions.push(format!("{} {} {}", column, op, placeholder));
+        self
+    }
+
+    pub fn status(self, status: Option<Status>) -> Self {
+        match status {
+            Some(status) => self.filter("status", "=", status.as_str()),
+            None => self,
+        }
+    }
+
+    pub fn priority(self, priority: Option<Priority>) -> Self {
+        match priority {
+            Some(priority) => self.filter("priority", "=", priority.as_str()),
+            None => self,
+        }
+    }
+
+    pub fn completed(self, completed: Option<bool>) -> Self {
+        match completed {
+            Some(completed) => self.filter("is_completed", "=", completed),
+            None => self,
+        }
+    }
+
+    /// 期限日の範囲（両端を含む）
+    pub fn due_between(self, from: Option<NaiveDateTime>, to: Option<NaiveDateTime>) -> Self {
+        let builder = match from {
+            Some(from) => self.filter("due_date", ">=", from.and_utc()),
+            None => self,
+        };
+        match to {
+            Some(to) => builder.filter("due_date", "<=", to.and_utc()),
+            None => builder,
+        }
+    }
+
+    /// 期限切れかつ未完了のタスクのみ
+    pub fn overdue(mut self, overdue: Option<bool>) -> Self {
+        if overdue == Some(true) {
+            self.conditions.push("due_date < CURRENT_TIMESTAMP".to_string());
+            self.conditions.push("is_completed = false".to_string());
+        }
+        self
+    }
+
+    /// タイトルの部分一致
+    ///
+    /// `%` `_` `\` はワイルドカードとして解釈されないようエスケープする
+    pub fn title_contains(mut self, text: Option<&str>) -> Self {
+        let Some(text) = text.map(str::trim).filter(|t| !t.is_empty()) else {
+            return self;
+        };
+        let escaped = text
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let placeholder = self.bind(format!("%{}%", escaped));
+        self.conditions.push(format!("title ILIKE {} ESCAPE '\\'", placeholder));
+        self
+    }
+
+    pub fn sort(mut self, sort: TaskSortField, order: SortOrder) -> Self {
+        self.sort = sort;
+        self.order = order;
+        self
+    }
+
+    /// クエリパラメータの条件を全て適用
+    pub fn apply(self, query: &TaskListQuery) -> Self {
+        self.status(query.status.clone())
+            .priority(query.priority.clone())
+            .completed(query.completed)
+            .due_between(query.due_from, query.due_to)
+            .overdue(query.overdue)
+            .title_contains(query.q.as_deref())
+            .sort(query.sort, query.order)
+    }
+
+    fn where_clause(&self) -> String {
+        self.conditions.join(" AND ")
+    }
+
+    fn order_by_clause(&self) -> String {
+        let column = match self.sort {
+            TaskSortField::CreatedAt => "created_at",
+            TaskSortField::UpdatedAt => "updated_at",
+            TaskSortField::DueDate => "due_date",
+            TaskSortField::Priority => "CASE priority WHEN 'high' THEN 3 WHEN 'medium' THEN 2 WHEN 'low' THEN 1 ELSE 0 END",
+            TaskSortField::Title => "title",
+        };
+        let direction = match self.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+
+        // 同じ値の行の順序を安定させるため、ID を第2キーにする
+        format!("{} {} NULLS LAST, id {}", column, direction, direction)
+    }
+
+    /// 件数取得用と1ページ分の取得用の SQL を組み立てる
+    ///
+    /// `LIMIT` と `OFFSET` もパラメータとしてバインドする
+    pub fn build(mut self, limit: i64, offset: i64) -> TaskListSql {
+        let where_clause = self.where_clause();
+        let order_by = self.order_by_clause();
+        let filter_param_count = self.params.len();
+        let limit = self.bind(limit);
+        let offset = self.bind(offset);
+
+        TaskListSql {
+            count_sql: format!("SELECT COUNT(*) FROM tasks WHERE {}", where_clause),
+            select_sql: format!(
+                "SELECT * FROM tasks WHERE {} ORDER BY {} LIMIT {} OFFSET {}",
+                where_clause, order_by, limit, offset
+            ),
+            params: self.params,
+            filter_param_count,
+        }
+    }
+}
+
+/// `TaskQueryBuilder` で組み立てた SQL とパラメータ
+pub struct TaskListSql {
+    pub count_sql: String,
+    pub select_sql: String,
+    params: Vec<Box<dyn ToSql + Sync + Send>>,
+    filter_param_count: usize,
+}
+
+impl TaskListSql {
+    /// 件数取得用のパラメータ（`LIMIT` と `OFFSET` を含まない）
+    pub fn count_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params[..self.filter_param_count]
+            .iter()
+            .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+
+    /// 1ページ分の取得用のパラメータ
+    pub fn select_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+            .collect()
+    }
+}
+
 #[async_trait]
 impl TaskRepository for TaskRepositoryImpl {
     /// タスク一覧取得
-    /// 
-    /// ユーザーが持つタスクを取得します。
-    /// 
+    ///
+    /// ユーザーが持つタスクを検索条件で絞り込み、並び替えて1ページ分取得します。
+    ///
     /// # 引数
-    /// 
-    /// * `user_id` - ユーザーID
-    /// 
+    ///
+    /// * `user_id`    - ユーザーID
+    /// * `query`      - `TaskListQuery` 型の検索条件
+    /// * `pagination` - ページ番号・1ページあたりの件数
+    ///
     /// # 戻り値
-    /// 
+    ///
     /// `Result` を返します：
-    /// 
-    /// - `Ok(Some(TaskListResponse)` - タスクを取得した場合、タスクリストを返します。
-    /// - `Err(TaskError)`         - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
+    ///
+    /// - `Ok(TaskListResponse)` - タスクを取得した場合、タスクリストと総件数を返します。
+    /// - `Err(TaskError)`       - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
     async fn get_tasks(
         &self,
         user_id: i32,
+        query: &TaskListQuery,
+        pagination: &Pagination,
     ) -> Result<TaskListResponse, TaskError> {
         let conn = self.pool.get().await?;
 
-        let rows = conn.query(
+        let per_page = pagination.per_page();
+        let sql = TaskQueryBuilder::new(user_id)
+            .apply(query)
+            .build(per_page, pagination.offset());
+
+        let total: i64 = conn
+            .query_one(sql.count_sql.as_str(), &sql.count_params())
+            .await?
+            .get(0);
+        let rows = conn.query(sql.select_sql.as_str(), &sql.select_params()).await?;
+
+        Ok(TaskListResponse {
+            tasks: rows.iter().map(row_to_task).collect(),
+            total,
+            page: pagination.page(),
+            per_page,
+            total_pages: pagination.total_pages(total),
+        })
+    }
+
+    /// タスク取得
+    ///
+    /// ユーザーが持つタスクを1件取得します。
+    ///
+    /// # 引数
+    ///
+    /// * `user_id` - ユーザーID
+    /// * `task_id` - タスクID
+    ///
+    /// # 戻り値
+    ///
+    /// `Result` を返します：
+    ///
+    /// - `Ok(Some(TaskItem))` - タスクを取得した場合、タスクを返します。
+    /// - `Ok(None)`           - タスクが存在しない、または他ユーザーのタスクの場合。
+    /// - `Err(TaskError)`     - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
+    async fn get_task(
+        &self,
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<Option<TaskItem>, TaskError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
             r#"
                 SELECT
                     *
                 FROM
                     tasks
                 WHERE
-                    user_id = $1
+                    id = $1
+                    AND user_id = $2
+                    AND deleted_at IS NULL
             "#,
-            &[&user_id],
+            &[&task_id, &user_id],
         ).await?;
 
-        let tasks: TaskListResponse = TaskListResponse {
-            tasks: rows.into_iter().map(|row| {
-                // データベースから抽出したタイムスタンプを YYYY-MM-dd HH:mm:ss フォーマットに変換
-                let convert_timestamp = |time: SystemTime| -> NaiveDateTime {
-                    let duration = time.duration_since(UNIX_EPOCH).unwrap();
-                    DateTime::<Utc>::from_timestamp(duration.as_secs() as i64, 0)
-                        .unwrap()
-                        .naive_utc()
-                };
-        
-                let status: Option<String> = row.get("status");
-                let priority: Option<String> = row.get("priority");
-        
-                let task = TaskItem {
-                    id: row.get("id"),
-                    title: row.get("title"),
-                    description: row.get("description"),
-                    due_date: row.get("due_date"),
-                    status: status.and_then(|s| Status::from_str(&s).ok()),
-                    completed: row.get("completed"),
-                    priority: priority.and_then(|p| Priority::from_str(&p).ok()),
-                    user_id: row.get("user_id"),
-                    created_at: convert_timestamp(row.get("created_at")),
-                    updated_at: convert_timestamp(row.get("updated_at")),
-                };
-        
-                task
-            }).collect(),
-        };
-
-        Ok(tasks)
+        Ok(row_opt.as_ref().map(row_to_task))
     }
 
     /// タスク作成
-    /// 
+    ///
     /// タスクを新規作成します。
-    /// 
+    ///
     /// # 引数
-    /// 
+    ///
     /// * `user_id` - ユーザーID
     /// * `task_req` - `RequestCreateTaskItem` 型のリクエストボディデータ
-    /// 
+    ///
     /// # 戻り値
-    /// 
+    ///
     /// `Result` を返します：
-    /// 
-    /// - `Ok(Some(ResponseCreateTaskItem)` - タスクを作成した場合、作成したタスクを返します。
-    /// - `Err(TaskError)`                  - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
+    ///
+    /// - `Ok(TaskItem)`   - タスクを作成した場合、作成したタスクを返します。
+    /// - `Err(TaskError)` - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
     async fn create_task(
         &self,
         user_id: i32,
         task_req: &RequestCreateTaskItem,
-    ) -> Result<ResponseCreateTaskItem, TaskError> {
+    ) -> Result<TaskItem, TaskError> {
         let conn = self.pool.get().await?;
 
-        let row_result = conn.query_one(
+        let due_date = task_req.due_date.map(|d| d.and_utc());
+        let priority = task_req.priority.as_ref().unwrap_or(&Priority::Low).as_str();
+
+        let row = conn.query_one(
             r#"
                 INSERT INTO tasks (
                     user_id,
                     title,
                     description,
+                    due_date,
+                    priority,
                     is_completed
                 ) VALUES (
                     $1,
                     $2,
                     $3,
+                    $4,
+                    $5,
                     false
                 )
                 RETURNING *
@@ -140,80 +378,98 @@ impl TaskRepository for TaskRepositoryImpl {
                 &user_id,
                 &task_req.title,
                 &task_req.description,
+                &due_date,
+                &priority,
             ]
         ).await?;
 
-        Ok(ResponseCreateTaskItem {
-            title: row_result.get("title"),
-            description: row_result.get("description"),
-            is_completed: row_result.get("is_completed")
-        })
+        Ok(row_to_task(&row))
     }
 
     /// タスク更新
-    /// 
-    /// タスクを更新します。
-    /// 
+    ///
+    /// タスクを更新します。リクエストで指定されなかった項目は現在の値を維持します。
+    ///
     /// # 引数
-    /// 
+    ///
+    /// * `user_id`  - ユーザーID
+    /// * `task_id`  - タスクID
     /// * `task_req` - `RequestUpdateTaskItem` 型のリクエストボディデータ
-    /// 
+    ///
     /// # 戻り値
-    /// 
+    ///
     /// `Result` を返します：
-    /// 
-    /// - `Ok(())`         - タスクを更新した場合。
-    /// - `Err(TaskError)` - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
+    ///
+    /// - `Ok(Some(TaskItem))` - タスクを更新した場合、更新後のタスクを返します。
+    /// - `Ok(None)`           - タスクが存在しない、または他ユーザーのタスクの場合。
+    /// - `Err(TaskError)`     - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
     async fn update_task(
         &self,
+        user_id: i32,
+        task_id: i32,
         task_req: &RequestUpdateTaskItem,
-    ) -> Result<(), TaskError> {
+    ) -> Result<Option<TaskItem>, TaskError> {
         let conn = self.pool.get().await?;
 
-        conn.execute(
+        let due_date = task_req.due_date.map(|d| d.and_utc());
+        let priority = task_req.priority.as_ref().map(|p| p.as_str());
+
+        let row_opt = conn.query_opt(
             r#"
                 UPDATE
                     tasks
                 SET
-                    title = $2,
-                    description = $3,
-                    is_completed = $4,
+                    title = COALESCE($3, title),
+                    description = COALESCE($4, description),
+                    due_date = COALESCE($5, due_date),
+                    priority = COALESCE($6, priority),
+                    is_completed = COALESCE($7, is_completed),
+                    reminder_sent_at = CASE WHEN $5 IS DISTINCT FROM due_date AND $5 IS NOT NULL THEN NULL ELSE reminder_sent_at END,
                     updated_at = CURRENT_TIMESTAMP
                 WHERE
                     id = $1
+                    AND user_id = $2
+                    AND deleted_at IS NULL
+                RETURNING *
             "#,
             &[
-                &task_req.id,
+                &task_id,
+                &user_id,
                 &task_req.title,
                 &task_req.description,
+                &due_date,
+                &priority,
                 &task_req.is_completed
             ]
         ).await?;
 
-        Ok(())
+        Ok(row_opt.as_ref().map(row_to_task))
     }
 
     /// タスク削除
-    /// 
-    /// タスクを削除します。
-    /// 
+    ///
+    /// タスクを論理削除します。
+    ///
     /// # 引数
-    /// 
-    /// * `task_req` - `RequestDeleteTaskItem` 型のリクエストボディデータ
-    /// 
+    ///
+    /// * `user_id` - ユーザーID
+    /// * `task_id` - タスクID
+    ///
     /// # 戻り値
-    /// 
+    ///
     /// `Result` を返します：
-    /// 
-    /// - `Ok(())`         - タスクを削除した場合。
+    ///
+    /// - `Ok(true)`       - タスクを削除した場合。
+    /// - `Ok(false)`      - タスクが存在しない、または他ユーザーのタスクの場合。
     /// - `Err(TaskError)` - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
     async fn delete_task(
         &self,
-        task_req: &RequestDeleteTaskItem,
-    ) -> Result<(), TaskError> {
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<bool, TaskError> {
         let conn = self.pool.get().await?;
 
-        conn.execute(
+        let affected = conn.execute(
             r#"
                 UPDATE
                     tasks
@@ -221,46 +477,54 @@ impl TaskRepository for TaskRepositoryImpl {
                     deleted_at = CURRENT_TIMESTAMP
                 WHERE
                     id = $1
+                    AND user_id = $2
+                    AND deleted_at IS NULL
             "#,
-            &[&task_req.id]
+            &[&task_id, &user_id]
         ).await?;
 
-        Ok(())
+        Ok(affected > 0)
     }
 
     /// タスク完了
-    /// 
+    ///
     /// タスクを完了します。
-    /// 
+    ///
     /// # 引数
-    /// 
-    /// * `task_req` - `RequestCompleteTaskItem` 型のリクエストボディデータ
-    /// 
+    ///
+    /// * `user_id` - ユーザーID
+    /// * `task_id` - タスクID
+    ///
     /// # 戻り値
-    /// 
+    ///
     /// `Result` を返します：
-    /// 
-    /// - `Ok(())`         - タスクを完了した場合。
-    /// - `Err(TaskError)` - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
+    ///
+    /// - `Ok(Some(TaskItem))` - タスクを完了した場合、完了後のタスクを返します。
+    /// - `Ok(None)`           - タスクが存在しない、または他ユーザーのタスクの場合。
+    /// - `Err(TaskError)`     - データベース接続やクエリエラーが発生した場合、カスタムエラーを返します。
     async fn complete_task(
         &self,
-        task_req: &RequestCompleteTaskItem,
-    ) -> Result<(), TaskError> {
+        user_id: i32,
+        task_id: i32,
+    ) -> Result<Option<TaskItem>, TaskError> {
         let conn = self.pool.get().await?;
 
-        conn.execute(
+        let row_opt = conn.query_opt(
             r#"
                 UPDATE
                     tasks
                 SET
-                    deleted_at = CURRENT_TIMESTAMP,
-                    is_completed = true
+                    is_completed = true,
+                    updated_at = CURRENT_TIMESTAMP
                 WHERE
                     id = $1
+                    AND user_id = $2
+                    AND deleted_at IS NULL
+                RETURNING *
             "#,
-            &[&task_req.id]
+            &[&task_id, &user_id]
         ).await?;
 
-        Ok(())
+        Ok(row_opt.as_ref().map(row_to_task))
     }
-}
\ No newline at end of file
+}
@@ -4,26 +4,52 @@
 //! 
 //! ## メソッド
 //! 
-//! `get_user_id` - ユーザーID取得
+//! `get_user_id`       - ユーザーID取得
+//! `find_user_by_id`   - ユーザー取得
+//! `update_user`       - プロフィール更新
+//! `get_password_hash` - パスワードハッシュ取得
+//! `update_password`   - パスワード更新
+//! `get_users`         - ユーザー一覧取得（管理者）
+//! `delete_user`       - ユーザー削除（管理者）
+//! `update_role`       - ロール変更と監査ログの記録（管理者）
 
 use async_trait::async_trait;
-use tokio_postgres::NoTls;
-use bb8_postgres::{PostgresConnectionManager, bb8::Pool};
+use tokio_postgres::Row;
+use crate::infrastructure::db::connection::DbPool;
 use crate::{
     application::{errors::user_error::UserError, jwt::jwt::Claims},
-    domain::{entities::user::UserResponse, repositories::user_repository::UserRepository},
+    domain::{
+        entities::pagination::Pagination,
+        entities::user::{UpdateUserRequest, UserListResponse, UserResponse},
+        enums::role::Role,
+        repositories::user_repository::UserRepository
+    },
 };
 
 pub struct UserRepositoryImpl {
-    pool: Pool<PostgresConnectionManager<NoTls>>
+    pool: DbPool
 }
 
 impl UserRepositoryImpl {
-    pub fn new(pool: Pool<PostgresConnectionManager<NoTls>>) -> Self {
+    pub fn new(pool: DbPool) -> Self {
         UserRepositoryImpl { pool }
     }
 }
 
+/// DB の行をユーザー情報に変換
+fn row_to_user(row: &Row) -> UserResponse {
+    UserResponse {
+        id: row.get::<_, i32>("id").to_string(),
+        name: row.get("name"),
+        email: row.get("email"),
+        role: row.get("role"),
+        photo: row.get("photo"),
+        bio: row.get("bio"),
+        is_verified: row.get("is_verified"),
+        locale: row.get("locale"),
+    }
+}
+
 #[async_trait]
 impl UserRepository for UserRepositoryImpl {
     /// ユーザーID取得
@@ -47,7 +73,7 @@ impl UserRepository for UserRepositoryImpl {
         let row = conn.query_opt(
             r#"
                 SELECT
-                    user_id
+                    id
                 FROM
                     users
                 WHERE
@@ -56,13 +82,15 @@ impl UserRepository for UserRepositoryImpl {
             &[&user.sub]
         ).await?;
 
-        Ok(row.and_then(|r| {
-            let user_id= r.get("user_id");
-            user_id
-        }))
+        Ok(row.map(|r| r.get("id")))
     }
 
     async fn find_user_by_id(&self, user_id: &str) -> Result<Option<UserResponse>, UserError> {
+        // 数値でない ID に該当するユーザーは存在しない
+        let Ok(user_id) = user_id.parse::<i32>() else {
+            return Ok(None);
+        };
+
         let conn = self.pool.get().await?;
 
         let row_opt = conn.query_opt(
@@ -74,7 +102,8 @@ impl UserRepository for UserRepositoryImpl {
                     role,
                     photo,
                     bio,
-                    is_verified
+                    is_verified,
+                    locale
                 FROM
                     users
                 WHERE
@@ -84,19 +113,229 @@ impl UserRepository for UserRepositoryImpl {
         ).await;
 
         match row_opt {
-            Ok(Some(row)) => {
-                Ok(Some(UserResponse {
-                    id: row.get("id"),
-                    name: row.get("name"),
-                    email: row.get("email"),
-                    role: row.get("role"),
-                    photo: row.get("photo"),
-                    bio: row.get("bio"),
-                    is_verified: row.get("is_verified"),
-                }))
-            },
+            Ok(Some(row)) => Ok(Some(row_to_user(&row))),
             Ok(None) => Ok(None),
-            Err(err) => Err(UserError::DatabaseError(err.into())),
+            Err(err) => Err(UserError::DatabaseError(err)),
         } 
     }
+    /// プロフィール更新
+    ///
+    /// 指定された項目のみ更新し、更新後のユーザー情報を返します。
+    async fn update_user(&self, user_id: i32, req: &UpdateUserRequest) -> Result<Option<UserResponse>, UserError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                UPDATE
+                    users
+                SET
+                    name = COALESCE($2, name),
+                    bio = COALESCE($3, bio),
+                    photo = COALESCE($4, photo),
+                    locale = COALESCE($5, locale),
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                RETURNING
+                    id,
+                    name,
+                    email,
+                    role,
+                    photo,
+                    bio,
+                    is_verified,
+                    locale;
+            "#,
+            &[&user_id, &req.name, &req.bio, &req.photo, &req.locale.map(|locale| locale.as_str())]
+        ).await?;
+
+        Ok(row_opt.map(|row| row_to_user(&row)))
+    }
+
+    async fn get_password_hash(&self, user_id: i32) -> Result<Option<String>, UserError> {
+        let conn = self.pool.get().await?;
+
+        let row_opt = conn.query_opt(
+            r#"
+                SELECT
+                    password
+                FROM
+                    users
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(row_opt.map(|row| row.get("password")))
+    }
+
+    /// パスワード更新
+    ///
+    /// パスワード変更日時を更新し、それ以前に発行された JWT を無効にします。
+    /// 現在のセッション以外は全て失効させます。
+    async fn update_password(&self, user_id: i32, password_hash: &str, current_session: &str) -> Result<bool, UserError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let updated = tx.execute(
+            r#"
+                UPDATE
+                    users
+                SET
+                    password = $2,
+                    password_changed_at = CURRENT_TIMESTAMP,
+                    has_password = TRUE,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id, &password_hash]
+        ).await?;
+
+        tx.execute(
+            r#"
+                UPDATE
+                    sessions
+                SET
+                    revoked_at = CURRENT_TIMESTAMP
+                WHERE
+                    user_id = $1
+                    AND public_id::text <> $2
+                    AND revoked_at IS NULL;
+            "#,
+            &[&user_id, &current_session]
+        ).await?;
+
+        tx.commit().await?;
+
+        Ok(updated == 1)
+    }
+    async fn get_users(&self, pagination: &Pagination) -> Result<UserListResponse, UserError> {
+        let conn = self.pool.get().await?;
+
+        let per_page = pagination.per_page();
+
+        let total: i64 = conn
+            .query_one("SELECT COUNT(*) FROM users;", &[])
+            .await?
+            .get(0);
+        let rows = conn.query(
+            r#"
+                SELECT
+                    id,
+                    name,
+                    email,
+                    role,
+                    photo,
+                    bio,
+                    is_verified,
+                    locale
+                FROM
+                    users
+                ORDER BY
+                    id
+                LIMIT $1
+                OFFSET $2;
+            "#,
+            &[&per_page, &pagination.offset()]
+        ).await?;
+
+        Ok(UserListResponse {
+            users: rows.iter().map(row_to_user).collect(),
+            total,
+            page: pagination.page(),
+            per_page,
+            total_pages: pagination.total_pages(total),
+        })
+    }
+
+    /// ユーザー削除
+    ///
+    /// タスク・トークン・セッションは外部キーの `ON DELETE CASCADE` で削除されます。
+    async fn delete_user(&self, user_id: i32) -> Result<bool, UserError> {
+        let conn = self.pool.get().await?;
+
+        let deleted = conn.execute(
+            r#"
+                DELETE FROM
+                    users
+                WHERE
+                    id = $1;
+            "#,
+            &[&user_id]
+        ).await?;
+
+        Ok(deleted == 1)
+    }
+
+    /// ロール変更
+    ///
+    /// ロールを更新し、変更前後のロールと変更したユーザーを監査ログに記録します。
+    /// 変更前と同じロールの場合は監査ログを記録しません。
+    async fn update_role(&self, user_id: i32, role: Role, changed_by: i32) -> Result<Option<UserResponse>, UserError> {
+        let mut conn = self.pool.get().await?;
+        let tx = conn.transaction().await?;
+
+        let Some(current) = tx.query_opt(
+            r#"
+                SELECT
+                    role
+                FROM
+                    users
+                WHERE
+                    id = $1
+                FOR UPDATE;
+            "#,
+            &[&user_id]
+        ).await? else {
+            return Ok(None);
+        };
+        let old_role: String = current.get("role");
+
+        let row = tx.query_one(
+            r#"
+                UPDATE
+                    users
+                SET
+                    role = $2,
+                    updated_at = CURRENT_TIMESTAMP
+                WHERE
+                    id = $1
+                RETURNING
+                    id,
+                    name,
+                    email,
+                    role,
+                    photo,
+                    bio,
+                    is_verified,
+                    locale;
+            "#,
+            &[&user_id, &role.as_str()]
+        ).await?;
+
+        if old_role != role.as_str() {
+            tx.execute(
+                r#"
+                    INSERT INTO role_audit_logs (
+                        user_id,
+                        changed_by,
+                        old_role,
+                        new_role
+                    ) VALUES (
+                        $1,
+                        $2,
+                        $3,
+                        $4
+                    );
+                "#,
+                &[&user_id, &changed_by, &old_role, &role.as_str()]
+            ).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(Some(row_to_user(&row)))
+    }
 }
\ No newline at end of file
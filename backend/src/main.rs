@@ -1,12 +1,28 @@
 use actix_cors::Cors;
-use actix_web::{web::Data, App, HttpServer};
+use actix_web::{web::{self, Data, JsonConfig, PathConfig, QueryConfig}, App, HttpServer};
 use dotenvy::dotenv;
 use std::env;
+use std::time::Duration;
 
-use application::middlewares::jwt_middleware::JwtMiddleware;
+use application::errors::api_error::bad_request_handler;
+use application::helpers::logger;
+use application::helpers::password::PasswordHasher;
+use application::i18n::catalogue::missing_messages;
+use application::jwt::jwt_keys::JwtKeys;
+use application::metrics::registry as metrics;
+use application::middlewares::locale_middleware::LocaleMiddleware;
+use application::middlewares::metrics_middleware::MetricsMiddleware;
+use application::middlewares::request_id_middleware::RequestIdMiddleware;
+use application::oidc::oidc_client::OidcProviders;
 use application::states::app_state::AppState;
+use application::workers::{guest_cleanup_worker, login_protection_worker, outbox_worker, supervisor::shutdown_signal, task_reminder_worker};
+use infrastructure::config::app_config::{self, AppConfig};
+use infrastructure::config::log_config::LogConfig;
+use infrastructure::config::mail_config::MailConfig;
 use infrastructure::db::connection::get_db_pool;
-use presentation::routes::api_routes::api_scopes;
+use infrastructure::db::migration;
+use infrastructure::mail::transport::create_mailer;
+use presentation::routes::api_routes::{api_scopes, handler, health_scope, metrics_routes, well_known_scope};
 
 mod application;
 mod domain;
@@ -16,44 +32,143 @@ mod tests;
 
 extern crate num_cpus;
 
-const PROJECT_PATH: &'static str = env!("CARGO_MANIFEST_DIR");
+const PROJECT_PATH: &str = env!("CARGO_MANIFEST_DIR");
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // 初期設定
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     dotenv().ok();
+    // 設定は起動時に全て読み込んで検証し、エラーがある場合はまとめて出力して起動しない
+    let config = match AppConfig::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    if env::args().nth(1).as_deref() == Some("--print-config") {
+        print!("{}", config.to_redacted_toml());
+        return Ok(());
+    }
+    let config = app_config::install(config);
 
-    let host: &str = &env::var("HOST_NAME").expect("環境変数 `HOST_NAME` は設定する必要があります。");
-    let backend_port: &str = &env::var("BACKEND_PORT").expect("環境変数 `BACKEND_PORT` は設定する必要があります。");
-    let uri = format!("{}:{}", host, backend_port);
+    // ロガーのガードは終了時に未出力のログを書き込むため、`main` の終了まで保持する
+    let _log_guards = logger::init(&LogConfig::from_settings(&config.log));
 
-    let pool = get_db_pool().await;
-    let app_state = AppState::init(&pool);
+    let uri = format!("{}:{}", config.server.host, config.server.port);
 
-    let cors_max_age: usize = env::var("CORS_MAX_AGE")
-        .unwrap_or_else(|_| "3600".to_string())
-        .parse()
-        .expect("環境変数 `CORS_MAX_AGE` は正しい整数値で設定する必要があります。");
+    metrics::init();
+    let pool = get_db_pool(&config.database).await;
+
+    // マイグレーション
+    // コマンドライン引数で指定された場合は、マイグレーションのみ実行して終了
+    match env::args().nth(1).as_deref() {
+        Some("--migrate") => {
+            migration::run_pending(&pool).await.map_err(std::io::Error::other)?;
+            return Ok(());
+        }
+        Some("--rollback") => {
+            migration::rollback_last(&pool).await.map_err(std::io::Error::other)?;
+            return Ok(());
+        }
+        Some("--migration-status") => {
+            for status in migration::status(&pool).await.map_err(std::io::Error::other)? {
+                println!("{:>4} {} {}", status.version, if status.applied { "applied" } else { "pending" }, status.name);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // `server.auto_migrate = false`（`AUTO_MIGRATE=false`）の場合は起動時に適用しない
+    if config.server.auto_migrate {
+        migration::run_pending(&pool).await.map_err(std::io::Error::other)?;
+    }
+
+    // 翻訳が不足しているメッセージは既定の言語で返すため、起動時に警告のみ出力
+    for (locale, key) in missing_messages() {
+        warning_log!("[main] - [main] message: missing message, locale = {}, key = {}", locale.as_str(), key.key());
+    }
+
+    // JWT の鍵は起動時に一度だけ読み込む
+    let jwt_keys = JwtKeys::from_settings(&config.jwt).map_err(std::io::Error::other)?;
+    let password_hasher = PasswordHasher::from_settings(&config.password).map_err(std::io::Error::other)?;
+    let oidc_providers = OidcProviders::from_settings(&config.oidc, &config.app.url).map_err(std::io::Error::other)?;
+    // メールの送信方法は起動時に決定する
+    let mail_config = MailConfig::from_settings(&config.mail).map_err(std::io::Error::other)?;
+    let mailer = create_mailer(&mail_config).map_err(std::io::Error::other)?;
+    let app_state = AppState::init(config.clone(), &pool, jwt_keys, password_hasher, oidc_providers, mailer);
+
+    // バックグラウンドジョブ
+    // メールはリクエストと同じトランザクションで登録し、送信はメール送信キューのワーカーが行う
+    let supervisor = app_state.supervisor.clone();
+    let (outbox_repository, mailer) = (app_state.outbox_repository.clone(), app_state.mailer.clone());
+    let outbox_config = outbox_worker::OutboxWorkerConfig::from_settings(&config.outbox);
+    supervisor.spawn("outbox_worker", move |context| outbox_worker::run(context, outbox_repository, mailer, outbox_config));
+    let outbox_repository = app_state.outbox_repository.clone();
+    let reminder_config = task_reminder_worker::TaskReminderWorkerConfig::from_settings(&config.task_reminder);
+    supervisor.spawn("task_reminder_worker", move |context| task_reminder_worker::run(context, outbox_repository, reminder_config));
+    let login_guard_service = app_state.login_guard_service.clone();
+    supervisor.spawn("login_protection_worker", move |context| login_protection_worker::run(context, login_guard_service));
+    let (auth_service, guest_cleanup_interval) = (app_state.auth_service.clone(), Duration::from_secs(config.guest.cleanup_interval_secs));
+    supervisor.spawn("guest_cleanup_worker", move |context| guest_cleanup_worker::run(context, auth_service, guest_cleanup_interval));
+
+    let cors_max_age = config.server.cors_max_age;
+    let cors_origins = config.cors_origins();
 
     // Web サーバー起動
-    HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
+    // シグナルは `shutdown_signal` で受け取り、ジョブと同時に停止する
+    let server_pool = pool.clone();
+    let server = HttpServer::new(move || {
+        // クッキーで認証するため、許可したオリジンのみクッキー付きのリクエストを受け付ける
+        let cors = cors_origins.iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
             .supports_credentials()
             .allowed_methods(vec!["GET", "PUT", "PATCH", "POST", "DELETE"])
-            .allowed_headers(vec!["Authorization", "Content-Type"])
+            .allowed_headers(vec!["Authorization", "Content-Type", "X-Request-Id"])
+            .expose_headers(vec!["X-Request-Id"])
             .max_age(cors_max_age);
 
         App::new()
-            .wrap(JwtMiddleware)
+            .wrap(LocaleMiddleware)
             .wrap(cors)
-            .app_data(Data::new(pool.clone()))
+            .wrap(MetricsMiddleware)
+            .wrap(RequestIdMiddleware)
+            .app_data(Data::new(server_pool.clone()))
             .app_data(Data::new(app_state.clone()))
+            .app_data(JsonConfig::default().error_handler(bad_request_handler))
+            .app_data(QueryConfig::default().error_handler(bad_request_handler))
+            .app_data(PathConfig::default().error_handler(bad_request_handler))
+            .configure(metrics_routes)
+            .service(health_scope())
+            .service(well_known_scope())
             .service(api_scopes())
+            .default_service(web::route().to(handler))
     })
     .bind(uri)?
     .workers(num_cpus::get())
-    .run()
-    .await
+    .disable_signals()
+    .shutdown_timeout(config.server.shutdown_timeout_secs)
+    .run();
+
+    // 停止のシグナルを受け取ったら、新しい接続の受け付けを止め、処理中のリクエストの完了を待つ
+    let server_handle = server.handle();
+    let signal_supervisor = supervisor.clone();
+    actix_web::rt::spawn(async move {
+        shutdown_signal().await;
+        signal_supervisor.shutdown();
+        server_handle.stop(true).await;
+    });
+
+    let result = server.await;
+
+    // 残りの期限までジョブの終了を待ってから接続プールを破棄する
+    // `bb8` にはプールを閉じる API がなく、`AppState`・ジョブが保持する参照を含め全て破棄した時点で接続を閉じる
+    if !supervisor.wait().await {
+        warning_log!("[main] - [main] message: some jobs were aborted at shutdown");
+    }
+    drop(pool);
+    info_log!("[main] - [main] shutdown complete");
+
+    result
 }
\ No newline at end of file
@@ -0,0 +1,185 @@
+//! # 管理者ハンドラー
+//!
+//! ユーザー・ログインの失敗の履歴の管理（`ManageUsers` / `ManageRoles` の権限が必要）
+//! メール送信キューの管理（`ManageMail` の権限が必要）
+//! バックグラウンドジョブの状態の確認（`ManageJobs` の権限が必要）
+//!
+//! ## 関数
+//!
+//! - `get_users`:          ユーザー一覧
+//! - `delete_user`:        ユーザー削除
+//! - `change_role`:        ロール変更
+//! - `get_login_failures`: ログインの失敗の履歴
+//! - `get_mail_outbox`:    メール送信キュー一覧
+//! - `requeue_mail`:       メール再送信
+//! - `get_jobs`:           バックグラウンドジョブ一覧
+
+use actix_web::{web, HttpResponse};
+use validator::Validate;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::application::states::app_state::AppState;
+use crate::domain::entities::job::JobListResponse;
+use crate::domain::entities::login_protection::LoginFailureListQuery;
+use crate::domain::entities::outbox::{OutboxListQuery, OutboxPath};
+use crate::domain::entities::pagination::Pagination;
+use crate::domain::entities::user::{ChangeRoleRequest, UserPath};
+use crate::{app_log, info_log, success_log};
+
+/// ユーザー一覧
+/// 
+/// 全てのユーザーを ID 順にページ分割して取得します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(users)`             - ユーザー一覧と総件数を返します。
+/// - `BadRequest()`          - クエリパラメータが不正な場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_users(
+    pagination: web::Query<Pagination>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [get_users] get_users called");
+
+    pagination.validate()?;
+
+    let users = app_state.user_service.get_users(&pagination).await?;
+
+    Ok(HttpResponse::Ok().json(users))
+}
+
+/// ユーザー削除
+/// 
+/// ユーザーと、そのユーザーのタスク・セッションを削除します。
+/// 
+/// # 戻り値
+/// 
+/// - `NoContent()`           - ユーザーを削除した場合。
+/// - `BadRequest()`          - 自分自身を削除しようとした場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - ユーザーが見つからない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn delete_user(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<UserPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [delete_user] delete_user called");
+
+    app_state.user_service.delete_user(&claims, path.id).await?;
+
+    success_log!("[admin_handler] - [delete_user] message: User deleted");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// ロール変更
+/// 
+/// ユーザーのロールを変更し、監査ログに記録します。変更されたユーザーのアクセストークンは無効になります。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(user)`              - 変更後のユーザー情報を返します。
+/// - `BadRequest()`          - 自分自身のロールを変更しようとした場合、またはゲストを指定した場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - ユーザーが見つからない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn change_role(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<UserPath>,
+    role_req: web::Json<ChangeRoleRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [change_role] change_role called");
+    role_req.validate()?;
+
+    let user = app_state.user_service.change_role(&claims, path.id, role_req.role).await?;
+
+    success_log!("[admin_handler] - [change_role] message: Role changed");
+    Ok(HttpResponse::Ok().json(user))
+}
+
+/// ログインの失敗の履歴
+/// 
+/// ログインの失敗を新しい順にページ分割して取得します。`email` / `ip_address` で絞り込めます。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(entries)`           - 失敗の履歴と総件数を返します。
+/// - `BadRequest()`          - クエリパラメータが不正な場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_login_failures(
+    query: web::Query<LoginFailureListQuery>,
+    pagination: web::Query<Pagination>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [get_login_failures] get_login_failures called");
+
+    pagination.validate()?;
+
+    let entries = app_state.login_guard_service.get_failures(&query, &pagination).await?;
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// メール送信キュー一覧
+/// 
+/// メール送信キューのメールを新しい順にページ分割して取得します。`status` で状態を絞り込めます。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(entries)`           - メール一覧と総件数を返します。
+/// - `BadRequest()`          - クエリパラメータが不正な場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_mail_outbox(
+    query: web::Query<OutboxListQuery>,
+    pagination: web::Query<Pagination>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [get_mail_outbox] get_mail_outbox called");
+
+    pagination.validate()?;
+
+    let entries = app_state.outbox_service.get_entries(&query, &pagination).await?;
+
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// メール再送信
+/// 
+/// 再送信の上限に達したメールを再送信待ちに戻します。
+/// 
+/// # 戻り値
+/// 
+/// - `NoContent()`           - 再送信待ちに戻した場合。
+/// - `NotFound()`            - メールが見つからない、または再送信の上限に達していない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn requeue_mail(
+    path: web::Path<OutboxPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [requeue_mail] requeue_mail called");
+
+    app_state.outbox_service.requeue(path.id).await?;
+
+    success_log!("[admin_handler] - [requeue_mail] message: Mail requeued");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// バックグラウンドジョブ一覧
+/// 
+/// 起動したバックグラウンドジョブの状態・実行回数・最後のエラーを取得します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(jobs)` - 停止中かどうかとジョブ一覧を返します。
+pub async fn get_jobs(
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[admin_handler] - [get_jobs] get_jobs called");
+
+    let supervisor = &app_state.supervisor;
+    let jobs = JobListResponse { shutting_down: supervisor.is_shutting_down(), jobs: supervisor.statuses() };
+
+    Ok(HttpResponse::Ok().json(jobs))
+}
\ No newline at end of file
@@ -1,95 +1,350 @@
 //! # 認証ハンドラー
 //!
 //! 未認証のユーザーのアクセスを許可しているハンドラー
+//! エラーは `ApiError` の problem+json で返す
 //!
 //! ## 関数
 //!
-//! `guest_login`  - ゲストログイン
-//! `signup`       - 新規登録
-//! `login`        - ログイン
-//! `current_user` - 認証済みユーザーチェック
+//! `guest_login`      - ゲストログイン
+//! `convert_guest`    - ゲストのアカウント登録
+//! `signup`           - 新規登録
+//! `login`            - ログイン
+//! `verify_mfa_login` - 2段階認証のコードを検証してログインを完了
+//! `current_user`     - 認証済みユーザーチェック
+//! `logout_user`      - ログアウト（セッションを失効）
+//! `refresh_session`  - トークン再発行
+//! `verify_email`     - メール認証リンクの再送信
+//! `verify_user`      - メール認証
+//! `forgot_password`  - パスワードリセットリンクの送信
+//! `reset_password`   - パスワードリセット
 
-use actix_web::{HttpResponse, Responder, web};
-use postgres::error::SqlState;
+use actix_web::{HttpRequest, HttpResponse, ResponseError, web};
 use serde_json::json;
 use validator::Validate;
+use crate::application::errors::api_error::ApiError;
 use crate::application::errors::auth_error::AuthError;
-use crate::application::helpers::cookie::{clear_cookie, create_cookie};
+use crate::application::helpers::cookie::{clear_cookie, clear_refresh_cookie, create_cookie, create_refresh_cookie};
+use crate::application::helpers::request::{refresh_token, session_meta};
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
 use crate::application::states::app_state::AppState;
-use crate::domain::entities::auth::{LoginRequest, SignupRequest};
+use crate::domain::entities::auth::{LoginOutcome, LoginRequest, ResetPasswordPath, SignupRequest, VerifyEmailPath};
+use crate::domain::entities::mfa::MfaLoginRequest;
+use crate::domain::entities::session::{RefreshRequest, RefreshResponse};
+use crate::domain::entities::user::{ForgotPasswordRequest, ResetPasswordRequest};
+use crate::domain::enums::role::Role;
 use crate::{app_log, info_log, error_log, success_log};
 
-pub async fn register_user(
+/// ゲストログイン
+///
+/// メールアドレス・パスワードなしでゲストユーザーを作成し、セッションを開始します。
+/// ゲストのデータは `expires_at` までにアカウント登録しない場合に削除されます。
+///
+/// # 戻り値
+///
+/// - `Created(guest)`        - 作成したゲストとトークン、有効期限を返します。
+/// - `Forbidden()`           - ゲストログインが無効な場合。
+/// - `TooManyRequests()`     - 接続元の試行回数の上限を超えた場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn guest_login(
+    http_req: HttpRequest,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [guest_login] guest_login called");
+
+    let (guest, tokens) = app_state.auth_service.guest_login(&session_meta(&http_req)).await?;
+
+    success_log!("[auth_controller] - [guest_login] message: Guest logged in successfully");
+    Ok(HttpResponse::Created()
+        .cookie(create_cookie(tokens.access_token))
+        .cookie(create_refresh_cookie(tokens.refresh_token))
+        .json(guest))
+}
+
+/// ゲストのアカウント登録
+///
+/// ログイン中のゲストに名前・メールアドレス・パスワードを設定し、一般ユーザーにします。
+/// ゲストで作成したデータは引き継がれます。新規登録と同じく認証メールを送信し、新しいセッションを開始します。
+///
+/// # 戻り値
+///
+/// - `Created(user)`         - 登録したユーザーとトークンを返します。
+/// - `BadRequest()`          - 入力値が不正な場合。`errors` に項目ごとの詳細を返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `Conflict()`            - ゲストではない・有効期限切れの場合、またはメールアドレスが登録済みの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn convert_guest(
+    http_req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
     req: web::Json<SignupRequest>,
     app_state: web::Data<AppState>
-) -> impl Responder {
-    info_log!("[auth_handler] - [register_user] register_user called");
-    if let Err(_validation_errors) = req.validate() {
-        return HttpResponse::BadRequest().finish();
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [convert_guest] convert_guest called");
+    if claims.role != Role::Guest {
+        return Err(ApiError::NotGuest);
     }
+    req.validate()?;
 
-    let auth_service = &app_state.auth_service;
+    let (user, tokens) = app_state.auth_service.convert_guest(claims.id, &req, &session_meta(&http_req)).await?;
 
-    match auth_service.register_user(&req).await {
-        Ok((signed_user, token)) => {
-            // クッキー生成            
-            let token_cookie = create_cookie(token);
+    success_log!("[auth_controller] - [convert_guest] message: Guest converted successfully");
+    Ok(HttpResponse::Created()
+        .cookie(create_cookie(tokens.access_token))
+        .cookie(create_refresh_cookie(tokens.refresh_token))
+        .json(user))
+}
 
-            success_log!("[auth_controller] - [register_user] message: Signed up successfully");
-            HttpResponse::Created().cookie(token_cookie).json(signed_user)
-        }
-        Err(AuthError::DatabaseError(ref error)) => {
-            if let Some(db_error) = error.as_db_error() {
-                if db_error.code() == &SqlState::UNIQUE_VIOLATION {
-                    error_log!("[auth_controller] - [register_user] message: db_error = {}", db_error);
-                    return HttpResponse::Conflict().finish();
-                }
-            }
+/// 新規登録
+///
+/// ユーザーを作成し、セッションを開始します。
+///
+/// # 戻り値
+///
+/// - `Created(user)`         - 作成したユーザーとトークンを返します。
+/// - `BadRequest()`          - 入力値が不正な場合。`errors` に項目ごとの詳細を返します。
+/// - `Conflict()`            - メールアドレスが登録済みの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn register_user(
+    http_req: HttpRequest,
+    req: web::Json<SignupRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [register_user] register_user called");
+    req.validate()?;
 
-            error_log!("[auth_controller] - [register_user] message: error = {}", error);
-            HttpResponse::InternalServerError().finish()
-        }
-        Err(auth_error) => {
-            error_log!("[auth_controller] - [register_user] message: auth_error = {}", auth_error);
-            HttpResponse::InternalServerError().finish()
-        }
-    }
+    let (signed_user, tokens) = app_state.auth_service.register_user(&req, &session_meta(&http_req)).await?;
+
+    success_log!("[auth_controller] - [register_user] message: Signed up successfully");
+    Ok(HttpResponse::Created()
+        .cookie(create_cookie(tokens.access_token))
+        .cookie(create_refresh_cookie(tokens.refresh_token))
+        .json(signed_user))
 }
 
+/// ログイン
+///
+/// メールアドレスとパスワードを検証し、セッションを開始します。
+/// 2段階認証が有効なユーザーはセッションを開始せず、`/auth/login/mfa` に送信するチャレンジのトークンを返します。
+///
+/// # 戻り値
+///
+/// - `Created(user)`         - ユーザーとトークンを返します。
+/// - `Ok(challenge)`         - 2段階認証が有効な場合。`mfa_required` と `mfa_token` を返します。
+/// - `BadRequest()`          - 入力値が不正な場合。
+/// - `Unauthorized()`        - メールアドレスまたはパスワードが正しくない場合。
+/// - `TooManyRequests()`     - 接続元の試行回数の上限を超えた、またはアカウントがロックされている場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn login_user(
+    http_req: HttpRequest,
     req: web::Json<LoginRequest>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     info_log!("[auth_handler] - [login_user] login_user called");
-    if let Err(_validation_errors) = req.validate() {
-        return HttpResponse::BadRequest().finish();
+    req.validate()?;
+
+    match app_state.auth_service.login_user(&req, &session_meta(&http_req)).await? {
+        LoginOutcome::Authenticated(user_data, tokens) => {
+            success_log!("[auth_controller] - [login_user] message: Logged in successfully");
+            Ok(HttpResponse::Created()
+                .cookie(create_cookie(tokens.access_token))
+                .cookie(create_refresh_cookie(tokens.refresh_token))
+                .json(user_data))
+        }
+        LoginOutcome::MfaRequired(challenge) => Ok(HttpResponse::Ok().json(challenge)),
     }
+}
 
-    let auth_service = &app_state.auth_service;
+/// 2段階認証のログイン
+///
+/// ログインで返したチャレンジのトークンと、認証アプリのコード（またはリカバリーコード）を検証し、セッションを開始します。
+///
+/// # 戻り値
+///
+/// - `Created(user)`         - ユーザーとトークンを返します。
+/// - `BadRequest()`          - チャレンジが無効・期限切れ・入力回数の上限に達した場合、またはコードが正しくない場合。
+/// - `TooManyRequests()`     - 接続元の試行回数の上限を超えた場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn verify_mfa_login(
+    http_req: HttpRequest,
+    req: web::Json<MfaLoginRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [verify_mfa_login] verify_mfa_login called");
+    req.validate()?;
 
-    info_log!("[auth_handler] - [login_user] before processing auth_service.login_user");
-    match auth_service.login_user(&req).await {
-        Ok((user_data, token)) => {
-            // クッキー生成            
-            let token_cookie = create_cookie(token);
+    let (user_data, tokens) = app_state.auth_service.verify_mfa_login(&req, &session_meta(&http_req)).await?;
 
-            success_log!("[auth_controller] - [login_user] message: Logged in successfully");
-            HttpResponse::Created().cookie(token_cookie).json(user_data)
-        }
-        Err(auth_error) => {
-            error_log!("[auth_controller] - [login] message: auth_error = {}", auth_error);
-            
-            if matches!(auth_error, AuthError::UserNotFound) {
-                HttpResponse::NotFound().finish()
-            } else {
-                HttpResponse::InternalServerError().finish()
+    success_log!("[auth_controller] - [verify_mfa_login] message: Logged in successfully");
+    Ok(HttpResponse::Created()
+        .cookie(create_cookie(tokens.access_token))
+        .cookie(create_refresh_cookie(tokens.refresh_token))
+        .json(user_data))
+}
+
+/// ログアウト
+///
+/// リフレッシュトークン（クッキー）またはアクセストークンのセッションを失効させ、クッキーを削除します。
+pub async fn logout_user(
+    req: HttpRequest,
+    user: Option<AuthenticatedUser>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [logout_user] logout_user called");
+
+    let refresh_token = refresh_token(&req, None);
+    let claims = user.map(AuthenticatedUser::into_inner);
+
+    app_state.auth_service.logout(refresh_token.as_deref(), claims.as_ref()).await?;
+
+    Ok(HttpResponse::Ok()
+        .cookie(clear_cookie())
+        .cookie(clear_refresh_cookie())
+        .json(json!({ "message": t(current_locale(), MessageKey::AuthLoggedOut) })))
+}
+
+/// トークン再発行
+///
+/// リフレッシュトークン（リクエストボディまたはクッキー）を新しいトークンに入れ替え、アクセストークンを再発行します。
+/// 使用済みのリフレッシュトークンが使われた場合は、そのセッションを失効させます。
+///
+/// クッキーのリフレッシュトークンを使用した場合は、新しいトークンをクッキーのみで返し、レスポンスボディには含めません。
+/// 他のサイトのスクリプトがクッキー付きのリクエストでトークンを読み取れないようにするためです。
+///
+/// # 戻り値
+///
+/// - `Ok(tokens)`            - リクエストボディで指定した場合は、新しいアクセストークンとリフレッシュトークンを返します。
+/// - `Unauthorized()`        - リフレッシュトークンが無効、期限切れ、または再利用された場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn refresh_session(
+    req: HttpRequest,
+    body: Option<web::Json<RefreshRequest>>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [refresh_session] refresh_session called");
+
+    let body_token = body.and_then(|body| body.into_inner().refresh_token);
+    let from_cookie = body_token.is_none();
+    let Some(token) = refresh_token(&req, body_token) else {
+        return Err(ApiError::Unauthorized);
+    };
+
+    match app_state.auth_service.refresh_session(&token, &session_meta(&req)).await {
+        Ok(tokens) if from_cookie => Ok(HttpResponse::Ok()
+            .cookie(create_cookie(tokens.access_token))
+            .cookie(create_refresh_cookie(tokens.refresh_token))
+            .json(json!({ "message": t(current_locale(), MessageKey::AuthSessionRefreshed) }))),
+        Ok(tokens) => Ok(HttpResponse::Ok()
+            .cookie(create_cookie(tokens.access_token.clone()))
+            .cookie(create_refresh_cookie(tokens.refresh_token.clone()))
+            .json(RefreshResponse {
+                token: tokens.access_token,
+                refresh_token: tokens.refresh_token,
+            })),
+        Err(AuthError::InvalidToken) | Err(AuthError::UserNotFound) => {
+            // 無効なリフレッシュトークンはクッキーごと削除
+            let mut response = ApiError::Unauthorized.error_response();
+            for cookie in [clear_cookie(), clear_refresh_cookie()] {
+                if let Err(error) = response.add_cookie(&cookie) {
+                    error_log!("[auth_controller] - [refresh_session] message: error = {}", error);
+                }
             }
+            Ok(response)
         }
+        Err(auth_error) => Err(auth_error.into()),
     }
 }
 
-pub async fn logout_user() -> impl Responder {
-    info_log!("[auth_handler] - [logout_user] logout_user called");
-    HttpResponse::Ok().cookie(clear_cookie()).json(json!({ "message": "User logged out"}))
+/// メール認証リンクの再送信
+///
+/// 認証済みユーザーにメール認証リンクを再送信します。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - メールを送信した場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - ユーザーが見つからない場合。
+/// - `Conflict()`            - メールアドレスが認証済みの場合。
+/// - `TooManyRequests()`     - 再送信の待機時間中の場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn verify_email(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [verify_email] verify_email called");
+
+    app_state.auth_service.resend_verification_email(claims.id).await?;
+
+    success_log!("[auth_controller] - [verify_email] message: Verification email sent");
+    Ok(HttpResponse::Ok().json(json!({ "message": t(current_locale(), MessageKey::AuthVerificationEmailSent) })))
+}
+
+/// メール認証
+///
+/// メールで送信したトークンを検証し、ユーザーを認証済みにします。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - 認証に成功した場合。
+/// - `BadRequest()`          - トークンが無効、または期限切れの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn verify_user(
+    path: web::Path<VerifyEmailPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [verify_user] verify_user called");
+
+    app_state.auth_service.verify_email(&path.verification_token).await?;
+
+    success_log!("[auth_controller] - [verify_user] message: Email verified");
+    Ok(HttpResponse::Ok().json(json!({ "message": t(current_locale(), MessageKey::AuthEmailVerified) })))
+}
+
+/// パスワードリセットリンクの送信
+///
+/// 登録済みのメールアドレスの場合のみリンクを送信しますが、
+/// ユーザーの存在有無が分からないよう、常に同じレスポンスを返します。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - リクエストを受け付けた場合。
+/// - `BadRequest()`          - メールアドレスが不正な場合。
+/// - `TooManyRequests()`     - 接続元・メールアドレスの試行回数の上限を超えた場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn forgot_password(
+    req: web::Json<ForgotPasswordRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [forgot_password] forgot_password called");
+    req.validate()?;
+
+    app_state.auth_service.forgot_password(&req.email).await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": t(current_locale(), MessageKey::AuthResetLinkSent)
+    })))
 }
 
+/// パスワードリセット
+///
+/// メールで送信したトークンを検証し、パスワードを更新します。
+/// リセット前に発行されたトークンは全て無効になるため、クッキーも削除します。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - パスワードを更新した場合。
+/// - `BadRequest()`          - パスワードが不正、またはトークンが無効・期限切れの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn reset_password(
+    path: web::Path<ResetPasswordPath>,
+    req: web::Json<ResetPasswordRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[auth_handler] - [reset_password] reset_password called");
+    req.validate()?;
+
+    app_state.auth_service.reset_password(&path.reset_password_token, &req.password).await?;
+
+    success_log!("[auth_controller] - [reset_password] message: Password reset");
+    Ok(HttpResponse::Ok().cookie(clear_cookie()).json(json!({ "message": t(current_locale(), MessageKey::AuthPasswordReset) })))
+}
\ No newline at end of file
@@ -1,9 +1,47 @@
-use actix_web::{HttpResponse, Responder};
+//! # ヘルスチェックハンドラー
+//!
+//! ## 関数
+//!
+//! - `healthcheck`: 簡易ヘルスチェック（互換性のため残す。依存先はチェックしない）
+//! - `live`:        生存確認
+//! - `ready`:       準備完了確認（依存先が1つでも利用できなければ 503）
+
+use actix_web::{web, HttpResponse, Responder};
 use serde_json::json;
 
+use crate::application::states::app_state::AppState;
+use crate::domain::entities::health::HealthStatus;
 use crate::{app_log, success_log};
 
 pub async fn healthcheck() -> impl Responder {
     success_log!("API is healthy");
     HttpResponse::Ok().json(json!({"message": "healthy"}))
+}
+
+/// 生存確認
+/// 
+/// プロセスが応答できるかのみ確認します。依存先の障害では失敗しません。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(report)` - 状態とビルド情報を返します。
+pub async fn live(app_state: web::Data<AppState>) -> HttpResponse {
+    HttpResponse::Ok().json(app_state.health_service.liveness())
+}
+
+/// 準備完了確認
+/// 
+/// DB・マイグレーション・メール送信をチェックします。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(report)`                 - 全ての依存先を利用できる場合、依存先ごとの結果とビルド情報を返します。
+/// - `ServiceUnavailable(report)` - 依存先が1つでも利用できない場合。
+pub async fn ready(app_state: web::Data<AppState>) -> HttpResponse {
+    let report = app_state.health_service.readiness().await;
+
+    match report.status {
+        HealthStatus::Ok => HttpResponse::Ok().json(report),
+        HealthStatus::Degraded => HttpResponse::ServiceUnavailable().json(report),
+    }
 }
\ No newline at end of file
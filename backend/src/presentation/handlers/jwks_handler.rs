@@ -0,0 +1,25 @@
+//! # JWKS ハンドラー
+//!
+//! ## 関数
+//!
+//! - `jwks`: JWT の検証に使用する公開鍵の一覧
+
+use actix_web::{http::header, web, HttpResponse, Responder};
+
+use crate::application::states::app_state::AppState;
+use crate::{app_log, info_log};
+
+/// JWT の検証に使用する公開鍵の一覧
+/// 
+/// ローテーション中の旧鍵を含む、RS256 / EdDSA の公開鍵を JWK Set で返します。HS256 の共通鍵は含みません。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(jwks)` - JWK Set を返します。
+pub async fn jwks(app_state: web::Data<AppState>) -> impl Responder {
+    info_log!("[jwks_handler] - [jwks] jwks called");
+
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(app_state.jwt_keys.jwks())
+}
\ No newline at end of file
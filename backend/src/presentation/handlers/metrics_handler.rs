@@ -0,0 +1,50 @@
+//! # メトリクスハンドラー
+//!
+//! `[metrics]` の `token`（環境変数 `METRICS_TOKEN`）を設定した場合は、`Authorization: Bearer {token}` のリクエストのみ許可する
+//!
+//! ## 関数
+//!
+//! - `metrics`: Prometheus 形式のメトリクス
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use ring::constant_time::verify_slices_are_equal;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::metrics::registry::render;
+use crate::application::states::app_state::AppState;
+use crate::infrastructure::db::connection::DbPool;
+
+/// Prometheus のテキスト形式
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Prometheus 形式のメトリクス
+/// 
+/// 取得時点の DB 接続プールの状態を記録してから出力します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(metrics)`    - メトリクスを返します。
+/// - `Unauthorized()` - トークンが設定されていて、一致しない場合。
+pub async fn metrics(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    if let Some(expected) = &app_state.config.metrics.token {
+        let token = req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+
+        if verify_slices_are_equal(token.as_bytes(), expected.as_bytes()).is_err() {
+            return Err(ApiError::Unauthorized);
+        }
+    }
+
+    pool.record_state();
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, CONTENT_TYPE))
+        .body(render()))
+}
\ No newline at end of file
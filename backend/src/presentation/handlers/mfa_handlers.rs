@@ -0,0 +1,142 @@
+//! # 2段階認証ハンドラー
+//!
+//! 認証アプリ（TOTP）の登録・無効化と、リカバリーコードの再発行
+//!
+//! ## 関数
+//!
+//! - `get_mfa_status`:            2段階認証の状態
+//! - `setup_totp`:                TOTP の登録を開始（シークレットと URI を発行）
+//! - `confirm_totp`:              コードを確認して TOTP を有効化
+//! - `disable_totp`:              パスワードを確認して TOTP を無効化
+//! - `regenerate_recovery_codes`: パスワードを確認してリカバリーコードを再発行
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde_json::json;
+use validator::Validate;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::helpers::request::session_meta;
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::application::states::app_state::AppState;
+use crate::domain::entities::mfa::{PasswordConfirmRequest, TotpCodeRequest};
+use crate::{app_log, info_log, success_log};
+
+/// 2段階認証の状態
+///
+/// # 戻り値
+///
+/// - `Ok(status)`            - 有効かどうかと、未使用のリカバリーコードの数を返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_mfa_status(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[mfa_handler] - [get_mfa_status] get_mfa_status called");
+
+    let status = app_state.auth_service.get_mfa_status(claims.id).await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// TOTP の登録を開始
+///
+/// 新しいシークレットを発行します。`confirm_totp` でコードを確認するまで、2段階認証は有効になりません。
+///
+/// # 戻り値
+///
+/// - `Ok(setup)`             - シークレットと `otpauth://` URI を返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `Conflict()`            - 既に2段階認証が有効な場合、またはパスワードを設定していない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn setup_totp(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[mfa_handler] - [setup_totp] setup_totp called");
+
+    let setup = app_state.auth_service.setup_totp(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(setup))
+}
+
+/// コードを確認して TOTP を有効化
+///
+/// 認証アプリのコードが正しい場合に2段階認証を有効にし、リカバリーコードを発行します。
+///
+/// # 戻り値
+///
+/// - `Ok(recovery_codes)`    - リカバリーコードを返します（この時のみ表示されます）。
+/// - `BadRequest()`          - コードが正しくない場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `Conflict()`            - 登録を開始していない、または既に有効な場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn confirm_totp(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    req: web::Json<TotpCodeRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[mfa_handler] - [confirm_totp] confirm_totp called");
+    req.validate()?;
+
+    let recovery_codes = app_state.auth_service.confirm_totp(claims.id, &req.code).await?;
+
+    success_log!("[mfa_handler] - [confirm_totp] message: Two-factor authentication enabled");
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
+
+/// パスワードを確認して TOTP を無効化
+///
+/// シークレット・リカバリーコードを削除します。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - 2段階認証を無効にした場合。
+/// - `BadRequest()`          - 入力値が不正な場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない、またはパスワードが正しくない場合。
+/// - `Conflict()`            - 2段階認証が有効でない場合。
+/// - `TooManyRequests()`     - パスワードの失敗が続き、アカウントがロックされている場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn disable_totp(
+    http_req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    req: web::Json<PasswordConfirmRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[mfa_handler] - [disable_totp] disable_totp called");
+    req.validate()?;
+
+    app_state.auth_service.disable_totp(claims.id, &req.current_password, &session_meta(&http_req)).await?;
+
+    success_log!("[mfa_handler] - [disable_totp] message: Two-factor authentication disabled");
+    Ok(HttpResponse::Ok().json(json!({ "message": t(current_locale(), MessageKey::AuthMfaDisabled) })))
+}
+
+/// パスワードを確認してリカバリーコードを再発行
+///
+/// 以前のリカバリーコードは全て無効になります。
+///
+/// # 戻り値
+///
+/// - `Ok(recovery_codes)`    - 新しいリカバリーコードを返します。
+/// - `BadRequest()`          - 入力値が不正な場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない、またはパスワードが正しくない場合。
+/// - `Conflict()`            - 2段階認証が有効でない場合。
+/// - `TooManyRequests()`     - パスワードの失敗が続き、アカウントがロックされている場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn regenerate_recovery_codes(
+    http_req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    req: web::Json<PasswordConfirmRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[mfa_handler] - [regenerate_recovery_codes] regenerate_recovery_codes called");
+    req.validate()?;
+
+    let recovery_codes = app_state.auth_service.regenerate_recovery_codes(claims.id, &req.current_password, &session_meta(&http_req)).await?;
+
+    success_log!("[mfa_handler] - [regenerate_recovery_codes] message: Recovery codes regenerated");
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
\ No newline at end of file
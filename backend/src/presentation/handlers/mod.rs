@@ -1,4 +1,10 @@
+pub mod admin_handlers;
 pub mod auth_handlers;
 pub mod healthcheck_handler;
+pub mod jwks_handler;
+pub mod metrics_handler;
+pub mod mfa_handlers;
+pub mod oidc_handlers;
+pub mod session_handlers;
 pub mod task_handlers;
 pub mod user_handlers;
\ No newline at end of file
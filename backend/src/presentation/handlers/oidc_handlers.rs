@@ -0,0 +1,176 @@
+//! # OpenID Connect ハンドラー
+//!
+//! 外部の ID プロバイダーでのログインと、アカウントの連携・解除
+//!
+//! フロントエンドは認可リクエストの URL に遷移し、リダイレクト先（`{app.url}/auth/callback/{provider}`）で受け取った
+//! `code` と `state` をコールバックに送信する
+//! 認可リクエストを開始したブラウザーには `oidc_state` クッキーを設定し、コールバックはクッキーを付けて送信する必要がある
+//!
+//! ## 関数
+//!
+//! - `get_providers`:  ログインに使用できるプロバイダーの一覧
+//! - `authorize`:      ログインの認可リクエストを作成
+//! - `callback`:       認可コードを検証し、ログインまたはアカウントを連携
+//! - `link_provider`:  アカウント連携の認可リクエストを作成
+//! - `get_accounts`:   連携しているアカウントの一覧
+//! - `unlink_account`: アカウントの連携を解除
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde_json::json;
+use validator::Validate;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::helpers::cookie::{
+    clear_oidc_state_cookie, create_cookie, create_oidc_state_cookie, create_refresh_cookie, OIDC_STATE_COOKIE
+};
+use crate::application::helpers::request::session_meta;
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::application::states::app_state::AppState;
+use crate::domain::entities::auth::LoginOutcome;
+use crate::domain::entities::oidc::{OidcCallbackOutcome, OidcCallbackRequest, OidcProviderPath, OidcProvidersResponse};
+use crate::{app_log, info_log, success_log};
+
+/// ログインに使用できるプロバイダーの一覧
+///
+/// # 戻り値
+///
+/// - `Ok(providers)` - 設定されているプロバイダーの名前を返します。
+pub async fn get_providers(app_state: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [get_providers] get_providers called");
+
+    Ok(HttpResponse::Ok().json(OidcProvidersResponse { providers: app_state.oidc_service.provider_names() }))
+}
+
+/// ログインの認可リクエストを作成
+///
+/// # 戻り値
+///
+/// - `Ok(authorization)`     - 認可リクエストの URL と有効期限を返し、`oidc_state` クッキーを設定します。
+/// - `NotFound()`            - プロバイダーが設定されていない場合。
+/// - `TooManyRequests()`     - 接続元の試行回数の上限を超えた場合。
+/// - `BadGateway()`          - プロバイダーとの通信に失敗した場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn authorize(
+    path: web::Path<OidcProviderPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [authorize] authorize called");
+
+    let (authorization, binding) = app_state.oidc_service.authorize(&path.provider, None).await?;
+
+    Ok(HttpResponse::Ok().cookie(create_oidc_state_cookie(binding)).json(authorization))
+}
+
+/// 認可コードを検証し、ログインまたはアカウントを連携
+///
+/// ログインの認可リクエストの場合はセッションを開始します。連携の場合は、連携を開始したユーザーでログインしている必要があります。
+/// 認可リクエストを開始したブラウザーの `oidc_state` クッキーが必要です。
+///
+/// # 戻り値
+///
+/// - `Created(user)`         - ログインした場合。ユーザーとトークンを返します。
+/// - `Ok(challenge)`         - 2段階認証が有効な場合。`/auth/login/mfa` に送信するトークンを返します。
+/// - `Ok(accounts)`          - アカウントを連携した場合。連携しているアカウントの一覧を返します。
+/// - `BadRequest()`          - `state` が無効・期限切れ、または `oidc_state` クッキーと一致しない場合、プロバイダーからメールアドレスを取得できない場合。
+/// - `NotFound()`            - プロバイダーが設定されていない場合。
+/// - `Conflict()`            - プロバイダーのアカウント・メールアドレスが他のユーザーで使用されている場合。
+/// - `TooManyRequests()`     - 接続元の試行回数の上限を超えた場合。
+/// - `BadGateway()`          - プロバイダーとの通信、または ID トークンの検証に失敗した場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn callback(
+    http_req: HttpRequest,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<OidcProviderPath>,
+    req: web::Json<OidcCallbackRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [callback] callback called");
+    req.validate()?;
+
+    let user_id = user.map(|user| user.id);
+    let binding = http_req.cookie(OIDC_STATE_COOKIE).map(|cookie| cookie.value().to_string());
+    match app_state.oidc_service.callback(&path.provider, &req, binding.as_deref(), user_id, &session_meta(&http_req)).await? {
+        OidcCallbackOutcome::Login(LoginOutcome::Authenticated(user_data, tokens)) => {
+            success_log!("[oidc_handler] - [callback] message: Logged in successfully");
+            Ok(HttpResponse::Created()
+                .cookie(create_cookie(tokens.access_token))
+                .cookie(create_refresh_cookie(tokens.refresh_token))
+                .cookie(clear_oidc_state_cookie())
+                .json(user_data))
+        }
+        OidcCallbackOutcome::Login(LoginOutcome::MfaRequired(challenge)) => {
+            Ok(HttpResponse::Ok().cookie(clear_oidc_state_cookie()).json(challenge))
+        }
+        OidcCallbackOutcome::Linked(accounts) => {
+            success_log!("[oidc_handler] - [callback] message: Account linked");
+            Ok(HttpResponse::Ok().cookie(clear_oidc_state_cookie()).json(accounts))
+        }
+    }
+}
+
+/// アカウント連携の認可リクエストを作成
+///
+/// コールバックで、ログイン中のユーザーにプロバイダーのアカウントを連携します。
+///
+/// # 戻り値
+///
+/// - `Ok(authorization)`     - 認可リクエストの URL と有効期限を返し、`oidc_state` クッキーを設定します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - プロバイダーが設定されていない場合。
+/// - `BadGateway()`          - プロバイダーとの通信に失敗した場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn link_provider(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<OidcProviderPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [link_provider] link_provider called");
+
+    let (authorization, binding) = app_state.oidc_service.authorize(&path.provider, Some(claims.id)).await?;
+
+    Ok(HttpResponse::Ok().cookie(create_oidc_state_cookie(binding)).json(authorization))
+}
+
+/// 連携しているアカウントの一覧
+///
+/// # 戻り値
+///
+/// - `Ok(accounts)`          - 連携しているアカウントと、パスワードを設定しているかを返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_accounts(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [get_accounts] get_accounts called");
+
+    let accounts = app_state.oidc_service.list_accounts(claims.id).await?;
+
+    Ok(HttpResponse::Ok().json(accounts))
+}
+
+/// アカウントの連携を解除
+///
+/// パスワードを設定していないユーザーは、最後に連携しているアカウントを解除できません。
+///
+/// # 戻り値
+///
+/// - `Ok()`                  - 連携を解除した場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - プロバイダーのアカウントを連携していない場合。
+/// - `Conflict()`            - 解除するとログインできなくなる場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn unlink_account(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<OidcProviderPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[oidc_handler] - [unlink_account] unlink_account called");
+
+    app_state.oidc_service.unlink_account(claims.id, &path.provider).await?;
+
+    success_log!("[oidc_handler] - [unlink_account] message: Account unlinked");
+    Ok(HttpResponse::Ok().json(json!({ "message": t(current_locale(), MessageKey::AuthOidcUnlinked) })))
+}
\ No newline at end of file
@@ -0,0 +1,88 @@
+//! # セッションハンドラー
+//!
+//! ログイン中の端末（セッション）の一覧と失効
+//!
+//! ## 関数
+//!
+//! - `get_sessions`:   有効なセッション一覧
+//! - `revoke_session`: 指定したセッションを失効
+//! - `logout_all`:     全端末からログアウト
+
+use actix_web::{web, HttpResponse};
+use serde_json::json;
+
+use crate::application::errors::api_error::ApiError;
+use crate::application::helpers::cookie::{clear_cookie, clear_refresh_cookie};
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
+use crate::application::states::app_state::AppState;
+use crate::domain::entities::session::SessionPath;
+use crate::{app_log, info_log, success_log};
+
+/// 有効なセッション一覧
+/// 
+/// 認証されたユーザーのログイン中の端末を、最後に使用された順に取得します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(sessions)`          - セッション一覧を返します。`current` はリクエストしたトークンのセッションです。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_sessions(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[session_handler] - [get_sessions] get_sessions called");
+
+    let sessions = app_state.auth_service.get_sessions(&claims).await?;
+
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+/// 指定したセッションを失効
+/// 
+/// 他の端末のセッションを失効させ、その端末のリフレッシュトークンとアクセストークンを無効にします。
+/// 
+/// # 戻り値
+/// 
+/// - `NoContent()`           - セッションを失効させた場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - セッションが見つからない、または失効済みの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn revoke_session(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<SessionPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[session_handler] - [revoke_session] revoke_session called");
+
+    app_state.auth_service.revoke_session(claims.id, &path.id).await?;
+
+    success_log!("[session_handler] - [revoke_session] message: Session revoked");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 全端末からログアウト
+/// 
+/// 認証されたユーザーの全てのセッションを失効させ、クッキーを削除します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok()`                  - 失効させたセッション数を返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn logout_all(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[session_handler] - [logout_all] logout_all called");
+
+    let revoked = app_state.auth_service.logout_all(claims.id).await?;
+
+    success_log!("[session_handler] - [logout_all] message: Logged out from all devices");
+    Ok(HttpResponse::Ok()
+        .cookie(clear_cookie())
+        .cookie(clear_refresh_cookie())
+        .json(json!({ "message": t(current_locale(), MessageKey::AuthLoggedOutAll), "revoked": revoked })))
+}
\ No newline at end of file
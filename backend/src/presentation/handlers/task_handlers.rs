@@ -4,85 +4,108 @@
 //!
 //! ## 関数
 //!
-//! - `get_tasks`: TODO 取得
+//! - `get_tasks`: TODO 一覧取得
+//! - `get_task`: TODO 取得
 //! - `create_task`: TODO 作成
 //! - `update_task`: TODO 更新
 //! - `delete_task`: TODO 削除
 //! - `complete_task`: TODO 完了
 
-use actix_web::{web, HttpRequest, HttpResponse, Responder};
-use serde_json::json;
+use actix_web::{web, HttpResponse};
+use validator::Validate;
 
-use crate::application::jwt::jwt;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
 use crate::application::states::app_state::AppState;
+use crate::application::errors::api_error::ApiError;
+use crate::domain::entities::pagination::Pagination;
 use crate::domain::entities::task::{
-    RequestCompleteTaskItem, RequestCreateTaskItem, RequestDeleteTaskItem, RequestUpdateTaskItem, TaskListRequest
+    RequestCreateTaskItem, RequestUpdateTaskItem, TaskListQuery, TaskListRequest, TaskPath
 };
 use crate::domain::entities::user::UserRequest;
 use crate::{app_log, error_log, info_log};
 
 /// タスク一覧の取得
 /// 
-/// 認証されたユーザーのタスクリストを取得します。
+/// 認証されたユーザーのタスクリストを、クエリパラメータの条件で絞り込み・並び替え・ページ分割して取得します。
 /// 
 /// # 引数
 /// 
-/// * `req`       - ヘッダーにJWTトークンを含むHTTPリクエスト
-/// * `app_state` - アプリケーションの状態
+/// * `claims`     - 認証済みユーザーの Claims
+/// * `query`      - `TaskListQuery` 型のクエリパラメータ
+/// * `pagination` - `Pagination` 型のクエリパラメータ（`page` / `per_page`）
+/// * `app_state`  - アプリケーションの状態
 /// 
 /// # 戻り値
 /// 
 /// `HttpResponse` 型を返します: 
 /// 
-/// - `Ok(tasks)`             - タスクリストが正常に取得された場合、`タスクリストを返します。
+/// - `Ok(tasks)`             - タスクリストが正常に取得された場合、タスクリストと総件数を返します。
+/// - `BadRequest()`          - クエリパラメータが不正な場合。
 /// - `Unauthorized()`        - ユーザーが認証されていない場合。
-/// - `NotFound`              - ユーザーが見つからない場合
+/// - `NotFound()`            - ユーザーが見つからない場合。
 /// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn get_tasks(
-    req: HttpRequest,
-    // user_req: web::Json<UserRequest>,
-    // task_req: web::Json<TaskListRequest>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    query: web::Query<TaskListQuery>,
+    pagination: web::Query<Pagination>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     info_log!("[task_controller] - [get_tasks] get_tasks called");
 
+    query.validate()?;
+    pagination.validate()?;
+
     let task_service = &app_state.task_service;
     let user_service = &app_state.user_service;
 
-    // 認証処理
-    let claims = match jwt::verify(&req) {
-        Ok(claims) => claims,
-        Err(error) => {
-            error_log!("[task_controller] - [get_tasks] message: error = {}", error);
-            return HttpResponse::Unauthorized().json(json!({ "message": "Not authorized, please login!"}))
-        }
-    };
-
     let user_req = UserRequest {
         user_id: claims.id.to_string(),
     };
 
     // ユーザー取得
-    match user_service.find_user_by_id(&user_req).await {
-        Ok(user) => user,
-        Err(user_error) => {
-            error_log!("[task_controller] - [get_tasks] message: user_error = {}", user_error);
-            return HttpResponse::NotFound().finish();
-        }
-    };
+    if user_service.find_user_by_id(&user_req).await?.is_none() {
+        error_log!("[task_controller] - [get_tasks] message: user not found");
+        return Err(ApiError::UserNotFound);
+    }
 
     let task_req = TaskListRequest {
         user_id: claims.id,
     };
 
     // タスク一覧取得
-    match task_service.get_tasks(task_req.user_id).await {
-        Ok(tasks_response) => HttpResponse::Ok().json(tasks_response.tasks),
-        Err(task_error) => {
-            error_log!("[task_controller] - [get_tasks] message: task_error = {}", task_error);
-            HttpResponse::InternalServerError().finish()
-        }
-    }
+    let tasks_response = task_service.get_tasks(task_req.user_id, &query, &pagination).await?;
+
+    Ok(HttpResponse::Ok().json(tasks_response))
+}
+
+/// タスクの取得
+/// 
+/// 認証されたユーザーが持つタスクを1件取得します。
+/// 
+/// # 引数
+/// 
+/// * `claims`    - 認証済みユーザーの Claims
+/// * `path`      - `TaskPath` 型のパスパラメータ
+/// * `app_state` - アプリケーションの状態
+/// 
+/// # 戻り値
+/// 
+/// `HttpResponse` 型を返します: 
+/// 
+/// - `Ok(task)`              - タスクが正常に取得された場合、タスクを返します。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - タスクが存在しない、または他ユーザーのタスクの場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn get_task(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<TaskPath>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    let task_service = &app_state.task_service;
+
+    let task = task_service.get_task(claims, path.id).await?;
+
+    Ok(HttpResponse::Ok().json(task))
 }
 
 /// タスクの新規作成
@@ -91,7 +114,7 @@ pub async fn get_tasks(
 /// 
 /// # 引数
 /// 
-/// * `req`       - ヘッダーにJWTトークンを含むHTTPリクエスト
+/// * `claims`    - 認証済みユーザーの Claims
 /// * `task_req`  - `RequestCreateTaskItem` 型のJSON
 /// * `app_state` - アプリケーションの状態
 /// 
@@ -99,30 +122,22 @@ pub async fn get_tasks(
 /// 
 /// `HttpResponse` 型を返します: 
 /// 
-/// - `Ok(response)`          - タスクが正常に作成された場合、作成されたタスクの詳細を返します。
+/// - `Created(task)`         - タスクが正常に作成された場合、作成されたタスクを返します。
+/// - `BadRequest()`          - 入力値が不正な場合。
 /// - `Unauthorized()`        - ユーザーが認証されていない場合。
 /// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn create_task(
-    req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
     task_req: web::Json<RequestCreateTaskItem>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    task_req.validate()?;
+
     let task_service = &app_state.task_service;
-    let user = jwt::verify(&req);
-
-    match user {
-        Ok(user_data) => match task_service.create_task(user_data, &task_req).await {
-            Ok(response) => HttpResponse::Ok().json(response),
-            Err(task_error) => {
-                error_log!("[task_controller] - [create_task] message: task_error = {}", task_error);
-                HttpResponse::InternalServerError().finish()
-            }
-        },
-        Err(error) => {
-            error_log!("[task_controller] - [create_task] message: error = {}", error);
-            HttpResponse::Unauthorized().finish()
-        }
-    }
+
+    let task = task_service.create_task(claims, &task_req).await?;
+
+    Ok(HttpResponse::Created().json(task))
 }
 
 /// タスクの更新
@@ -131,7 +146,8 @@ pub async fn create_task(
 /// 
 /// # 引数
 /// 
-/// * `req`       - ヘッダーにJWTトークンを含むHTTPリクエスト
+/// * `claims`    - 認証済みユーザーの Claims
+/// * `path`      - `TaskPath` 型のパスパラメータ
 /// * `task_req`  - `RequestUpdateTaskItem` 型のJSON
 /// * `app_state` - アプリケーションの状態
 /// 
@@ -139,30 +155,24 @@ pub async fn create_task(
 /// 
 /// `HttpResponse` 型を返します: 
 /// 
-/// - `Ok(())`                - タスクが正常に更新された場合。
+/// - `Ok(task)`              - タスクが正常に更新された場合、更新後のタスクを返します。
+/// - `BadRequest()`          - 入力値が不正な場合。
 /// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - タスクが存在しない、または他ユーザーのタスクの場合。
 /// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn update_task(
-    req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<TaskPath>,
     task_req: web::Json<RequestUpdateTaskItem>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
+    task_req.validate()?;
+
     let task_service = &app_state.task_service;
-    let user = jwt::verify(&req);
-
-    match user {
-        Ok(user_data) => match task_service.update_task(user_data, &task_req).await {
-            Ok(()) => HttpResponse::Ok().finish(),
-            Err(task_error) => {
-                error_log!("[task_controller] - [update_task] message: task_error = {}", task_error);
-                HttpResponse::InternalServerError().finish()
-            }
-        },
-        Err(error) => {
-            error_log!("[task_controller] - [update_task] message: error = {}", error);
-            HttpResponse::Unauthorized().finish()
-        }
-    }
+
+    let task = task_service.update_task(claims, path.id, &task_req).await?;
+
+    Ok(HttpResponse::Ok().json(task))
 }
 
 /// タスクの削除
@@ -171,38 +181,28 @@ pub async fn update_task(
 /// 
 /// # 引数
 /// 
-/// * `req`       - ヘッダーにJWTトークンを含むHTTPリクエスト
-/// * `task_req`  - `RequestDeleteTaskItem` 型のJSON
+/// * `claims`    - 認証済みユーザーの Claims
+/// * `path`      - `TaskPath` 型のパスパラメータ
 /// * `app_state` - アプリケーションの状態
 /// 
 /// # 戻り値
 /// 
 /// `HttpResponse` 型を返します: 
 /// 
-/// - `Ok(())`                - タスクが正常に削除された場合。
+/// - `NoContent()`           - タスクが正常に削除された場合。
 /// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - タスクが存在しない、または他ユーザーのタスクの場合。
 /// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn delete_task(
-    req: HttpRequest,
-    task_req: web::Json<RequestDeleteTaskItem>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<TaskPath>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let task_service = &app_state.task_service;
-    let user = jwt::verify(&req);
-
-    match user {
-        Ok(user_data) => match task_service.delete_task(user_data, &task_req).await {
-            Ok(()) => HttpResponse::Ok().finish(),
-            Err(task_error) => {
-                error_log!("[task_controller] - [delete_task] message: task_error = {}", task_error);
-                HttpResponse::InternalServerError().finish()
-            }
-        },
-        Err(error) => {
-            error_log!("[task_controller] - [delete_task] message: error = {}", error);
-            HttpResponse::Unauthorized().finish()
-        }
-    }
+
+    task_service.delete_task(claims, path.id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
 }
 
 /// タスクの完了
@@ -211,36 +211,26 @@ pub async fn delete_task(
 /// 
 /// # 引数
 /// 
-/// * `req`       - ヘッダーにJWTトークンを含むHTTPリクエスト
-/// * `task_req`  - `RequestCompleteTaskItem` 型のJSON
+/// * `claims`    - 認証済みユーザーの Claims
+/// * `path`      - `TaskPath` 型のパスパラメータ
 /// * `app_state` - DIを含むアプリケーションの状態
 /// 
 /// # 戻り値
 /// 
 /// `HttpResponse` 型を返します: 
 /// 
-/// - `Ok(())`                - タスクが正常に完了した場合。
+/// - `Ok(task)`              - タスクが正常に完了した場合、完了後のタスクを返します。
 /// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `NotFound()`            - タスクが存在しない、または他ユーザーのタスクの場合。
 /// - `InternalServerError()` - サーバーエラーが発生した場合。
 pub async fn complete_task(
-    req: HttpRequest,
-    task_req: web::Json<RequestCompleteTaskItem>,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    path: web::Path<TaskPath>,
     app_state: web::Data<AppState>
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let task_service = &app_state.task_service;
-    let user = jwt::verify(&req);
-
-    match user {
-        Ok(user_data) => match task_service.complete_task(user_data, &task_req).await {
-            Ok(()) => HttpResponse::Ok().finish(),
-            Err(task_error) => {
-                error_log!("[task_controller] - [complete_task] message: task_error = {}", task_error);
-                HttpResponse::InternalServerError().finish()
-            }
-        },
-        Err(error) => {
-            error_log!("[task_controller] - [complete_task] message: error = {}", error);
-            HttpResponse::Unauthorized().finish()
-        }
-    }
-}
\ No newline at end of file
+
+    let task = task_service.complete_task(claims, path.id).await?;
+
+    Ok(HttpResponse::Ok().json(task))
+}
@@ -1,49 +1,120 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
-use serde_json::json;
-use crate::application::jwt::jwt;
+use validator::Validate;
+use crate::application::errors::api_error::ApiError;
+use crate::application::helpers::cookie::create_cookie;
+use crate::application::helpers::request::session_meta;
+use crate::application::i18n::catalogue::{t, MessageKey};
+use crate::application::i18n::request_locale::current_locale;
+use crate::application::jwt::authenticated_user::AuthenticatedUser;
 use crate::application::states::app_state::AppState;
-use crate::domain::entities::user::UserRequest;
-use crate::{app_log, error_log, success_log};
+use crate::domain::entities::user::{ChangePasswordRequest, ChangePasswordResponse, PermissionsResponse, UpdateUserRequest, UserRequest};
+use crate::{app_log, error_log, info_log, success_log};
 
+/// ユーザー取得
+/// 
+/// JWT のユーザーIDに該当するユーザー情報を取得します。
 pub async fn get_user(
-    req: HttpRequest,
-    user_req: web::Json<UserRequest>,
+    AuthenticatedUser(claims): AuthenticatedUser,
     app_state: web::Data<AppState>
-) -> impl Responder {
-    let user = jwt::verify(&req);
+) -> Result<HttpResponse, ApiError> {
+    let user_service = &app_state.user_service;
+    let user_req = UserRequest {
+        user_id: claims.id.to_string(),
+    };
 
-    match user {
-        Ok(_user_data) => {
-            let user_service = &app_state.user_service;
-
-            match user_service.find_user_by_id(&user_req).await {
-                Ok(Some(user)) => {
-                    HttpResponse::Ok().json(user)
-                }
-                Ok(None) => {
-                    HttpResponse::NotFound().json(json!({ "message": "User not found"}))
-                }
-                Err(err) => {
-                    error_log!("Internal server error: {:?}", err);
-                    HttpResponse::InternalServerError().json(json!({ "message": "Internal server error"}))
-                }
-            }
-        },
-        Err(_) => HttpResponse::Unauthorized().json(json!({ "message": "Invalid token"}))
+    match user_service.find_user_by_id(&user_req).await? {
+        Some(user) => Ok(HttpResponse::Ok().json(user)),
+        None => Err(ApiError::UserNotFound),
     }
 }
 
+/// プロフィール更新
+/// 
+/// JWT のユーザーの名前・自己紹介・写真・言語を更新します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(user)`              - 更新後のユーザー情報を返します。
+/// - `BadRequest()`          - 入力値が不正な場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn update_user(
+    AuthenticatedUser(claims): AuthenticatedUser,
+    user_req: web::Json<UpdateUserRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[user_handler] - [update_user] update_user called");
+
+    user_req.validate()?;
+
+    let user = app_state.user_service.update_profile(claims.id, &user_req).await?;
+
+    success_log!("[user_handler] - [update_user] message: User updated");
+    Ok(HttpResponse::Ok().json(user))
+}
+
+/// パスワード変更
+/// 
+/// 現在のパスワードを検証し、新しいパスワードに変更します。
+/// 変更前に発行されたトークンは無効になるため、新しいトークンをクッキーとレスポンスで返します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok()`                  - パスワードを変更した場合。
+/// - `BadRequest()`          - 新しいパスワードが不正な場合。
+/// - `Unauthorized()`        - ユーザーが認証されていない、または現在のパスワードが正しくない場合。
+/// - `TooManyRequests()`     - パスワードの失敗が続き、アカウントがロックされている場合。
+/// - `InternalServerError()` - サーバーエラーが発生した場合。
+pub async fn change_password(
+    http_req: HttpRequest,
+    AuthenticatedUser(claims): AuthenticatedUser,
+    password_req: web::Json<ChangePasswordRequest>,
+    app_state: web::Data<AppState>
+) -> Result<HttpResponse, ApiError> {
+    info_log!("[user_handler] - [change_password] change_password called");
+
+    password_req.validate()?;
+
+    let token = app_state.user_service.change_password(&claims, &password_req, &session_meta(&http_req)).await?;
+
+    success_log!("[user_handler] - [change_password] message: Password changed");
+    Ok(HttpResponse::Ok().cookie(create_cookie(token.clone())).json(ChangePasswordResponse {
+        message: t(current_locale(), MessageKey::UserPasswordChanged),
+        token,
+    }))
+}
+
+/// ログイン状態
+/// 
+/// 認証なしでコール可能なため、未ログインの場合もハンドラーで `Unauthorized` を返します。
 pub async fn login_status(
-    req: HttpRequest,
+    user: Option<AuthenticatedUser>,
 ) -> impl Responder {
-    match jwt::verify(&req) {
-        Ok(_claims) => {
+    match user {
+        Some(_user) => {
             success_log!("[user_handler] - [login_status] message: Authorized!");
             HttpResponse::Ok().json(true)
         },
-        Err(_) => {
+        None => {
             error_log!("[user_handler] - [login_status] Not authorized");
             HttpResponse::Unauthorized().json(false)
         }
     }
+}
+
+/// 権限一覧
+/// 
+/// JWT のロールと、そのロールに許可された操作を返します。フロントエンドでの表示の切り替えに使用します。
+/// 
+/// # 戻り値
+/// 
+/// - `Ok(permissions)`  - ロールと操作の一覧を返します。
+/// - `Unauthorized()`   - ユーザーが認証されていない場合。
+pub async fn get_permissions(
+    AuthenticatedUser(claims): AuthenticatedUser,
+) -> impl Responder {
+    HttpResponse::Ok().json(PermissionsResponse {
+        role: claims.role,
+        permissions: claims.role.permissions(),
+    })
 }
\ No newline at end of file
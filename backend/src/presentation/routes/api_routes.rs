@@ -1,21 +1,41 @@
+use actix_web::dev::HttpServiceFactory;
 use actix_web::{
     HttpRequest,
     HttpResponse,
-    Result, Scope
+    Scope
 };
-use actix_web::web::{delete, get, patch, post, route, scope};
+use actix_web::web::{delete, get, patch, post, route, scope, ServiceConfig};
 use crate::{app_log, error_log};
-use crate::presentation::handlers::auth_handlers::{login_user, logout_user, register_user};
-use crate::presentation::handlers::healthcheck_handler::healthcheck;
-use crate::presentation::handlers::user_handlers::{get_user, login_status};
-use crate::presentation::handlers::task_handlers::get_tasks;
+use crate::application::errors::api_error::ApiError;
+use crate::application::middlewares::jwt_middleware::JwtMiddleware;
+use crate::application::middlewares::login_rate_limit_middleware::LoginRateLimit;
+use crate::application::middlewares::permission_middleware::RequirePermission;
+use crate::application::middlewares::registered_user_middleware::RequireRegisteredUser;
+use crate::application::middlewares::verified_email_middleware::RequireVerifiedEmail;
+use crate::domain::enums::role::Permission;
+use crate::presentation::handlers::admin_handlers::{change_role, delete_user, get_jobs, get_login_failures, get_mail_outbox, get_users, requeue_mail};
+use crate::presentation::handlers::auth_handlers::{
+    convert_guest, forgot_password, guest_login, login_user, logout_user, refresh_session, register_user, reset_password, verify_email,
+    verify_mfa_login, verify_user
+};
+use crate::presentation::handlers::mfa_handlers::{confirm_totp, disable_totp, get_mfa_status, regenerate_recovery_codes, setup_totp};
+use crate::presentation::handlers::oidc_handlers::{authorize, callback, get_accounts, get_providers, link_provider, unlink_account};
+use crate::presentation::handlers::session_handlers::{get_sessions, logout_all, revoke_session};
+use crate::presentation::handlers::healthcheck_handler::{healthcheck, live, ready};
+use crate::presentation::handlers::jwks_handler::jwks;
+use crate::presentation::handlers::metrics_handler::metrics;
+use crate::presentation::handlers::user_handlers::{change_password, get_permissions, get_user, login_status, update_user};
+use crate::presentation::handlers::task_handlers::{complete_task, create_task, delete_task, get_task, get_tasks, update_task};
 
-async fn handler(req: HttpRequest) -> Result<HttpResponse> {
+/// 見つからない API
+///
+/// スコープ内で一致しないパスも対象にするため、`App::default_service` にも登録する
+pub async fn handler(req: HttpRequest) -> Result<HttpResponse, ApiError> {
     let path = req.path();
     let uri = req.uri();
 
     error_log!("[api_routes] APIが見つかりませんでした： path = '{}' uri = '{}'", path, uri);
-    Ok(HttpResponse::NotFound().body(format!("APIが見つかりませんでした： '{}'", path)))
+    Err(ApiError::RouteNotFound)
 }
 
 pub fn api_scopes() -> Scope {
@@ -24,42 +44,116 @@ pub fn api_scopes() -> Scope {
         .default_service(route().to(handler))
 }
 
+/// 公開鍵など、認証なしで参照する設定
+pub fn well_known_scope() -> Scope {
+    scope("/.well-known")
+        .route("/jwks.json", get().to(jwks))
+}
+
+/// メトリクス（Prometheus のスクレイプ用）
+///
+/// API のバージョンに依存しないため、`/api` の外に登録する
+pub fn metrics_routes(cfg: &mut ServiceConfig) {
+    cfg.route("/metrics", get().to(metrics));
+}
+
+/// ヘルスチェック（ロードバランサー・オーケストレーターの確認用）
+///
+/// `/health/live` は生存確認、`/health/ready` は依存先を含めた準備完了確認
+/// メトリクスと同じく、`/api` の外に登録する
+pub fn health_scope() -> Scope {
+    scope("/health")
+        .route("/live", get().to(live))
+        .route("/ready", get().to(ready))
+}
+
+/// バージョン 1 の API
+///
+/// `public_routes` に登録したルート以外は全て `protected_scope` で認証が必要
 pub fn version_scope() -> Scope {
     scope("/v1")
+        .configure(public_routes)
+        .service(protected_scope())
+}
+
+/// 認証なしでコール可能な API
+///
+/// トークンが無効でも拒否しない。ログイン状態で処理を変える場合はハンドラーで `Option<AuthenticatedUser>` を受け取る
+fn public_routes(cfg: &mut ServiceConfig) {
+    cfg.route("/auth/register", post().to(register_user))
+        .route("/auth/login", post().to(login_user).wrap(LoginRateLimit))
+        .route("/auth/guest", post().to(guest_login).wrap(LoginRateLimit))
+        .route("/auth/login/mfa", post().to(verify_mfa_login).wrap(LoginRateLimit))
+        .route("/auth/oidc/providers", get().to(get_providers))
+        .route("/auth/oidc/{provider}/authorize", post().to(authorize).wrap(LoginRateLimit))
+        .route("/auth/oidc/{provider}/callback", post().to(callback).wrap(LoginRateLimit))
+        .route("/auth/logout", post().to(logout_user))
+        .route("/auth/refresh", post().to(refresh_session))
+        .route("/auth/login-status", get().to(login_status))
+        .route("/auth/healthcheck", get().to(healthcheck))
+        .route("/auth/verify-email/{verificationToken}", post().to(verify_user))
+        .route("/auth/forgot-password", post().to(forgot_password).wrap(LoginRateLimit))
+        .route("/auth/reset-password/{resetPasswordToken}", post().to(reset_password));
+}
+
+/// 認証が必要な API
+///
+/// 新しいスコープはここに追加する
+fn protected_scope() -> impl HttpServiceFactory {
+    scope("")
+        .wrap(JwtMiddleware)
         .service(auth_scope())
+        .service(admin_scope())
         .service(task_scope())
 }
 
 /// 認証API
+///
+/// メールアドレス・パスワードが必要な API は、ゲストを拒否する（`RequireRegisteredUser`）
 fn auth_scope() -> Scope {
     scope("/auth")
-        // .route("/guest_login", post().to(guest_login))
-        // .route("/signup", post().to(signup))
-        // .route("/login", post().to(login))
-        // .route("/current_user", get().to(current_user))
-        // new api down below (認証後にコールを許可するAPIの仕分けが必要)
-        .route("/register", post().to(register_user))
-        .route("/login", post().to(login_user))
-        .route("/logout", get().to(logout_user))
+        .route("/guest/convert", post().to(convert_guest))
+        .route("/logout-all", post().to(logout_all))
+        .route("/sessions", get().to(get_sessions))
+        .route("/sessions/{id}", delete().to(revoke_session))
+        .route("/mfa", get().to(get_mfa_status))
+        .route("/mfa/totp", post().to(setup_totp).wrap(RequireRegisteredUser))
+        .route("/mfa/totp/confirm", post().to(confirm_totp).wrap(RequireRegisteredUser))
+        .route("/mfa/totp/disable", post().to(disable_totp).wrap(RequireRegisteredUser))
+        .route("/mfa/recovery-codes", post().to(regenerate_recovery_codes).wrap(RequireRegisteredUser))
+        .route("/oidc/accounts", get().to(get_accounts))
+        .route("/oidc/accounts/{provider}", delete().to(unlink_account))
+        .route("/oidc/{provider}/link", post().to(link_provider).wrap(RequireRegisteredUser))
         .route("/user", get().to(get_user))
-        // .route("/user", patch().to(update_user))
-        // .route("/admin/users/{id}", delete().to(delete_user))
-        // .route("/admin/users", get().to(get_all_users))
-        .route("/login-status", get().to(login_status))
-        // .route("/verify-email", post().to(verify_email))
-        // .route("/verify-email/{verificationToken}", post().to(verify_user))
-        // .route("/forgot-password", post().to(forgot_password))
-        // .route("/reset-password/{resetPasswordToken}", post().to(reset_password))
-        // .route("/change-password", patch().to(change_password))
-        .route("healthcheck", get().to(healthcheck))
+        .route("/user", patch().to(update_user))
+        .route("/permissions", get().to(get_permissions))
+        .route("/verify-email", post().to(verify_email).wrap(RequireRegisteredUser))
+        .route("/change-password", patch().to(change_password).wrap(RequireRegisteredUser))
+}
+
+/// 管理者API
+///
+/// ロールの権限マトリクス（`Role::permissions`）で許可されたユーザーのみ
+fn admin_scope() -> Scope {
+    scope("/admin")
+        .route("/users", get().to(get_users).wrap(RequirePermission::new(Permission::ManageUsers)))
+        .route("/users/{id}", delete().to(delete_user).wrap(RequirePermission::new(Permission::ManageUsers)))
+        .route("/users/{id}/role", patch().to(change_role).wrap(RequirePermission::new(Permission::ManageRoles)))
+        .route("/login-failures", get().to(get_login_failures).wrap(RequirePermission::new(Permission::ManageUsers)))
+        .route("/mail-outbox", get().to(get_mail_outbox).wrap(RequirePermission::new(Permission::ManageMail)))
+        .route("/mail-outbox/{id}/requeue", post().to(requeue_mail).wrap(RequirePermission::new(Permission::ManageMail)))
+        .route("/jobs", get().to(get_jobs).wrap(RequirePermission::new(Permission::ManageJobs)))
 }
 
 /// task api
+///
+/// 更新系の API は `REQUIRE_VERIFIED_EMAIL=true` の場合、メール認証済みのユーザーのみ許可
 fn task_scope() -> Scope {
-    scope("")
-        // .route("/task/create", post().to(create_task))
-        .route("/tasks", get().to(get_tasks))
-        // .route("/task/{id}", get().to(get_task))
-        // .route("/task/{id}", patch().to(update_task))
-        // .route("/task/{id}", delete().to(delete_task))
+    scope("/tasks")
+        .route("", get().to(get_tasks))
+        .route("", post().to(create_task).wrap(RequireVerifiedEmail))
+        .route("/{id}", get().to(get_task))
+        .route("/{id}", patch().to(update_task).wrap(RequireVerifiedEmail))
+        .route("/{id}", delete().to(delete_task).wrap(RequireVerifiedEmail))
+        .route("/{id}/complete", post().to(complete_task).wrap(RequireVerifiedEmail))
 }
\ No newline at end of file
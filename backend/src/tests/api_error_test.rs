@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use validator::Validate;
+    use crate::application::errors::api_error::ApiError;
+    use crate::application::errors::auth_error::AuthError;
+    use crate::application::errors::task_error::TaskError;
+    use crate::domain::entities::auth::SignupRequest;
+    use crate::domain::enums::locale::Locale;
+
+    // バリデーションエラーは項目ごとの詳細を項目名の順に返す
+    #[test]
+    fn test_validation_error_field_details() {
+        let req = SignupRequest {
+            name: "".to_string(),
+            email: "john@example.com".to_string(),
+            password: "short".to_string(),
+        };
+        let error = ApiError::from(req.validate().unwrap_err());
+        let problem = error.problem_details(Locale::En);
+
+        assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(problem.code, "validation_failed");
+
+        let fields: Vec<(&str, &str)> = problem.errors.iter()
+            .map(|error| (error.field.as_str(), error.code.as_str()))
+            .collect();
+        assert_eq!(fields, vec![
+            ("email", "email_invalid_domain"),
+            ("name", "name_length"),
+            ("password", "password_too_short"),
+        ]);
+        assert!(problem.errors.iter().all(|error| error.message.is_some()));
+
+        // 詳細とメッセージは指定した言語で返す
+        let problem = error.problem_details(Locale::Ja);
+        assert_eq!(problem.errors[1].message.as_deref(), Some("名前は 1〜255 文字で入力してください"));
+        assert_ne!(problem.detail, error.problem_details(Locale::En).detail);
+    }
+
+    // サービスのエラーは固定のエラーコードとステータスに変換し、サーバーエラーの詳細は返さない
+    #[test]
+    fn test_service_error_mapping() {
+        let cases = [
+            (ApiError::from(AuthError::InvalidCredentials), StatusCode::UNAUTHORIZED, "invalid_credentials"),
+            (ApiError::from(AuthError::AlreadyVerified), StatusCode::CONFLICT, "already_verified"),
+            (ApiError::from(AuthError::TooManyRequests), StatusCode::TOO_MANY_REQUESTS, "too_many_requests"),
+            (ApiError::from(AuthError::NotGuest), StatusCode::CONFLICT, "not_guest"),
+            (ApiError::from(AuthError::GuestLoginDisabled), StatusCode::FORBIDDEN, "guest_login_disabled"),
+            (ApiError::from(TaskError::TaskNotFound), StatusCode::NOT_FOUND, "task_not_found"),
+            (ApiError::from(TaskError::UserNotFound), StatusCode::UNAUTHORIZED, "unauthorized"),
+            (ApiError::InternalError("connection refused".to_string()), StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
+        ];
+
+        for (error, status, code) in cases {
+            assert_eq!(error.status_code(), status);
+            assert_eq!(error.code(), code);
+            assert!(!error.problem_details(Locale::En).detail.contains("connection refused"));
+        }
+
+        let response = ApiError::TaskNotFound.error_response();
+        assert_eq!(response.headers().get("content-type").unwrap(), "application/problem+json");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use crate::application::errors::config_error::ConfigError;
+    use crate::application::helpers::redact::REDACTED;
+    use crate::domain::enums::locale::Locale;
+    use crate::infrastructure::config::app_config::AppConfig;
+    use crate::infrastructure::config::log_config::LogFormat;
+    use crate::infrastructure::config::mail_config::MailTransportKind;
+
+    const VALID_TOML: &str = r#"
+        [database]
+        host = "localhost"
+        user = "postgres"
+        password = "db-password"
+        name = "gamernage"
+
+        [jwt]
+        secret = "jwt-secret"
+        previous_secrets = { old = "old-secret" }
+
+        [mail]
+        transport = "log"
+    "#;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    fn load(toml: &str, vars: &[(&str, &str)]) -> Result<AppConfig, ConfigError> {
+        let mut errors = ConfigError::default();
+        let mut config = AppConfig::from_toml(toml).unwrap();
+        config.apply_env(env(vars), &mut errors);
+        config.validate(&mut errors);
+        errors.into_result(config)
+    }
+
+    // 既定値 < 設定ファイル < 環境変数 の順に上書きする
+    #[test]
+    fn test_file_and_env_overrides() {
+        let config = load(VALID_TOML, &[("BACKEND_PORT", "9000"), ("LOG_FORMAT", "text"), ("DEFAULT_LOCALE", "ja"), ("GUEST_ENABLED", "false")]).unwrap();
+
+        assert_eq!(config.database.host, "localhost");
+        assert_eq!(config.mail.transport, MailTransportKind::Log);
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.log.format, LogFormat::Text);
+        assert_eq!(config.app.default_locale, Locale::Ja);
+        assert_eq!(config.jwt.access_token_ttl_minutes, 15);
+        assert!(!config.guest.enabled);
+        assert_eq!(config.guest.ttl_hours, 72);
+    }
+
+    // 最初のエラーで止めず、全てのエラーをまとめて返す
+    #[test]
+    fn test_validation_reports_all_errors() {
+        let errors = load("", &[("BACKEND_PORT", "http"), ("MAIL_TRANSPORT", "pigeon"), ("OUTBOX_BATCH_SIZE", "0")])
+            .unwrap_err()
+            .errors;
+
+        for expected in ["BACKEND_PORT", "MAIL_TRANSPORT", "DATABASE_HOST", "DATABASE_NAME", "JWT_SECRET", "SMTP_SERVER", "OUTBOX_BATCH_SIZE"] {
+            assert!(errors.iter().any(|e| e.contains(expected)), "{} is missing: {:?}", expected, errors);
+        }
+    }
+
+    // `OIDC_PROVIDERS` に列挙したプロバイダーは `OIDC_{NAME}_*` から読み込む
+    #[test]
+    fn test_oidc_providers_from_env() {
+        let vars = [
+            ("OIDC_PROVIDERS", "google,my-idp"),
+            ("OIDC_GOOGLE_ISSUER", "https://accounts.google.com"),
+            ("OIDC_GOOGLE_CLIENT_ID", "google-client"),
+            ("OIDC_GOOGLE_CLIENT_SECRET", "google-secret"),
+            ("OIDC_MY_IDP_ISSUER", "idp.example.com"),
+        ];
+        let errors = load(VALID_TOML, &vars).unwrap_err().errors;
+
+        for expected in ["OIDC_MY_IDP_ISSUER", "OIDC_MY_IDP_CLIENT_ID"] {
+            assert!(errors.iter().any(|e| e.contains(expected)), "{} is missing: {:?}", expected, errors);
+        }
+        assert!(!errors.iter().any(|e| e.contains("OIDC_GOOGLE")), "{:?}", errors);
+
+        let config = load(VALID_TOML, &[&[("OIDC_PROVIDERS", "google")], &vars[1..4]].concat()).unwrap();
+        let google = &config.oidc.providers["google"];
+        assert_eq!(google.client_secret.as_deref(), Some("google-secret"));
+        assert_eq!(google.scopes, ["openid", "email", "profile"]);
+        assert!(!config.to_redacted_toml().contains("google-secret"));
+    }
+
+    // CORS のオリジンは未設定の場合 `app.url` のオリジンのみ。パスや `*` は拒否する
+    #[test]
+    fn test_cors_origins() {
+        let config = load(VALID_TOML, &[("APP_URL", "https://app.example.com/home")]).unwrap();
+        assert_eq!(config.cors_origins(), ["https://app.example.com"]);
+
+        let config = load(VALID_TOML, &[("CORS_ALLOWED_ORIGINS", "https://a.example.com, http://localhost:3000")]).unwrap();
+        assert_eq!(config.cors_origins(), ["https://a.example.com", "http://localhost:3000"]);
+        assert!(config.is_allowed_origin("http://localhost:3000"));
+        assert!(config.is_allowed_origin("https://a.example.com/tasks?page=2"));
+        assert!(!config.is_allowed_origin("https://a.example.com.evil.example"));
+        assert!(!config.is_allowed_origin("null"));
+
+        let errors = load(VALID_TOML, &[("CORS_ALLOWED_ORIGINS", "*,https://a.example.com/path")]).unwrap_err().errors;
+        assert_eq!(errors.iter().filter(|e| e.contains("CORS_ALLOWED_ORIGINS")).count(), 2, "{:?}", errors);
+    }
+
+    #[test]
+    fn test_unknown_key_is_rejected() {
+        assert!(AppConfig::from_toml("[database]\nhots = \"localhost\"").is_err());
+    }
+
+    // `--print-config` ではシークレットを出力しない
+    #[test]
+    fn test_redacted_toml() {
+        let config = load(VALID_TOML, &[("METRICS_TOKEN", "metrics-token")]).unwrap();
+        let output = config.to_redacted_toml();
+
+        for secret in ["db-password", "jwt-secret", "old-secret", "metrics-token"] {
+            assert!(!output.contains(secret), "{} is not redacted", secret);
+        }
+        assert!(output.contains(REDACTED));
+        assert!(AppConfig::from_toml(&output).is_ok());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,580 @@
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+    use uuid::Uuid;
+    use crate::application::errors::auth_error::AuthError;
+    use crate::application::helpers::password::PasswordHasher;
+    use crate::application::helpers::token::hash_token;
+    use crate::application::jwt::jwt_keys::JwtKeys;
+    use crate::application::mail::mailer::Mail;
+    use crate::domain::entities::auth::{LoginSelectResult, SignupInsertResult, VerificationSelectResult};
+    use crate::domain::entities::login_protection::{LoginAttempt, LoginFailureListQuery, LoginFailureListResponse};
+    use crate::domain::entities::mfa::{MfaChallenge, UserTotp};
+    use crate::domain::entities::pagination::Pagination;
+    use crate::domain::entities::session::{RefreshTokenSelectResult, SessionMeta, SessionSelectResult, SessionStatus};
+    use crate::domain::enums::login_failure::LoginFailureReason;
+    use crate::domain::enums::role::Role;
+    use crate::domain::repositories::auth_repository::AuthRepository;
+    use crate::domain::repositories::mfa_repository::MfaRepository;
+    use crate::domain::repositories::session_repository::SessionRepository;
+    use crate::domain::services::auth_service::{AuthService, AuthServiceImpl, FORGOT_PASSWORD_MIN_DURATION};
+    use crate::domain::services::login_guard_service::LoginGuardService;
+    use crate::infrastructure::config::app_config::{AppConfig, PasswordSettings};
+    use crate::infrastructure::db::connection::get_db_pool;
+    use crate::infrastructure::repositories::auth_repository::AuthRepositoryImpl;
+    use crate::infrastructure::repositories::session_repository::SessionRepositoryImpl;
+
+    /// 発行したパスワードリセットトークン（DB と同じくハッシュのみ保持する）
+    struct ResetToken {
+        user_id: i32,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    }
+
+    /// 認証リポジトリ
+    ///
+    /// * `users`         - 登録済みのユーザー（ID・メールアドレス・ロール）
+    /// * `reset_sent_at` - 最後にパスワードリセットトークンを発行した日時
+    /// * `reset_tokens`  - 発行したトークン
+    /// * `reset_mails`   - トークンと同時に登録したメール
+    #[derive(Default)]
+    struct MockAuthRepository {
+        users: Vec<(i32, &'static str, Role)>,
+        reset_sent_at: Option<DateTime<Utc>>,
+        reset_tokens: Mutex<Vec<ResetToken>>,
+        reset_mails: Mutex<Vec<Mail>>,
+    }
+
+    #[async_trait]
+    impl AuthRepository for MockAuthRepository {
+        async fn register_user(
+            &self,
+            _name: &str,
+            _email: &str,
+            _password: &str,
+            _verification_token_hash: &str,
+            _expires_at: DateTime<Utc>,
+            _verification_mail: &Mail,
+        ) -> Result<SignupInsertResult, AuthError> {
+            unimplemented!()
+        }
+
+        async fn create_guest(&self, _name: &str, _email: &str, _password: &str, _expires_at: DateTime<Utc>) -> Result<SignupInsertResult, AuthError> {
+            unimplemented!()
+        }
+
+        async fn convert_guest(&self, _user_id: i32, _name: &str, _email: &str, _password: &str) -> Result<Option<SignupInsertResult>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn delete_expired_guests(&self) -> Result<u64, AuthError> {
+            unimplemented!()
+        }
+
+        async fn get_user_by_email(&self, email: &str) -> Result<Option<LoginSelectResult>, AuthError> {
+            Ok(self.users.iter().find(|(_, user_email, _)| *user_email == email).map(|(id, email, role)| LoginSelectResult {
+                id: *id,
+                name: "John".to_string(),
+                email: email.to_string(),
+                password: String::new(),
+                role: role.as_str().to_string(),
+                photo: None,
+                bio: None,
+                is_verified: true,
+                locale: None,
+            }))
+        }
+
+        async fn get_verification_user(&self, user_id: i32) -> Result<Option<VerificationSelectResult>, AuthError> {
+            Ok(self.users.iter().find(|(id, ..)| *id == user_id).map(|(id, email, role)| VerificationSelectResult {
+                id: *id,
+                email: email.to_string(),
+                role: role.as_str().to_string(),
+                is_verified: true,
+                locale: None,
+            }))
+        }
+
+        async fn get_password_hash(&self, _user_id: i32) -> Result<Option<String>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn has_password(&self, _user_id: i32) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn update_password_hash(&self, _user_id: i32, _current_hash: &str, _new_hash: &str) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn create_verification_token(
+            &self,
+            _user_id: i32,
+            _token_hash: &str,
+            _expires_at: DateTime<Utc>,
+            _mail: &Mail,
+        ) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn get_last_verification_sent_at(&self, _user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn verify_email_token(&self, _token_hash: &str) -> Result<Option<i32>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn create_password_reset_token(
+            &self,
+            user_id: i32,
+            token_hash: &str,
+            expires_at: DateTime<Utc>,
+            mail: &Mail,
+        ) -> Result<(), AuthError> {
+            self.reset_tokens.lock().unwrap().push(ResetToken { user_id, token_hash: token_hash.to_string(), expires_at });
+            self.reset_mails.lock().unwrap().push(mail.clone());
+            Ok(())
+        }
+
+        async fn get_last_password_reset_sent_at(&self, _user_id: i32) -> Result<Option<DateTime<Utc>>, AuthError> {
+            Ok(self.reset_sent_at)
+        }
+
+        /// DB と同じく、有効期限内のトークンを削除してユーザーIDを返す
+        async fn reset_password(&self, token_hash: &str, _password_hash: &str) -> Result<Option<i32>, AuthError> {
+            let mut tokens = self.reset_tokens.lock().unwrap();
+            let Some(index) = tokens.iter().position(|token| token.token_hash == token_hash && token.expires_at > Utc::now()) else {
+                return Ok(None);
+            };
+
+            Ok(Some(tokens.remove(index).user_id))
+        }
+    }
+
+    /// リフレッシュトークン（DB と同じくハッシュのみ保持する）
+    struct RefreshToken {
+        id: i32,
+        session_id: i32,
+        token_hash: String,
+        used_at: Option<DateTime<Utc>>,
+    }
+
+    /// セッション
+    struct Session {
+        id: i32,
+        public_id: Uuid,
+        user_id: i32,
+        revoked_at: Option<DateTime<Utc>>,
+    }
+
+    /// セッションリポジトリ
+    ///
+    /// * `sessions`            - セッション
+    /// * `tokens`              - 発行したリフレッシュトークン
+    /// * `role`                - ユーザーの現在のロール
+    /// * `password_changed_at` - ユーザーのパスワード変更日時
+    #[derive(Default)]
+    struct MockSessionRepository {
+        sessions: Mutex<Vec<Session>>,
+        tokens: Mutex<Vec<RefreshToken>>,
+        role: Mutex<Role>,
+        password_changed_at: Mutex<Option<DateTime<Utc>>>,
+    }
+
+    impl MockSessionRepository {
+        /// セッションと最初のリフレッシュトークンを作成
+        fn with_session(user_id: i32, public_id: Uuid, refresh_token: &str) -> Self {
+            let repository = MockSessionRepository::default();
+            repository.sessions.lock().unwrap().push(Session { id: 1, public_id, user_id, revoked_at: None });
+            repository.tokens.lock().unwrap().push(RefreshToken { id: 1, session_id: 1, token_hash: hash_token(refresh_token), used_at: None });
+
+            repository
+        }
+
+        fn is_revoked(&self, session_id: i32) -> bool {
+            self.sessions.lock().unwrap().iter().any(|session| session.id == session_id && session.revoked_at.is_some())
+        }
+    }
+
+    #[async_trait]
+    impl SessionRepository for MockSessionRepository {
+        async fn create_session(
+            &self,
+            _user_id: i32,
+            _public_id: Uuid,
+            _meta: &SessionMeta,
+            _expires_at: DateTime<Utc>,
+            _token_hash: &str,
+        ) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenSelectResult>, AuthError> {
+            let tokens = self.tokens.lock().unwrap();
+            let Some(token) = tokens.iter().find(|token| token.token_hash == token_hash) else {
+                return Ok(None);
+            };
+            let sessions = self.sessions.lock().unwrap();
+            let session = sessions.iter().find(|session| session.id == token.session_id).unwrap();
+            let now = Utc::now();
+
+            Ok(Some(RefreshTokenSelectResult {
+                id: token.id,
+                used_at: token.used_at,
+                session: SessionSelectResult {
+                    id: session.id,
+                    public_id: session.public_id,
+                    user_id: session.user_id,
+                    user_agent: None,
+                    ip_address: None,
+                    expires_at: now + Duration::days(1),
+                    revoked_at: session.revoked_at,
+                    last_used_at: now,
+                    created_at: now,
+                },
+            }))
+        }
+
+        /// DB と同じく、未使用のトークンのみ使用済みにして新しいトークンを追加する
+        async fn rotate_refresh_token(
+            &self,
+            token_id: i32,
+            session_id: i32,
+            _meta: &SessionMeta,
+            new_token_hash: &str,
+        ) -> Result<bool, AuthError> {
+            let mut tokens = self.tokens.lock().unwrap();
+            let Some(token) = tokens.iter_mut().find(|token| token.id == token_id && token.used_at.is_none()) else {
+                return Ok(false);
+            };
+            token.used_at = Some(Utc::now());
+
+            let id = tokens.len() as i32 + 1;
+            tokens.push(RefreshToken { id, session_id, token_hash: new_token_hash.to_string(), used_at: None });
+            Ok(true)
+        }
+
+        async fn revoke_session(&self, session_id: i32) -> Result<(), AuthError> {
+            for session in self.sessions.lock().unwrap().iter_mut().filter(|session| session.id == session_id) {
+                session.revoked_at = Some(Utc::now());
+            }
+            Ok(())
+        }
+
+        async fn revoke_session_by_public_id(&self, _user_id: i32, _public_id: Uuid) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn revoke_all_sessions(&self, _user_id: i32) -> Result<u64, AuthError> {
+            unimplemented!()
+        }
+
+        async fn get_active_sessions(&self, _user_id: i32) -> Result<Vec<SessionSelectResult>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn get_session_status(&self, user_id: i32, public_id: Uuid) -> Result<Option<SessionStatus>, AuthError> {
+            let sessions = self.sessions.lock().unwrap();
+            let Some(session) = sessions.iter().find(|session| session.user_id == user_id && session.public_id == public_id) else {
+                return Ok(None);
+            };
+
+            Ok(Some(SessionStatus {
+                password_changed_at: *self.password_changed_at.lock().unwrap(),
+                is_active: session.revoked_at.is_none(),
+                role: self.role.lock().unwrap().as_str().to_string(),
+                locale: None,
+            }))
+        }
+    }
+
+    /// 2段階認証リポジトリ（2段階認証を使用しないテストのみ）
+    struct UnusedMfaRepository;
+
+    #[async_trait]
+    impl MfaRepository for UnusedMfaRepository {
+        async fn get_totp(&self, _user_id: i32) -> Result<Option<UserTotp>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn save_pending_totp(&self, _user_id: i32, _secret: &str) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn enable_totp(&self, _user_id: i32, _step: i64, _recovery_code_hashes: &[String]) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn use_totp_step(&self, _user_id: i32, _step: i64) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn disable_totp(&self, _user_id: i32) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn replace_recovery_codes(&self, _user_id: i32, _recovery_code_hashes: &[String]) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn use_recovery_code(&self, _user_id: i32, _code_hash: &str) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+
+        async fn count_recovery_codes(&self, _user_id: i32) -> Result<i64, AuthError> {
+            unimplemented!()
+        }
+
+        async fn create_challenge(&self, _user_id: i32, _token_hash: &str, _expires_at: DateTime<Utc>) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn find_challenge(&self, _token_hash: &str) -> Result<Option<MfaChallenge>, AuthError> {
+            unimplemented!()
+        }
+
+        async fn record_challenge_failure(&self, _challenge_id: i32) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn consume_challenge(&self, _challenge_id: i32) -> Result<bool, AuthError> {
+            unimplemented!()
+        }
+    }
+
+    /// ログイン保護サービス（ログインしないテストのみ）
+    struct UnusedLoginGuard;
+
+    #[async_trait]
+    impl LoginGuardService for UnusedLoginGuard {
+        async fn check_request(&self, _ip_address: Option<&str>, _email: Option<&str>) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn record_failure(&self, _attempt: &LoginAttempt, _user_id: Option<i32>, _reason: LoginFailureReason) -> Result<std::time::Duration, AuthError> {
+            unimplemented!()
+        }
+
+        async fn record_success(&self, _attempt: &LoginAttempt) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+
+        async fn get_failures(&self, _query: &LoginFailureListQuery, _pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError> {
+            unimplemented!()
+        }
+
+        async fn prune(&self) -> Result<(), AuthError> {
+            unimplemented!()
+        }
+    }
+
+    fn service(auth_repository: Arc<MockAuthRepository>, session_repository: Arc<MockSessionRepository>) -> AuthServiceImpl {
+        let password_hasher = PasswordHasher::from_settings(&PasswordSettings { argon2_memory_kib: 8, argon2_iterations: 1, argon2_parallelism: 1 }).unwrap();
+
+        AuthServiceImpl::new(
+            auth_repository,
+            session_repository,
+            Arc::new(UnusedMfaRepository),
+            Arc::new(UnusedLoginGuard),
+            Arc::new(password_hasher),
+            Arc::new(JwtKeys::from_secret("current", b"secret")),
+            &AppConfig::default()
+        )
+    }
+
+    /// メールのリンクに含まれるトークン
+    fn token_in(mail: &Mail) -> String {
+        mail.text.split("/reset-password/").nth(1).unwrap().split_whitespace().next().unwrap().to_string()
+    }
+
+    // 待機時間中は発行せず、待機時間を過ぎると新しいトークンを発行する
+    #[actix_rt::test]
+    async fn test_forgot_password_cooldown() {
+        let cooldown = Duration::seconds(AppConfig::default().auth.password_reset_cooldown_secs);
+        let users = vec![(1, "john@example.com", Role::User)];
+
+        let repository = Arc::new(MockAuthRepository { users: users.clone(), reset_sent_at: Some(Utc::now() - cooldown + Duration::seconds(10)), ..Default::default() });
+        service(repository.clone(), Arc::default()).forgot_password("john@example.com").await.unwrap();
+        assert!(repository.reset_tokens.lock().unwrap().is_empty());
+
+        let repository = Arc::new(MockAuthRepository { users, reset_sent_at: Some(Utc::now() - cooldown - Duration::seconds(1)), ..Default::default() });
+        service(repository.clone(), Arc::default()).forgot_password("john@example.com").await.unwrap();
+        assert_eq!(repository.reset_tokens.lock().unwrap().len(), 1);
+    }
+
+    // 未登録・ゲストのメールアドレスも、登録済みの場合と同じく最短の処理時間まで待ってから `Ok` を返す
+    #[actix_rt::test]
+    async fn test_forgot_password_min_duration() {
+        let repository = Arc::new(MockAuthRepository {
+            users: vec![(1, "john@example.com", Role::User), (2, "guest@guest.invalid", Role::Guest)],
+            ..Default::default()
+        });
+        let service = service(repository.clone(), Arc::default());
+
+        for email in ["john@example.com", "unknown@example.com", "guest@guest.invalid"] {
+            let started_at = Instant::now();
+            service.forgot_password(email).await.unwrap();
+            assert!(started_at.elapsed() >= FORGOT_PASSWORD_MIN_DURATION, "email = {}", email);
+        }
+        assert_eq!(repository.reset_tokens.lock().unwrap().len(), 1);
+    }
+
+    // DB にはトークンのハッシュのみを保存し、メールのトークンは一度だけ使用できる
+    #[actix_rt::test]
+    async fn test_reset_password_single_use() {
+        let repository = Arc::new(MockAuthRepository { users: vec![(1, "john@example.com", Role::User)], ..Default::default() });
+        let service = service(repository.clone(), Arc::default());
+
+        service.forgot_password("john@example.com").await.unwrap();
+        let token = token_in(&repository.reset_mails.lock().unwrap()[0]);
+        let token_hash = repository.reset_tokens.lock().unwrap()[0].token_hash.clone();
+        assert_eq!(token_hash, hash_token(&token));
+        assert!(matches!(service.reset_password(&token_hash, "Newpass123").await, Err(AuthError::InvalidToken)));
+
+        service.reset_password(&token, "Newpass123").await.unwrap();
+        assert!(matches!(service.reset_password(&token, "Newpass123").await, Err(AuthError::InvalidToken)));
+    }
+
+    // 有効期限を過ぎたトークンは使用できない
+    #[actix_rt::test]
+    async fn test_reset_password_expired() {
+        let repository = Arc::new(MockAuthRepository::default());
+        repository.reset_tokens.lock().unwrap().push(ResetToken {
+            user_id: 1,
+            token_hash: hash_token("expired-token"),
+            expires_at: Utc::now() - Duration::seconds(1),
+        });
+
+        let result = service(repository, Arc::default()).reset_password("expired-token", "Newpass123").await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    // リフレッシュトークンは使用するたびに新しいトークンに入れ替え、同じセッションのアクセストークンを発行する
+    #[actix_rt::test]
+    async fn test_refresh_session_rotates_token() {
+        let public_id = Uuid::new_v4();
+        let auth_repository = Arc::new(MockAuthRepository { users: vec![(1, "john@example.com", Role::User)], ..Default::default() });
+        let session_repository = Arc::new(MockSessionRepository::with_session(1, public_id, "refresh-1"));
+        let service = service(auth_repository, session_repository.clone());
+        let meta = SessionMeta::default();
+
+        let tokens = service.refresh_session("refresh-1", &meta).await.unwrap();
+        assert_ne!(tokens.refresh_token, "refresh-1");
+        let claims = JwtKeys::from_secret("current", b"secret").decode_token(&tokens.access_token).unwrap();
+        assert_eq!((claims.id, claims.sid, claims.role), (1, public_id.to_string(), Role::User));
+
+        let tokens = service.refresh_session(&tokens.refresh_token, &meta).await.unwrap();
+        assert!(service.refresh_session(&tokens.refresh_token, &meta).await.is_ok());
+        assert!(!session_repository.is_revoked(1));
+    }
+
+    // 使用済みのリフレッシュトークンが再度使われた場合は、セッションごと失効させる
+    #[actix_rt::test]
+    async fn test_refresh_token_reuse_revokes_session() {
+        let auth_repository = Arc::new(MockAuthRepository { users: vec![(1, "john@example.com", Role::User)], ..Default::default() });
+        let session_repository = Arc::new(MockSessionRepository::with_session(1, Uuid::new_v4(), "refresh-1"));
+        let service = service(auth_repository, session_repository.clone());
+        let meta = SessionMeta::default();
+
+        let tokens = service.refresh_session("refresh-1", &meta).await.unwrap();
+
+        assert!(matches!(service.refresh_session("refresh-1", &meta).await, Err(AuthError::InvalidToken)));
+        assert!(session_repository.is_revoked(1));
+        assert!(matches!(service.refresh_session(&tokens.refresh_token, &meta).await, Err(AuthError::InvalidToken)));
+        assert!(matches!(service.refresh_session("unknown", &meta).await, Err(AuthError::InvalidToken)));
+    }
+
+    // 有効なセッションで、パスワード・ロールの変更前に発行されていない JWT のみ受け付ける
+    #[actix_rt::test]
+    async fn test_find_valid_session() {
+        let public_id = Uuid::new_v4();
+        let session_repository = Arc::new(MockSessionRepository::with_session(1, public_id, "refresh-1"));
+        *session_repository.role.lock().unwrap() = Role::User;
+        let service = service(Arc::default(), session_repository.clone());
+        let keys = JwtKeys::from_secret("current", b"secret");
+        let claims = keys.decode_token(&keys.create_token("john@example.com", &1, &public_id.to_string(), Role::User).unwrap()).unwrap();
+        let issued_at = DateTime::from_timestamp(claims.iat as i64, 0).unwrap();
+
+        assert!(service.find_valid_session(&claims).await.unwrap().is_some());
+
+        // `iat` は秒単位のため、同じ秒の変更後に発行したトークンは有効
+        *session_repository.password_changed_at.lock().unwrap() = Some(issued_at);
+        assert!(service.find_valid_session(&claims).await.unwrap().is_some());
+
+        *session_repository.password_changed_at.lock().unwrap() = Some(issued_at + Duration::seconds(1));
+        assert!(service.find_valid_session(&claims).await.unwrap().is_none());
+
+        *session_repository.password_changed_at.lock().unwrap() = None;
+        *session_repository.role.lock().unwrap() = Role::Admin;
+        assert!(service.find_valid_session(&claims).await.unwrap().is_none());
+    }
+
+    // 失効したセッション・セッションを持たない旧形式の JWT は無効
+    #[actix_rt::test]
+    async fn test_find_valid_session_rejects_revoked_session() {
+        let public_id = Uuid::new_v4();
+        let session_repository = Arc::new(MockSessionRepository::with_session(1, public_id, "refresh-1"));
+        let service = service(Arc::default(), session_repository.clone());
+        let keys = JwtKeys::from_secret("current", b"secret");
+
+        let legacy = keys.decode_token(&keys.create_token("john@example.com", &1, "", Role::User).unwrap()).unwrap();
+        assert!(service.find_valid_session(&legacy).await.unwrap().is_none());
+
+        let claims = keys.decode_token(&keys.create_token("john@example.com", &1, &public_id.to_string(), Role::User).unwrap()).unwrap();
+        session_repository.revoke_session(1).await.unwrap();
+        assert!(service.find_valid_session(&claims).await.unwrap().is_none());
+    }
+
+    // パスワードリセットは、期限切れ・使用済みのトークンを拒否し、全端末のセッションを失効させる
+    //
+    // DATABASE_HOST=localhost DATABASE_NAME=gamernage ... cargo test reset_password_repository -- --ignored
+    #[actix_rt::test]
+    #[ignore]
+    async fn test_reset_password_repository() {
+        let mut config = AppConfig::default();
+        let mut errors = Default::default();
+        config.apply_env(|key| env::var(key).ok(), &mut errors);
+        config.database.min_idle = 0;
+
+        let pool = get_db_pool(&config.database).await;
+        let conn = pool.get().await.unwrap();
+        let email = format!("reset-{}@example.com", Uuid::new_v4());
+        let user_id: i32 = conn.query_one(
+            "INSERT INTO users (name, email, password) VALUES ('Reset', $1, 'x') RETURNING id;",
+            &[&email]
+        ).await.unwrap().get(0);
+
+        let auth_repository = AuthRepositoryImpl::new(pool.clone());
+        let session_repository = SessionRepositoryImpl::new(pool.clone());
+        let meta = SessionMeta::default();
+        for _ in 0..2 {
+            session_repository.create_session(user_id, Uuid::new_v4(), &meta, Utc::now() + Duration::days(1), &hash_token(&Uuid::new_v4().to_string())).await.unwrap();
+        }
+        let mail = Mail { to: email.clone(), subject: String::new(), text: String::new(), html: String::new() };
+
+        auth_repository.create_password_reset_token(user_id, &hash_token("expired-token"), Utc::now() - Duration::seconds(1), &mail).await.unwrap();
+        let expired = auth_repository.reset_password(&hash_token("expired-token"), "new-hash").await.unwrap();
+
+        auth_repository.create_password_reset_token(user_id, &hash_token("valid-token"), Utc::now() + Duration::hours(1), &mail).await.unwrap();
+        let reset = auth_repository.reset_password(&hash_token("valid-token"), "new-hash").await.unwrap();
+        let reused = auth_repository.reset_password(&hash_token("valid-token"), "other-hash").await.unwrap();
+
+        let active_sessions = session_repository.get_active_sessions(user_id).await.unwrap().len();
+        let row = conn.query_one("SELECT password, password_changed_at IS NOT NULL FROM users WHERE id = $1;", &[&user_id]).await.unwrap();
+
+        conn.execute("DELETE FROM email_outbox WHERE recipient = $1;", &[&email]).await.unwrap();
+        conn.execute("DELETE FROM users WHERE id = $1;", &[&user_id]).await.unwrap();
+
+        assert_eq!(expired, None);
+        assert_eq!(reset, Some(user_id));
+        assert_eq!(reused, None);
+        assert_eq!(active_sessions, 0);
+        assert_eq!(row.get::<_, String>(0), "new-hash");
+        assert!(row.get::<_, bool>(1));
+    }
+}
\ No newline at end of file
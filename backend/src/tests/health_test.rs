@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, Instant};
+    use serde_json::json;
+    use crate::application::mail::mailer::Mailer;
+    use crate::domain::entities::health::{ComponentCheck, HealthStatus, ReadinessResponse};
+    use crate::domain::services::health_service::build_info;
+    use crate::infrastructure::mail::file_mailer::FileMailer;
+
+    // 依存先が全て正常な場合のみ `ok`
+    #[test]
+    fn test_readiness_status() {
+        let checks = BTreeMap::from([("database", ComponentCheck::ok(1)), ("mail", ComponentCheck::ok(2))]);
+        assert_eq!(ReadinessResponse::new(checks, build_info(Instant::now())).status, HealthStatus::Ok);
+
+        let checks = BTreeMap::from([("database", ComponentCheck::error(2000, "timed out")), ("mail", ComponentCheck::ok(2))]);
+        assert_eq!(ReadinessResponse::new(checks, build_info(Instant::now())).status, HealthStatus::Degraded);
+    }
+
+    // 正常な依存先は `error` を出力しない
+    #[test]
+    fn test_readiness_json() {
+        let checks = BTreeMap::from([("database", ComponentCheck::ok(3)), ("migrations", ComponentCheck::error(1, "pending migrations: add_x"))]);
+        let report = serde_json::to_value(ReadinessResponse::new(checks, build_info(Instant::now()))).unwrap();
+
+        assert_eq!(report["status"], "degraded");
+        assert_eq!(report["checks"]["database"], json!({"status": "ok", "latency_ms": 3}));
+        assert_eq!(report["checks"]["migrations"]["error"], "pending migrations: add_x");
+        assert_eq!(report["build"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_build_info_uptime() {
+        let info = build_info(Instant::now() - Duration::from_secs(90));
+
+        assert_eq!(info.uptime_secs, 90);
+        assert!(!info.git_sha.is_empty());
+    }
+
+    // 出力先ディレクトリが削除された場合は失敗する
+    #[actix_rt::test]
+    async fn test_file_mailer_check() {
+        let dir = std::env::temp_dir().join(format!("health-test-{}", uuid::Uuid::new_v4()));
+        let mailer = FileMailer::new(&dir, "noreply@example.com".parse().unwrap()).unwrap();
+        assert!(mailer.check().await.is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(mailer.check().await.is_err());
+    }
+}
\ No newline at end of file
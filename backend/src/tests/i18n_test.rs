@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use crate::application::i18n::catalogue::{missing_messages, t, t_with, MessageKey};
+    use crate::application::i18n::request_locale::negotiate;
+    use crate::domain::enums::locale::Locale;
+
+    // 全ての言語のリソースファイルに、全てのメッセージキーが定義されている
+    #[test]
+    fn test_catalogue_is_complete() {
+        assert_eq!(missing_messages(), Vec::new());
+
+        for key in MessageKey::ALL {
+            assert_eq!(MessageKey::from_key(key.key()), Some(*key));
+        }
+    }
+
+    // 品質値の高い順に、対応している言語を選択する
+    #[test]
+    fn test_negotiate_accept_language() {
+        assert_eq!(negotiate("ja-JP,ja;q=0.9,en;q=0.8"), Some(Locale::Ja));
+        assert_eq!(negotiate("fr-FR, en;q=0.5, ja;q=0.7"), Some(Locale::Ja));
+        assert_eq!(negotiate("en-US;q=0.9, ja;q=0"), Some(Locale::En));
+        assert_eq!(negotiate("fr, de;q=0.8"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    // プレースホルダーを置き換える
+    #[test]
+    fn test_placeholder_replacement() {
+        let subject = t_with(Locale::Ja, MessageKey::MailTaskReminderSubject, &[("title", "脚トレ")]);
+
+        assert!(subject.contains("「脚トレ」"));
+        assert!(!subject.contains("{title}"));
+        assert_ne!(t(Locale::En, MessageKey::MailResetSubject), t(Locale::Ja, MessageKey::MailResetSubject));
+    }
+}
\ No newline at end of file
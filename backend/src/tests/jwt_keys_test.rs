@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::errors::ErrorKind;
+    use ring::rand::SystemRandom;
+    use ring::signature::Ed25519KeyPair;
+    use crate::application::jwt::jwt_keys::{JwtKeys, KeyAlgorithm};
+    use crate::domain::enums::role::Role;
+
+    // 発行したトークンを同じ鍵で検証できる
+    #[test]
+    fn test_create_and_decode_token() {
+        let keys = JwtKeys::from_secret("current", b"secret");
+
+        let token = keys.create_token("user@gmail.com", &1, "sid", Role::Creator).unwrap();
+        let claims = keys.decode_token(&token).unwrap();
+
+        assert_eq!(claims.id, 1);
+        assert_eq!(claims.sub, "user@gmail.com");
+        assert_eq!(claims.sid, "sid");
+        assert_eq!(claims.role, Role::Creator);
+    }
+
+    // 有効期限の上限を指定した場合は、アクセストークンの有効期限より短くする
+    #[test]
+    fn test_token_expiry_is_capped() {
+        let keys = JwtKeys::from_secret("current", b"secret");
+        let not_after = chrono::Utc::now().timestamp() as u64 + 60;
+
+        let token = keys.create_token_until("guest@guest.invalid", &1, "sid", Role::Guest, not_after).unwrap();
+        let claims = keys.decode_token(&token).unwrap();
+        assert_eq!(claims.exp, not_after as usize);
+        assert_eq!(claims.role, Role::Guest);
+
+        let token = keys.create_token_until("user@gmail.com", &1, "sid", Role::User, u64::MAX).unwrap();
+        assert!(keys.decode_token(&token).unwrap().exp < not_after as usize + 3600);
+    }
+
+    // ローテーション後も旧鍵で署名したトークンを検証でき、未登録の `kid` は拒否する
+    #[test]
+    fn test_rotated_key_is_accepted() {
+        let old_keys = JwtKeys::from_secret("old", b"old-secret");
+        let token = old_keys.create_token("user@gmail.com", &1, "sid", Role::User).unwrap();
+
+        let rotated = JwtKeys::from_secret("new", b"new-secret")
+            .with_verification_secret("old", b"old-secret")
+            .unwrap();
+        assert!(rotated.decode_token(&token).is_ok());
+
+        let without_old = JwtKeys::from_secret("new", b"new-secret");
+        assert!(without_old.decode_token(&token).is_err());
+    }
+
+    // 発行者・対象者が一致しないトークンは拒否する
+    #[test]
+    fn test_issuer_and_audience_are_validated() {
+        let keys = JwtKeys::from_secret("current", b"secret")
+            .with_issuer(Some("backend".to_string()))
+            .with_audience(vec!["frontend".to_string()]);
+        let token = keys.create_token("user@gmail.com", &1, "sid", Role::User).unwrap();
+
+        let other_issuer = JwtKeys::from_secret("current", b"secret").with_issuer(Some("other".to_string()));
+        let error = other_issuer.decode_token(&token).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidIssuer));
+
+        let other_audience = JwtKeys::from_secret("current", b"secret").with_audience(vec!["admin".to_string()]);
+        let error = other_audience.decode_token(&token).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidAudience));
+    }
+
+    // EdDSA の鍵で署名し、公開鍵を JWK Set で公開する
+    #[test]
+    fn test_eddsa_key_is_published_in_jwks() {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem = pem::encode(&pem::Pem { tag: "PRIVATE KEY".to_string(), contents: pkcs8.as_ref().to_vec() });
+        let keys = JwtKeys::from_private_key_pem(KeyAlgorithm::EdDSA, "ed", pem.as_bytes()).unwrap();
+
+        let token = keys.create_token("user@gmail.com", &1, "sid", Role::User).unwrap();
+        assert!(keys.decode_token(&token).is_ok());
+
+        let jwks = keys.jwks();
+        assert_eq!(jwks.keys.len(), 1);
+        assert_eq!(jwks.keys[0].kid, "ed");
+        assert_eq!(jwks.keys[0].crv.as_deref(), Some("Ed25519"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use actix_web::http::header::HeaderValue;
+    use uuid::Uuid;
+    use crate::application::helpers::redact::redact;
+    use crate::application::middlewares::request_id_middleware::request_id;
+    use crate::infrastructure::config::log_config::{LogFileConfig, LogRotation};
+    use crate::infrastructure::logging::rolling_file::RollingFileWriter;
+
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(redact("login password=hunter2 ok"), "login password=[REDACTED] ok");
+        assert_eq!(
+            redact(r#"{"message":"body {\"email\":\"a@b.c\",\"password\":\"hunter2\"}"}"#),
+            r#"{"message":"body {\"email\":\"a@b.c\",\"password\":\"[REDACTED]\"}"}"#
+        );
+        assert_eq!(redact("refresh_token: abc123"), "refresh_token: [REDACTED]");
+        assert_eq!(redact("Authorization: Bearer abc.def"), "Authorization: [REDACTED] [REDACTED]");
+        assert_eq!(redact("token eyJhbGciOi.eyJzdWIiOi.c2lnbmF0dXJl"), "token [REDACTED]");
+        assert_eq!(redact("postgres://app:s3cret@db:5432/gamernage"), "postgres://app:[REDACTED]@db:5432/gamernage");
+    }
+
+    // モジュールパスや秘匿情報を含まない行は変更しない
+    #[test]
+    fn test_redact_keeps_plain_lines() {
+        let line = r#"{"level":"INFO","target":"backend::application::helpers::token::x","message":"Token expired"}"#;
+        assert_eq!(redact(line), line);
+    }
+
+    #[test]
+    fn test_request_id() {
+        assert_eq!(request_id(Some(&HeaderValue::from_static("abc-123_x.y:z"))), "abc-123_x.y:z");
+
+        // 不正な値・未指定の場合は UUID を生成する
+        for header in [None, Some(HeaderValue::from_static("a b")), Some(HeaderValue::from_static(""))] {
+            assert!(Uuid::parse_str(&request_id(header.as_ref())).is_ok());
+        }
+        let too_long = HeaderValue::from_str(&"a".repeat(129)).unwrap();
+        assert!(Uuid::parse_str(&request_id(Some(&too_long))).is_ok());
+    }
+
+    // サイズを超えるとローテーションし、保持数を超えたファイルは削除する
+    #[test]
+    fn test_rolling_file_size_rotation_and_retention() {
+        let dir = std::env::temp_dir().join(format!("gamernage-log-{}", Uuid::new_v4()));
+        let mut writer = RollingFileWriter::open(LogFileConfig {
+            dir: dir.clone(),
+            file_name: "actix.log".to_string(),
+            rotation: LogRotation::Never,
+            max_bytes: 16,
+            max_files: 2,
+        }).unwrap();
+
+        for i in 0..5 {
+            writer.write_all(format!("line {:08}\n", i).as_bytes()).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+
+        assert_eq!(names.len(), 3, "{:?}", names);
+        assert!(names.contains(&"actix.log".to_string()));
+        assert_eq!(fs::read_to_string(dir.join("actix.log")).unwrap(), "line 00000004\n");
+        // 古いものから削除される
+        assert_eq!(fs::read_to_string(dir.join(&names[0])).unwrap(), "line 00000002\n");
+        assert_eq!(fs::read_to_string(dir.join(&names[1])).unwrap(), "line 00000003\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration as StdDuration;
+    use actix_web::http::header::RETRY_AFTER;
+    use actix_web::ResponseError;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Duration, Utc};
+    use crate::application::errors::api_error::ApiError;
+    use crate::application::errors::auth_error::AuthError;
+    use crate::domain::entities::login_protection::{LoginAttempt, LoginFailureListQuery, LoginFailureListResponse, NewLoginFailure};
+    use crate::domain::entities::pagination::Pagination;
+    use crate::domain::enums::login_failure::LoginFailureReason;
+    use crate::domain::repositories::login_failure_repository::LoginFailureRepository;
+    use crate::domain::repositories::throttle_store::ThrottleStore;
+    use crate::domain::services::login_guard_service::{progressive_delay, LoginGuardService, LoginGuardServiceImpl};
+    use crate::infrastructure::config::app_config::LoginProtectionSettings;
+    use crate::infrastructure::repositories::memory_throttle_store::MemoryThrottleStore;
+
+    /// 記録した失敗の理由とロックの有無を保持する
+    #[derive(Default)]
+    struct RecordedFailures(Mutex<Vec<(LoginFailureReason, bool)>>);
+
+    #[async_trait]
+    impl LoginFailureRepository for RecordedFailures {
+        async fn record_failure(&self, failure: &NewLoginFailure<'_>) -> Result<(), AuthError> {
+            self.0.lock().unwrap().push((failure.reason, failure.locked_until.is_some()));
+            Ok(())
+        }
+
+        async fn get_failures(&self, _query: &LoginFailureListQuery, _pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError> {
+            unimplemented!()
+        }
+
+        async fn prune(&self, _before: DateTime<Utc>) -> Result<u64, AuthError> {
+            Ok(0)
+        }
+    }
+
+    fn guard(settings: LoginProtectionSettings) -> (LoginGuardServiceImpl, Arc<RecordedFailures>) {
+        let failures = Arc::new(RecordedFailures::default());
+        (LoginGuardServiceImpl::new(Arc::new(MemoryThrottleStore::new()), failures.clone(), settings), failures)
+    }
+
+    fn attempt(email: &str) -> LoginAttempt {
+        LoginAttempt { email: email.to_string(), ip_address: Some("192.0.2.1".to_string()), user_agent: None }
+    }
+
+    #[test]
+    fn test_progressive_delay() {
+        assert_eq!(progressive_delay(2, 2, 500, 4000), StdDuration::ZERO);
+        assert_eq!(progressive_delay(3, 2, 500, 4000), StdDuration::from_millis(500));
+        assert_eq!(progressive_delay(5, 2, 500, 4000), StdDuration::from_millis(2000));
+        assert_eq!(progressive_delay(100, 2, 500, 4000), StdDuration::from_millis(4000));
+    }
+
+    // 期間を過ぎたカウンターは 1 から数え直し、ロックは残す
+    #[actix_rt::test]
+    async fn test_memory_store_window() {
+        let store = MemoryThrottleStore::new();
+        store.increment("request:ip:192.0.2.1", Duration::minutes(1)).await.unwrap();
+        assert_eq!(store.increment("request:ip:192.0.2.1", Duration::minutes(1)).await.unwrap().count, 2);
+
+        store.lock("request:ip:192.0.2.1", Utc::now() + Duration::minutes(5)).await.unwrap();
+        let counter = store.increment("request:ip:192.0.2.1", Duration::zero()).await.unwrap();
+        assert_eq!(counter.count, 1);
+        assert!(counter.locked_until.is_some());
+
+        assert_eq!(store.prune(Utc::now() + Duration::minutes(1)).await.unwrap(), 0);
+        store.reset("request:ip:192.0.2.1").await.unwrap();
+        assert!(store.get("request:ip:192.0.2.1").await.unwrap().is_none());
+    }
+
+    // 失敗回数が上限に達したアカウントはロックし、未登録のメールアドレスも同じように扱う
+    #[actix_rt::test]
+    async fn test_account_lockout() {
+        let settings = LoginProtectionSettings { account_max_failures: 3, lockout_secs: 600, ..Default::default() };
+        let (guard, failures) = guard(settings);
+
+        for _ in 0..3 {
+            guard.check_request(Some("192.0.2.1"), Some("Unknown@Example.com ")).await.unwrap();
+            guard.record_failure(&attempt("unknown@example.com"), None, LoginFailureReason::UnknownUser).await.unwrap();
+        }
+
+        match guard.check_request(Some("192.0.2.2"), Some("unknown@example.com")).await {
+            Err(AuthError::RateLimited(retry_after)) => assert!((599..=600).contains(&retry_after)),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+        assert!(guard.check_request(Some("192.0.2.2"), Some("other@example.com")).await.is_ok());
+        assert_eq!(failures.0.lock().unwrap().last(), Some(&(LoginFailureReason::UnknownUser, true)));
+    }
+
+    // 成功するとアカウントの失敗回数をリセットする
+    #[actix_rt::test]
+    async fn test_success_resets_failures() {
+        let settings = LoginProtectionSettings { account_max_failures: 2, delay_after_failures: 0, delay_base_ms: 100, ..Default::default() };
+        let (guard, _) = guard(settings);
+
+        let delay = guard.record_failure(&attempt("john@example.com"), Some(1), LoginFailureReason::WrongPassword).await.unwrap();
+        assert_eq!(delay, StdDuration::from_millis(100));
+
+        guard.record_success(&attempt("john@example.com")).await.unwrap();
+        guard.record_failure(&attempt("john@example.com"), Some(1), LoginFailureReason::WrongPassword).await.unwrap();
+        assert!(guard.check_request(None, Some("john@example.com")).await.is_ok());
+    }
+
+    // IP アドレスごとのリクエスト数の上限を超えると、Retry-After 付きの 429 を返す
+    #[actix_rt::test]
+    async fn test_ip_rate_limit() {
+        let settings = LoginProtectionSettings { ip_max_requests: 2, rate_window_secs: 60, ..Default::default() };
+        let (guard, _) = guard(settings);
+
+        assert!(guard.check_request(Some("192.0.2.1"), Some("a@example.com")).await.is_ok());
+        assert!(guard.check_request(Some("192.0.2.1"), Some("b@example.com")).await.is_ok());
+        let error = ApiError::from(guard.check_request(Some("192.0.2.1"), Some("c@example.com")).await.unwrap_err());
+
+        let response = error.error_response();
+        assert_eq!(response.status().as_u16(), 429);
+        assert_eq!(error.code(), "rate_limited");
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "60");
+    }
+}
\ No newline at end of file
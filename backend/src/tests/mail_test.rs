@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use crate::application::mail::mailer::Mailer;
+    use crate::application::mail::template::{render, MailTemplate};
+    use crate::application::use_cases::mail_composer::{reset_mail, task_reminder_mail, verification_mail};
+    use crate::domain::entities::outbox::TaskReminder;
+    use crate::domain::enums::locale::Locale;
+    use crate::infrastructure::mail::memory_mailer::MemoryMailer;
+
+    // 全てのテンプレートのプレースホルダーを、全ての言語で置換する
+    #[test]
+    fn test_render_all_templates() {
+        let args = [("link", "https://example.com/x"), ("title", "Leg day"), ("due_date", "2026-10-20")];
+
+        for template in [MailTemplate::Verification, MailTemplate::PasswordReset, MailTemplate::TaskReminder] {
+            for locale in Locale::ALL {
+                let mail = render(template, locale, "john@gmail.com", &args);
+
+                assert!(!mail.subject.is_empty());
+                assert!(mail.text.contains("https://example.com/x"), "{:?} {:?}", template, locale);
+                assert!(mail.html.contains("href=\"https://example.com/x\""), "{:?} {:?}", template, locale);
+                assert!(!mail.text.contains('{') && !mail.html.contains("{link}"), "{:?} {:?}", template, locale);
+            }
+        }
+    }
+
+    // HTML 形式の本文に置換する値はエスケープする
+    #[test]
+    fn test_render_escapes_html() {
+        let mail = render(
+            MailTemplate::TaskReminder,
+            Locale::En,
+            "john@gmail.com",
+            &[("title", "<b>Squats</b> & lunges"), ("due_date", "2026-10-20"), ("link", "https://example.com/tasks/1")],
+        );
+
+        assert!(mail.html.contains("&lt;b&gt;Squats&lt;/b&gt; &amp; lunges"));
+        assert!(mail.text.contains("<b>Squats</b> & lunges"));
+        assert!(mail.subject.contains("<b>Squats</b> & lunges"));
+    }
+
+    // 置換した値に含まれるプレースホルダーは置換しない
+    #[test]
+    fn test_render_does_not_expand_values() {
+        let mail = render(
+            MailTemplate::TaskReminder,
+            Locale::En,
+            "john@gmail.com",
+            &[("title", "{link} {due_date}"), ("due_date", "2026-10-20"), ("link", "https://example.com/tasks/1")],
+        );
+
+        assert!(mail.subject.contains("{link} {due_date}"));
+        assert!(mail.text.contains("{link} {due_date}"));
+        assert!(mail.html.contains("{link} {due_date}"));
+        assert_eq!(mail.text.matches("https://example.com/tasks/1").count(), 1);
+    }
+
+    // リマインダーはユーザーの言語で作成し、未設定の場合は既定の言語にする
+    #[test]
+    fn test_task_reminder_mail() {
+        let mut reminder = TaskReminder {
+            task_id: 42,
+            title: "Leg day".to_string(),
+            due_date: Utc.with_ymd_and_hms(2026, 10, 20, 9, 30, 0).unwrap(),
+            email: "john@gmail.com".to_string(),
+            locale: Some("ja".to_string()),
+        };
+
+        let mail = task_reminder_mail(&reminder);
+        assert_eq!(mail.to, "john@gmail.com");
+        assert!(mail.subject.contains("Leg day"));
+        assert!(mail.text.contains("2026-10-20 09:30 UTC"));
+        assert!(mail.text.contains("/tasks/42"));
+
+        reminder.locale = None;
+        assert_ne!(task_reminder_mail(&reminder).subject, mail.subject);
+    }
+
+    // 送信したメールは受信者の言語で作成され、テキストと HTML の本文を持つ
+    #[tokio::test]
+    async fn test_send_with_memory_mailer() {
+        let mailer = MemoryMailer::default();
+
+        mailer.send(&verification_mail("john@gmail.com", "abc", Locale::Ja)).await.unwrap();
+        mailer.send(&reset_mail("jane@gmail.com", "def", Locale::En)).await.unwrap();
+
+        let sent = mailer.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].to, "john@gmail.com");
+        assert_eq!(sent[0].subject, "メールアドレスの認証");
+        assert_eq!(sent[1].subject, "Password Reset Request");
+        assert!(sent[0].text.contains("/verify-email/abc"));
+        assert!(sent[1].html.contains("/reset-password/def"));
+
+        let message = sent[0].to_message(&"Gamernage <no-reply@example.com>".parse().unwrap()).unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(formatted.contains("multipart/alternative"));
+        assert!(formatted.contains("From: Gamernage <no-reply@example.com>"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use crate::application::metrics::registry::{init, render, HTTP_REQUESTS_TOTAL};
+    use crate::application::middlewares::metrics_middleware::MetricsMiddleware;
+
+    // 一度も更新されていないメトリクスも出力する
+    #[test]
+    fn test_render_includes_all_metrics() {
+        init();
+        let output = render();
+
+        for name in [
+            "http_requests_total",
+            "db_pool_wait_seconds",
+            "user_registrations_total",
+            "user_logins_total{result=\"failure\"} 0",
+            "tasks_completed_total",
+            "mail_deliveries_total{result=\"dead\"}",
+            "mail_deliveries_total{result=\"expired\"}",
+        ] {
+            assert!(output.contains(name), "{} is missing", name);
+        }
+    }
+
+    // ルートはパスではなく登録したパターンで記録し、一致しないパスは `unmatched` にまとめる
+    #[actix_rt::test]
+    async fn test_http_metrics_use_route_pattern() {
+        let app = init_service(
+            App::new()
+                .wrap(MetricsMiddleware)
+                .route("/metrics-test/{id}", web::get().to(HttpResponse::Ok))
+        ).await;
+
+        for path in ["/metrics-test/1", "/metrics-test/2", "/metrics-test-unknown"] {
+            call_service(&app, TestRequest::get().uri(path).to_request()).await;
+        }
+
+        assert_eq!(HTTP_REQUESTS_TOTAL.with_label_values(&["GET", "/metrics-test/{id}", "200"]).get(), 2);
+        assert!(HTTP_REQUESTS_TOTAL.with_label_values(&["GET", "unmatched", "404"]).get() >= 1);
+    }
+}
\ No newline at end of file
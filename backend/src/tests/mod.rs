@@ -1,2 +1,23 @@
 // pub mod auth_test;
-// pub mod todo_test;
\ No newline at end of file
+// pub mod todo_test;
+pub mod api_error_test;
+pub mod app_config_test;
+pub mod auth_service_test;
+pub mod db_tls_test;
+pub mod health_test;
+pub mod i18n_test;
+pub mod jwt_keys_test;
+pub mod logger_test;
+pub mod login_guard_test;
+pub mod mail_test;
+pub mod metrics_test;
+pub mod oidc_test;
+pub mod outbox_test;
+pub mod pagination_test;
+pub mod password_test;
+pub mod role_test;
+pub mod supervisor_test;
+pub mod task_query_test;
+pub mod token_test;
+pub mod totp_test;
+pub mod user_service_test;
\ No newline at end of file
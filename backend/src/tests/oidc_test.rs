@@ -0,0 +1,253 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::{Arc, Mutex};
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use chrono::Utc;
+    use reqwest::Url;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::{json, Value};
+    use crate::application::errors::oidc_error::OidcError;
+    use crate::application::oidc::oidc_client::{pkce_challenge, AuthorizationRequest, OidcClient, OidcProviders};
+    use crate::domain::services::oidc_service::{state_binding, verify_state_binding};
+    use crate::infrastructure::config::app_config::{OidcProviderSettings, OidcSettings};
+
+    const CLIENT_ID: &str = "test-client";
+    const CLIENT_SECRET: &str = "test-secret";
+    const CODE: &str = "valid-code";
+
+    /// ローカルの OpenID Connect プロバイダー
+    ///
+    /// * `code_challenge` - トークンの交換で照合する PKCE の `code_challenge`
+    /// * `claims`         - 発行する ID トークンのクレーム
+    /// * `userinfo`       - UserInfo エンドポイントのレスポンス
+    struct MockProvider {
+        issuer: String,
+        key_pair: Ed25519KeyPair,
+        code_challenge: Mutex<Option<String>>,
+        claims: Mutex<Value>,
+        userinfo: Mutex<Value>,
+    }
+
+    impl MockProvider {
+        fn sign(&self, claims: &Value) -> String {
+            let encode = |value: &Value| base64::encode_config(value.to_string(), base64::URL_SAFE_NO_PAD);
+            let message = format!("{}.{}", encode(&json!({"alg": "EdDSA", "kid": "mock-key"})), encode(claims));
+            let signature = base64::encode_config(self.key_pair.sign(message.as_bytes()), base64::URL_SAFE_NO_PAD);
+
+            format!("{}.{}", message, signature)
+        }
+
+        /// 認可リクエストの値で、次に発行する ID トークンを設定
+        fn expect(&self, request: &AuthorizationRequest, claims: Value) {
+            let mut base = json!({
+                "iss": self.issuer,
+                "sub": "provider-user-1",
+                "aud": CLIENT_ID,
+                "exp": Utc::now().timestamp() + 300,
+                "iat": Utc::now().timestamp(),
+                "nonce": request.nonce,
+            });
+            base.as_object_mut().unwrap().extend(claims.as_object().unwrap().clone());
+
+            *self.code_challenge.lock().unwrap() = Some(query(&request.url)["code_challenge"].clone());
+            *self.claims.lock().unwrap() = base;
+        }
+    }
+
+    async fn discovery(mock: web::Data<MockProvider>) -> HttpResponse {
+        HttpResponse::Ok().json(json!({
+            "issuer": mock.issuer,
+            "authorization_endpoint": format!("{}/authorize", mock.issuer),
+            "token_endpoint": format!("{}/token", mock.issuer),
+            "userinfo_endpoint": format!("{}/userinfo", mock.issuer),
+            "jwks_uri": format!("{}/jwks", mock.issuer),
+            "token_endpoint_auth_methods_supported": ["client_secret_post"],
+        }))
+    }
+
+    async fn jwks(mock: web::Data<MockProvider>) -> HttpResponse {
+        let x = base64::encode_config(mock.key_pair.public_key().as_ref(), base64::URL_SAFE_NO_PAD);
+
+        HttpResponse::Ok().json(json!({"keys": [{"kty": "OKP", "crv": "Ed25519", "kid": "mock-key", "use": "sig", "x": x}]}))
+    }
+
+    /// 認可コード・クライアントシークレット・PKCE の検証コードが正しい場合のみトークンを発行する
+    async fn token(mock: web::Data<MockProvider>, form: web::Form<HashMap<String, String>>) -> HttpResponse {
+        let challenge = mock.code_challenge.lock().unwrap().clone();
+        let valid = form.get("grant_type").map(String::as_str) == Some("authorization_code")
+            && form.get("code").map(String::as_str) == Some(CODE)
+            && form.get("client_id").map(String::as_str) == Some(CLIENT_ID)
+            && form.get("client_secret").map(String::as_str) == Some(CLIENT_SECRET)
+            && form.get("code_verifier").map(|verifier| pkce_challenge(verifier)) == challenge;
+        if !valid {
+            return HttpResponse::BadRequest().json(json!({"error": "invalid_grant", "error_description": "invalid code"}));
+        }
+
+        let claims = mock.claims.lock().unwrap().clone();
+        HttpResponse::Ok().json(json!({
+            "access_token": "provider-access-token",
+            "token_type": "Bearer",
+            "expires_in": 3600,
+            "refresh_token": "provider-refresh-token",
+            "id_token": mock.sign(&claims),
+        }))
+    }
+
+    async fn userinfo(mock: web::Data<MockProvider>, req: actix_web::HttpRequest) -> HttpResponse {
+        match req.headers().get("Authorization").and_then(|value| value.to_str().ok()) {
+            Some("Bearer provider-access-token") => HttpResponse::Ok().json(mock.userinfo.lock().unwrap().clone()),
+            _ => HttpResponse::Unauthorized().finish(),
+        }
+    }
+
+    /// プロバイダーを起動し、そのプロバイダーを設定したクライアントを作成
+    async fn start() -> (web::Data<MockProvider>, Arc<OidcClient>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let mock = web::Data::new(MockProvider {
+            issuer: issuer.clone(),
+            key_pair: Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap(),
+            code_challenge: Mutex::new(None),
+            claims: Mutex::new(json!({})),
+            userinfo: Mutex::new(json!({})),
+        });
+
+        let data = mock.clone();
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/.well-known/openid-configuration", web::get().to(discovery))
+                .route("/jwks", web::get().to(jwks))
+                .route("/token", web::post().to(token))
+                .route("/userinfo", web::get().to(userinfo))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_rt::spawn(server);
+
+        let settings = OidcSettings {
+            providers: BTreeMap::from([("mock".to_string(), OidcProviderSettings {
+                issuer,
+                client_id: CLIENT_ID.to_string(),
+                client_secret: Some(CLIENT_SECRET.to_string()),
+                scopes: vec!["email".to_string(), "profile".to_string()],
+            })]),
+            ..OidcSettings::default()
+        };
+        let client = OidcProviders::from_settings(&settings, "http://localhost:3000/").unwrap().get("mock").unwrap();
+
+        (mock, client)
+    }
+
+    fn query(url: &str) -> HashMap<String, String> {
+        Url::parse(url).unwrap().query_pairs().into_owned().collect()
+    }
+
+    // 認可リクエストに PKCE（S256）・state・nonce を含める
+    #[actix_rt::test]
+    async fn test_authorization_request() {
+        let (mock, client) = start().await;
+        let request = client.authorization_request().await.unwrap();
+        let params = query(&request.url);
+
+        assert!(request.url.starts_with(&format!("{}/authorize?", mock.issuer)));
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["redirect_uri"], "http://localhost:3000/auth/callback/mock");
+        assert_eq!(params["scope"], "openid email profile");
+        assert_eq!(params["state"], request.state);
+        assert_eq!(params["nonce"], request.nonce);
+        assert_eq!(params["code_challenge"], pkce_challenge(&request.code_verifier));
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_ne!(request.state, request.nonce);
+    }
+
+    // 認可コードを交換し、ID トークンのユーザー情報とトークンを返す
+    #[actix_rt::test]
+    async fn test_exchange_code() {
+        let (mock, client) = start().await;
+        let request = client.authorization_request().await.unwrap();
+        mock.expect(&request, json!({"email": "oidc@gmail.com", "email_verified": true, "name": "OIDC User"}));
+
+        let identity = client.exchange_code(CODE, &request.code_verifier, &request.nonce).await.unwrap();
+
+        assert_eq!(identity.subject, "provider-user-1");
+        assert_eq!(identity.email.as_deref(), Some("oidc@gmail.com"));
+        assert!(identity.email_verified);
+        assert_eq!(identity.name.as_deref(), Some("OIDC User"));
+        assert_eq!(identity.tokens.access_token, "provider-access-token");
+        assert_eq!(identity.tokens.refresh_token.as_deref(), Some("provider-refresh-token"));
+        assert!(identity.tokens.expires_at.is_some());
+    }
+
+    // PKCE の検証コード・認可コードが一致しない場合は、プロバイダーのエラーを返す
+    #[actix_rt::test]
+    async fn test_exchange_code_rejected_by_provider() {
+        let (mock, client) = start().await;
+        let request = client.authorization_request().await.unwrap();
+        mock.expect(&request, json!({"email": "oidc@gmail.com"}));
+
+        let result = client.exchange_code(CODE, "other-verifier", &request.nonce).await;
+        assert!(matches!(result, Err(OidcError::Provider(message)) if message.contains("invalid_grant")));
+
+        let result = client.exchange_code("other-code", &request.code_verifier, &request.nonce).await;
+        assert!(matches!(result, Err(OidcError::Provider(_))));
+    }
+
+    // nonce・対象者・発行者・有効期限が一致しない ID トークンは受け付けない
+    #[actix_rt::test]
+    async fn test_invalid_id_token_claims() {
+        let (mock, client) = start().await;
+        let cases = [
+            json!({"nonce": "other-nonce"}),
+            json!({"aud": "other-client"}),
+            json!({"aud": [CLIENT_ID, "other-client"], "azp": "other-client"}),
+            json!({"iss": "https://evil.example.com"}),
+            json!({"exp": Utc::now().timestamp() - 3600}),
+        ];
+
+        for claims in cases {
+            let request = client.authorization_request().await.unwrap();
+            mock.expect(&request, claims.clone());
+
+            let result = client.exchange_code(CODE, &request.code_verifier, &request.nonce).await;
+            assert!(matches!(result, Err(OidcError::IdToken(_))), "claims = {}", claims);
+        }
+    }
+
+    // ID トークンにメールアドレスがない場合は UserInfo エンドポイントから取得し、`sub` が異なる場合は拒否する
+    #[actix_rt::test]
+    async fn test_userinfo_fallback() {
+        let (mock, client) = start().await;
+        let request = client.authorization_request().await.unwrap();
+        mock.expect(&request, json!({}));
+        *mock.userinfo.lock().unwrap() = json!({"sub": "provider-user-1", "email": "info@gmail.com", "email_verified": "true"});
+
+        let identity = client.exchange_code(CODE, &request.code_verifier, &request.nonce).await.unwrap();
+        assert_eq!(identity.email.as_deref(), Some("info@gmail.com"));
+        assert!(identity.email_verified);
+
+        let request = client.authorization_request().await.unwrap();
+        mock.expect(&request, json!({}));
+        *mock.userinfo.lock().unwrap() = json!({"sub": "provider-user-2", "email": "other@gmail.com"});
+
+        let result = client.exchange_code(CODE, &request.code_verifier, &request.nonce).await;
+        assert!(matches!(result, Err(OidcError::Provider(_))));
+    }
+
+    // コールバックは認可リクエストを開始したブラウザーのクッキーがある場合のみ受け付ける
+    #[test]
+    fn test_state_binding() {
+        let binding = state_binding("state-1");
+        assert!(verify_state_binding("state-1", Some(&binding)));
+        assert!(!verify_state_binding("state-1", None));
+        assert!(!verify_state_binding("state-1", Some("")));
+        assert!(!verify_state_binding("state-2", Some(&binding)));
+        assert!(!verify_state_binding("state-1", Some("state-1")));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use actix_web::web;
+    use validator::Validate;
+    use crate::domain::entities::pagination::{Pagination, MAX_PAGE};
+    use crate::domain::entities::task::TaskListQuery;
+
+    // ページ番号と件数から読み飛ばす件数・総ページ数を計算する
+    #[test]
+    fn test_pagination_offset() {
+        let pagination = Pagination { page: Some(3), per_page: Some(10) };
+        assert_eq!(pagination.offset(), 20);
+        assert_eq!(pagination.total_pages(21), 3);
+
+        let pagination = Pagination::default();
+        assert_eq!(pagination.page(), 1);
+        assert_eq!(pagination.per_page(), 20);
+        assert_eq!(pagination.offset(), 0);
+        assert_eq!(pagination.total_pages(0), 0);
+    }
+
+    // 範囲外の値は拒否し、読み飛ばす件数もオーバーフローしない
+    #[test]
+    fn test_pagination_is_bounded() {
+        let pagination = Pagination { page: Some(100_000_000_000_000_000), per_page: Some(100) };
+        assert!(pagination.validate().is_err());
+        assert_eq!(pagination.page(), MAX_PAGE);
+        assert_eq!(pagination.offset(), 99_999_900);
+
+        assert!(Pagination { page: Some(0), per_page: None }.validate().is_err());
+        assert!(Pagination { page: None, per_page: Some(101) }.validate().is_err());
+        assert!(Pagination { page: Some(MAX_PAGE), per_page: Some(100) }.validate().is_ok());
+    }
+
+    // 絞り込み条件と同じクエリ文字列から受け取る
+    #[test]
+    fn test_pagination_from_query() {
+        let query_string = "completed=true&page=2&per_page=5";
+
+        let pagination = web::Query::<Pagination>::from_query(query_string).unwrap();
+        assert_eq!(*pagination, Pagination { page: Some(2), per_page: Some(5) });
+
+        let query = web::Query::<TaskListQuery>::from_query(query_string).unwrap();
+        assert_eq!(query.completed, Some(true));
+
+        assert!(web::Query::<Pagination>::from_query("page=abc").is_err());
+    }
+}
\ No newline at end of file
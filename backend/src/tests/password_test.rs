@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use crate::application::errors::password_error::PasswordError;
+    use crate::application::helpers::password::{PasswordHasher, PasswordScheme};
+    use crate::infrastructure::config::app_config::PasswordSettings;
+
+    fn hasher(argon2_memory_kib: u32, argon2_iterations: u32) -> PasswordHasher {
+        PasswordHasher::from_settings(&PasswordSettings { argon2_memory_kib, argon2_iterations, argon2_parallelism: 1 }).unwrap()
+    }
+
+    // 現在の設定で作成したハッシュは作り直さない
+    #[test]
+    fn test_hash_and_verify() {
+        let hasher = hasher(256, 1);
+        let hash = hasher.hash("Password1").unwrap();
+
+        assert!(hash.starts_with("$argon2id$v=19$m=256,t=1,p=1$"));
+        assert!(hasher.verify("Password1", &hash).unwrap());
+        assert!(!hasher.verify("Password2", &hash).unwrap());
+        assert!(!hasher.needs_rehash(&hash));
+    }
+
+    // bcrypt のハッシュも検証でき、argon2id に作り直す
+    #[test]
+    fn test_verify_bcrypt() {
+        let hasher = hasher(256, 1);
+        let hash = bcrypt::hash("Password1", 4).unwrap();
+
+        assert_eq!(PasswordScheme::detect(&hash), Some(PasswordScheme::Bcrypt));
+        assert!(hasher.verify("Password1", &hash).unwrap());
+        assert!(!hasher.verify("Password2", &hash).unwrap());
+        assert!(hasher.needs_rehash(&hash));
+    }
+
+    // コストの異なる argon2 のハッシュは、ハッシュのコストで検証し、現在のコストで作り直す
+    #[test]
+    fn test_verify_other_argon2_params() {
+        let old_hash = hasher(128, 2).hash("Password1").unwrap();
+        let hasher = hasher(256, 1);
+
+        assert!(hasher.verify("Password1", &old_hash).unwrap());
+        assert!(hasher.needs_rehash(&old_hash));
+    }
+
+    #[test]
+    fn test_invalid_hash_and_params() {
+        let hasher = hasher(256, 1);
+
+        assert!(matches!(hasher.verify("Password1", "plain"), Err(PasswordError::UnsupportedScheme)));
+        assert!(matches!(hasher.verify("Password1", "$2b$04$short"), Err(PasswordError::Bcrypt(_))));
+        assert!(PasswordHasher::from_settings(&PasswordSettings { argon2_memory_kib: 1, argon2_iterations: 1, argon2_parallelism: 1 }).is_err());
+    }
+}
\ No newline at end of file
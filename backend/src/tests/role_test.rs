@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use crate::domain::enums::role::{Permission, Role};
+
+    // 権限マトリクス：ゲストは操作の権限なし、クリエイターはトレーニングメニューの公開のみ、管理者は全ての操作を許可
+    #[test]
+    fn test_permission_matrix() {
+        assert!(Role::Guest.permissions().is_empty());
+        assert!(!Role::User.has_permission(Permission::PublishTrainingMenus));
+        assert!(Role::Creator.has_permission(Permission::PublishTrainingMenus));
+        assert!(!Role::Creator.has_permission(Permission::ManageUsers));
+        assert!(Role::Admin.has_permission(Permission::ModerateContent));
+        assert!(Role::Admin.has_permission(Permission::ManageRoles));
+        assert!(Role::Admin.has_permission(Permission::ManageMail));
+        assert!(!Role::Creator.has_permission(Permission::ManageMail));
+        assert!(Role::Admin.has_permission(Permission::ManageJobs));
+        assert!(!Role::Creator.has_permission(Permission::ManageJobs));
+    }
+
+    // ロールを含まない旧形式のトークンは一般ユーザーとして扱う
+    #[test]
+    fn test_role_defaults_to_user() {
+        assert_eq!(Role::default(), Role::User);
+        assert_eq!("creator".parse::<Role>(), Ok(Role::Creator));
+        assert_eq!("guest".parse::<Role>(), Ok(Role::Guest));
+        assert!("owner".parse::<Role>().is_err());
+    }
+}
\ No newline at end of file
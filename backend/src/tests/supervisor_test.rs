@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::application::workers::supervisor::Supervisor;
+    use crate::domain::enums::job::JobState;
+
+    // 待機中のジョブは停止の要求ですぐに終了し、実行結果が記録される
+    #[actix_rt::test]
+    async fn test_job_stops_on_shutdown() {
+        let supervisor = Supervisor::new(Duration::from_secs(5));
+        supervisor.spawn("poller", |mut context| async move {
+            loop {
+                context.record_run(&Err::<(), _>("smtp unavailable"));
+                if !context.sleep(Duration::from_secs(3600)).await {
+                    break;
+                }
+            }
+        });
+        tokio::task::yield_now().await;
+
+        assert_eq!(supervisor.statuses()[0].state, JobState::Running);
+        assert!(supervisor.wait().await);
+
+        let status = &supervisor.statuses()[0];
+        assert!(supervisor.is_shutting_down());
+        assert_eq!(status.state, JobState::Stopped);
+        assert_eq!((status.runs, status.failures), (1, 1));
+        assert_eq!(status.last_error.as_deref(), Some("smtp unavailable"));
+    }
+
+    // パニックしたジョブは `failed`、期限までに終了しないジョブは `aborted`
+    #[actix_rt::test]
+    async fn test_failed_and_aborted_jobs() {
+        let supervisor = Supervisor::new(Duration::from_millis(50));
+        supervisor.spawn("panics", |_| async { panic!("boom") });
+        supervisor.spawn("ignores_shutdown", |_| async { tokio::time::sleep(Duration::from_secs(3600)).await });
+        tokio::task::yield_now().await;
+
+        assert!(!supervisor.wait().await);
+
+        let statuses = supervisor.statuses();
+        assert_eq!(statuses[0].state, JobState::Failed);
+        assert_eq!(statuses[0].last_error.as_deref(), Some("boom"));
+        assert_eq!(statuses[1].state, JobState::Aborted);
+        assert!(statuses[1].stopped_at.is_some());
+    }
+}
\ No newline at end of file
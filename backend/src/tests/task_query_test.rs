@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use validator::Validate;
+    use crate::domain::entities::task::{RequestCreateTaskItem, TaskListQuery};
+    use crate::domain::enums::task::{Priority, SortOrder, TaskSortField};
+    use crate::infrastructure::repositories::task_repository::TaskQueryBuilder;
+
+    // 条件なし　ユーザーと論理削除のみで絞り込む
+    #[test]
+    fn test_build_without_filters() {
+        let sql = TaskQueryBuilder::new(1).build(20, 0);
+
+        assert_eq!(sql.count_sql, "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND deleted_at IS NULL");
+        assert_eq!(
+            sql.select_sql,
+            "SELECT * FROM tasks WHERE user_id = $1 AND deleted_at IS NULL ORDER BY created_at DESC NULLS LAST, id DESC LIMIT $2 OFFSET $3"
+        );
+        assert_eq!(sql.count_params().len(), 1);
+        assert_eq!(sql.select_params().len(), 3);
+    }
+
+    // 複数条件　プレースホルダの番号が順に振られる
+    #[test]
+    fn test_build_with_filters() {
+        let query = TaskListQuery {
+            priority: Some(Priority::High),
+            completed: Some(false),
+            due_from: Some(NaiveDateTime::parse_from_str("2024-09-01T00:00:00", "%Y-%m-%dT%H:%M:%S").unwrap()),
+            q: Some("aim".to_string()),
+            sort: TaskSortField::DueDate,
+            order: SortOrder::Asc,
+            ..Default::default()
+        };
+
+        let sql = TaskQueryBuilder::new(1).apply(&query).build(20, 0);
+
+        assert_eq!(
+            sql.count_sql,
+            "SELECT COUNT(*) FROM tasks WHERE user_id = $1 AND deleted_at IS NULL AND priority = $2 AND is_completed = $3 AND due_date >= $4 AND title ILIKE $5 ESCAPE '\\'"
+        );
+        assert!(sql.select_sql.ends_with("ORDER BY due_date ASC NULLS LAST, id ASC LIMIT $6 OFFSET $7"));
+        assert_eq!(sql.count_params().len(), 5);
+        assert_eq!(sql.select_params().len(), 7);
+    }
+
+    // 期限切れ　パラメータを使わない条件を追加する
+    #[test]
+    fn test_build_overdue() {
+        let query = TaskListQuery {
+            overdue: Some(true),
+            ..Default::default()
+        };
+
+        let sql = TaskQueryBuilder::new(1).apply(&query).build(20, 0);
+
+        assert!(sql.count_sql.ends_with("AND due_date < CURRENT_TIMESTAMP AND is_completed = false"));
+        assert_eq!(sql.count_params().len(), 1);
+    }
+
+    // 空白のみの検索文字列は無視する
+    #[test]
+    fn test_build_blank_title_is_ignored() {
+        let query = TaskListQuery {
+            q: Some("   ".to_string()),
+            ..Default::default()
+        };
+
+        let sql = TaskQueryBuilder::new(1).apply(&query).build(20, 0);
+
+        assert!(!sql.count_sql.contains("ILIKE"));
+    }
+
+    // タイトルは 1〜100 文字のみ許可
+    #[test]
+    fn test_create_task_title_length() {
+        let task = |title: &str| RequestCreateTaskItem {
+            title: title.to_string(),
+            description: String::new(),
+            due_date: None,
+            priority: None,
+        };
+
+        assert!(task("aim training").validate().is_ok());
+        assert!(task("").validate().is_err());
+        assert!(task(&"a".repeat(101)).validate().is_err());
+    }
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod tests {
+    use crate::application::helpers::token::{generate_token, hash_token};
+
+    // 生成したトークンのハッシュが DB 保存用のハッシュと一致する
+    #[test]
+    fn test_generate_token_hash_matches() {
+        let (token, hash) = generate_token();
+
+        assert_eq!(token.len(), 64);
+        assert_eq!(hash, hash_token(&token));
+        assert_ne!(token, hash);
+    }
+
+    // 毎回異なるトークンを生成する
+    #[test]
+    fn test_generate_token_is_random() {
+        let (first, _) = generate_token();
+        let (second, _) = generate_token();
+
+        assert_ne!(first, second);
+    }
+}
\ No newline at end of file
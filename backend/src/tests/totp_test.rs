@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use crate::application::helpers::token::{generate_recovery_code, hash_recovery_code};
+    use crate::application::helpers::totp::{base32_decode, base32_encode, otpauth_uri, totp_code, verify_code, PERIOD_SECS};
+
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    // RFC 6238 付録 B のテストベクター（SHA1・下6桁）
+    #[test]
+    fn test_totp_code_rfc6238_vectors() {
+        let cases = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+
+        for (time, expected) in cases {
+            assert_eq!(totp_code(RFC_SECRET, time / PERIOD_SECS), expected, "time = {}", time);
+        }
+    }
+
+    // 前後1ステップのずれは受け付け、一致したステップを返す
+    #[test]
+    fn test_verify_code_allows_clock_skew() {
+        let secret = base32_encode(RFC_SECRET);
+        let now = 1111111111;
+        let step = now / PERIOD_SECS;
+
+        assert_eq!(verify_code(&secret, &totp_code(RFC_SECRET, step), now), Some(step));
+        assert_eq!(verify_code(&secret, &totp_code(RFC_SECRET, step - 1), now), Some(step - 1));
+        assert_eq!(verify_code(&secret, &totp_code(RFC_SECRET, step + 1), now), Some(step + 1));
+        assert_eq!(verify_code(&secret, &totp_code(RFC_SECRET, step - 2), now), None);
+        assert_eq!(verify_code(&secret, "12345", now), None);
+        assert_eq!(verify_code("not base32!", "123456", now), None);
+    }
+
+    #[test]
+    fn test_base32_roundtrip() {
+        assert_eq!(base32_encode(RFC_SECRET), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap(), RFC_SECRET);
+        assert_eq!(base32_decode(&base32_encode(b"f")).unwrap(), b"f");
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("Gamernage", "user+1@gmail.com", "ABC");
+
+        assert_eq!(uri, "otpauth://totp/Gamernage:user%2B1%40gmail.com?secret=ABC&issuer=Gamernage&algorithm=SHA1&digits=6&period=30");
+    }
+
+    // リカバリーコードは大文字・区切り文字の有無にかかわらず同じハッシュになる
+    #[test]
+    fn test_recovery_code_normalization() {
+        let (code, hash) = generate_recovery_code();
+
+        assert_eq!(code.len(), 14);
+        assert_eq!(hash, hash_recovery_code(&code));
+        assert_eq!(hash, hash_recovery_code(&code.replace('-', "").to_uppercase()));
+    }
+}
\ No newline at end of file
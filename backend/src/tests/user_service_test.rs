@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use crate::application::errors::auth_error::AuthError;
+    use crate::application::errors::user_error::UserError;
+    use crate::application::helpers::password::PasswordHasher;
+    use crate::application::jwt::jwt::Claims;
+    use crate::application::jwt::jwt_keys::JwtKeys;
+    use crate::domain::entities::login_protection::{LoginFailureListQuery, LoginFailureListResponse, NewLoginFailure};
+    use crate::domain::entities::pagination::Pagination;
+    use crate::domain::entities::session::SessionMeta;
+    use crate::domain::entities::user::{ChangePasswordRequest, UpdateUserRequest, UserListResponse, UserResponse};
+    use crate::domain::enums::role::Role;
+    use crate::domain::repositories::login_failure_repository::LoginFailureRepository;
+    use crate::domain::repositories::user_repository::UserRepository;
+    use crate::domain::services::login_guard_service::LoginGuardServiceImpl;
+    use crate::domain::services::user_service::{UserService, UserServiceImpl};
+    use crate::infrastructure::config::app_config::{LoginProtectionSettings, PasswordSettings};
+    use crate::infrastructure::repositories::memory_throttle_store::MemoryThrottleStore;
+
+    /// ユーザーリポジトリ
+    ///
+    /// * `password_hash`    - ユーザーのパスワードのハッシュ
+    /// * `updated_user_id`  - プロフィールを更新したユーザーID
+    /// * `password_updated` - パスワードを更新したかどうか
+    #[derive(Default)]
+    struct MockUserRepository {
+        password_hash: String,
+        updated_user_id: Mutex<Option<i32>>,
+        password_updated: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl UserRepository for MockUserRepository {
+        async fn get_user_id(&self, _user: &Claims) -> Result<Option<i32>, UserError> {
+            unimplemented!()
+        }
+
+        async fn find_user_by_id(&self, _user_id: &str) -> Result<Option<UserResponse>, UserError> {
+            unimplemented!()
+        }
+
+        async fn update_user(&self, user_id: i32, req: &UpdateUserRequest) -> Result<Option<UserResponse>, UserError> {
+            *self.updated_user_id.lock().unwrap() = Some(user_id);
+
+            Ok(Some(UserResponse {
+                id: user_id.to_string(),
+                name: req.name.clone().unwrap_or_default(),
+                email: "john@example.com".to_string(),
+                role: Role::User.as_str().to_string(),
+                photo: None,
+                bio: None,
+                is_verified: true,
+                locale: None,
+            }))
+        }
+
+        async fn get_password_hash(&self, _user_id: i32) -> Result<Option<String>, UserError> {
+            Ok(Some(self.password_hash.clone()))
+        }
+
+        async fn update_password(&self, _user_id: i32, _password_hash: &str, _current_session: &str) -> Result<bool, UserError> {
+            *self.password_updated.lock().unwrap() = true;
+            Ok(true)
+        }
+
+        async fn get_users(&self, _pagination: &Pagination) -> Result<UserListResponse, UserError> {
+            unimplemented!()
+        }
+
+        async fn delete_user(&self, _user_id: i32) -> Result<bool, UserError> {
+            unimplemented!()
+        }
+
+        async fn update_role(&self, _user_id: i32, _role: Role, _changed_by: i32) -> Result<Option<UserResponse>, UserError> {
+            unimplemented!()
+        }
+    }
+
+    /// ログインの失敗の履歴（記録のみ）
+    struct NoopLoginFailures;
+
+    #[async_trait]
+    impl LoginFailureRepository for NoopLoginFailures {
+        async fn record_failure(&self, _failure: &NewLoginFailure<'_>) -> Result<(), AuthError> {
+            Ok(())
+        }
+
+        async fn get_failures(&self, _query: &LoginFailureListQuery, _pagination: &Pagination) -> Result<LoginFailureListResponse, AuthError> {
+            unimplemented!()
+        }
+
+        async fn prune(&self, _before: DateTime<Utc>) -> Result<u64, AuthError> {
+            Ok(0)
+        }
+    }
+
+    fn hasher() -> PasswordHasher {
+        PasswordHasher::from_settings(&PasswordSettings { argon2_memory_kib: 8, argon2_iterations: 1, argon2_parallelism: 1 }).unwrap()
+    }
+
+    fn service(user_repository: Arc<MockUserRepository>, settings: LoginProtectionSettings) -> UserServiceImpl {
+        let login_guard_service = LoginGuardServiceImpl::new(Arc::new(MemoryThrottleStore::new()), Arc::new(NoopLoginFailures), settings);
+
+        UserServiceImpl::new(user_repository, Arc::new(login_guard_service), Arc::new(hasher()), Arc::new(JwtKeys::from_secret("current", b"secret")))
+    }
+
+    fn claims(id: i32) -> Claims {
+        let keys = JwtKeys::from_secret("current", b"secret");
+
+        keys.decode_token(&keys.create_token("john@example.com", &id, "sid", Role::User).unwrap()).unwrap()
+    }
+
+    // 本文の `user_id` は無視し、トークンのユーザーのみ更新する
+    #[actix_rt::test]
+    async fn test_update_profile_ignores_body_user_id() {
+        let repository = Arc::new(MockUserRepository::default());
+        let req: UpdateUserRequest = serde_json::from_str(r#"{"user_id": 2, "name": "Jane"}"#).unwrap();
+
+        let user = service(repository.clone(), LoginProtectionSettings::default()).update_profile(claims(1).id, &req).await.unwrap();
+
+        assert_eq!(*repository.updated_user_id.lock().unwrap(), Some(1));
+        assert_eq!(user.id, "1");
+        assert_eq!(user.name, "Jane");
+    }
+
+    // 現在のパスワードが正しくない場合は変更せず、失敗が続くとアカウントをロックする
+    #[actix_rt::test]
+    async fn test_change_password_rejects_wrong_current_password() {
+        let repository = Arc::new(MockUserRepository { password_hash: hasher().hash("Current1pw").unwrap(), ..Default::default() });
+        let settings = LoginProtectionSettings { account_max_failures: 2, delay_after_failures: 10, ..Default::default() };
+        let service = service(repository.clone(), settings);
+        let meta = SessionMeta::default();
+        let req = |current_password: &str| ChangePasswordRequest {
+            current_password: current_password.to_string(),
+            new_password: "Newpass123".to_string(),
+        };
+
+        for _ in 0..2 {
+            let result = service.change_password(&claims(1), &req("Wrong1pw"), &meta).await;
+            assert!(matches!(result, Err(UserError::InvalidCredentials)));
+        }
+        assert!(!*repository.password_updated.lock().unwrap());
+
+        let result = service.change_password(&claims(1), &req("Current1pw"), &meta).await;
+        assert!(matches!(result, Err(UserError::LoginGuardError(AuthError::RateLimited(_)))));
+        assert!(!*repository.password_updated.lock().unwrap());
+    }
+
+    // 現在のパスワードが正しい場合は変更し、新しいトークンを発行する
+    #[actix_rt::test]
+    async fn test_change_password() {
+        let repository = Arc::new(MockUserRepository { password_hash: hasher().hash("Current1pw").unwrap(), ..Default::default() });
+        let service = service(repository.clone(), LoginProtectionSettings::default());
+        let req = ChangePasswordRequest { current_password: "Current1pw".to_string(), new_password: "Newpass123".to_string() };
+
+        let token = service.change_password(&claims(1), &req, &SessionMeta::default()).await.unwrap();
+
+        assert!(*repository.password_updated.lock().unwrap());
+        assert_eq!(JwtKeys::from_secret("current", b"secret").decode_token(&token).unwrap().id, 1);
+    }
+}
\ No newline at end of file
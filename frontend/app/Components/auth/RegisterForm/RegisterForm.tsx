@@ -3,7 +3,8 @@ import { useUserContext } from "@/context/userContext";
 import React from "react";
 
 function RegisterForm() {
-  const { registerUser, userState, handlerUserInput } = useUserContext();
+  const { registerUser, registerErrors, userState, handlerUserInput } =
+    useUserContext();
   const { name, email, password } = userState;
   const [showPassword, setShowPassword] = React.useState(false);
 
@@ -37,6 +38,9 @@ function RegisterForm() {
             className="px-4 py-3 border-[2px] rounded-md outline-[#36a866] text-gray-800"
             placeholder="John Doe"
           />
+          {registerErrors.name && (
+            <p className="mt-1 text-[14px] text-red-500">{registerErrors.name}</p>
+          )}
         </div>
         <div className="mt-[1rem] flex flex-col">
           <label htmlFor="email" className="mb-1 text-[#999]">
@@ -51,6 +55,9 @@ function RegisterForm() {
             className="px-4 py-3 border-[2px] rounded-md outline-[#36a866] text-gray-800"
             placeholder="johndoe@gmail.com"
           />
+          {registerErrors.email && (
+            <p className="mt-1 text-[14px] text-red-500">{registerErrors.email}</p>
+          )}
         </div>
         <div className="relative mt-[1rem] flex flex-col">
           <label htmlFor="password" className="mb-1 text-[#999]">
@@ -65,6 +72,9 @@ function RegisterForm() {
             className="px-4 py-3 border-[2px] rounded-md outline-[#2ECC71] text-gray-800"
             placeholder="***************"
           />
+          {registerErrors.password && (
+            <p className="mt-1 text-[14px] text-red-500">{registerErrors.password}</p>
+          )}
           <button
             type="button"
             className="absolute p-1 right-4 top-[43%] text-[22px] text-[#999] opacity-45"
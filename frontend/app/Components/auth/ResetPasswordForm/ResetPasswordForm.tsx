@@ -0,0 +1,69 @@
+"use client";
+import { useUserContext } from "@/context/userContext";
+import React, { useState } from "react";
+
+interface Props {
+  resetToken: string;
+}
+
+function ResetPasswordForm({ resetToken }: Props) {
+  const { resetPassword } = useUserContext();
+
+  // state
+  const [password, setPassword] = useState("");
+
+  const handlePasswordChange = (e: React.ChangeEvent<HTMLInputElement>) => {
+    setPassword(e.target.value);
+  };
+
+  const handleSubmit = (e: any) => {
+    e.preventDefault();
+    resetPassword(resetToken, password);
+
+    // clear input
+    setPassword("");
+  };
+
+  return (
+    <form className="relative m-[2rem] px-10 py-14 rounded-lg border border-[#EDEDED] bg-[#E6E6E6]/20 max-w-[520px] w-full">
+      <div className="relative z-10">
+        <h1 className="mb-2 text-center text-[1.35rem] text-white font-medium">
+          Enter a new password
+        </h1>
+        <div className="mt-[1rem] flex flex-col">
+          <label htmlFor="password" className="mb-1 text-[#999]">
+            New Password
+          </label>
+          <input
+            type="password"
+            value={password}
+            onChange={handlePasswordChange}
+            name="password"
+            placeholder="********"
+            className="px-4 py-3 border-[2px] rounded-md outline-[#2ECC71] text-gray-800"
+          />
+        </div>
+        <div className="mt-4 flex justify-end">
+          <a
+            href="/login"
+            className="font-bold text-[#2ECC71] text-[14px] hover:text-[#7263F3] transition-all duration-300"
+          >
+            Back to login
+          </a>
+        </div>
+        <div className="flex">
+          <button
+            type="submit"
+            onClick={handleSubmit}
+            className="mt-[1.5rem] flex-1 px-4 py-3 font-bold bg-[#2ECC71] text-white rounded-md hover:bg-[#1abc9c] transition-colors"
+          >
+            Reset Password
+          </button>
+        </div>
+      </div>
+      <img src="/flurry.png" alt="" />
+    </form>
+  );
+}
+
+export default ResetPasswordForm;
@@ -0,0 +1,18 @@
+import React from "react";
+import ResetPasswordForm from "../../Components/auth/ResetPasswordForm/ResetPasswordForm";
+
+interface Props {
+  params: {
+    resetToken: string;
+  };
+}
+
+function page({ params: { resetToken } }: Props) {
+  return (
+    <div className="auth-page w-full h-full flex justify-center items-center">
+      <ResetPasswordForm resetToken={resetToken} />
+    </div>
+  );
+}
+
+export default page;
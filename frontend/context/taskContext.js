@@ -55,9 +55,11 @@ export const TasksProvider = ({ children }) => {
     if (!userId) return;
     setLoading(true);
     try {
-      const response = await axios.get(`${server_url}/api/v1/tasks`);
+      const response = await axios.get(`${server_url}/api/v1/tasks`, {
+        params: { per_page: 100 },
+      });
 
-      setTasks(response.data);
+      setTasks(response.data.tasks);
     } catch (error) {
       console.log("Error getting tasks", error);
     }
@@ -68,7 +70,7 @@ export const TasksProvider = ({ children }) => {
   const getTask = async (taskId) => {
     setLoading(true);
     try {
-      const response = await axios.get(`${server_url}/api/v1/task/${taskId}`);
+      const response = await axios.get(`${server_url}/api/v1/tasks/${taskId}`);
 
       setTask(response.data);
     } catch (error) {
@@ -80,7 +82,7 @@ export const TasksProvider = ({ children }) => {
   const createTask = async (task) => {
     setLoading(true);
     try {
-      const res = await axios.post(`${server_url}/api/v1/task/create`, task);
+      const res = await axios.post(`${server_url}/api/v1/tasks`, task);
 
       console.log("Task created", res.data);
 
@@ -96,7 +98,7 @@ export const TasksProvider = ({ children }) => {
     setLoading(true);
     try {
       const res = await axios.patch(
-        `${server_url}/api/v1/task/${task.id}`,
+        `${server_url}/api/v1/tasks/${task.id}`,
         task
       );
 
@@ -116,7 +118,7 @@ export const TasksProvider = ({ children }) => {
   const deleteTask = async (taskId) => {
     setLoading(true);
     try {
-      await axios.delete(`${server_url}/api/v1/task/${taskId}`);
+      await axios.delete(`${server_url}/api/v1/tasks/${taskId}`);
 
       // remove the task from the tasks array
       const newTasks = tasks.filter((new_task) => new_task.id !== taskId);
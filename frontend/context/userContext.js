@@ -8,6 +8,22 @@ const UserContext = React.createContext();
 // set axios to include credentials with every request
 axios.defaults.withCredentials = true;
 
+// API のエラー（problem+json）からメッセージを取得
+const errorMessage = (error) =>
+  error.response?.data?.detail ??
+  error.response?.data?.message ??
+  "Something went wrong";
+
+// バリデーションエラーを項目ごとのメッセージに変換
+const fieldErrors = (error) =>
+  (error.response?.data?.errors ?? []).reduce(
+    (errors, { field, code, message }) => ({
+      ...errors,
+      [field]: errors[field] ?? message ?? code,
+    }),
+    {}
+  );
+
 export const UserContextProvider = ({ children }) => {
   const server_url = "";
 
@@ -21,11 +37,14 @@ export const UserContextProvider = ({ children }) => {
     password: "",
   });
 
+  const [registerErrors, setRegisterErrors] = useState({});
+
   const [loggedIn, setLoggedIn] = useState(false);
 
   // register user
   const registerUser = async (e) => {
     setLoggedIn(false);
+    setRegisterErrors({});
     e.preventDefault();
     if (
       !userState.email.includes("@") ||
@@ -52,7 +71,7 @@ export const UserContextProvider = ({ children }) => {
         password: "",
       });
 
-      const loginUserData = response.data;
+      const loginUserData = res.data;
       // ローカルストレージにトークンを保存
       window.localStorage.setItem("login_token", loginUserData.token);
 
@@ -60,7 +79,8 @@ export const UserContextProvider = ({ children }) => {
       router.push(`${server_url}/login`);
     } catch (error) {
       console.log("Error registering user", error);
-      toast.error(error.response.data.message);
+      setRegisterErrors(fieldErrors(error));
+      toast.error(errorMessage(error));
     }
   };
 
@@ -96,7 +116,7 @@ export const UserContextProvider = ({ children }) => {
       router.push("/");
     } catch (error) {
       console.log("Error logging in user", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -145,9 +165,13 @@ export const UserContextProvider = ({ children }) => {
   // logout user
   const logoutUser = async () => {
     try {
-      const res = await axios.get(`${server_url}/api/v1/auth/logout`, {
-        withCredentials: true, // send cookies to the server
-      });
+      const res = await axios.post(
+        `${server_url}/api/v1/auth/logout`,
+        {},
+        {
+          withCredentials: true, // send cookies to the server
+        }
+      );
 
       toast.success("User logged out successfully");
 
@@ -157,7 +181,7 @@ export const UserContextProvider = ({ children }) => {
       router.push("/login");
     } catch (error) {
       console.log("Error logging out user", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -180,7 +204,7 @@ export const UserContextProvider = ({ children }) => {
     } catch (error) {
       console.log("Error getting user details", error);
       setLoading(false);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -208,7 +232,7 @@ export const UserContextProvider = ({ children }) => {
     } catch (error) {
       console.log("Error updating user details", error);
       setLoading(false);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -229,7 +253,7 @@ export const UserContextProvider = ({ children }) => {
     } catch (error) {
       console.log("Error sending email verification", error);
       setLoading(false);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -255,7 +279,7 @@ export const UserContextProvider = ({ children }) => {
       router.push("/");
     } catch (error) {
       console.log("Error verifying user", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
       setLoading(false);
     }
   };
@@ -279,7 +303,33 @@ export const UserContextProvider = ({ children }) => {
       setLoading(false);
     } catch (error) {
       console.log("Error sending forgot password email", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
+      setLoading(false);
+    }
+  };
+
+  // reset password
+  const resetPassword = async (token, password) => {
+    setLoading(true);
+
+    try {
+      const res = await axios.post(
+        `${server_url}/api/v1/auth/reset-password/${token}`,
+        {
+          password,
+        },
+        {
+          withCredentials: true, // send cookies to the server
+        }
+      );
+
+      toast.success("Password reset successfully");
+      setLoading(false);
+      // redirect to login page
+      router.push("/login");
+    } catch (error) {
+      console.log("Error resetting password", error);
+      toast.error(errorMessage(error));
       setLoading(false);
     }
   };
@@ -290,7 +340,7 @@ export const UserContextProvider = ({ children }) => {
 
     try {
       const res = await axios.patch(
-        `${server_url}/api/v1/change-password`,
+        `${server_url}/api/v1/auth/change-password`,
         { currentPassword, newPassword },
         {
           withCredentials: true, // send cookies to the server
@@ -301,7 +351,7 @@ export const UserContextProvider = ({ children }) => {
       setLoading(false);
     } catch (error) {
       console.log("Error changing password", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
       setLoading(false);
     }
   };
@@ -314,6 +364,7 @@ export const UserContextProvider = ({ children }) => {
       ...prevState,
       [name]: value,
     }));
+    setRegisterErrors((prevErrors) => ({ ...prevErrors, [name]: undefined }));
   };
 
   // healthchecker
@@ -324,7 +375,7 @@ export const UserContextProvider = ({ children }) => {
       toast.success(`API is ${res.data.message}`);
     } catch (error) {
       console.log("API is inactive", error);
-      toast.error(error.response.data.message);
+      toast.error(errorMessage(error));
     }
   };
 
@@ -354,6 +405,7 @@ export const UserContextProvider = ({ children }) => {
     <UserContext.Provider
       value={{
         registerUser,
+        registerErrors,
         userState,
         handlerUserInput,
         loginUser,
@@ -364,6 +416,7 @@ export const UserContextProvider = ({ children }) => {
         emailVerification,
         verifyUser,
         forgotPasswordEmail,
+        resetPassword,
         changePassword,
         healthcheck,
         loggedIn,